@@ -0,0 +1,18 @@
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { testConfig } from '../test.config'
+
+describe('ACME challenges', () => {
+  let api: TestAgent
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+  })
+
+  it('returns 404 for unknown challenge tokens', async () => {
+    await api
+      .get('/.well-known/acme-challenge/unknown-token')
+      .set('Host', 'www.myblog.org')
+      .expect(404)
+  })
+})
@@ -0,0 +1,121 @@
+import { WwwPolicy } from '@pubstudio/shared/type-api-platform-site'
+import { ICustomDomainRelationViewModel } from '@pubstudio/shared/type-api-shared'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Canonical Host', () => {
+  const testEndpoint = '/api/sites_metadata'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let siteId: string
+  let domains: ICustomDomainRelationViewModel[]
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    domains = [
+      { domain: 'www.myblog.org', verified: true, primary: true },
+      { domain: 'user1-site2.localhost', verified: false },
+    ]
+    await resetService.reset()
+  })
+
+  it('serves every host when no primary domain is set', async () => {
+    await api.get('/about').set('Host', 'user1-site2.localhost').expect(200)
+  })
+
+  it('redirects other hosts to the primary domain', async () => {
+    // Owners can't verify domains
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send({ domains })
+      .expect(200)
+
+    await api
+      .get('/about?a=1')
+      .set('Host', 'user1-site2.localhost')
+      .expect(301)
+      .expect('Location', 'https://www.myblog.org/about?a=1')
+
+    // Plain http behind a proxy is upgraded
+    await api
+      .get('/about')
+      .set('Host', 'www.myblog.org')
+      .set('X-Forwarded-Proto', 'http')
+      .expect(301)
+      .expect('Location', 'https://www.myblog.org/about')
+  })
+
+  it('applies the www policy to the primary domain', async () => {
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send({ domains, www_policy: WwwPolicy.Apex })
+      .expect(200)
+
+    // The apex domain resolves to the site, and is canonical
+    await api
+      .get('/')
+      .set('Host', 'www.myblog.org')
+      .expect(301)
+      .expect('Location', 'https://myblog.org/')
+    await api.get('/sitemap.xml').set('Host', 'myblog.org').expect(404)
+  })
+
+  it('does not enforce a primary domain verified by the owner', async () => {
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains })
+      .expect(200)
+
+    await api.get('/about').set('Host', 'user1-site2.localhost').expect(200)
+    const response = await api
+      .get(`/api/sites/${siteId}/domains`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    expect(response.body.domains[0]).toMatchObject({
+      domain: 'www.myblog.org',
+      verified: false,
+    })
+  })
+
+  it('returns 400 when the primary domain is not verified', async () => {
+    domains[0].verified = false
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains })
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Primary domain must be verified',
+        status: 400,
+      })
+  })
+
+  it('returns 400 when more than one domain is primary', async () => {
+    domains[1] = { domain: 'user1-site2.localhost', verified: true, primary: true }
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains })
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Only one domain can be primary',
+        status: 400,
+      })
+  })
+})
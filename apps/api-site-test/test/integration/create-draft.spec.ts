@@ -51,6 +51,30 @@ describe('Create Site Draft', () => {
     expect(versionsBefore + 1).toEqual(body.length)
   })
 
+  it('creates a draft with a message and tag', async () => {
+    const res = await api
+      .post(testEndpoint(siteId))
+      .send({ message: 'Holiday campaign', tag: 'holiday-2026' })
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListSiteVersionsApiResponse = res.body
+
+    expect(body[0].message).toEqual('Holiday campaign')
+    expect(body[0].tag).toEqual('holiday-2026')
+  })
+
+  it('when tag is invalid', async () => {
+    await api
+      .post(testEndpoint(siteId))
+      .send({ tag: 'not a tag' })
+      .set('Authorization', adminAuth)
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Failed to validate request',
+        status: 400,
+      })
+  })
+
   describe('when requestor is Owner', () => {
     let ownerAuth: string
 
@@ -0,0 +1,132 @@
+import {
+  DomainKind,
+  ICustomDomainRelationViewModel,
+} from '@pubstudio/shared/type-api-shared'
+import { ISiteViewModel } from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Domain Kinds', () => {
+  const testEndpoint = '/api/sites_metadata'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let ownerAuth: string
+  let siteId: string
+  let domains: ICustomDomainRelationViewModel[]
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    const adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    domains = [
+      { domain: 'test3.localhost', verified: true },
+      { domain: 'oldbrand.localhost', verified: true, kind: DomainKind.Redirect },
+      { domain: '*.events.localhost', verified: true, kind: DomainKind.Wildcard },
+    ]
+    await resetService.reset()
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains })
+      .expect(200)
+  })
+
+  it('redirects aliases to the served domain', async () => {
+    await api
+      .get('/about?a=1')
+      .set('Host', 'oldbrand.localhost')
+      .expect(301)
+      .expect('Location', 'http://test3.localhost/about?a=1')
+  })
+
+  it('serves the site on wildcard subdomains', async () => {
+    const response = await api
+      .get('/api/sites/current')
+      .set('Host', 'summit.events.localhost')
+      .expect(200)
+
+    const body: ISiteViewModel = response.body
+    expect(body.name).toEqual('Test Site 3')
+    expect(body.subdomain).toEqual('summit')
+
+    const served = await api
+      .get('/api/sites/current')
+      .set('Host', 'test3.localhost')
+      .expect(200)
+    expect(served.body.subdomain).toBeUndefined()
+  })
+
+  it('serves exact domains of other sites over a resolved wildcard', async () => {
+    await api.get('/api/sites/current').set('Host', 'summit.events.localhost').expect(200)
+    await api
+      .patch(`${testEndpoint}/6d2c8359-6094-402c-bcbb-37202fd7c336`)
+      .set('Authorization', ownerAuth)
+      .send({ domains: [{ domain: 'admin.events.localhost', verified: true }] })
+      .expect(200)
+
+    // Test Site 2 is unpublished
+    const response = await api
+      .get('/api/sites/current')
+      .set('Host', 'admin.events.localhost')
+      .expect(400)
+    expect(response.body.code).toEqual('SiteUnpublished')
+
+    const served = await api
+      .get('/api/sites/current')
+      .set('Host', 'expo.events.localhost')
+      .expect(200)
+    expect(served.body.subdomain).toEqual('expo')
+  })
+
+  it('validates hosts matched by a wildcard domain', async () => {
+    const endpoint = '/api/sites/validate_domain'
+    await api.get(endpoint).query({ domain: 'summit.events.localhost' }).expect(200)
+    // Wildcards match a single label
+    await api.get(endpoint).query({ domain: 'a.summit.events.localhost' }).expect(404)
+    await api.get(endpoint).query({ domain: 'events.localhost' }).expect(404)
+  })
+
+  it('stops serving subdomains when the wildcard is removed', async () => {
+    await api.get('/api/sites/current').set('Host', 'summit.events.localhost').expect(200)
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains: domains.slice(0, 1) })
+      .expect(200)
+    await api.get('/api/sites/current').set('Host', 'summit.events.localhost').expect(400)
+  })
+
+  it('returns 400 for a wildcard without the wildcard kind', async () => {
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains: [{ domain: '*.events.localhost', verified: true }] })
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Wildcard domains must look like *.example.com: *.events.localhost',
+        status: 400,
+      })
+  })
+
+  it('returns 400 when a redirect alias is primary', async () => {
+    domains[1].primary = true
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', ownerAuth)
+      .send({ domains })
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Primary domain must serve the site',
+        status: 400,
+      })
+  })
+})
@@ -0,0 +1,68 @@
+import { IListDomainVerificationsApiResponse } from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Domain verifications', () => {
+  const testEndpoint = '/api/sites'
+  const siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+  })
+
+  const listVerifications = async (): Promise<IListDomainVerificationsApiResponse> => {
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/domain_verifications`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    return response.body
+  }
+
+  it('issues a token for each custom domain', async () => {
+    const verifications = await listVerifications()
+    const verification = verifications.find((v) => v.domain === 'www.myblog.org')
+    expect(verification?.txt_name).toEqual('_pubstudio.www.myblog.org')
+    expect(verification?.token.length).toEqual(32)
+    expect(verification?.status).toEqual('Pending')
+    expect(verification?.last_checked_at).toBeNull()
+
+    // Tokens are kept between requests
+    const again = await listVerifications()
+    expect(again.find((v) => v.domain === 'www.myblog.org')?.token).toEqual(
+      verification?.token,
+    )
+  })
+
+  it('records failed checks of pending domains', async () => {
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/domain_verifications/actions/check`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    const body: IListDomainVerificationsApiResponse = response.body
+    const verification = body.find((v) => v.domain === 'www.myblog.org')
+    expect(verification?.status).toEqual('Pending')
+    expect(verification?.verified).toEqual(false)
+    expect(verification?.last_checked_at).toBeTruthy()
+    expect(verification?.last_error).toBeTruthy()
+    expect(verification?.failing_since).toBeTruthy()
+  })
+
+  it('returns 401 without auth', async () => {
+    await api.get(`${testEndpoint}/${siteId}/domain_verifications`).expect(401)
+  })
+})
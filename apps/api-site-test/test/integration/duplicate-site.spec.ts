@@ -0,0 +1,126 @@
+import {
+  ICreateSiteApiResponse,
+  IDuplicateSiteApiRequest,
+  IListSiteVersionsApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Duplicate Site', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let payload: IDuplicateSiteApiRequest
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    payload = {
+      owner_id: '903b3c28-deaa-45dc-a43f-511fe965d34e',
+      name: 'Client Site',
+      site_type: 'Paid1',
+      copy_custom_tables: true,
+    }
+    await resetService.reset()
+  })
+
+  it('duplicates a site into a new site id', async () => {
+    // Only admins may change the site type and owner email
+    payload.owner_email = 'test1@samatech.tw'
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(201)
+    const body: ICreateSiteApiResponse = response.body
+    expect(body.id).not.toEqual(siteId)
+
+    const versions = await api
+      .get(`${testEndpoint}/${body.id}/versions`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    const versionsBody: IListSiteVersionsApiResponse = versions.body
+    expect(versionsBody.length).toEqual(1)
+    expect(versionsBody[0].name).toEqual('Client Site')
+    expect(versionsBody[0].published).toEqual(false)
+
+    const metadata = await api
+      .get(`/api/sites_metadata/${body.id}`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    expect(metadata.body.site_type).toEqual('Paid1')
+    expect(metadata.body.owner_email).toEqual('test1@samatech.tw')
+    expect(metadata.body.custom_domains).toEqual([])
+  })
+
+  it('when owner has reached the site capacity', async () => {
+    // The owner already has a Free site
+    payload.site_type = 'Free'
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(400, {
+        code: 'SiteCapacityExceeded',
+        message: 'Site capacity reached for this site type',
+        status: 400,
+      })
+  })
+
+  it('when owner changes the site type', async () => {
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(403, {
+        code: 'None',
+        message: 'Only admins can change the site type',
+        status: 403,
+      })
+  })
+
+  it('when owner changes the owner email', async () => {
+    payload.site_type = undefined
+    payload.owner_email = 'test1@samatech.tw'
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(403, {
+        code: 'None',
+        message: 'Only admins can change the owner email',
+        status: 403,
+      })
+  })
+
+  it('when owner duplicates into another account', async () => {
+    payload.owner_id = '0c069253-e45d-487c-b7c0-cbe467c33a10'
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+
+  it('when requester is not the site owner', async () => {
+    ownerAuth = ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10')
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/duplicate`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+})
@@ -0,0 +1,183 @@
+import {
+  ICreateExperimentApiRequest,
+  IExperimentResultsViewModel,
+  IExperimentViewModel,
+  IListExperimentsApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Experiments', () => {
+  const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+  const host = 'test3.localhost'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let payload: ICreateExperimentApiRequest
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+    payload = {
+      route: '/home',
+      name: 'Hero',
+      conversion_table: 'signups',
+      variants: [
+        { name: 'Control', page_route: '/home', weight: 1 },
+        { name: 'B', page_route: '/home-b', weight: 1 },
+      ],
+    }
+  })
+
+  const parsePages = (encoded: string) => {
+    const pages = JSON.parse(encoded)
+    return typeof pages === 'string' ? JSON.parse(pages) : pages
+  }
+
+  // Copy the home page to /home-b, with its own title
+  const addVariantPage = async () => {
+    const site = (await api.get('/api/sites/current').set('Host', host).expect(200)).body
+    const pages = parsePages(site.pages)
+    pages['/home-b'] = { ...pages['/home'], name: 'Home B', head: { title: 'Variant B' } }
+    await api
+      .patch(`/api/sites/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send({ pages: JSON.stringify(pages) })
+      .expect(200)
+  }
+
+  const createExperiment = async (): Promise<IExperimentViewModel> => {
+    const response = await api
+      .post(`/api/sites/${siteId}/experiments`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(201)
+    return response.body
+  }
+
+  const getCookie = (response: supertest.Response): string | undefined => {
+    const cookies = response.headers['set-cookie'] as unknown as string[] | undefined
+    return cookies?.find((cookie) => cookie.startsWith('ps_ab='))?.split(';')[0]
+  }
+
+  it('creates and lists experiments', async () => {
+    await addVariantPage()
+    const experiment = await createExperiment()
+    expect(experiment.enabled).toEqual(true)
+    expect(experiment.variants.map((variant) => variant.page_route)).toEqual([
+      '/home',
+      '/home-b',
+    ])
+
+    const response = await api
+      .get(`/api/sites/${siteId}/experiments`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListExperimentsApiResponse = response.body
+    expect(body.length).toEqual(1)
+    expect(body[0].id).toEqual(experiment.id)
+  })
+
+  it('returns 400 for invalid experiments', async () => {
+    let response = await api
+      .post(`/api/sites/${siteId}/experiments`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(400)
+    expect(response.body.message).toEqual('Variant page /home-b does not exist')
+
+    await addVariantPage()
+    await createExperiment()
+    response = await api
+      .post(`/api/sites/${siteId}/experiments`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(400)
+    expect(response.body.code).toEqual('ExperimentRouteExists')
+  })
+
+  it('assigns sticky variants and counts exposures and conversions', async () => {
+    await addVariantPage()
+    const experiment = await createExperiment()
+
+    let response = await api.get('/').set('Host', host).expect(200)
+    expect(response.headers['cache-control']).toEqual('private, no-cache')
+    let cookie = getCookie(response) as string
+    expect(cookie).toBeDefined()
+    const title = response.text.match(/<title>(.*)<\/title>/)?.[1]
+
+    // The visitor keeps their variant
+    response = await api.get('/').set('Host', host).set('Cookie', cookie).expect(200)
+    expect(getCookie(response)).toBeUndefined()
+    expect(response.text).toContain(`<title>${title}</title>`)
+
+    // Conversions only count after the visitor saw the experiment route
+    await api
+      .post(`/api/sites/${siteId}/experiments/${experiment.id}/actions/convert`)
+      .set('Host', host)
+      .set('Cookie', cookie)
+      .expect(204)
+
+    response = await api
+      .post(`/api/sites/${siteId}/usage/actions/page_view`)
+      .set('Host', host)
+      .set('Cookie', cookie)
+      .send({ route: '/home' })
+      .expect(200)
+    cookie = getCookie(response) as string
+
+    response = await api
+      .post(`/api/sites/${siteId}/experiments/${experiment.id}/actions/convert`)
+      .set('Host', host)
+      .set('Cookie', cookie)
+      .expect(204)
+    cookie = getCookie(response) as string
+    await api
+      .post(`/api/sites/${siteId}/experiments/${experiment.id}/actions/convert`)
+      .set('Host', host)
+      .set('Cookie', cookie)
+      .expect(204)
+
+    response = await api
+      .get(`/api/sites/${siteId}/experiments/${experiment.id}/results`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const results: IExperimentResultsViewModel = response.body
+    expect(results.control_id).toEqual(experiment.variants[0].id)
+    const assigned = results.variants.find((variant) => variant.exposures === 1)
+    expect(assigned?.conversions).toEqual(1)
+    expect(assigned?.conversion_rate).toEqual(1)
+    expect(results.variants.every((variant) => !variant.significant)).toEqual(true)
+  })
+
+  it('stops assigning disabled experiments', async () => {
+    await addVariantPage()
+    const experiment = await createExperiment()
+    await api
+      .patch(`/api/sites/${siteId}/experiments/${experiment.id}`)
+      .set('Authorization', adminAuth)
+      .send({ enabled: false, conversion_table: '' })
+      .expect(200)
+
+    const response = await api.get('/').set('Host', host).expect(200)
+    expect(getCookie(response)).toBeUndefined()
+
+    await api
+      .post(`/api/sites/${siteId}/experiments/${experiment.id}/actions/convert`)
+      .set('Host', host)
+      .expect(404)
+    await api
+      .delete(`/api/sites/${siteId}/experiments/${experiment.id}`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+  })
+})
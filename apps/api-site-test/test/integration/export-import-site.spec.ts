@@ -0,0 +1,114 @@
+import {
+  IImportSiteApiRequest,
+  IListSiteVersionsApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+const binaryParser = (
+  res: supertest.Response,
+  callback: (err: Error | null, body: Buffer) => void,
+) => {
+  const chunks: Buffer[] = []
+  res.on('data', (chunk: Buffer) => chunks.push(chunk))
+  res.on('end', () => callback(null, Buffer.concat(chunks)))
+}
+
+describe('Export and Import Site', () => {
+  const testEndpoint = '/api/sites'
+  const importedSiteId = '5b1a4b0e-7c0f-4b59-9a63-7d4a3fb0c0a1'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let siteId: string
+  let importQuery: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    const query: IImportSiteApiRequest = {
+      site_id: importedSiteId,
+      owner_id: '903b3c28-deaa-45dc-a43f-511fe965d34e',
+      owner_email: 'test1@samatech.tw',
+      site_type: 'Free',
+    }
+    importQuery = new URLSearchParams({ ...query }).toString()
+    await resetService.reset()
+  })
+
+  const exportSite = async (): Promise<Buffer> => {
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/export`)
+      .set('Authorization', ownerAuth)
+      .buffer(true)
+      .parse(binaryParser)
+      .expect(200)
+      .expect('Content-Type', 'application/x-tar')
+    return response.body
+  }
+
+  it('exports a site archive', async () => {
+    const archive = await exportSite()
+
+    const contents = archive.toString('latin1')
+    expect(contents).toContain('manifest.json')
+    expect(contents).toContain('site_versions/0.json')
+    expect(contents).toContain('custom_tables/0/table.json')
+  })
+
+  it('imports an exported site', async () => {
+    const archive = await exportSite()
+
+    const response = await api
+      .post(`${testEndpoint}/actions/import?${importQuery}`)
+      .set('Authorization', adminAuth)
+      .set('Content-Type', 'application/x-tar')
+      .send(archive)
+      .expect(201)
+    expect(response.body.id).toEqual(importedSiteId)
+
+    const versions = await api
+      .get(`${testEndpoint}/${importedSiteId}/versions`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListSiteVersionsApiResponse = versions.body
+    expect(body.length).toEqual(1)
+
+    // Importing the same site twice fails
+    await api
+      .post(`${testEndpoint}/actions/import?${importQuery}`)
+      .set('Authorization', adminAuth)
+      .set('Content-Type', 'application/x-tar')
+      .send(archive)
+      .expect(400)
+  })
+
+  it('when archive is invalid', async () => {
+    const response = await api
+      .post(`${testEndpoint}/actions/import?${importQuery}`)
+      .set('Authorization', adminAuth)
+      .set('Content-Type', 'application/x-tar')
+      .send(Buffer.from('not an archive'))
+      .expect(400)
+    expect(response.body.code).toEqual('InvalidSiteArchive')
+  })
+
+  it('when requester is not the site owner', async () => {
+    ownerAuth = ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10')
+    await api
+      .get(`${testEndpoint}/${siteId}/export`)
+      .set('Authorization', ownerAuth)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+})
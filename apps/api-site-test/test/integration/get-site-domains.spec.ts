@@ -34,9 +34,13 @@ describe('Get Site Domains', () => {
     const body: IGetSiteDomainsApiResponse = response.body
 
     expect(body.domains).toEqual([
-      { domain: 'www.myblog.org', verified: false },
-      { domain: 'user1-site2.localhost', verified: false },
-      { domain: `user1-site2-subdomain.${execEnv}.pubstud.io`, verified: false },
+      { domain: 'www.myblog.org', verified: false, primary: false },
+      { domain: 'user1-site2.localhost', verified: false, primary: false },
+      {
+        domain: `user1-site2-subdomain.${execEnv}.pubstud.io`,
+        verified: false,
+        primary: false,
+      },
     ])
   })
 
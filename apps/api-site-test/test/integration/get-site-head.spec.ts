@@ -56,4 +56,32 @@ describe('Get site head', () => {
     expect(body.title).toEqual(newTitle)
     expect(body.description).toEqual(newDesc)
   })
+
+  it('returns the head of a page route', async () => {
+    const pages = {
+      '/about': {
+        name: 'About',
+        route: '/about',
+        public: true,
+        head: { title: 'About', meta: [{ name: 'keywords', content: 'about' }] },
+      },
+    }
+    const payload: IUpdateSiteApiRequest = { pages: JSON.stringify(pages) }
+    await api
+      .patch(`/api/sites/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(200)
+
+    const response = await api.get(`${testEndpoint(siteId)}?route=/about`).expect(200)
+    const body: ISiteHeadApiResponse = response.body
+
+    expect(body.route).toEqual('/about')
+    expect(body.title).toEqual('About')
+    // Page description falls back to the site description
+    expect(body.description).toEqual('Test Site 3')
+    expect(body.meta.length).toEqual(1)
+    expect(body.meta[0].name).toEqual('keywords')
+    expect(body.meta[0].content).toEqual('about')
+  })
 })
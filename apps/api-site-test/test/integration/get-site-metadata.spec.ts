@@ -1,4 +1,4 @@
-import { SiteType } from '@pubstudio/shared/type-api-platform-site'
+import { SiteType, WwwPolicy } from '@pubstudio/shared/type-api-platform-site'
 import { ISiteMetadata } from '@pubstudio/shared/type-api-site-sites'
 import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
 import supertest from 'supertest'
@@ -41,9 +41,14 @@ describe('List Sites', () => {
       expect(body.location).toEqual(`site-api/db/sites/site_${siteId}.db`)
       expect(body.disabled).toEqual(false)
       expect(body.site_type).toEqual(SiteType.Paid2)
+      expect(body.www_policy).toEqual(WwwPolicy.Keep)
       expect(body.custom_domains).toEqual([
-        { domain: 'test3.localhost', verified: false },
-        { domain: `user1-site3-subdomain.${execEnv}.pubstud.io`, verified: false },
+        { domain: 'test3.localhost', verified: false, primary: false },
+        {
+          domain: `user1-site3-subdomain.${execEnv}.pubstud.io`,
+          verified: false,
+          primary: false,
+        },
       ])
     })
   })
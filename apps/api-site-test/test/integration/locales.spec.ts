@@ -0,0 +1,95 @@
+import {
+  ISiteHeadApiResponse,
+  IUpdateSiteApiRequest,
+} from '@pubstudio/shared/type-api-site-sites'
+import { ISiteDefaults } from '@pubstudio/shared/type-site'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Site locales', () => {
+  const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+  const host = 'test3.localhost'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let defaults: ISiteDefaults
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+    defaults = {
+      homePage: '/home',
+      head: { title: 'Test Site 3' },
+      locales: [{ code: 'en' }, { code: 'zh-TW', title: '測試網站' }],
+    }
+  })
+
+  const updateDefaults = async (status = 200) => {
+    const payload: IUpdateSiteApiRequest = { defaults: JSON.stringify(defaults) }
+    return api
+      .patch(`/api/sites/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(status)
+  }
+
+  it('redirects / to the negotiated locale', async () => {
+    await updateDefaults()
+
+    await api
+      .get('/?a=1')
+      .set('Host', host)
+      .set('Accept-Language', 'fr;q=0.9, zh;q=0.8')
+      .expect(302)
+      .expect('Location', '/zh-TW?a=1')
+      .expect('Vary', /accept-language/)
+
+    // Unsupported languages fall back to the default locale
+    await api
+      .get('/')
+      .set('Host', host)
+      .set('Accept-Language', 'fr')
+      .expect(302)
+      .expect('Location', '/en')
+  })
+
+  it('serves localized pages with hreflang alternates', async () => {
+    await updateDefaults()
+
+    const response = await api.get('/zh-tw').set('Host', host).expect(200)
+    expect(response.text).toContain('<html lang="zh-TW">')
+    expect(response.text).toContain('<title>測試網站</title>')
+    expect(response.text).toContain('hreflang="en"')
+    expect(response.text).toContain('hreflang="x-default"')
+
+    const head: ISiteHeadApiResponse = (
+      await api.get(`/api/sites/${siteId}/head?route=/zh-TW/home`).expect(200)
+    ).body
+    expect(head.lang).toEqual('zh-TW')
+    expect(head.route).toEqual('/zh-TW/home')
+    expect(head.title).toEqual('測試網站')
+    expect(head.alternates.map((link) => link.hreflang)).toEqual([
+      'en',
+      'zh-TW',
+      'x-default',
+    ])
+  })
+
+  it('returns 400 for invalid locales', async () => {
+    defaults.locales = [{ code: 'en' }, { code: 'EN' }]
+    const response = await updateDefaults(400)
+    expect(response.body.message).toEqual('Duplicate locale EN')
+
+    defaults.locales = [{ code: 'english!' }]
+    await updateDefaults(400)
+  })
+})
@@ -0,0 +1,146 @@
+import { MaintenancePage } from '@pubstudio/shared/type-api-platform-site'
+import {
+  IMaintenanceViewModel,
+  IUpdateMaintenanceApiRequest,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Maintenance mode', () => {
+  const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+  const testEndpoint = `/api/sites/${siteId}/maintenance`
+  const host = 'test3.localhost'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+  })
+
+  const updateMaintenance = async (
+    payload: IUpdateMaintenanceApiRequest,
+  ): Promise<IMaintenanceViewModel> => {
+    const response = await api
+      .put(testEndpoint)
+      .send(payload)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    return response.body
+  }
+
+  const minutesFromNow = (minutes: number) =>
+    new Date(Date.now() + minutes * 60 * 1000).toISOString()
+
+  it('returns 404 when maintenance is not configured', async () => {
+    await api.get(testEndpoint).set('Authorization', adminAuth).expect(404)
+    await api.get('/').set('Host', host).expect(200)
+  })
+
+  it('serves the built-in holding page with 503', async () => {
+    const maintenance = await updateMaintenance({ enabled: true, message: 'Back <soon>' })
+    expect(maintenance.active).toBe(true)
+    expect(maintenance.page).toEqual(MaintenancePage.Builtin)
+    expect(maintenance.bypass_token.length).toBeGreaterThan(0)
+
+    const response = await api.get('/about').set('Host', host).expect(503)
+    expect(response.headers['retry-after']).toEqual('3600')
+    expect(response.headers['cache-control']).toEqual('no-store')
+    expect(response.text).toContain('<title>Test Site 3</title>')
+    expect(response.text).toContain('Back &lt;soon&gt;')
+
+    await api.get('/api/sites/current').set('Host', host).expect(503, {
+      code: 'SiteMaintenance',
+      message: 'The site is under maintenance',
+      status: 503,
+    })
+  })
+
+  it('serves a custom page until the end time', async () => {
+    await updateMaintenance({
+      enabled: true,
+      ends_at: minutesFromNow(10),
+      page: MaintenancePage.Custom,
+      custom_page: '<h1>Upgrading</h1>',
+    })
+
+    const response = await api.get('/').set('Host', host).expect(503)
+    expect(response.text).toEqual('<h1>Upgrading</h1>')
+    const retryAfter = parseInt(response.headers['retry-after'])
+    expect(retryAfter).toBeGreaterThan(500)
+    expect(retryAfter).toBeLessThanOrEqual(600)
+  })
+
+  it('does not affect the site before the start time', async () => {
+    const maintenance = await updateMaintenance({
+      enabled: true,
+      starts_at: minutesFromNow(10),
+      ends_at: minutesFromNow(20),
+    })
+    expect(maintenance.active).toBe(false)
+    await api.get('/').set('Host', host).expect(200)
+  })
+
+  it('lets allowlisted IPs and previewers see the site', async () => {
+    const maintenance = await updateMaintenance({
+      enabled: true,
+      allowed_ips: ['203.0.113.9'],
+    })
+    await api.get('/').set('Host', host).set('X-Forwarded-For', '203.0.113.9').expect(200)
+
+    await api.get('/_ps/maintenance?token=wrong').set('Host', host).expect(403)
+    const response = await api
+      .get(`/_ps/maintenance?token=${maintenance.bypass_token}`)
+      .set('Host', host)
+      .expect(303)
+      .expect('Location', '/')
+    const cookie = response.headers['set-cookie'][0].split(';')[0]
+    await api.get('/').set('Host', host).set('Cookie', cookie).expect(200)
+
+    // Resetting the token signs previewers out
+    await updateMaintenance({ enabled: true, reset_bypass_token: true })
+    await api.get('/').set('Host', host).set('Cookie', cookie).expect(503)
+  })
+
+  it('does not count views during maintenance', async () => {
+    await updateMaintenance({ enabled: true })
+    await api.get('/api/sites/current').set('Host', host).expect(503)
+    await api
+      .post(`/api/sites/${siteId}/usage/actions/page_view`)
+      .set('Host', host)
+      .send({ route: '/home' })
+      .expect(503)
+  })
+
+  it('returns 400 for invalid settings', async () => {
+    await api
+      .put(testEndpoint)
+      .send({ enabled: true, starts_at: minutesFromNow(20), ends_at: minutesFromNow(10) })
+      .set('Authorization', adminAuth)
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Maintenance must end after it starts',
+        status: 400,
+      })
+    await api
+      .put(testEndpoint)
+      .send({ enabled: true, page: MaintenancePage.Custom })
+      .set('Authorization', adminAuth)
+      .expect(400)
+    await api
+      .put(testEndpoint)
+      .send({ enabled: true, allowed_ips: ['not-an-ip'] })
+      .set('Authorization', adminAuth)
+      .expect(400)
+  })
+})
@@ -0,0 +1,101 @@
+import {
+  ISiteMergeConflictApiResponse,
+  IUpdateSiteApiRequest,
+  IUpdateSiteApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Merge Site Updates', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let siteId: string
+  let baseRevision: number
+
+  const makePages = (a: string, b: string, aboutName = 'About') =>
+    JSON.stringify({
+      '/': {
+        name: 'Home',
+        root: {
+          id: 'root',
+          children: [
+            { id: 'a', content: a },
+            { id: 'b', content: b },
+          ],
+        },
+      },
+      '/about': { name: aboutName, root: { id: 'root-about', children: [] } },
+    })
+
+  const update = (payload: IUpdateSiteApiRequest) =>
+    api.patch(`${testEndpoint}/${siteId}`).set('Authorization', adminAuth).send(payload)
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    await resetService.reset()
+    const response = await update({ pages: makePages('A', 'B') }).expect(200)
+    baseRevision = (response.body as IUpdateSiteApiResponse).revision_id as number
+  })
+
+  it('merges changes to separate components and pages', async () => {
+    await update({ pages: makePages('A2', 'B'), base_revision_id: baseRevision }).expect(200)
+
+    const response = await update({
+      pages: makePages('A', 'B2', 'About us'),
+      base_revision_id: baseRevision,
+    }).expect(200)
+    const body: IUpdateSiteApiResponse = response.body
+
+    const pages = JSON.parse(JSON.parse(body.pages))
+    expect(pages['/'].root.children).toEqual([
+      { id: 'a', content: 'A2' },
+      { id: 'b', content: 'B2' },
+    ])
+    expect(pages['/about'].name).toEqual('About us')
+  })
+
+  it('returns conflicting components', async () => {
+    await update({ pages: makePages('A2', 'B'), base_revision_id: baseRevision }).expect(200)
+
+    const response = await update({
+      pages: makePages('A3', 'B'),
+      base_revision_id: baseRevision,
+    }).expect(409)
+    const body: ISiteMergeConflictApiResponse = response.body
+
+    expect(body.code).toEqual('MergeConflict')
+    expect(body.conflicts).toEqual([{ field: 'pages', route: '/', component_id: 'a' }])
+  })
+
+  it('keeps checking update_key for history only updates', async () => {
+    await update({
+      history: JSON.stringify({ back: [], forward: [] }),
+      update_key: '2023-11-07T08:25:58.131123Z',
+      base_revision_id: baseRevision,
+    }).expect(400, {
+      code: 'UpdateStale',
+      message: 'update_key did not match',
+      status: 400,
+    })
+  })
+
+  it('when base revision is unknown', async () => {
+    await update({ pages: makePages('A2', 'B'), base_revision_id: 99999 }).expect(400, {
+      code: 'UpdateStale',
+      message: 'Base revision is no longer available',
+      status: 400,
+    })
+  })
+})
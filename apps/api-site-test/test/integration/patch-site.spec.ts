@@ -0,0 +1,98 @@
+import {
+  IGetSiteVersionApiResponse,
+  IPatchSiteApiRequest,
+  IPatchSiteApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Patch Site', () => {
+  const testEndpoint = (siteId: string) => `/api/sites/${siteId}/actions/patch`
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let siteId: string
+  let payload: IPatchSiteApiRequest
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  const getLatest = async (): Promise<IGetSiteVersionApiResponse> => {
+    const response = await api
+      .get(`/api/sites/${siteId}/versions/latest`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    return response.body
+  }
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    await resetService.reset()
+    const site = await getLatest()
+    payload = {
+      update_key: site.updated_at.toString(),
+      pages: [{ op: 'replace', path: '/~1home/name', value: 'Index' }],
+    }
+  })
+
+  it('patches site pages and returns a new update key', async () => {
+    const response = await api
+      .post(testEndpoint(siteId))
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(200)
+    const body: IPatchSiteApiResponse = response.body
+    expect(body.update_key).toBeDefined()
+    expect(body.content_updated_at).toBeGreaterThan(0)
+
+    const site = await getLatest()
+    const pages = JSON.parse(JSON.parse(site.pages))
+    expect(pages['/home'].name).toEqual('Index')
+  })
+
+  it('patches site when requester is owner', async () => {
+    await api
+      .post(testEndpoint(siteId))
+      .set('Authorization', ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e'))
+      .send(payload)
+      .expect(200)
+  })
+
+  it('when update_key is stale', async () => {
+    payload.update_key = '2023-11-07T08:25:58.131Z'
+    await api.post(testEndpoint(siteId)).set('Authorization', adminAuth).send(payload).expect(400, {
+      code: 'UpdateStale',
+      message: 'update_key did not match',
+      status: 400,
+    })
+  })
+
+  it('when patch cannot be applied', async () => {
+    payload.pages = [{ op: 'remove', path: '/missing' }]
+    const response = await api
+      .post(testEndpoint(siteId))
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(400)
+    expect(response.body.code).toEqual('InvalidPatch')
+  })
+
+  it('when user is other owner', async () => {
+    await api
+      .post(testEndpoint(siteId))
+      .set('Authorization', ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10'))
+      .send(payload)
+      .expect(403, {
+        code: 'None',
+        message: 'Forbidden',
+        status: 403,
+      })
+  })
+})
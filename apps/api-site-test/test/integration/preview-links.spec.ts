@@ -0,0 +1,150 @@
+import {
+  ICreatePreviewLinkApiRequest,
+  IListPreviewLinksApiResponse,
+  IPreviewLinkViewModel,
+  IUnlockPreviewLinkApiRequest,
+  IUnlockPreviewLinkApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Preview Links', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let payload: ICreatePreviewLinkApiRequest
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    payload = { version_id: 1 }
+    await resetService.reset()
+  })
+
+  const createLink = async (): Promise<IPreviewLinkViewModel> => {
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/preview_links`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(201)
+    return response.body
+  }
+
+  it('creates and lists preview links', async () => {
+    const link = await createLink()
+    expect(link.version_id).toEqual(1)
+    expect(link.has_password).toEqual(false)
+    expect(link.view_count).toEqual(0)
+
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/preview_links`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListPreviewLinksApiResponse = response.body
+    expect(body.length).toEqual(1)
+    expect(body[0].id).toEqual(link.id)
+  })
+
+  it('resolves a preview link', async () => {
+    const link = await createLink()
+
+    const response = await api.get(`${testEndpoint}/${siteId}/head?p=${link.id}`).expect(200)
+    expect(response.body.title).toEqual('Test Site 2')
+  })
+
+  it('requires the password of a protected link', async () => {
+    payload.password = 'preview-password'
+    const link = await createLink()
+    expect(link.has_password).toEqual(true)
+
+    await api.get(`${testEndpoint}/${siteId}/head?p=${link.id}`).expect(401, {
+      code: 'PreviewPasswordRequired',
+      message: 'Unauthorized',
+      status: 401,
+    })
+    await api
+      .post(`${testEndpoint}/${siteId}/preview_links/${link.id}/unlock`)
+      .send({ password: 'wrong' })
+      .expect(401, {
+        code: 'InvalidAuth',
+        message: 'Invalid preview password',
+        status: 401,
+      })
+    const unlock = await api
+      .post(`${testEndpoint}/${siteId}/preview_links/${link.id}/unlock`)
+      .send({ password: 'preview-password' } as IUnlockPreviewLinkApiRequest)
+      .expect(200)
+    const body: IUnlockPreviewLinkApiResponse = unlock.body
+    expect(unlock.headers['set-cookie'][0]).toContain(`ps_preview=${body.token}`)
+
+    await api
+      .get(`${testEndpoint}/${siteId}/head?p=${link.id}`)
+      .set('X-Preview-Token', body.token)
+      .expect(200)
+    await api
+      .get(`${testEndpoint}/${siteId}/head?p=${link.id}`)
+      .set('Cookie', `ps_preview=${body.token}`)
+      .expect(200)
+    // Tokens only unlock the link they were issued for
+    const other = await createLink()
+    await api
+      .get(`${testEndpoint}/${siteId}/head?p=${other.id}`)
+      .set('X-Preview-Token', body.token)
+      .expect(401)
+  })
+
+  it('revokes a preview link', async () => {
+    const link = await createLink()
+
+    await api
+      .delete(`${testEndpoint}/${siteId}/preview_links/${link.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+
+    await api.get(`${testEndpoint}/${siteId}/head?p=${link.id}`).expect(404)
+    await api
+      .delete(`${testEndpoint}/${siteId}/preview_links/${link.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(404)
+  })
+
+  it('when expires_at is in the past', async () => {
+    payload.expires_at = new Date(Date.now() - 60 * 1000).toISOString()
+    await api
+      .post(`${testEndpoint}/${siteId}/preview_links`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(400)
+  })
+
+  it('when version does not exist', async () => {
+    payload.version_id = 99
+    await api
+      .post(`${testEndpoint}/${siteId}/preview_links`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(404)
+  })
+
+  it('when requester is not the site owner', async () => {
+    ownerAuth = ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10')
+    await api
+      .post(`${testEndpoint}/${siteId}/preview_links`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+})
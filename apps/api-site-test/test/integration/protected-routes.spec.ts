@@ -0,0 +1,198 @@
+import {
+  ICreateProtectedRouteApiRequest,
+  IListProtectedRoutesApiResponse,
+  IListStaticBuildsApiResponse,
+  IProtectedRouteViewModel,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Protected routes', () => {
+  const testEndpoint = '/api/sites'
+  const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+  const host = 'test3.localhost'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+  })
+
+  const createProtectedRoute = async (
+    payload: ICreateProtectedRouteApiRequest,
+  ): Promise<IProtectedRouteViewModel> => {
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/protected_routes`)
+      .send(payload)
+      .set('Authorization', adminAuth)
+      .expect(201)
+    return response.body
+  }
+
+  // Returns the access cookie set by a successful login
+  const login = async (password: string, redirect: string): Promise<string> => {
+    const response = await api
+      .post('/_ps/login')
+      .set('Host', host)
+      .type('form')
+      .send({ password, redirect })
+      .expect(303)
+      .expect('Location', redirect)
+    const cookie: string = response.headers['set-cookie'][0]
+    expect(cookie).toContain('HttpOnly')
+    return cookie.split(';')[0]
+  }
+
+  it('creates and lists protected routes', async () => {
+    const route = await createProtectedRoute({ route: '/about/', password: 'secret' })
+    expect(route.route).toEqual('/about')
+
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/protected_routes`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListProtectedRoutesApiResponse = response.body
+    expect(body).toEqual([route])
+    expect(JSON.stringify(body)).not.toContain('password')
+  })
+
+  it('returns 400 for a duplicate route', async () => {
+    await createProtectedRoute({ route: '/about', password: 'secret' })
+    await api
+      .post(`${testEndpoint}/${siteId}/protected_routes`)
+      .send({ route: '/about', password: 'other' })
+      .set('Authorization', adminAuth)
+      .expect(400, {
+        code: 'ProtectedRouteExists',
+        message: 'This route is already protected',
+        status: 400,
+      })
+  })
+
+  it('shows a login page until the visitor logs in', async () => {
+    await createProtectedRoute({ route: '/about', password: 'secret' })
+
+    let response = await api.get('/about/team').set('Host', host).expect(401)
+    expect(response.headers['cache-control']).toEqual('no-store')
+    expect(response.text).toContain('action="/_ps/login"')
+    expect(response.text).toContain('value="/about/team"')
+
+    response = await api
+      .post('/_ps/login')
+      .set('Host', host)
+      .type('form')
+      .send({ password: 'wrong', redirect: '/about/team' })
+      .expect(401)
+    expect(response.text).toContain('Incorrect password')
+
+    const cookie = await login('secret', '/about/team')
+    response = await api.get('/about/team').set('Host', host).set('Cookie', cookie).expect(200)
+    expect(response.headers['cache-control']).toEqual('private, no-cache')
+
+    // Other pages stay public
+    await api.get('/contact').set('Host', host).expect(200)
+  })
+
+  it('locks the site API when the home page is protected', async () => {
+    await createProtectedRoute({ route: '/home', password: 'secret' })
+
+    await api.get('/api/sites/current').set('Host', host).expect(401, {
+      code: 'SitePasswordRequired',
+      message: 'This page is password protected',
+      status: 401,
+    })
+    await api.get(`${testEndpoint}/${siteId}/head`).expect(401)
+
+    const cookie = await login('secret', '/')
+    const response = await api
+      .get('/api/sites/current')
+      .set('Host', host)
+      .set('Cookie', cookie)
+      .expect(200)
+    expect(response.body.pages).toContain('/home')
+  })
+
+  it('signs out visitors when the password changes', async () => {
+    const route = await createProtectedRoute({ route: '/about', password: 'secret' })
+    const cookie = await login('secret', '/about')
+    await api.get('/about').set('Host', host).set('Cookie', cookie).expect(200)
+
+    await api
+      .patch(`${testEndpoint}/${siteId}/protected_routes/${route.id}`)
+      .send({ password: 'changed' })
+      .set('Authorization', adminAuth)
+      .expect(200)
+    await api.get('/about').set('Host', host).set('Cookie', cookie).expect(401)
+
+    await api
+      .delete(`${testEndpoint}/${siteId}/protected_routes/${route.id}`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    await api.get('/about').set('Host', host).expect(200)
+  })
+
+  // Needs site-api running with an SSG service
+  it('keeps protected pages out of public static pages', async (ctx) => {
+    const current = await api.get('/api/sites/current').set('Host', host).expect(200)
+    const pages = JSON.parse(current.body.pages)
+    const members = JSON.stringify(pages['/home'])
+      .replace(/"\/home"/g, '"/members"')
+      .replace('My Site', 'Members only')
+    pages['/members'] = JSON.parse(members)
+    await api
+      .patch(`${testEndpoint}/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send({
+        pages: JSON.stringify(pages),
+        pageOrder: JSON.stringify(['/home', '/members']),
+      })
+      .expect(200)
+    await createProtectedRoute({ route: '/members', password: 'secret' })
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/publish`)
+      .set('Authorization', adminAuth)
+      .send({ publish: true })
+      .expect(204)
+
+    let builds: IListStaticBuildsApiResponse = []
+    for (let i = 0; i < 50; i += 1) {
+      const response = await api
+        .get(`${testEndpoint}/${siteId}/static_builds`)
+        .set('Authorization', adminAuth)
+        .expect(200)
+      builds = response.body
+      if (builds[0] && builds[0].status !== 'Running') {
+        break
+      }
+      await new Promise((resolve) => setTimeout(resolve, 200))
+    }
+    if (builds.length === 0) {
+      ctx.skip()
+    }
+    expect(builds[0].status).toEqual('Succeeded')
+
+    const home = await api
+      .get(`${testEndpoint}/${siteId}/static_pages`)
+      .query({ path: '/' })
+      .expect(200)
+    expect(home.body.body).toContain('window.__PUBSTUDIO_SITE__')
+    expect(home.body.body).not.toContain('Members only')
+    expect(home.body.body).not.toContain('/members')
+
+    await api
+      .get(`${testEndpoint}/${siteId}/static_pages`)
+      .query({ path: '/members' })
+      .expect(401)
+  })
+})
@@ -0,0 +1,117 @@
+import {
+  ICreatePublishScheduleApiRequest,
+  IListPublishSchedulesApiResponse,
+  IPublishScheduleViewModel,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Publish Schedules', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let payload: ICreatePublishScheduleApiRequest
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    payload = { publish_at: new Date(Date.now() + 24 * 60 * 60 * 1000).toISOString() }
+    await resetService.reset()
+  })
+
+  const createSchedule = async (): Promise<IPublishScheduleViewModel> => {
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/publish_schedules`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(201)
+    return response.body
+  }
+
+  it('creates and lists schedules', async () => {
+    const schedule = await createSchedule()
+    expect(schedule.status).toEqual('Pending')
+    expect(schedule.site_id).toEqual(siteId)
+    expect(schedule.publish_at).toEqual(payload.publish_at.replace('Z', '000Z'))
+
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/publish_schedules`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListPublishSchedulesApiResponse = response.body
+    expect(body.length).toEqual(1)
+    expect(body[0].id).toEqual(schedule.id)
+  })
+
+  it('cancels a pending schedule', async () => {
+    const schedule = await createSchedule()
+
+    const response = await api
+      .delete(`${testEndpoint}/${siteId}/publish_schedules/${schedule.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    const body: IPublishScheduleViewModel = response.body
+    expect(body.status).toEqual('Cancelled')
+
+    // Cancelling again fails
+    await api
+      .delete(`${testEndpoint}/${siteId}/publish_schedules/${schedule.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(400)
+  })
+
+  it('when publish_at is in the past', async () => {
+    payload.publish_at = new Date(Date.now() - 60 * 1000).toISOString()
+    await api
+      .post(`${testEndpoint}/${siteId}/publish_schedules`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(400)
+  })
+
+  it('when publish_at is invalid', async () => {
+    payload.publish_at = '2030-11-07T08:25:58.131'
+    await api
+      .post(`${testEndpoint}/${siteId}/publish_schedules`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(400, {
+        code: 'InvalidFormData',
+        message: 'Failed to validate request',
+        status: 400,
+      })
+  })
+
+  it('when user is other owner', async () => {
+    await api
+      .post(`${testEndpoint}/${siteId}/publish_schedules`)
+      .send(payload)
+      .set('Authorization', ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10'))
+      .expect(403, {
+        code: 'None',
+        message: 'Forbidden',
+        status: 403,
+      })
+  })
+
+  it('when requester is anonymous', async () => {
+    await api.get(`${testEndpoint}/${siteId}/publish_schedules`).expect(401, {
+      code: 'Unauthorized',
+      message: 'Unauthorized',
+      status: 401,
+    })
+  })
+})
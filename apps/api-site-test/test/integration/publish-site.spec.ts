@@ -1,5 +1,6 @@
 import {
   IGetSiteVersionApiResponse,
+  IListSiteVersionsApiResponse,
   IPublishSiteApiRequest,
 } from '@pubstudio/shared/type-api-site-sites'
 import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
@@ -67,6 +68,25 @@ describe('Publish Site', () => {
       await verifyPublished(siteId, true)
     })
 
+    it('publishes site with a message and tag', async () => {
+      payload = { publish: true, message: 'Launch day', tag: 'v1.0' }
+
+      await api
+        .post(`${testEndpoint}/${siteId}/actions/publish`)
+        .send(payload)
+        .set('Authorization', ownerAuth)
+        .expect(204)
+
+      const response = await api
+        .get(`${testEndpoint}/${siteId}/versions`)
+        .set('Authorization', ownerAuth)
+        .expect(200)
+      const body: IListSiteVersionsApiResponse = response.body
+      const published = body.find((version) => version.published)
+      expect(published?.message).toEqual('Launch day')
+      expect(published?.tag).toEqual('v1.0')
+    })
+
     it('unpublishes site when requester is owner', async () => {
       payload.publish = false
 
@@ -0,0 +1,129 @@
+import {
+  ICreateRedirectApiRequest,
+  IListRedirectsApiResponse,
+  IRedirectViewModel,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Redirects', () => {
+  const testEndpoint = '/api/sites'
+  const host = 'www.myblog.org'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    await resetService.reset()
+  })
+
+  const createRedirect = async (
+    payload: ICreateRedirectApiRequest,
+  ): Promise<IRedirectViewModel> => {
+    const response = await api
+      .post(`${testEndpoint}/${siteId}/redirects`)
+      .send(payload)
+      .set('Authorization', ownerAuth)
+      .expect(201)
+    return response.body
+  }
+
+  it('creates and lists redirects', async () => {
+    const redirect = await createRedirect({ source: '/old/', target: '/new' })
+    expect(redirect.source).toEqual('/old')
+    expect(redirect.status_code).toEqual(301)
+    expect(redirect.enabled).toEqual(true)
+    expect(redirect.hit_count).toEqual(0)
+
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/redirects`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const body: IListRedirectsApiResponse = response.body
+    expect(body.length).toEqual(1)
+    expect(body[0].id).toEqual(redirect.id)
+  })
+
+  it('redirects served paths and records hits', async () => {
+    await createRedirect({ source: '/old', target: '/new' })
+    await createRedirect({ source: '/posts/:slug', target: '/blog/:slug', status_code: 302 })
+    await createRedirect({ source: '/docs/*', target: 'https://docs.example.com/*' })
+
+    let response = await api.get('/old/?ref=1').set('Host', host).expect(301)
+    expect(response.headers['location']).toEqual('/new?ref=1')
+    response = await api.get('/posts/hello').set('Host', host).expect(302)
+    expect(response.headers['location']).toEqual('/blog/hello')
+    response = await api.get('/docs/a/b').set('Host', host).expect(301)
+    expect(response.headers['location']).toEqual('https://docs.example.com/a/b')
+
+    const list = await api
+      .get(`${testEndpoint}/${siteId}/redirects`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    const body: IListRedirectsApiResponse = list.body
+    expect(body.map((r) => r.hit_count)).toEqual([1, 1, 1])
+  })
+
+  it('updates and deletes a redirect', async () => {
+    const redirect = await createRedirect({ source: '/old', target: '/new' })
+
+    const response = await api
+      .patch(`${testEndpoint}/${siteId}/redirects/${redirect.id}`)
+      .send({ enabled: false })
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    expect(response.body.enabled).toEqual(false)
+    await api.get('/old').set('Host', host).expect(200)
+
+    await api
+      .delete(`${testEndpoint}/${siteId}/redirects/${redirect.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    await api
+      .delete(`${testEndpoint}/${siteId}/redirects/${redirect.id}`)
+      .set('Authorization', ownerAuth)
+      .expect(404)
+  })
+
+  it('when the redirect is invalid', async () => {
+    await createRedirect({ source: '/old', target: '/new' })
+    await api
+      .post(`${testEndpoint}/${siteId}/redirects`)
+      .send({ source: '/old/', target: '/other' })
+      .set('Authorization', ownerAuth)
+      .expect(400)
+      .expect((res) => expect(res.body.code).toEqual('RedirectSourceExists'))
+    await api
+      .post(`${testEndpoint}/${siteId}/redirects`)
+      .send({ source: '/a/:id', target: '/b/:slug' })
+      .set('Authorization', ownerAuth)
+      .expect(400)
+    await api
+      .post(`${testEndpoint}/${siteId}/redirects`)
+      .send({ source: '/a', target: '/b', status_code: 200 })
+      .set('Authorization', ownerAuth)
+      .expect(400)
+  })
+
+  it('when user is not the site owner', async () => {
+    ownerAuth = ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10')
+    await api
+      .get(`${testEndpoint}/${siteId}/redirects`)
+      .set('Authorization', ownerAuth)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+})
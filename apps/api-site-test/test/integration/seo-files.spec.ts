@@ -0,0 +1,60 @@
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Sitemap and robots.txt', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    await resetService.reset()
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/publish`)
+      .set('Authorization', ownerAuth)
+      .send({ publish: true })
+      .expect(204)
+  })
+
+  it('serves robots.txt for a published site', async () => {
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/static_pages`)
+      .query({ path: '/robots.txt' })
+      .expect(200)
+    expect(response.body.content_type).toEqual('text/plain')
+    expect(response.body.body).toEqual('User-agent: *\nAllow: /\n')
+  })
+
+  it('when the site has no verified domain for the sitemap', async () => {
+    await api
+      .get(`${testEndpoint}/${siteId}/static_pages`)
+      .query({ path: '/sitemap.xml' })
+      .expect(404)
+  })
+
+  it('when the site is unpublished', async () => {
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/publish`)
+      .set('Authorization', ownerAuth)
+      .send({ publish: false })
+      .expect(204)
+    await api
+      .get(`${testEndpoint}/${siteId}/static_pages`)
+      .query({ path: '/robots.txt' })
+      .expect(404)
+  })
+})
@@ -86,4 +86,87 @@ describe('Serve site', () => {
     expect(body.length).toBeGreaterThan(10000)
     expect(body.includes('<title>Test Site 2</title>')).toBe(false)
   })
+
+  it('answers conditional requests with 304', async () => {
+    const r1 = await api.get('').set('Host', 'test3.localhost').expect(200)
+    const etag = r1.headers['etag']
+    expect(etag).toMatch(/^"[0-9a-f]+"$/)
+    expect(r1.headers['cache-control']).toEqual('no-cache')
+
+    const r2 = await api
+      .get('')
+      .set('Host', 'test3.localhost')
+      .set('If-None-Match', etag)
+      .expect(304)
+    expect(r2.headers['etag']).toEqual(etag)
+    expect(r2.text).toBeFalsy()
+
+    await api
+      .get('')
+      .set('Host', 'test3.localhost')
+      .set('If-None-Match', '"stale"')
+      .expect(200)
+  })
+
+  it('uses the Cache-Control configured in site defaults', async () => {
+    const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+    const r1 = await api.get('').set('Host', 'test3.localhost').expect(200)
+    const payload: IUpdateSiteApiRequest = {
+      defaults: `{"homePage":"/test","head":{"title":"Cached"},"cacheControl":"public, max-age=300"}`,
+    }
+    await api
+      .patch(`/api/sites/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(200)
+
+    // Updated content changes the ETag
+    const r2 = await api
+      .get('')
+      .set('Host', 'test3.localhost')
+      .set('If-None-Match', r1.headers['etag'])
+      .expect(200)
+    expect(r2.headers['cache-control']).toEqual('public, max-age=300')
+  })
+
+  it('renders the head of the served page', async () => {
+    const siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+    const pages = {
+      '/test': { name: 'Home', route: '/test', public: true, head: {} },
+      '/about': {
+        name: 'About',
+        route: '/about',
+        public: true,
+        head: {
+          title: 'About <Us> & "More"',
+          description: 'About page',
+          meta: [{ property: 'og:image', content: '/about.png' }],
+          link: [{ rel: 'alternate', hreflang: 'fr', href: '/fr/about' }],
+        },
+      },
+    }
+    const payload: IUpdateSiteApiRequest = { pages: JSON.stringify(pages) }
+    await api
+      .patch(`/api/sites/${siteId}`)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(200)
+
+    const response = await api.get('/about/').set('Host', 'test3.localhost').expect(200)
+    const body = response.text
+
+    expect(body.includes('<title>About &lt;Us&gt; &amp; &quot;More&quot;</title>')).toBe(true)
+    expect(body.includes('<meta name="description" content="About page" />')).toBe(true)
+    expect(body.includes('<meta property="og:image" content="/about.png" />')).toBe(true)
+    expect(body.includes('<link rel="alternate" hreflang="fr" href="/fr/about" />')).toBe(
+      true,
+    )
+    expect(body.includes('<script type="application/ld+json">')).toBe(true)
+    // The site has no verified domain to build a canonical URL from
+    expect(body.includes('rel="canonical"')).toBe(false)
+
+    // Other routes keep the site head
+    const home = await api.get('').set('Host', 'test3.localhost').expect(200)
+    expect(home.text.includes('<title>Test Site 3</title>')).toBe(true)
+  })
 })
@@ -0,0 +1,111 @@
+import {
+  IRecordPageViewApiRequest,
+  ISiteAnalyticsViewModel,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+const IPHONE_UA = 'Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) Mobile/15E148'
+const WINDOWS_UA = 'Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0'
+
+describe('Site Analytics', () => {
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let ownerAuth: string
+  let siteId: string
+  let analyticsEndpoint: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    const adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+    siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    analyticsEndpoint = `/api/sites/${siteId}/analytics`
+  })
+
+  const recordView = async (view: IRecordPageViewApiRequest, userAgent: string) => {
+    await api
+      .post(`/api/sites/${siteId}/usage/actions/page_view`)
+      .set('Host', 'test3.localhost')
+      .set('User-Agent', userAgent)
+      .send(view)
+      .expect(200)
+  }
+
+  const recordViews = async () => {
+    const campaign = {
+      referrer: 'https://news.example.org/post/1',
+      utm_source: 'newsletter',
+      utm_medium: 'email',
+      utm_campaign: 'launch',
+    }
+    await recordView({ route: '/home', ...campaign }, IPHONE_UA)
+    await recordView({ route: '/home', ...campaign }, IPHONE_UA)
+    await recordView({ route: '/home' }, WINDOWS_UA)
+    // Navigation within the site is not counted as a referrer
+    await recordView({ route: '/home', referrer: 'http://test3.localhost/' }, WINDOWS_UA)
+    await api.get('/api/actions/persist-usage').expect(200)
+  }
+
+  it('returns hourly views and breakdowns', async () => {
+    await recordViews()
+
+    const response = await api
+      .get(analyticsEndpoint)
+      .set('Authorization', ownerAuth)
+      .query({ group_by: 'referrer' })
+      .expect(200)
+    const body: ISiteAnalyticsViewModel = response.body
+    expect(body.total_views).toEqual(4)
+    // Hours without views are included
+    const hourViews = body.hours.map((hour) => hour.views)
+    expect(hourViews.length).toBeGreaterThanOrEqual(24)
+    expect(hourViews.reduce((sum, views) => sum + views, 0)).toEqual(4)
+    // Direct visits sort first among equal views
+    expect(body.breakdown).toEqual([
+      { value: null, views: 2 },
+      { value: 'news.example.org', views: 2 },
+    ])
+
+    const devices = await api
+      .get(analyticsEndpoint)
+      .set('Authorization', ownerAuth)
+      .query({ group_by: 'device', utm_campaign: 'launch' })
+      .expect(200)
+    expect(devices.body.total_views).toEqual(2)
+    expect(devices.body.breakdown).toEqual([{ value: 'Mobile', views: 2 }])
+  })
+
+  it('filters direct visits with an empty referrer', async () => {
+    await recordViews()
+
+    const response = await api
+      .get(analyticsEndpoint)
+      .set('Authorization', ownerAuth)
+      .query({ referrer: '', group_by: 'device' })
+      .expect(200)
+    expect(response.body.total_views).toEqual(2)
+    expect(response.body.breakdown).toEqual([{ value: 'Desktop', views: 2 }])
+  })
+
+  it('returns 400 for an invalid date range', async () => {
+    await api
+      .get(analyticsEndpoint)
+      .set('Authorization', ownerAuth)
+      .query({ start: '2026-01-02T00:00:00Z', end: '2026-01-01T00:00:00Z' })
+      .expect(400)
+  })
+
+  it('returns 401 without authorization', async () => {
+    await api.get(analyticsEndpoint).expect(401)
+  })
+})
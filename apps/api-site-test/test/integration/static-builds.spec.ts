@@ -0,0 +1,68 @@
+import {
+  IListStaticBuildsApiResponse,
+  IPublishSiteApiRequest,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+// The test server runs without an SSG service, so no builds are recorded and blocker
+// checks always pass
+describe('Static Builds', () => {
+  const testEndpoint = '/api/sites'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let ownerAuth: string
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
+    ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    await resetService.reset()
+  })
+
+  it('lists static builds', async () => {
+    const response = await api
+      .get(`${testEndpoint}/${siteId}/static_builds`)
+      .query({ limit: 5 })
+      .set('Authorization', ownerAuth)
+      .expect(200)
+    const body: IListStaticBuildsApiResponse = response.body
+    expect(body).toEqual([])
+  })
+
+  it('when limit is out of range', async () => {
+    await api
+      .get(`${testEndpoint}/${siteId}/static_builds`)
+      .query({ limit: 0 })
+      .set('Authorization', ownerAuth)
+      .expect(400)
+  })
+
+  it('when requester is not the site owner', async () => {
+    ownerAuth = ownerAuthHeader('0c069253-e45d-487c-b7c0-cbe467c33a10')
+    await api
+      .get(`${testEndpoint}/${siteId}/static_builds`)
+      .set('Authorization', ownerAuth)
+      .expect(403, { code: 'None', message: 'Forbidden', status: 403 })
+  })
+
+  it('publishes when refusing blockers and none are reported', async () => {
+    const payload: IPublishSiteApiRequest = { publish: true, ssg_blockers: 'Refuse' }
+    await api
+      .post(`${testEndpoint}/${siteId}/actions/publish`)
+      .set('Authorization', ownerAuth)
+      .send(payload)
+      .expect(204)
+  })
+})
@@ -0,0 +1,133 @@
+import {
+  ICreateSiteApiResponse,
+  ICreateSiteFromTemplateApiRequest,
+  ICreateTemplateApiRequest,
+  ICreateTemplateApiResponse,
+  IListSiteVersionsApiResponse,
+  IListTemplatesApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader, ownerAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+describe('Site Templates', () => {
+  const testEndpoint = '/api/templates'
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let payload: ICreateTemplateApiRequest
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    payload = {
+      site_id: '6d2c8359-6094-402c-bcbb-37202fd7c336',
+      name: 'Portfolio',
+      description: 'A simple portfolio',
+      category: 'personal',
+      preview: { content_type: 'image/png', size: 1000 },
+      include_custom_tables: true,
+    }
+    await resetService.reset()
+  })
+
+  const createTemplate = async (): Promise<ICreateTemplateApiResponse> => {
+    const response = await api
+      .post(testEndpoint)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(201)
+    return response.body
+  }
+
+  it('creates and lists templates', async () => {
+    const template = await createTemplate()
+    expect(template.name).toEqual('Portfolio')
+    expect(template.preview_key).toEqual(template.id)
+    expect(template.preview_upload_url).toContain('template-previews')
+
+    const response = await api.get(testEndpoint).query({ category: 'personal' }).expect(200)
+    const body: IListTemplatesApiResponse = response.body
+    expect(body.length).toEqual(1)
+    expect(body[0].id).toEqual(template.id)
+
+    const empty = await api.get(testEndpoint).query({ category: 'business' }).expect(200)
+    expect(empty.body).toEqual([])
+  })
+
+  it('when template name exists', async () => {
+    await createTemplate()
+    await api
+      .post(testEndpoint)
+      .set('Authorization', adminAuth)
+      .send(payload)
+      .expect(400, {
+        code: 'TemplateNameExists',
+        message: 'Template name already exists',
+        status: 400,
+      })
+  })
+
+  it('when requester is not admin', async () => {
+    const ownerAuth = ownerAuthHeader('903b3c28-deaa-45dc-a43f-511fe965d34e')
+    await api.post(testEndpoint).set('Authorization', ownerAuth).send(payload).expect(403)
+  })
+
+  it('creates a site from a template', async () => {
+    const template = await createTemplate()
+    const sitePayload: ICreateSiteFromTemplateApiRequest = {
+      id: '11111111-2222-4333-8444-555555555555',
+      owner_id: '903b3c28-deaa-45dc-a43f-511fe965d34e',
+      owner_email: 'test1@samatech.tw',
+      name: 'From Template',
+      template_id: template.id,
+      published: false,
+      domains: [],
+      site_type: 'Paid2',
+    }
+    const response = await api
+      .post('/api/sites')
+      .set('Authorization', adminAuth)
+      .send(sitePayload)
+      .expect(201)
+    const body: ICreateSiteApiResponse = response.body
+
+    const versions = await api
+      .get(`/api/sites/${body.id}/versions`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    const versionsBody: IListSiteVersionsApiResponse = versions.body
+    expect(versionsBody.length).toEqual(1)
+    expect(versionsBody[0].name).toEqual('From Template')
+  })
+
+  it('when creating a site from a missing template', async () => {
+    await api
+      .post('/api/sites')
+      .set('Authorization', adminAuth)
+      .send({
+        id: '11111111-2222-4333-8444-555555555555',
+        owner_id: '903b3c28-deaa-45dc-a43f-511fe965d34e',
+        owner_email: 'test1@samatech.tw',
+        name: 'From Template',
+        template_id: '11111111-2222-4333-8444-555555555559',
+        published: false,
+        domains: [],
+        site_type: 'Paid2',
+      })
+      .expect(404, { code: 'None', message: 'Template not found', status: 404 })
+  })
+
+  it('deletes a template', async () => {
+    const template = await createTemplate()
+    await api.delete(`${testEndpoint}/${template.id}`).set('Authorization', adminAuth).expect(200)
+    await api.delete(`${testEndpoint}/${template.id}`).set('Authorization', adminAuth).expect(404)
+  })
+})
@@ -0,0 +1,80 @@
+import {
+  IGetPublicSiteUsageApiResponse,
+  IGetSiteUsageApiResponse,
+} from '@pubstudio/shared/type-api-site-sites'
+import { SiteApiResetService } from '@pubstudio/shared/util-test-reset'
+import supertest from 'supertest'
+import TestAgent from 'supertest/lib/agent'
+import { adminAuthHeader } from '../helpers/auth-helpers'
+import { SITE_SEEDS } from '../mocks/site-seeds'
+import { testConfig } from '../test.config'
+
+const WINDOWS_UA = 'Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0'
+const IPHONE_UA = 'Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) Mobile/15E148'
+
+describe('Unique Visitors', () => {
+  let api: TestAgent
+  let resetService: SiteApiResetService
+  let adminAuth: string
+  let siteId: string
+
+  beforeAll(() => {
+    api = supertest(testConfig.get('apiUrl'))
+    adminAuth = adminAuthHeader()
+    resetService = new SiteApiResetService('http://127.0.0.1:3100', adminAuth, SITE_SEEDS)
+  })
+
+  beforeEach(async () => {
+    await resetService.reset()
+    siteId = '870aafc9-36e9-476a-b38c-c1aaaad9d9fe'
+  })
+
+  const visit = async (ip: string, userAgent: string) => {
+    await api
+      .get('/api/sites/current')
+      .set('Host', 'test3.localhost')
+      .set('X-Forwarded-For', ip)
+      .set('User-Agent', userAgent)
+      .expect(200)
+  }
+
+  const getUsage = async () => {
+    const res = await api
+      .get(`/api/sites/${siteId}/usage`)
+      .set('Authorization', adminAuth)
+      .expect(200)
+    return res.body as IGetSiteUsageApiResponse
+  }
+
+  it('counts repeat visits once', async () => {
+    const body1 = await getUsage()
+    expect(body1.daily_unique_visitors).toEqual(0)
+    expect(body1.monthly_visitor_days).toEqual(0)
+
+    await visit('203.0.113.1', WINDOWS_UA)
+    await visit('203.0.113.1', WINDOWS_UA)
+    await visit('203.0.113.1', IPHONE_UA)
+    await visit('203.0.113.2', WINDOWS_UA)
+    // Bots are not visitors
+    await visit('203.0.113.3', 'Mozilla/5.0 (compatible; Googlebot/2.1)')
+
+    const body2 = await getUsage()
+    expect(body2.site_view_count).toEqual(5)
+    expect(body2.daily_unique_visitors).toEqual(3)
+    expect(body2.monthly_visitor_days).toEqual(3)
+  })
+
+  it('merges persisted visitors with new visits', async () => {
+    await visit('203.0.113.1', WINDOWS_UA)
+    await visit('203.0.113.2', WINDOWS_UA)
+    await api.get('/api/actions/persist-usage').expect(200)
+
+    await visit('203.0.113.2', WINDOWS_UA)
+    await visit('203.0.113.4', WINDOWS_UA)
+
+    const res = await api.get(`/api/sites/${siteId}/public_usage`).expect(200)
+    const body: IGetPublicSiteUsageApiResponse = res.body
+    expect(body.daily_unique_visitors).toEqual(3)
+    expect(body.monthly_visitor_days).toEqual(3)
+  })
+})
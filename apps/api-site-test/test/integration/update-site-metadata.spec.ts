@@ -34,8 +34,8 @@ describe('Update Site Metadata', () => {
     await resetService.reset()
     siteId = '6d2c8359-6094-402c-bcbb-37202fd7c336'
     domains = [
-      { domain: 'update-subdomain.dev.pubstud.io', verified: true },
-      { domain: 'shop.abc.com', verified: false },
+      { domain: 'update-subdomain.dev.pubstud.io', verified: true, primary: false },
+      { domain: 'shop.abc.com', verified: false, primary: false },
     ]
     payload = { domains }
   })
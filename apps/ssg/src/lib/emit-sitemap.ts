@@ -1,4 +1,5 @@
-import { ISite } from '@pubstudio/shared/type-site'
+import { localizeRoute } from '@pubstudio/frontend/feature-render'
+import { ISite, ISiteRobotsRule } from '@pubstudio/shared/type-site'
 
 const escapeXml = (value: string): string => {
   return value
@@ -26,23 +27,65 @@ export const emitSitemap = (
   const lastmodTag = lastmod
     ? `<lastmod>${escapeXml(new Date(lastmod).toISOString())}</lastmod>`
     : ''
+  const url = (route: string, alternates = '') =>
+    `<url><loc>${escapeXml(origin + route)}</loc>${lastmodTag}${alternates}</url>`
+  // Multi-language sites list each route once per locale, with hreflang alternates.
+  // Matches the sitemap served by site-api
+  const locales = site.defaults.locales ?? []
+  if (locales.length === 0) {
+    return [
+      '<?xml version="1.0" encoding="UTF-8"?>',
+      '<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">',
+      routes.map((route) => url(route)).join('\n'),
+      '</urlset>',
+      '',
+    ].join('\n')
+  }
+  const alternateLink = (hreflang: string, route: string) =>
+    `<xhtml:link rel="alternate" hreflang="${escapeXml(hreflang)}" href="${escapeXml(origin + route)}"/>`
   const urls = routes
-    .map((route) => `<url><loc>${escapeXml(origin + route)}</loc>${lastmodTag}</url>`)
+    .flatMap((route) => {
+      const alternates = [
+        ...locales.map((locale) =>
+          alternateLink(locale.code, localizeRoute(locale.code, route)),
+        ),
+        alternateLink('x-default', route),
+      ].join('')
+      return locales.map((locale) => url(localizeRoute(locale.code, route), alternates))
+    })
     .join('\n')
   return [
     '<?xml version="1.0" encoding="UTF-8"?>',
-    '<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">',
+    '<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">',
     urls,
     '</urlset>',
     '',
   ].join('\n')
 }
 
-export const emitRobots = (baseUrl?: string): string => {
-  const lines = ['User-agent: *', 'Allow: /']
+// Robots values are owner provided, and must not start new directives
+const robotsValue = (value: string): string => value.replace(/[\r\n]/g, '').trim()
+
+// Matches the robots.txt served by site-api
+export const emitRobots = (baseUrl?: string, rules?: ISiteRobotsRule[]): string => {
+  const groups = (rules ?? []).map((rule) => {
+    const lines = [`User-agent: ${robotsValue(rule.userAgent)}`]
+    for (const allow of rule.allow ?? []) {
+      lines.push(`Allow: ${robotsValue(allow)}`)
+    }
+    for (const disallow of rule.disallow ?? []) {
+      lines.push(`Disallow: ${robotsValue(disallow)}`)
+    }
+    if (rule.crawlDelay !== undefined && rule.crawlDelay !== null) {
+      lines.push(`Crawl-delay: ${rule.crawlDelay}`)
+    }
+    return lines.join('\n')
+  })
+  if (groups.length === 0) {
+    groups.push('User-agent: *\nAllow: /')
+  }
   if (baseUrl) {
-    lines.push(`Sitemap: ${baseUrl.replace(/\/+$/, '')}/sitemap.xml`)
+    groups.push(`Sitemap: ${baseUrl.replace(/\/+$/, '')}/sitemap.xml`)
   }
-  lines.push('')
-  return lines.join('\n')
+  return `${groups.join('\n\n')}\n`
 }
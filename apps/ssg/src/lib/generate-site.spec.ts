@@ -1,6 +1,7 @@
 import { mockSerializedSite } from '@pubstudio/frontend/util-test-mock'
 import { deserializedHelper } from '@pubstudio/frontend/util-site-deserialize'
 import { ComponentArgPrimitive, ISerializedSite, Tag } from '@pubstudio/shared/type-site'
+import { emitRobots } from './emit-sitemap'
 import { generateSite, renderPageBody } from './generate-site'
 import { normalizeSiteInput, parseJsonField } from './normalize-input'
 import { ISsgSiteInput } from './ssg-types'
@@ -62,6 +63,45 @@ describe('generateSite', () => {
     expect(result.warnings.join(' ')).toContain('Home page /home is missing')
   })
 
+  it('keeps protected pages out of the payload of other pages', async () => {
+    const input = makeInput((site) => {
+      const members = JSON.parse(JSON.stringify(site.pages['/home']))
+      members.name = 'members'
+      members.route = '/members'
+      members.root.children[0].content = 'Members only'
+      site.pages['/members'] = members
+      site.pageOrder = ['/home', '/members']
+    })
+    const pages = JSON.parse(input.pages as string)
+    input.protectedPages = JSON.stringify({ '/members': pages['/members'] })
+    delete pages['/members']
+    input.pages = JSON.stringify(pages)
+
+    const result = await generateSite(input, { baseUrl: 'https://mock.example.com' })
+    const home = result.pages.find((p) => p.route === '/')
+    expect(home?.body).not.toContain('Members only')
+    expect(home?.body).not.toContain('/members')
+
+    // A protected page carries its own data, and the unprotected pages
+    const members = result.pages.find((p) => p.route === '/members')
+    expect(members?.body).toContain('Members only')
+    expect(members?.body).toContain('\\"/home\\"')
+
+    const sitemap = result.pages.find((p) => p.route === '/sitemap.xml')
+    expect(sitemap?.body).not.toContain('/members')
+  })
+
+  it('renders only the requested routes', async () => {
+    const unchanged = await generateSite(makeInput(), { routes: [] })
+    expect(unchanged.pages.map((p) => p.route)).toEqual(['/robots.txt'])
+    expect(unchanged.warnings).toEqual([])
+
+    const changed = await generateSite(makeInput(), { routes: ['/home'] })
+    const routes = changed.pages.map((p) => p.route)
+    expect(routes).toContain('/home')
+    expect(routes).toContain('/')
+  })
+
   it('omits payload and runtime script with noJs', async () => {
     const result = await generateSite(makeInput(), { noJs: true })
     const home = result.pages.find((p) => p.route === '/')
@@ -143,6 +183,43 @@ describe('generateSite', () => {
     expect(overrideHome?.body).not.toContain('content="website"')
   })
 
+  it('renders each page once per locale with hreflang alternates', async () => {
+    const input = makeInput((site) => {
+      site.defaults.locales = [{ code: 'en' }, { code: 'zh-TW', title: '網站' }]
+      site.pages['/home'].head.locales = { 'zh-TW': { title: '首頁' } }
+    })
+    const result = await generateSite(input, { baseUrl: 'https://mock.example.com' })
+    const routes = result.pages.map((p) => p.route)
+    expect(routes).toEqual(
+      expect.arrayContaining(['/en', '/en/home', '/zh-TW', '/zh-TW/home']),
+    )
+
+    const zh = result.pages.find((p) => p.route === '/zh-TW')
+    expect(zh?.body).toContain('<html lang="zh-TW">')
+    expect(zh?.body).toContain('<title>首頁</title>')
+    expect(zh?.body).toContain(
+      '<link rel="canonical" href="https://mock.example.com/zh-TW" />',
+    )
+    expect(zh?.body).toContain(
+      '<link rel="alternate" hreflang="en" href="https://mock.example.com/en" />',
+    )
+    expect(zh?.body).toContain(
+      '<link rel="alternate" hreflang="x-default" href="https://mock.example.com/" />',
+    )
+
+    // Unprefixed routes serve the default locale
+    const home = result.pages.find((p) => p.route === '/')
+    const en = result.pages.find((p) => p.route === '/en')
+    expect(home?.body).toEqual(en?.body)
+    expect(home?.body).toContain('<html lang="en">')
+
+    const sitemap = result.pages.find((p) => p.route === '/sitemap.xml')
+    expect(sitemap?.body).toContain('<loc>https://mock.example.com/zh-TW</loc>')
+    expect(sitemap?.body).toContain(
+      '<xhtml:link rel="alternate" hreflang="x-default" href="https://mock.example.com/"/>',
+    )
+  })
+
   it('prerenders custom Vue components as an empty placeholder to hydrate into', async () => {
     const input = makeInput((site) => {
       const child = site.pages['/home'].root.children?.[0]
@@ -214,3 +291,16 @@ describe('parseJsonField', () => {
     expect(parseJsonField(null)).toBeUndefined()
   })
 })
+
+describe('emitRobots', () => {
+  it('omits an unset crawl delay', () => {
+    const robots = emitRobots('https://mock.example.com/', [
+      { userAgent: 'GPTBot', disallow: ['/'], crawlDelay: null },
+      { userAgent: '*', crawlDelay: 10 },
+    ])
+    expect(robots).toEqual(
+      'User-agent: GPTBot\nDisallow: /\n\nUser-agent: *\nCrawl-delay: 10\n\n' +
+        'Sitemap: https://mock.example.com/sitemap.xml\n',
+    )
+  })
+})
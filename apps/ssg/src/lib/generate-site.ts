@@ -1,7 +1,19 @@
-import { createStaticSiteApp, getHead } from '@pubstudio/frontend/feature-render'
+import {
+  createStaticSiteApp,
+  getHead,
+  IRenderSiteHead,
+  localizeRoute,
+} from '@pubstudio/frontend/feature-render'
 import { setCssValidation } from '@pubstudio/frontend/util-render'
 import { deserializedHelper } from '@pubstudio/frontend/util-site-deserialize'
-import { IPage, ISite, IStaticSitePayload } from '@pubstudio/shared/type-site'
+import {
+  IHeadLink,
+  IPage,
+  ISite,
+  ISiteLocale,
+  IStaticSitePayload,
+} from '@pubstudio/shared/type-site'
+import { filterRecord } from '@pubstudio/shared/util-core'
 import { renderToString } from '@vue/server-renderer'
 import { createSSRApp, shallowRef } from 'vue'
 import { detectNoJsBlockers } from './detect-capabilities'
@@ -36,6 +48,33 @@ export const renderPageBody = async (
   return renderToString(app)
 }
 
+// Head of a page in a locale. Localized page values win, then the locale's site head
+const localizeHead = (
+  head: IRenderSiteHead,
+  page: IPage,
+  locale: ISiteLocale,
+  alternates: IHeadLink[],
+): IRenderSiteHead => {
+  const localized = page.head.locales?.[locale.code]
+  const title = localized?.title || locale.title
+  const description = localized?.description || locale.description
+  const meta = head.meta.map((m) => {
+    const key = m.property ?? m.name
+    if (title && (key === 'og:title' || key === 'twitter:title')) {
+      return { ...m, content: title }
+    } else if (description && (key === 'description' || key === 'og:description')) {
+      return { ...m, content: description }
+    }
+    return m
+  })
+  return {
+    ...head,
+    title: title || head.title,
+    meta,
+    link: [...head.link.filter((l) => !l.hreflang), ...alternates],
+  }
+}
+
 export const generateSite = async (
   input: ISsgSiteInput,
   options: ISsgOptions = {},
@@ -44,7 +83,7 @@ export const generateSite = async (
   // both sides skip CSS.supports validation (unavailable in Node anyway)
   setCssValidation(false)
 
-  const { serialized, publicPages } = normalizeSiteInput(input)
+  const { serialized, publicPages, protectedRoutes } = normalizeSiteInput(input)
   const site = deserializedHelper(serialized)
   const warnings: string[] = []
   const blockers = detectNoJsBlockers(site)
@@ -66,65 +105,135 @@ export const generateSite = async (
   }
 
   // The hydration payload is single-encoded JSON, which must match what the
-  // hydration runtime passes to unstoreSite.
-  const payload: IStaticSitePayload = {
-    id: input.id,
-    name: serialized.name,
-    version: serialized.version,
-    defaults: JSON.stringify(serialized.defaults),
-    context: JSON.stringify(serialized.context),
-    pages: JSON.stringify(serialized.pages),
-    pageOrder: JSON.stringify(serialized.pageOrder),
+  // hydration runtime passes to unstoreSite. Protected pages are left out, a protected
+  // page only adds its own data
+  const pagePayload = (route: string): string | undefined => {
+    if (noJs) {
+      return undefined
+    }
+    const pages = filterRecord(
+      serialized.pages,
+      (_page, pageRoute) => pageRoute === route || !protectedRoutes.includes(pageRoute),
+    )
+    const payload: IStaticSitePayload = {
+      id: input.id,
+      name: serialized.name,
+      version: serialized.version,
+      defaults: JSON.stringify(serialized.defaults),
+      context: JSON.stringify(serialized.context),
+      pages: JSON.stringify(pages),
+      pageOrder: JSON.stringify(serialized.pageOrder.filter((r) => r in pages)),
+    }
+    return JSON.stringify(payload)
   }
-  const payloadJson = noJs ? undefined : JSON.stringify(payload)
   const runtimeSrc = options.runtimeSrc ?? DEFAULT_RUNTIME_SRC
 
   const lang = site.context.activeI18n ?? 'en'
   const origin = options.baseUrl?.replace(/\/$/, '')
   const canonical = (route: string) => (origin ? `${origin}${route}` : undefined)
   const pages: IStaticPage[] = []
+  const shouldRender = (route: string) => !options.routes || options.routes.includes(route)
   let homeGenerated = false
 
+  // Multi-language sites render each page once per locale, under the locale's prefix.
+  // Unprefixed routes serve the default locale
+  const locales = site.defaults.locales ?? []
+  const localeSites = locales.map((locale) => ({
+    ...site,
+    context: { ...site.context, activeI18n: locale.code },
+  }))
+  // hreflang links to every locale of a served route, `x-default` is the unprefixed route
+  const alternates = (route: string): IHeadLink[] => {
+    if (!origin) {
+      return []
+    }
+    return [
+      ...locales.map((locale) => ({
+        rel: 'alternate',
+        hreflang: locale.code,
+        href: `${origin}${localizeRoute(locale.code, route)}`,
+      })),
+      { rel: 'alternate', hreflang: 'x-default', href: `${origin}${route}` },
+    ]
+  }
+
   for (const route of Object.keys(publicPages)) {
+    if (!shouldRender(route)) {
+      continue
+    }
     const page = site.pages[route]
     if (!page) {
       warnings.push(`Page not found after deserialization: ${route}`)
       continue
     }
-    const bodyHtml = await renderPageBody(site, page, (message) =>
-      warnings.push(`Render error on ${page.route}: ${message}`),
-    )
-    const head = getHead(site, page)
     const isHome = page.route === site.defaults.homePage
     // The home page is served at both its route and `/`; `/` is the canonical one
-    const html = buildHtmlPage({
-      lang,
-      head,
-      bodyHtml,
-      canonicalUrl: canonical(isHome ? '/' : page.route),
-      payloadJson,
-      runtimeSrc,
-    })
-    pages.push({ route: page.route, body: html, contentType: 'text/html' })
-    if (isHome) {
-      pages.push({ route: '/', body: html, contentType: 'text/html' })
-      homeGenerated = true
+    const servedRoute = isHome ? '/' : page.route
+    const payloadJson = pagePayload(route)
+    if (locales.length === 0) {
+      const bodyHtml = await renderPageBody(site, page, (message) =>
+        warnings.push(`Render error on ${page.route}: ${message}`),
+      )
+      const html = buildHtmlPage({
+        lang,
+        head: getHead(site, page),
+        bodyHtml,
+        canonicalUrl: canonical(servedRoute),
+        payloadJson,
+        runtimeSrc,
+      })
+      pages.push({ route: page.route, body: html, contentType: 'text/html' })
+      if (isHome) {
+        pages.push({ route: '/', body: html, contentType: 'text/html' })
+      }
+    }
+    for (const [index, locale] of locales.entries()) {
+      const localeSite = localeSites[index]
+      const bodyHtml = await renderPageBody(localeSite, page, (message) =>
+        warnings.push(`Render error on ${page.route} (${locale.code}): ${message}`),
+      )
+      const head = localizeHead(
+        getHead(localeSite, page),
+        page,
+        locale,
+        alternates(servedRoute),
+      )
+      const html = buildHtmlPage({
+        lang: locale.code,
+        head,
+        bodyHtml,
+        canonicalUrl: canonical(localizeRoute(locale.code, servedRoute)),
+        payloadJson,
+        runtimeSrc,
+      })
+      const localized = [localizeRoute(locale.code, page.route)]
+      if (isHome) {
+        localized.push(localizeRoute(locale.code, '/'))
+      }
+      if (index === 0) {
+        localized.push(page.route, ...(isHome ? ['/'] : []))
+      }
+      for (const localizedRoute of localized) {
+        pages.push({ route: localizedRoute, body: html, contentType: 'text/html' })
+      }
     }
+    homeGenerated ||= isHome
   }
-  if (!homeGenerated) {
+  if (!homeGenerated && shouldRender(site.defaults.homePage)) {
     warnings.push(`Home page ${site.defaults.homePage} is missing or not public`)
   }
 
   if (options.baseUrl) {
+    const pageOrder = site.pageOrder.filter((route) => !protectedRoutes.includes(route))
     pages.push({
       route: '/sitemap.xml',
-      body: emitSitemap(site, options.baseUrl, input.updated_at),
+      body: emitSitemap({ ...site, pageOrder }, options.baseUrl, input.updated_at),
       contentType: 'application/xml',
     })
   }
   pages.push({
     route: '/robots.txt',
-    body: emitRobots(options.baseUrl),
+    body: emitRobots(options.baseUrl, site.defaults.robots),
     contentType: 'text/plain',
   })
 
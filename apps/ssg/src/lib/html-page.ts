@@ -87,9 +87,11 @@ export const buildHtmlPage = (options: IHtmlPageOptions): string => {
     // Escape `<` so `</script>` inside the JSON cannot close the tag early
     const safePayload = payloadJson.replace(/</g, '\\u003c')
     // ___SITE_API_URL___ is substituted by platform-api when it serves the page.
-    // site-api serves it raw and the hydration runtime falls back to the same-origin API
+    // site-api serves it raw and the hydration runtime falls back to the same-origin API.
+    // site-api substitutes ___SUBDOMAIN___ on hosts matched by a wildcard domain
     bodyLines.push(
       `<script>window.__PUBSTUDIO_SITE_API__ = '___SITE_API_URL___';` +
+        `window.__PUBSTUDIO_SUBDOMAIN__ = '___SUBDOMAIN___';` +
         `window.__PUBSTUDIO_SITE__ = ${safePayload}</script>`,
     )
     bodyLines.push(`<script defer src="${escapeAttr(runtimeSrc)}"></script>`)
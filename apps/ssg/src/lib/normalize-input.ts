@@ -23,6 +23,8 @@ export const parseJsonField = <T>(value: unknown): T | undefined => {
 export interface INormalizedSiteInput {
   serialized: ISerializedSite
   publicPages: Record<string, ISerializedPage>
+  // Routes of public pages behind a protected route
+  protectedRoutes: string[]
 }
 
 export const normalizeSiteInput = (input: ISsgSiteInput): INormalizedSiteInput => {
@@ -30,10 +32,13 @@ export const normalizeSiteInput = (input: ISsgSiteInput): INormalizedSiteInput =
   const context = parseJsonField<ISerializedSiteContext>(input.context)
   const pages = parseJsonField<Record<string, ISerializedPage>>(input.pages)
   const pageOrder = parseJsonField<string[]>(input.pageOrder)
+  const protectedPages =
+    parseJsonField<Record<string, ISerializedPage>>(input.protectedPages) ?? {}
   if (!defaults || !context || !pages) {
     throw new Error('Site input is missing defaults, context, or pages')
   }
-  const publicPages = filterRecord(pages, (page) => !!page.public)
+  const publicPages = filterRecord({ ...pages, ...protectedPages }, (page) => !!page.public)
+  const protectedRoutes = Object.keys(protectedPages).filter((route) => route in publicPages)
   const serialized: ISerializedSite = {
     name: input.name,
     version: input.version,
@@ -46,5 +51,5 @@ export const normalizeSiteInput = (input: ISsgSiteInput): INormalizedSiteInput =
     // Epoch millis from site API `SsgSiteDto`, or ISO string from a CLI fixture.
     updated_at: input.updated_at ? new Date(input.updated_at).toISOString() : undefined,
   }
-  return { serialized, publicPages }
+  return { serialized, publicPages, protectedRoutes }
 }
@@ -8,6 +8,9 @@ export interface ISsgSiteInput {
   context: unknown
   pages: unknown
   pageOrder?: unknown
+  // Pages behind a protected route, encoded like `pages`. They are rendered, but left out of
+  // the hydration payload of other pages
+  protectedPages?: unknown
   // Timestamp of the published site version (ISO string or epoch millis),
   // echoed back in the result and used as sitemap <lastmod>.
   updated_at?: string | number | null
@@ -22,6 +25,9 @@ export interface ISsgOptions {
   force?: boolean
   // Src of the shared hydration runtime script tag
   runtimeSrc?: string
+  // Only render these page routes, for incremental regeneration. The sitemap and robots.txt
+  // are always emitted. All public pages are rendered when unset
+  routes?: string[]
 }
 
 export interface IStaticPage {
@@ -0,0 +1,25 @@
+import { IRecordPageViewApiRequest } from '@pubstudio/shared/type-api-site-sites'
+
+type PageViewSource = Omit<IRecordPageViewApiRequest, 'route'>
+
+let landingSource: PageViewSource | undefined
+
+// Where the visitor came from, read from the landing page. Views after in-site navigation
+// keep the landing referrer and UTM parameters, so the whole visit is attributed to them
+const getLandingSource = (): PageViewSource => {
+  if (!landingSource) {
+    const params = new URLSearchParams(window.location.search)
+    landingSource = {
+      referrer: document.referrer || undefined,
+      utm_source: params.get('utm_source') ?? undefined,
+      utm_medium: params.get('utm_medium') ?? undefined,
+      utm_campaign: params.get('utm_campaign') ?? undefined,
+    }
+  }
+  return landingSource
+}
+
+export const pageViewData = (route: string): IRecordPageViewApiRequest => ({
+  route,
+  ...getLandingSource(),
+})
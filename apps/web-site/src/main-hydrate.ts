@@ -1,6 +1,7 @@
 import {
   createStaticSiteApp,
   replaceHead,
+  setPathLocale,
   setupRoutes,
   StaticNotFound,
   useNotFoundPage,
@@ -13,6 +14,7 @@ import { loadSiteLanguage, unstoreSite } from '@pubstudio/frontend/util-site-des
 import { ISite, IStaticSitePayload } from '@pubstudio/shared/type-site'
 import { rootSiteApi } from '@pubstudio/shared/util-web-site-api'
 import { computed, createSSRApp, ref, Ref } from 'vue'
+import { pageViewData } from './app/page-view'
 import './window-vue'
 
 // Hydration runtime for SSG pages. The page body is already prerendered, this script reads
@@ -22,6 +24,7 @@ declare global {
   interface Window {
     __PUBSTUDIO_SITE__?: IStaticSitePayload
     __PUBSTUDIO_SITE_API__?: string
+    __PUBSTUDIO_SUBDOMAIN__?: string
   }
 }
 
@@ -95,13 +98,14 @@ const main = async () => {
     if (oldPage && oldPage !== page) {
       replaceHead(site.value, page, oldPage)
     }
+    setPathLocale(site.value, window.location.pathname)
     if (newRoute) {
       const id = rootSiteApi.siteId.value
       rootSiteApi.request({
         url: `${rootSiteApi.baseUrl}api/sites/${id}/usage/actions/page_view`,
         method: 'POST',
         ignoreBaseUrl: true,
-        data: { route: newRoute.path },
+        data: pageViewData(newRoute.path),
       })
     }
   })
@@ -112,10 +116,19 @@ const main = async () => {
   })
   await waitForRoute(router)
 
+  // Localized pages are prerendered in the locale of their path, set it before hydrating
+  const localized = setPathLocale(site.value, window.location.pathname)
   createSSRApp(App).mount('#app')
 
   // Apply the user's stored/browser language after hydration to avoid mismatch.
-  loadSiteLanguage(site.value)
+  if (!localized) {
+    loadSiteLanguage(site.value)
+  }
+  // Pages are prerendered without a subdomain, site-api fills it in on wildcard domains
+  const subdomain = window.__PUBSTUDIO_SUBDOMAIN__
+  if (subdomain && !subdomain.startsWith('___')) {
+    site.value.subdomain = subdomain
+  }
 }
 
 main()
@@ -8,7 +8,10 @@ use uuid::Uuid;
 
 use crate::error::api_error::ApiError;
 
-use super::types::{ConfirmClaims, JwtClaims, UserToken};
+use super::types::{
+    AccessGrant, ConfirmClaims, ExperimentAssignment, ExperimentClaims, JwtClaims, PreviewClaims,
+    SiteAccessClaims, UserToken,
+};
 
 pub fn generate_admin_jwt(private_key: String) -> Result<UserToken, ApiError> {
     generate_jwt(
@@ -76,3 +79,62 @@ pub fn generate_confirm_token(
 
     Ok(token)
 }
+
+pub fn generate_site_access_token(
+    site_id: &str,
+    grants: Vec<AccessGrant>,
+    // TTL in minutes
+    ttl: i64,
+    secret: &str,
+) -> Result<String, ApiError> {
+    let key = &EncodingKey::from_secret(secret.as_ref());
+
+    let claims = SiteAccessClaims {
+        sub: site_id.to_string(),
+        grants,
+        exp: (Utc::now() + Duration::minutes(ttl)).timestamp(),
+    };
+
+    jsonwebtoken::encode(&Header::default(), &claims, key)
+        .map_err(|_| ApiError::internal_error().message("Failed to encode site access token"))
+}
+
+pub fn generate_experiment_token(
+    site_id: &str,
+    assignments: Vec<ExperimentAssignment>,
+    // TTL in minutes
+    ttl: i64,
+    secret: &str,
+) -> Result<String, ApiError> {
+    let key = &EncodingKey::from_secret(secret.as_ref());
+
+    let claims = ExperimentClaims {
+        sub: site_id.to_string(),
+        assignments,
+        exp: (Utc::now() + Duration::minutes(ttl)).timestamp(),
+    };
+
+    jsonwebtoken::encode(&Header::default(), &claims, key)
+        .map_err(|_| ApiError::internal_error().message("Failed to encode experiment token"))
+}
+
+pub fn generate_preview_token(
+    site_id: &str,
+    preview_id: &str,
+    fingerprint: String,
+    // TTL in minutes
+    ttl: i64,
+    secret: &str,
+) -> Result<String, ApiError> {
+    let key = &EncodingKey::from_secret(secret.as_ref());
+
+    let claims = PreviewClaims {
+        sub: site_id.to_string(),
+        p: preview_id.to_string(),
+        fp: fingerprint,
+        exp: (Utc::now() + Duration::minutes(ttl)).timestamp(),
+    };
+
+    jsonwebtoken::encode(&Header::default(), &claims, key)
+        .map_err(|_| ApiError::internal_error().message("Failed to encode preview token"))
+}
@@ -17,6 +17,57 @@ pub struct JwtClaims {
     pub exp: i64,
 }
 
+// A protected route unlocked by a visitor. `fp` fingerprints the password hash, so changing
+// the password invalidates the grant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessGrant {
+    pub id: i64,
+    pub fp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteAccessClaims {
+    // Site ID, a cookie is only valid for the site that issued it
+    pub sub: String,
+    pub grants: Vec<AccessGrant>,
+    pub exp: i64,
+}
+
+// A/B experiment variant assigned to a visitor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExperimentAssignment {
+    // Experiment ID
+    pub e: i64,
+    // Variant ID
+    pub v: i64,
+    // The visitor viewed the experiment route, counted as an exposure
+    #[serde(default)]
+    pub x: bool,
+    // The visitor converted after exposure, later conversions are not counted
+    #[serde(default)]
+    pub c: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExperimentClaims {
+    // Site ID, assignments are only valid for the site that issued them
+    pub sub: String,
+    pub assignments: Vec<ExperimentAssignment>,
+    pub exp: i64,
+}
+
+// A password protected preview link unlocked by a visitor
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewClaims {
+    // Site ID
+    pub sub: String,
+    // Preview link ID
+    pub p: String,
+    // Fingerprint of the link's password hash
+    pub fp: String,
+    pub exp: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfirmClaims {
     pub sub: String,
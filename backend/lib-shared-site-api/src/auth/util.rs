@@ -3,6 +3,8 @@ use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 
 use crate::error::api_error::ApiError;
 
@@ -34,3 +36,12 @@ pub async fn extract_bearer_optional<B>(
         (None, request)
     }
 }
+
+// Signing key for one use of a shared secret, so a token signed for one use is not accepted
+// by another
+pub fn derive_secret(secret: &str, purpose: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(purpose.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
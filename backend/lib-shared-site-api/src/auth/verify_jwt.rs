@@ -7,7 +7,10 @@ use uuid::Uuid;
 
 use crate::error::api_error::ApiError;
 
-use super::types::{ConfirmClaims, JwtClaims, UserToken};
+use super::types::{
+    AccessGrant, ConfirmClaims, ExperimentAssignment, ExperimentClaims, JwtClaims, PreviewClaims,
+    SiteAccessClaims, UserToken,
+};
 
 fn unauthorized() -> ApiError {
     return ApiError::unauthorized().code(ApiErrorCode::InvalidAuth);
@@ -59,3 +62,43 @@ pub fn verify_confirm_token(secret: String, token: &str) -> Result<Uuid, ApiErro
 
     Ok(user_id)
 }
+
+// Routes unlocked by a site access cookie. Expired, tampered or other site tokens grant nothing
+pub fn verify_site_access_token(secret: &str, site_id: &str, token: &str) -> Vec<AccessGrant> {
+    let key = &DecodingKey::from_secret(secret.as_ref());
+    match jsonwebtoken::decode::<SiteAccessClaims>(token, key, &Validation::default()) {
+        Ok(decoded) if decoded.claims.sub == site_id => decoded.claims.grants,
+        _ => vec![],
+    }
+}
+
+// Experiment variants assigned to a visitor. Expired, tampered or other site tokens are
+// ignored, and the visitor is assigned again
+pub fn verify_experiment_token(
+    secret: &str,
+    site_id: &str,
+    token: &str,
+) -> Vec<ExperimentAssignment> {
+    let key = &DecodingKey::from_secret(secret.as_ref());
+    match jsonwebtoken::decode::<ExperimentClaims>(token, key, &Validation::default()) {
+        Ok(decoded) if decoded.claims.sub == site_id => decoded.claims.assignments,
+        _ => vec![],
+    }
+}
+
+// Fingerprint of the password a preview link was unlocked with. Expired, tampered or other
+// link tokens unlock nothing
+pub fn verify_preview_token(
+    secret: &str,
+    site_id: &str,
+    preview_id: &str,
+    token: &str,
+) -> Option<String> {
+    let key = &DecodingKey::from_secret(secret.as_ref());
+    match jsonwebtoken::decode::<PreviewClaims>(token, key, &Validation::default()) {
+        Ok(decoded) if decoded.claims.sub == site_id && decoded.claims.p == preview_id => {
+            Some(decoded.claims.fp)
+        }
+        _ => None,
+    }
+}
@@ -6,20 +6,43 @@ use std::{
 
 use lib_shared_types::{
     cache::site_usage_data::SiteUsageData,
-    entity::site_api::site_usage_entity::SiteUsageEntityWithTotals,
+    entity::site_api::{
+        custom_domain_entity::CustomDomainRelationEntity,
+        site_usage_entity::SiteUsageEntityWithTotals,
+    },
     shared::{core::ExecEnv, js_date::JsDate, site::SiteType},
 };
 use moka::future::Cache;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::error::api_error::ApiError;
+use crate::{
+    error::api_error::ApiError,
+    util::{
+        analytics::HourlyUsage,
+        domains::wildcard_pattern,
+        experiments::SiteExperiments,
+        maintenance::SiteMaintenance,
+        redirects::{RedirectHits, SiteRedirects},
+        site_access::{LoginAttempts, SiteProtections},
+        site_seo::SiteSeoFiles,
+        unique_visitors::UniqueVisitors,
+    },
+};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SiteMetadata {
     pub owner_id: String,
     pub site_type: SiteType,
     pub disabled: bool,
+    // Host other domains of the site redirect to, when a primary domain is set
+    pub canonical_host: Option<String>,
+    // Origin of canonical URLs in served pages, None when the site has no verified domain
+    pub base_url: Option<String>,
+    // Host redirect aliases point to
+    pub alias_host: Option<String>,
+    // Custom domains, to tell redirect aliases and wildcard matches apart from served hosts
+    pub domains: Vec<CustomDomainRelationEntity>,
 }
 
 pub type SiteUsageCache = Cache<String, SiteUsageData>; // key: site_id, value: SiteUsageData
@@ -27,6 +50,11 @@ pub type SiteDomainCache = Cache<String, String>; // key: domain, value: site_id
 pub type SiteMetadataCache = Cache<String, SiteMetadata>; // key: site_id, value: SiteMetadata
 pub type SiteDataCache = Cache<String, Value>; // key: site_id, value: site JSON
 pub type PageNamesCache = Cache<String, HashSet<String>>; // key: site_id, value: page names
+pub type SiteSeoCache = Cache<String, SiteSeoFiles>; // key: site_id, value: sitemap and robots.txt
+pub type SiteRedirectCache = Cache<String, SiteRedirects>; // key: site_id, value: enabled redirects
+pub type SiteProtectionCache = Cache<String, SiteProtections>; // key: site_id, value: protected routes
+pub type SiteMaintenanceCache = Cache<String, SiteMaintenance>; // key: site_id, value: maintenance settings
+pub type SiteExperimentCache = Cache<String, SiteExperiments>; // key: site_id, value: enabled experiments
 
 #[derive(Clone)]
 pub struct AppCache {
@@ -35,6 +63,19 @@ pub struct AppCache {
     pub metadata_cache: SiteMetadataCache,
     pub site_data_cache: SiteDataCache,
     pub page_routes_cache: PageNamesCache,
+    pub seo_cache: SiteSeoCache,
+    pub redirect_cache: SiteRedirectCache,
+    pub protection_cache: SiteProtectionCache,
+    pub maintenance_cache: SiteMaintenanceCache,
+    pub experiment_cache: SiteExperimentCache,
+    // Page views by hour and dimension, persisted to `site_usage_hourly`
+    pub hourly_usage: HourlyUsage,
+    // Daily unique visitor sketches, merged into `site_visitor_sketches`
+    pub unique_visitors: UniqueVisitors,
+    // Redirect hits, added to `redirects.hit_count`
+    pub redirect_hits: RedirectHits,
+    // Failed visitor logins, to lock out password guessing
+    pub login_attempts: LoginAttempts,
     exec_env: ExecEnv,
 }
 
@@ -46,12 +87,26 @@ impl AppCache {
         let metadata_cache = Cache::new(2_000);
         let site_data_cache = Cache::new(2_000);
         let page_routes_cache = Cache::new(2_000);
+        let seo_cache = Cache::new(2_000);
+        let redirect_cache = Cache::new(2_000);
+        let protection_cache = Cache::new(2_000);
+        let maintenance_cache = Cache::new(2_000);
+        let experiment_cache = Cache::new(2_000);
         AppCache {
             cache,
             domain_cache,
             metadata_cache,
             site_data_cache,
             page_routes_cache,
+            seo_cache,
+            redirect_cache,
+            protection_cache,
+            maintenance_cache,
+            experiment_cache,
+            hourly_usage: HourlyUsage::default(),
+            unique_visitors: UniqueVisitors::default(),
+            redirect_hits: RedirectHits::default(),
+            login_attempts: LoginAttempts::default(),
             exec_env,
         }
     }
@@ -126,6 +181,20 @@ impl AppCache {
         self.cache.insert(site_id.to_string(), data).await;
     }
 
+    // A 304 revalidation, counted as a request without the page body bandwidth
+    pub async fn increase_not_modified_count(
+        &self,
+        site_id: &str,
+        site_size: u64,
+        site_type: SiteType,
+    ) {
+        let mut data = self.get_with_usage(site_id, site_size, site_type).await;
+
+        data = self.increase_request_count_helper(data, 0);
+
+        self.cache.insert(site_id.to_string(), data).await;
+    }
+
     // Increment both site view and request count
     pub async fn increase_view_count(&self, site_id: &str, site_size: u64, site_type: SiteType) {
         let mut data = self.get_with_usage(site_id, site_size, site_type).await;
@@ -248,6 +317,17 @@ impl AppCache {
         }
     }
 
+    // Forget the sites cached for these hosts, and the wildcard patterns covering them, e.g.
+    // when a domain moves from a wildcard match of one site to another site
+    pub async fn remove_domains(&self, domains: &[String]) {
+        for domain in domains {
+            self.domain_cache.remove(domain.as_str()).await;
+            if let Some(pattern) = wildcard_pattern(domain) {
+                self.domain_cache.remove(pattern.as_str()).await;
+            }
+        }
+    }
+
     pub async fn get_site_id_by_domain(&self, domain: &str) -> Option<String> {
         self.domain_cache.get(domain).await
     }
@@ -313,5 +393,86 @@ impl AppCache {
 
     pub async fn remove_site(&self, site_id: &str) {
         self.site_data_cache.remove(site_id).await;
+        self.remove_seo_files(site_id).await;
+    }
+
+    // Sitemap and robots.txt
+    pub async fn get_seo_files_with(
+        &self,
+        site_id: &str,
+        init: impl Future<Output = Result<SiteSeoFiles, ApiError>>,
+    ) -> Result<SiteSeoFiles, ApiError> {
+        self.seo_cache
+            .try_get_with(site_id.to_string(), init)
+            .await
+            .map_err(|e| Borrow::<ApiError>::borrow(&e).clone())
+    }
+
+    pub async fn remove_seo_files(&self, site_id: &str) {
+        self.seo_cache.invalidate(site_id).await;
+    }
+
+    // Enabled redirect rules
+    pub async fn get_redirects_with(
+        &self,
+        site_id: &str,
+        init: impl Future<Output = Result<SiteRedirects, ApiError>>,
+    ) -> Result<SiteRedirects, ApiError> {
+        self.redirect_cache
+            .try_get_with(site_id.to_string(), init)
+            .await
+            .map_err(|e| Borrow::<ApiError>::borrow(&e).clone())
+    }
+
+    pub async fn remove_redirects(&self, site_id: &str) {
+        self.redirect_cache.invalidate(site_id).await;
+    }
+
+    // Password protected routes
+    pub async fn get_protections_with(
+        &self,
+        site_id: &str,
+        init: impl Future<Output = Result<SiteProtections, ApiError>>,
+    ) -> Result<SiteProtections, ApiError> {
+        self.protection_cache
+            .try_get_with(site_id.to_string(), init)
+            .await
+            .map_err(|e| Borrow::<ApiError>::borrow(&e).clone())
+    }
+
+    pub async fn remove_protections(&self, site_id: &str) {
+        self.protection_cache.invalidate(site_id).await;
+    }
+
+    // Maintenance mode
+    pub async fn get_maintenance_with(
+        &self,
+        site_id: &str,
+        init: impl Future<Output = Result<SiteMaintenance, ApiError>>,
+    ) -> Result<SiteMaintenance, ApiError> {
+        self.maintenance_cache
+            .try_get_with(site_id.to_string(), init)
+            .await
+            .map_err(|e| Borrow::<ApiError>::borrow(&e).clone())
+    }
+
+    pub async fn remove_maintenance(&self, site_id: &str) {
+        self.maintenance_cache.invalidate(site_id).await;
+    }
+
+    // Enabled A/B experiments
+    pub async fn get_experiments_with(
+        &self,
+        site_id: &str,
+        init: impl Future<Output = Result<SiteExperiments, ApiError>>,
+    ) -> Result<SiteExperiments, ApiError> {
+        self.experiment_cache
+            .try_get_with(site_id.to_string(), init)
+            .await
+            .map_err(|e| Borrow::<ApiError>::borrow(&e).clone())
+    }
+
+    pub async fn remove_experiments(&self, site_id: &str) {
+        self.experiment_cache.invalidate(site_id).await;
     }
 }
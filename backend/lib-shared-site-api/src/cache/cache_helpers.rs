@@ -1,7 +1,18 @@
+use std::collections::HashMap;
+
 use lib_shared_types::{
-    domain::site_defaults::SiteHeadDefaults,
+    cache::site_data::{CachedPageHead, LocalizedHead},
+    domain::site_defaults::{
+        SiteHeadDefaults, SiteHeadLink, SiteHeadMeta, SiteLocale, TrailingSlashPolicy,
+    },
     dto::site_api::get_current_site_dto::GetCurrentSiteResponse,
 };
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::util::site_document::parse_site_document;
+
+const OG_IMAGE: &str = "og:image";
 
 pub fn get_site_defaults(site: &GetCurrentSiteResponse) -> Option<SiteHeadDefaults> {
     let unwrapped_defaults_res: Result<String, _> = serde_json::from_str(&site.defaults);
@@ -35,3 +46,100 @@ pub fn get_site_description(defaults: &Option<SiteHeadDefaults>, fallback: &str)
     }
     fallback.to_string()
 }
+
+pub fn get_site_trailing_slash(defaults: &Option<SiteHeadDefaults>) -> TrailingSlashPolicy {
+    defaults
+        .as_ref()
+        .map(|defaults| defaults.trailing_slash)
+        .unwrap_or_default()
+}
+
+pub fn get_site_cache_control(defaults: &Option<SiteHeadDefaults>) -> Option<String> {
+    defaults.as_ref()?.cache_control.clone()
+}
+
+pub fn get_site_home_page(defaults: &Option<SiteHeadDefaults>) -> String {
+    defaults
+        .as_ref()
+        .map(|defaults| defaults.home_page.clone())
+        .unwrap_or_default()
+}
+
+pub fn get_site_locales(defaults: &Option<SiteHeadDefaults>) -> Vec<SiteLocale> {
+    defaults
+        .as_ref()
+        .map(|defaults| defaults.locales.clone())
+        .unwrap_or_default()
+}
+
+// `og:image` meta content, set as either property or name
+fn og_image(meta: &[SiteHeadMeta]) -> Option<String> {
+    meta.iter()
+        .find(|m| m.property.as_deref().or(m.name.as_deref()) == Some(OG_IMAGE))
+        .and_then(|m| m.content.clone())
+        .filter(|content| !content.is_empty())
+}
+
+pub fn get_site_image(defaults: &Option<SiteHeadDefaults>) -> Option<String> {
+    og_image(defaults.as_ref()?.head.meta.as_deref()?)
+}
+
+// The parts of a page head used to render the served HTML. Unknown fields are ignored, so
+// head entries the server does not render cannot fail the whole site.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PageHeadDocument {
+    title: Option<String>,
+    description: Option<String>,
+    meta: Option<Vec<SiteHeadMeta>>,
+    link: Option<Vec<SiteHeadLink>>,
+    // Locale code -> localized title and description
+    locales: Option<HashMap<String, LocalizedHead>>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+// Route -> head of the site's public pages. Page values override the site head
+pub fn get_page_heads(
+    site: &GetCurrentSiteResponse,
+    site_title: &str,
+    site_description: &str,
+    site_image: Option<&str>,
+) -> HashMap<String, CachedPageHead> {
+    let Ok((Value::Object(pages), _)) = parse_site_document(&site.pages) else {
+        return HashMap::new();
+    };
+    pages
+        .into_iter()
+        .filter(|(_, page)| page.get("public").and_then(Value::as_bool) == Some(true))
+        .map(|(route, page)| {
+            let head: PageHeadDocument = page
+                .get("head")
+                .cloned()
+                .and_then(|head| serde_json::from_value(head).ok())
+                .unwrap_or_default();
+            let meta = head.meta.unwrap_or_default();
+            let image = og_image(&meta).or(site_image.map(str::to_string));
+            let alternates = head
+                .link
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|link| link.rel.as_deref() == Some("alternate") && link.hreflang.is_some())
+                .collect();
+            let page_head = CachedPageHead {
+                route: route.clone(),
+                title: non_empty(head.title).unwrap_or_else(|| site_title.to_string()),
+                description: non_empty(head.description)
+                    .unwrap_or_else(|| site_description.to_string()),
+                image,
+                meta,
+                alternates,
+                lang: None,
+                localized: head.locales.unwrap_or_default(),
+            };
+            (route, page_head)
+        })
+        .collect()
+}
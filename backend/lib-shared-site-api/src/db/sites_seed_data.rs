@@ -6,7 +6,10 @@ use lib_shared_types::{
         create_site_dto::CreateSiteDto, site_metadata_viewmodel::CustomDomainViewModel,
     },
     entity::site_api::custom_domain_entity::CustomDomainRelationEntity,
-    shared::{core::ExecEnv, site::SiteType},
+    shared::{
+        core::ExecEnv,
+        site::{DomainKind, SiteType},
+    },
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -73,6 +76,7 @@ pub fn sites_seed_data(exec_env: ExecEnv) -> Vec<CreateSiteDto> {
             let namespace = make_namespace(&seed.name);
             CreateSiteDto {
                 id: seed.id.to_string(),
+                template_id: None,
                 version: SITE_SEED_VERSION.into(),
                 name: seed.name,
                 owner_id: seed.owner_id.to_string(),
@@ -94,6 +98,8 @@ pub fn sites_seed_data(exec_env: ExecEnv) -> Vec<CreateSiteDto> {
                 .map(|d| CustomDomainViewModel {
                     domain: d.clone(),
                     verified: false,
+                    primary: false,
+                    kind: DomainKind::Serve,
                 })
                 .collect(),
             }
@@ -120,6 +126,8 @@ pub fn platform_sites_seed_data() -> Vec<PlatformSiteSeed> {
                 .map(|d| CustomDomainRelationEntity {
                     domain: d,
                     verified: false,
+                    primary: false,
+                    kind: DomainKind::Serve,
                 })
                 .collect(),
             created_at: seed.created_at,
@@ -89,6 +89,22 @@ impl ApiError {
         }
     }
 
+    pub fn too_many_requests() -> ApiError {
+        Self {
+            code: ApiErrorCode::None,
+            message: "Too many requests".to_string(),
+            status: StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    pub fn service_unavailable() -> ApiError {
+        Self {
+            code: ApiErrorCode::None,
+            message: "Service unavailable".to_string(),
+            status: StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
     // builder
     pub fn code(mut self, code: ApiErrorCode) -> Self {
         self.code = code;
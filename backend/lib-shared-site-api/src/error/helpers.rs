@@ -2,6 +2,7 @@ use super::api_error::ApiError;
 
 use lib_shared_types::{
     dto::site_api::site_metadata_viewmodel::CustomDomainViewModel, error::api_error::ApiErrorCode,
+    shared::site::DomainKind,
 };
 use regex::Regex;
 use validator::ValidationErrors;
@@ -73,7 +74,48 @@ pub fn validate_custom_domains(custom_domains: &Vec<String>) -> Result<(), ApiEr
     Ok(())
 }
 
+// A site has at most one primary domain, and it must be verified before it is enforced
+pub fn validate_primary_domain(domains: &[CustomDomainViewModel]) -> Result<(), ApiError> {
+    let mut primary = domains.iter().filter(|d| d.primary);
+    let error = |message: &str| {
+        ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(message.to_string())
+    };
+    match (primary.next(), primary.next()) {
+        (Some(_), Some(_)) => Err(error("Only one domain can be primary")),
+        (Some(domain), None) if !domain.verified => Err(error("Primary domain must be verified")),
+        (Some(domain), None) if domain.kind != DomainKind::Serve => {
+            Err(error("Primary domain must serve the site"))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Wildcard domains are written `*.events.example.com`, and only they may contain `*`
+pub fn validate_domain_kinds(domains: &[CustomDomainViewModel]) -> Result<(), ApiError> {
+    for domain in domains {
+        let valid = match domain.kind {
+            DomainKind::Wildcard => domain
+                .domain
+                .strip_prefix("*.")
+                .is_some_and(|parent| parent.contains('.') && !parent.contains('*')),
+            _ => !domain.domain.contains('*'),
+        };
+        if !valid {
+            return Err(ApiError::bad_request()
+                .code(ApiErrorCode::InvalidFormData)
+                .message(format!(
+                    "Wildcard domains must look like *.example.com: {}",
+                    domain.domain
+                )));
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_domain(domain: &str) -> Result<(), ApiError> {
+    let domain = domain.strip_prefix("*.").unwrap_or(domain);
     let domain_without_dot = domain.replace('.', "");
     validate_domain_helper(&domain_without_dot).map_err(|e| {
         ApiError::bad_request()
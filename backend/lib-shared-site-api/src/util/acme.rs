@@ -0,0 +1,122 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+// HTTP-01 challenges are served from this path, on port 80 of the domain
+pub const ACME_CHALLENGE_PATH: &str = "/.well-known/acme-challenge/{token}";
+
+pub fn base64url(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+// JSON Web Key of a P-256 account key
+#[derive(Debug, Clone, PartialEq)]
+pub struct EcJwk {
+    pub x: String,
+    pub y: String,
+}
+
+impl EcJwk {
+    // From an uncompressed SEC1 public key: 0x04, then the X and Y coordinates
+    pub fn from_public_key(public_key: &[u8]) -> Result<Self, String> {
+        if public_key.len() != 65 || public_key[0] != 0x04 {
+            return Err("Expected an uncompressed P-256 public key".into());
+        }
+        Ok(EcJwk {
+            x: base64url(&public_key[1..33]),
+            y: base64url(&public_key[33..]),
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({ "crv": "P-256", "kty": "EC", "x": self.x, "y": self.y })
+    }
+
+    // RFC 7638 thumbprint, hashing the required members in lexicographic order
+    pub fn thumbprint(&self) -> String {
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            self.x, self.y
+        );
+        base64url(&Sha256::digest(canonical.as_bytes()))
+    }
+}
+
+// Response body of an HTTP-01 challenge
+pub fn key_authorization(token: &str, thumbprint: &str) -> String {
+    format!("{}.{}", token, thumbprint)
+}
+
+// Challenge tokens are base64url, so they are safe to use in paths
+pub fn is_challenge_token(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// Certificates are renewed `renew_before` their expiry
+pub fn needs_renewal(
+    not_after: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    renew_before: Duration,
+) -> bool {
+    match not_after {
+        Some(not_after) => not_after - now <= renew_before,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_jwk_thumbprint() {
+        // Key from RFC 7515 appendix A.3
+        let x = "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU";
+        let y = "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0";
+        let mut public_key = vec![0x04];
+        public_key.extend(URL_SAFE_NO_PAD.decode(x).unwrap());
+        public_key.extend(URL_SAFE_NO_PAD.decode(y).unwrap());
+
+        let jwk = EcJwk::from_public_key(&public_key).unwrap();
+        assert_eq!(jwk.x, x);
+        assert_eq!(jwk.y, y);
+        assert_eq!(jwk.to_value()["kty"], "EC");
+        let thumbprint = jwk.thumbprint();
+        assert_eq!(thumbprint, "oKIywvGUpTVTyxMQ3bwIIeQUudfr_CkLMjCE19ECD-U");
+        assert_eq!(
+            key_authorization("tok-en_1", &thumbprint),
+            "tok-en_1.oKIywvGUpTVTyxMQ3bwIIeQUudfr_CkLMjCE19ECD-U"
+        );
+
+        assert!(EcJwk::from_public_key(&public_key[1..]).is_err());
+        assert!(is_challenge_token("tok-en_1"));
+        assert!(!is_challenge_token("../key"));
+        assert!(!is_challenge_token(""));
+    }
+
+    #[test]
+    fn renews_expiring_certificates() {
+        let now = Utc::now();
+        let renew_before = Duration::days(30);
+        assert!(needs_renewal(None, now, renew_before));
+        assert!(!needs_renewal(
+            Some(now + Duration::days(60)),
+            now,
+            renew_before
+        ));
+        assert!(needs_renewal(
+            Some(now + Duration::days(30)),
+            now,
+            renew_before
+        ));
+        assert!(needs_renewal(
+            Some(now - Duration::days(1)),
+            now,
+            renew_before
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use lib_shared_types::entity::site_api::site_usage_hourly_entity::DeviceClass;
+
+// Referrers and UTM parameters are cut to this many characters
+pub const MAX_DIMENSION_LENGTH: usize = 100;
+// Hour buckets held in memory between persists. Once full, views of new combinations
+// are recorded without their referrer, UTM parameters and country
+const MAX_BUCKETS: usize = 100_000;
+
+// Trimmed dimension value, None when empty
+pub fn dimension_value(value: Option<&str>) -> Option<String> {
+    let value = value?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.chars().take(MAX_DIMENSION_LENGTH).collect())
+}
+
+// Host of a referrer URL, e.g. `https://www.google.com/search?q=a` -> `www.google.com`.
+// None for direct visits and navigation within the site
+pub fn referrer_host(referrer: Option<&str>, site_host: &str) -> Option<String> {
+    let (_, rest) = referrer?.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => host,
+    };
+    let host = host.to_ascii_lowercase();
+    if host.is_empty() || host == site_host {
+        return None;
+    }
+    dimension_value(Some(&host))
+}
+
+// Coarse device class from a User-Agent header
+pub fn device_class(user_agent: Option<&str>) -> DeviceClass {
+    let Some(user_agent) = user_agent else {
+        return DeviceClass::Unknown;
+    };
+    let ua = user_agent.to_ascii_lowercase();
+    let has = |parts: &[&str]| parts.iter().any(|part| ua.contains(part));
+    if has(&["bot", "crawl", "spider", "slurp", "headless"]) {
+        DeviceClass::Bot
+    } else if has(&["ipad", "tablet"]) || (ua.contains("android") && !ua.contains("mobile")) {
+        DeviceClass::Tablet
+    } else if has(&["mobi", "iphone", "android"]) {
+        DeviceClass::Mobile
+    } else if has(&["windows", "macintosh", "x11", "cros", "linux"]) {
+        DeviceClass::Desktop
+    } else {
+        DeviceClass::Unknown
+    }
+}
+
+// ISO 3166 alpha-2 code from a proxy's country header. Cloudflare reports unknown
+// countries as XX and Tor as T1, which are not counted as countries
+pub fn country_code(value: Option<&str>) -> Option<String> {
+    let value = value?.trim();
+    if value.len() != 2 || !value.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let code = value.to_ascii_uppercase();
+    (code != "XX").then_some(code)
+}
+
+pub fn hour_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.duration_trunc(Duration::hours(1)).unwrap_or(time)
+}
+
+// Views of every hour from `start` to `end`, with hours missing from `views` as zero
+pub fn fill_hours(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    views: &[(DateTime<Utc>, i64)],
+) -> Vec<(DateTime<Utc>, i64)> {
+    let counts: HashMap<DateTime<Utc>, i64> = views.iter().copied().collect();
+    let mut hour = hour_start(start);
+    let mut hours = vec![];
+    while hour < end {
+        hours.push((hour, counts.get(&hour).copied().unwrap_or(0)));
+        hour += Duration::hours(1);
+    }
+    hours
+}
+
+// Dimensions of a page view, counted per hour
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HourlyUsageKey {
+    pub site_id: String,
+    pub hour: DateTime<Utc>,
+    pub route: String,
+    pub referrer: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub device: DeviceClass,
+    pub country: Option<String>,
+}
+
+// Page views aggregated in memory, until they are persisted
+#[derive(Debug, Clone, Default)]
+pub struct HourlyUsage {
+    buckets: Arc<Mutex<HashMap<HourlyUsageKey, i64>>>,
+}
+
+impl HourlyUsage {
+    pub fn record(&self, key: HourlyUsageKey) {
+        self.add(key, 1);
+    }
+
+    fn add(&self, mut key: HourlyUsageKey, views: i64) {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(&key) {
+            key.referrer = None;
+            key.utm_source = None;
+            key.utm_medium = None;
+            key.utm_campaign = None;
+            key.country = None;
+        }
+        *buckets.entry(key).or_default() += views;
+    }
+
+    // Take the buckets to persist. Views recorded meanwhile go to new buckets
+    pub fn drain(&self) -> Vec<(HourlyUsageKey, i64)> {
+        self.buckets.lock().unwrap().drain().collect()
+    }
+
+    // Put back buckets that failed to persist, to retry on the next persist
+    pub fn restore(&self, buckets: Vec<(HourlyUsageKey, i64)>) {
+        for (key, views) in buckets {
+            self.add(key, views);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parses_view_dimensions() {
+        assert_eq!(
+            referrer_host(Some("https://www.Google.com/search?q=a"), "example.com").as_deref(),
+            Some("www.google.com")
+        );
+        assert_eq!(
+            referrer_host(Some("http://user@news.site:8080#top"), "example.com").as_deref(),
+            Some("news.site")
+        );
+        // Navigation within the site and direct visits have no referrer
+        assert_eq!(
+            referrer_host(Some("https://example.com/about"), "example.com"),
+            None
+        );
+        assert_eq!(referrer_host(Some(""), "example.com"), None);
+
+        let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) Mobile/15E148";
+        let ipad = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X)";
+        let android_tablet = "Mozilla/5.0 (Linux; Android 14; SM-X910) Safari/537.36";
+        let windows = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0";
+        let bot = "Mozilla/5.0 (compatible; Googlebot/2.1)";
+        assert_eq!(device_class(Some(iphone)), DeviceClass::Mobile);
+        assert_eq!(device_class(Some(ipad)), DeviceClass::Tablet);
+        assert_eq!(device_class(Some(android_tablet)), DeviceClass::Tablet);
+        assert_eq!(device_class(Some(windows)), DeviceClass::Desktop);
+        assert_eq!(device_class(Some(bot)), DeviceClass::Bot);
+        assert_eq!(device_class(None), DeviceClass::Unknown);
+
+        assert_eq!(country_code(Some(" tw ")).as_deref(), Some("TW"));
+        assert_eq!(country_code(Some("XX")), None);
+        assert_eq!(country_code(Some("USA")), None);
+        assert_eq!(dimension_value(Some("  ")), None);
+        assert_eq!(
+            dimension_value(Some(&"a".repeat(200))).map(|v| v.len()),
+            Some(MAX_DIMENSION_LENGTH)
+        );
+    }
+
+    #[test]
+    fn aggregates_hourly_views() {
+        let time = Utc.with_ymd_and_hms(2026, 10, 19, 10, 42, 5).unwrap();
+        let hour = hour_start(time);
+        assert_eq!(hour, Utc.with_ymd_and_hms(2026, 10, 19, 10, 0, 0).unwrap());
+
+        let key = HourlyUsageKey {
+            site_id: "site".into(),
+            hour,
+            route: "/".into(),
+            referrer: Some("news.site".into()),
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+            device: DeviceClass::Mobile,
+            country: Some("TW".into()),
+        };
+        let usage = HourlyUsage::default();
+        usage.record(key.clone());
+        usage.record(key.clone());
+        usage.record(HourlyUsageKey {
+            device: DeviceClass::Desktop,
+            ..key.clone()
+        });
+        let mut buckets = usage.drain();
+        buckets.sort_by_key(|(_, views)| *views);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[1], (key.clone(), 2));
+        assert!(usage.drain().is_empty());
+
+        // Failed persists are retried with views recorded meanwhile
+        usage.record(key.clone());
+        usage.restore(buckets);
+        let restored = usage.drain();
+        assert!(restored.contains(&(key, 3)));
+
+        let filled = fill_hours(
+            hour - Duration::hours(1),
+            hour + Duration::hours(1),
+            &[(hour, 5)],
+        );
+        assert_eq!(filled, vec![(hour - Duration::hours(1), 0), (hour, 5)]);
+    }
+}
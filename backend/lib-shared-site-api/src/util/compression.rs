@@ -0,0 +1,126 @@
+use std::io::Write;
+
+use axum::http::{header::ACCEPT_ENCODING, HeaderMap};
+use flate2::{write::GzEncoder, Compression};
+
+// Precompressed content is generated once per SSG build, so use the highest quality
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Identity,
+    Gzip,
+    Brotli,
+}
+
+impl ContentEncoding {
+    // Value of the Content-Encoding header, None for uncompressed responses
+    pub fn header_value(&self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Identity => None,
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Brotli => Some("br"),
+        }
+    }
+}
+
+pub fn compress_gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    // Writing to a Vec cannot fail
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+pub fn compress_brotli(data: &[u8]) -> Vec<u8> {
+    let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+    writer.write_all(data).unwrap();
+    writer.into_inner()
+}
+
+// Pick the response encoding from Accept-Encoding. Brotli is preferred over gzip at equal
+// quality values, and a missing header or `q=0` for both means no compression.
+pub fn negotiate_encoding(headers: &HeaderMap) -> ContentEncoding {
+    let Some(accept) = headers.get(ACCEPT_ENCODING).and_then(|v| v.to_str().ok()) else {
+        return ContentEncoding::Identity;
+    };
+    let mut brotli_q = None;
+    let mut gzip_q = None;
+    let mut any_q = None;
+    for item in accept.split(',') {
+        let mut parts = item.split(';');
+        let coding = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .filter_map(|q| q.trim().parse::<f32>().ok())
+            .next()
+            .unwrap_or(1.0);
+        match coding.as_str() {
+            "br" => brotli_q = Some(q),
+            "gzip" | "x-gzip" => gzip_q = Some(q),
+            "*" => any_q = Some(q),
+            _ => {}
+        }
+    }
+    let brotli_q = brotli_q.or(any_q).unwrap_or(0.0);
+    let gzip_q = gzip_q.or(any_q).unwrap_or(0.0);
+    if brotli_q > 0.0 && brotli_q >= gzip_q {
+        ContentEncoding::Brotli
+    } else if gzip_q > 0.0 {
+        ContentEncoding::Gzip
+    } else {
+        ContentEncoding::Identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn negotiates_encoding() {
+        use ContentEncoding::*;
+
+        assert_eq!(negotiate_encoding(&HeaderMap::new()), Identity);
+        assert_eq!(
+            negotiate_encoding(&accept("gzip, deflate, br, zstd")),
+            Brotli
+        );
+        assert_eq!(negotiate_encoding(&accept("gzip, deflate")), Gzip);
+        assert_eq!(negotiate_encoding(&accept("br;q=0.5, gzip")), Gzip);
+        assert_eq!(negotiate_encoding(&accept("br;q=0, gzip;q=0")), Identity);
+        assert_eq!(negotiate_encoding(&accept("*")), Brotli);
+        assert_eq!(negotiate_encoding(&accept("identity")), Identity);
+    }
+
+    #[test]
+    fn compresses_round_trip() {
+        let body = "<html><body>".to_string() + &"Pub Studio ".repeat(200) + "</body></html>";
+
+        let gzip = compress_gzip(body.as_bytes());
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+        assert!(gzip.len() < body.len());
+
+        let brotli = compress_brotli(body.as_bytes());
+        let mut decoded = String::new();
+        brotli::Decompressor::new(brotli.as_slice(), 4096)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+        assert!(brotli.len() < body.len());
+    }
+}
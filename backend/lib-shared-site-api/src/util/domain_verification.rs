@@ -0,0 +1,261 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use lib_shared_types::entity::site_api::domain_verification_entity::DomainVerificationStatus;
+
+pub const VERIFICATION_RECORD_PREFIX: &str = "_pubstudio";
+// Owners are told a verified domain is failing once it fails this many checks in a row
+pub const FAILING_NOTIFY_CHECKS: i64 = 3;
+
+// Name of the TXT record holding a domain's verification token. Wildcard domains are
+// verified on their parent domain
+pub fn verification_record_name(domain: &str) -> String {
+    let domain = domain.strip_prefix("*.").unwrap_or(domain);
+    format!("{}.{}", VERIFICATION_RECORD_PREFIX, domain)
+}
+
+// Local development domains resolve to the loopback address, and are not checked
+pub fn is_local_domain(domain: &str) -> bool {
+    domain == "localhost" || domain.ends_with(".localhost")
+}
+
+// DNS records of a domain. Missing records are empty
+#[derive(Debug, Default, Clone)]
+pub struct DomainRecords {
+    // TXT values of the verification record
+    pub txt: Vec<String>,
+    pub cnames: Vec<String>,
+    pub ips: Vec<IpAddr>,
+}
+
+// Where verified domains point, as an alternative to the TXT token
+#[derive(Debug, Default, Clone)]
+pub struct VerificationTargets {
+    pub cname: Option<String>,
+    pub ips: Vec<IpAddr>,
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+// Record type that verifies the domain: a TXT record with the token, a CNAME to the target
+// host, or an A/AAAA record to a target address
+pub fn verify_records(
+    domain: &str,
+    token: &str,
+    records: &DomainRecords,
+    targets: &VerificationTargets,
+) -> Result<&'static str, String> {
+    if records.txt.iter().any(|txt| txt.trim() == token) {
+        return Ok("TXT");
+    }
+    let mut expected = vec![format!(
+        "a TXT record {} with the verification token",
+        verification_record_name(domain)
+    )];
+    if let Some(target) = &targets.cname {
+        let target = normalize_host(target);
+        if records
+            .cnames
+            .iter()
+            .any(|cname| normalize_host(cname) == target)
+        {
+            return Ok("CNAME");
+        }
+        expected.push(format!("a CNAME record to {}", target));
+    }
+    if !targets.ips.is_empty() {
+        if records.ips.iter().any(|ip| targets.ips.contains(ip)) {
+            return Ok("A");
+        }
+        let ips: Vec<String> = targets.ips.iter().map(IpAddr::to_string).collect();
+        expected.push(format!("an A record to {}", ips.join(", ")));
+    }
+    Err(format!("Expected {}", expected.join(" or ")))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationState {
+    pub status: DomainVerificationStatus,
+    pub verified: bool,
+    pub failing_since: Option<DateTime<Utc>>,
+    // Checks failed in a row
+    pub failed_checks: i64,
+}
+
+// State after a check. Verified domains stay verified while failing, until they have failed
+// for `suspend_after` and are suspended
+pub fn next_verification_state(
+    current: &VerificationState,
+    passed: bool,
+    now: DateTime<Utc>,
+    suspend_after: Duration,
+) -> VerificationState {
+    if passed {
+        return VerificationState {
+            status: DomainVerificationStatus::Verified,
+            verified: true,
+            failing_since: None,
+            failed_checks: 0,
+        };
+    }
+    let failing_since = current.failing_since.unwrap_or(now);
+    let failed_checks = current.failed_checks + 1;
+    if current.status == DomainVerificationStatus::Suspended || now - failing_since >= suspend_after
+    {
+        return VerificationState {
+            status: DomainVerificationStatus::Suspended,
+            verified: false,
+            failing_since: Some(failing_since),
+            failed_checks,
+        };
+    }
+    let status = match current.status {
+        DomainVerificationStatus::Pending => DomainVerificationStatus::Pending,
+        _ => DomainVerificationStatus::Failing,
+    };
+    VerificationState {
+        status,
+        verified: current.verified,
+        failing_since: Some(failing_since),
+        failed_checks,
+    }
+}
+
+// Whether the owner is notified of a check. A failing domain is reported once it failed
+// FAILING_NOTIFY_CHECKS checks in a row, and its recovery only when the failure was reported
+pub fn notify_verification(current: &VerificationState, next: &VerificationState) -> bool {
+    match next.status {
+        DomainVerificationStatus::Pending => false,
+        DomainVerificationStatus::Failing => next.failed_checks == FAILING_NOTIFY_CHECKS,
+        DomainVerificationStatus::Verified => match current.status {
+            DomainVerificationStatus::Verified => false,
+            DomainVerificationStatus::Failing => current.failed_checks >= FAILING_NOTIFY_CHECKS,
+            _ => true,
+        },
+        DomainVerificationStatus::Suspended => current.status != next.status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_dns_records() {
+        let targets = VerificationTargets {
+            cname: Some("sites.pubstud.io".into()),
+            ips: vec!["203.0.113.7".parse().unwrap()],
+        };
+        let mut records = DomainRecords::default();
+        let error = verify_records("myblog.org", "abc", &records, &targets).unwrap_err();
+        assert_eq!(
+            error,
+            "Expected a TXT record _pubstudio.myblog.org with the verification token or a \
+             CNAME record to sites.pubstud.io or an A record to 203.0.113.7"
+        );
+
+        records.ips = vec![
+            "198.51.100.1".parse().unwrap(),
+            "203.0.113.7".parse().unwrap(),
+        ];
+        assert_eq!(
+            verify_records("myblog.org", "abc", &records, &targets),
+            Ok("A")
+        );
+        records.cnames = vec!["Sites.PubStud.io.".into()];
+        assert_eq!(
+            verify_records("myblog.org", "abc", &records, &targets),
+            Ok("CNAME")
+        );
+        records.txt = vec!["other".into(), "abc".into()];
+        assert_eq!(
+            verify_records("myblog.org", "abc", &records, &targets),
+            Ok("TXT")
+        );
+
+        // Only the token verifies without targets
+        records.txt.clear();
+        let error = verify_records(
+            "myblog.org",
+            "abc",
+            &records,
+            &VerificationTargets::default(),
+        );
+        assert_eq!(
+            error,
+            Err("Expected a TXT record _pubstudio.myblog.org with the verification token".into())
+        );
+    }
+
+    #[test]
+    fn suspends_domains_failing_too_long() {
+        let now = Utc::now();
+        let suspend_after = Duration::days(7);
+        let pending = VerificationState {
+            status: DomainVerificationStatus::Pending,
+            verified: false,
+            failing_since: None,
+            failed_checks: 0,
+        };
+        let state = next_verification_state(&pending, false, now, suspend_after);
+        assert_eq!(state.status, DomainVerificationStatus::Pending);
+        assert_eq!(state.failing_since, Some(now));
+
+        let verified = next_verification_state(&state, true, now, suspend_after);
+        assert_eq!(verified.status, DomainVerificationStatus::Verified);
+        assert!(verified.verified);
+        assert_eq!(verified.failing_since, None);
+
+        // Failing domains keep their verified flag during the grace period
+        let failing = next_verification_state(&verified, false, now, suspend_after);
+        assert_eq!(failing.status, DomainVerificationStatus::Failing);
+        assert!(failing.verified);
+        let failing =
+            next_verification_state(&failing, false, now + Duration::days(6), suspend_after);
+        assert_eq!(failing.status, DomainVerificationStatus::Failing);
+        assert_eq!(failing.failing_since, Some(now));
+
+        let suspended =
+            next_verification_state(&failing, false, now + Duration::days(7), suspend_after);
+        assert_eq!(suspended.status, DomainVerificationStatus::Suspended);
+        assert!(!suspended.verified);
+
+        let restored = next_verification_state(&suspended, true, now, suspend_after);
+        assert_eq!(restored.status, DomainVerificationStatus::Verified);
+    }
+
+    #[test]
+    fn notifies_after_consecutive_failures() {
+        let now = Utc::now();
+        let suspend_after = Duration::days(7);
+        let pending = VerificationState {
+            status: DomainVerificationStatus::Pending,
+            verified: false,
+            failing_since: None,
+            failed_checks: 0,
+        };
+        let verified = next_verification_state(&pending, true, now, suspend_after);
+        assert!(notify_verification(&pending, &verified));
+
+        // A single failed check is not reported, nor is the recovery from it
+        let failing = next_verification_state(&verified, false, now, suspend_after);
+        assert_eq!(failing.failed_checks, 1);
+        assert!(!notify_verification(&verified, &failing));
+        let recovered = next_verification_state(&failing, true, now, suspend_after);
+        assert_eq!(recovered.failed_checks, 0);
+        assert!(!notify_verification(&failing, &recovered));
+
+        let mut state = recovered;
+        let mut notified = 0;
+        for _ in 0..FAILING_NOTIFY_CHECKS + 2 {
+            let next = next_verification_state(&state, false, now, suspend_after);
+            notified += notify_verification(&state, &next) as i32;
+            state = next;
+        }
+        assert_eq!(notified, 1);
+        let recovered = next_verification_state(&state, true, now, suspend_after);
+        assert!(notify_verification(&state, &recovered));
+    }
+}
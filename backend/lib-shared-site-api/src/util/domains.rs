@@ -1,5 +1,9 @@
 use axum::http::{header::ORIGIN, HeaderMap};
-use lib_shared_types::type_util::{is_port, REGEX_PORT};
+use lib_shared_types::{
+    entity::site_api::custom_domain_entity::CustomDomainRelationEntity,
+    shared::site::{DomainKind, WwwPolicy},
+    type_util::{is_port, REGEX_PORT},
+};
 
 pub fn domain_from_parts(domain: &str, subdomain: &str) -> String {
     format!("{}.{}", subdomain, domain)
@@ -11,6 +15,142 @@ pub fn merge_domains(custom: Vec<String>, subdomain: &str, domain_suffix: &str)
     domains
 }
 
+// Site origin for a domain. Local development domains are served over plain http
+pub fn base_url_from_domain(domain: &str) -> String {
+    if domain == "localhost" || domain.ends_with(".localhost") {
+        format!("http://{}", domain)
+    } else {
+        format!("https://{}", domain)
+    }
+}
+
+// `example.com` <-> `www.example.com`
+pub fn toggle_www(domain: &str) -> String {
+    match domain.strip_prefix("www.") {
+        Some(apex) => apex.to_string(),
+        None => format!("www.{}", domain),
+    }
+}
+
+// The site's canonical host: its verified primary domain with the www policy applied.
+// None when no primary domain is set, and every host is served as is.
+pub fn canonical_host(
+    domains: &[CustomDomainRelationEntity],
+    www_policy: WwwPolicy,
+) -> Option<String> {
+    let primary = domains
+        .iter()
+        .find(|d| d.primary && d.verified && d.kind == DomainKind::Serve)?;
+    let has_www = primary.domain.starts_with("www.");
+    Some(match www_policy {
+        WwwPolicy::Www if !has_www => toggle_www(&primary.domain),
+        WwwPolicy::Apex if has_www => toggle_www(&primary.domain),
+        _ => primary.domain.clone(),
+    })
+}
+
+// URL a request is redirected to, when the host isn't canonical or a proxy reports that
+// it arrived over plain http. None when the request is already canonical
+pub fn canonical_redirect_url(
+    canonical_host: &str,
+    host: &str,
+    forwarded_proto: Option<&str>,
+    path_and_query: &str,
+) -> Option<String> {
+    let base_url = base_url_from_domain(canonical_host);
+    let insecure = base_url.starts_with("https://")
+        && forwarded_proto
+            .and_then(|proto| proto.split(',').next())
+            .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("http"));
+    if host == canonical_host && !insecure {
+        return None;
+    }
+    Some(format!("{}{}", base_url, path_and_query))
+}
+
+// Base URL of generated pages and the sitemap: the canonical host when set, otherwise
+// the first served domain accepted by `fallback`
+pub fn site_base_url(
+    domains: &[CustomDomainRelationEntity],
+    www_policy: WwwPolicy,
+    fallback: impl Fn(&CustomDomainRelationEntity) -> bool,
+) -> Option<String> {
+    canonical_host(domains, www_policy)
+        .or_else(|| {
+            domains
+                .iter()
+                .find(|d| d.kind == DomainKind::Serve && fallback(d))
+                .map(|d| d.domain.clone())
+        })
+        .map(|domain| base_url_from_domain(&domain))
+}
+
+// Wildcard domain matching a host, `shop.events.example.com` -> `*.events.example.com`.
+// Wildcards cover a single DNS label, and never a whole top level domain. The label is
+// handed to pages as their subdomain
+pub fn wildcard_pattern(host: &str) -> Option<String> {
+    let (label, parent) = host.split_once('.')?;
+    let valid_label = |c: char| c.is_ascii_alphanumeric() || c == '-';
+    if label.is_empty() || !label.chars().all(valid_label) || !parent.contains('.') {
+        return None;
+    }
+    Some(format!("*.{}", parent))
+}
+
+// How a site handles a request host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostMatch {
+    Serve,
+    Redirect,
+    // Matched by a wildcard domain, with the subdomain label of the host
+    Wildcard(String),
+}
+
+// Match a host against the site's domains. Exact domains win over their www/apex variant,
+// and both win over wildcards. Hosts that aren't a custom domain of the site are served
+pub fn match_host(domains: &[CustomDomainRelationEntity], host: &str) -> HostMatch {
+    let named = |d: &&CustomDomainRelationEntity| d.kind != DomainKind::Wildcard;
+    let exact = domains
+        .iter()
+        .filter(named)
+        .find(|d| d.domain == host)
+        .or_else(|| {
+            domains
+                .iter()
+                .filter(named)
+                .find(|d| toggle_www(&d.domain) == host)
+        });
+    if let Some(domain) = exact {
+        return match domain.kind {
+            DomainKind::Redirect => HostMatch::Redirect,
+            _ => HostMatch::Serve,
+        };
+    }
+    let wildcard = wildcard_pattern(host).filter(|pattern| {
+        domains
+            .iter()
+            .any(|d| d.kind == DomainKind::Wildcard && &d.domain == pattern)
+    });
+    match (wildcard, host.split_once('.')) {
+        (Some(_), Some((subdomain, _))) => HostMatch::Wildcard(subdomain.to_string()),
+        _ => HostMatch::Serve,
+    }
+}
+
+// Where redirect aliases point: the canonical host, otherwise the first verified domain
+// serving the site
+pub fn alias_redirect_host(
+    domains: &[CustomDomainRelationEntity],
+    www_policy: WwwPolicy,
+) -> Option<String> {
+    canonical_host(domains, www_policy).or_else(|| {
+        domains
+            .iter()
+            .find(|d| d.verified && d.kind == DomainKind::Serve)
+            .map(|d| d.domain.clone())
+    })
+}
+
 // Get the domain from host with port stripped
 pub fn domain_without_port(hostname: String) -> String {
     if is_port(&hostname) {
@@ -30,3 +170,120 @@ pub fn origin_domain(headers: &HeaderMap) -> Option<String> {
         host.trim_end_matches('/').to_ascii_lowercase(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain(domain: &str, verified: bool, primary: bool) -> CustomDomainRelationEntity {
+        CustomDomainRelationEntity {
+            domain: domain.to_string(),
+            verified,
+            primary,
+            kind: DomainKind::Serve,
+        }
+    }
+
+    fn domain_of_kind(domain: &str, kind: DomainKind) -> CustomDomainRelationEntity {
+        CustomDomainRelationEntity {
+            kind,
+            ..self::domain(domain, true, false)
+        }
+    }
+
+    #[test]
+    fn resolves_canonical_host() {
+        let domains = vec![
+            domain("site.pubstud.io", false, false),
+            domain("example.com", true, true),
+        ];
+        assert_eq!(
+            canonical_host(&domains, WwwPolicy::Keep).as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            canonical_host(&domains, WwwPolicy::Www).as_deref(),
+            Some("www.example.com")
+        );
+        assert_eq!(
+            canonical_host(&[domain("www.example.com", true, true)], WwwPolicy::Apex).as_deref(),
+            Some("example.com")
+        );
+        // An unverified primary domain is not enforced
+        assert_eq!(
+            canonical_host(&[domain("example.com", false, true)], WwwPolicy::Keep),
+            None
+        );
+        assert_eq!(
+            site_base_url(&domains[..1], WwwPolicy::Keep, |_| true).as_deref(),
+            Some("https://site.pubstud.io")
+        );
+    }
+
+    #[test]
+    fn redirects_to_canonical_url() {
+        assert_eq!(
+            canonical_redirect_url("example.com", "www.example.com", None, "/a?b=1").as_deref(),
+            Some("https://example.com/a?b=1")
+        );
+        assert_eq!(
+            canonical_redirect_url("example.com", "example.com", Some("http"), "/").as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(
+            canonical_redirect_url("example.com", "example.com", Some("https, http"), "/"),
+            None
+        );
+        assert_eq!(
+            canonical_redirect_url("site.localhost", "site.localhost", Some("http"), "/"),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_aliases_and_wildcards() {
+        let domains = vec![
+            domain_of_kind("newbrand.com", DomainKind::Serve),
+            domain_of_kind("oldbrand.com", DomainKind::Redirect),
+            domain_of_kind("*.events.example.com", DomainKind::Wildcard),
+            domain_of_kind("admin.events.example.com", DomainKind::Redirect),
+        ];
+        assert_eq!(match_host(&domains, "newbrand.com"), HostMatch::Serve);
+        assert_eq!(
+            match_host(&domains, "www.oldbrand.com"),
+            HostMatch::Redirect
+        );
+        assert_eq!(
+            match_host(&domains, "summit.events.example.com"),
+            HostMatch::Wildcard("summit".into())
+        );
+        // Exact domains win over the wildcard
+        assert_eq!(
+            match_host(&domains, "admin.events.example.com"),
+            HostMatch::Redirect
+        );
+        // Wildcards match a single label
+        assert_eq!(
+            match_host(&domains, "a.b.events.example.com"),
+            HostMatch::Serve
+        );
+        assert_eq!(match_host(&domains, "events.example.com"), HostMatch::Serve);
+
+        assert_eq!(
+            wildcard_pattern("a.example.com").as_deref(),
+            Some("*.example.com")
+        );
+        assert_eq!(wildcard_pattern("example.com"), None);
+        assert_eq!(wildcard_pattern("*.example.com"), None);
+        assert_eq!(wildcard_pattern("a'b.example.com"), None);
+
+        assert_eq!(
+            alias_redirect_host(&domains, WwwPolicy::Keep).as_deref(),
+            Some("newbrand.com")
+        );
+        assert_eq!(
+            site_base_url(&domains[1..], WwwPolicy::Keep, |_| true),
+            None
+        );
+    }
+}
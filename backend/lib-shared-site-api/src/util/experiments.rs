@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+
+use lib_shared_types::entity::site_api::experiment_entity::ExperimentEntity;
+
+use crate::auth::types::ExperimentAssignment;
+
+pub const EXPERIMENT_COOKIE: &str = "ps_ab";
+// Visitors keep their variant for the length of a typical experiment
+pub const ASSIGNMENT_TTL_MINUTES: i64 = 90 * 24 * 60;
+// Results with a lower p-value are reported as significant
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+#[derive(Debug, Clone)]
+pub struct ExperimentVariant {
+    pub id: i64,
+    pub page_route: String,
+    pub weight: u64,
+}
+
+// Enabled experiment of a site, with variants in creation order
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    pub id: i64,
+    pub route: String,
+    pub conversion_table: Option<String>,
+    pub variants: Vec<ExperimentVariant>,
+}
+
+impl From<ExperimentEntity> for Experiment {
+    fn from(entity: ExperimentEntity) -> Self {
+        Experiment {
+            id: entity.id,
+            route: entity.route,
+            conversion_table: entity.conversion_table,
+            variants: entity
+                .variants
+                .into_iter()
+                .map(|variant| ExperimentVariant {
+                    id: variant.id,
+                    page_route: variant.page_route,
+                    weight: variant.weight.max(0) as u64,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Experiment {
+    // Variant for a random draw, each variant is chosen in proportion to its weight
+    pub fn pick(&self, draw: u64) -> Option<&ExperimentVariant> {
+        let total: u64 = self.variants.iter().map(|variant| variant.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut point = draw % total;
+        for variant in &self.variants {
+            if point < variant.weight {
+                return Some(variant);
+            }
+            point -= variant.weight;
+        }
+        None
+    }
+
+    pub fn variant(&self, variant_id: i64) -> Option<&ExperimentVariant> {
+        self.variants
+            .iter()
+            .find(|variant| variant.id == variant_id)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SiteExperiments {
+    experiments: Vec<Experiment>,
+}
+
+impl SiteExperiments {
+    pub fn new(experiments: Vec<Experiment>) -> Self {
+        SiteExperiments { experiments }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.experiments.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Experiment> {
+        self.experiments.iter()
+    }
+
+    // Experiment running on a page route
+    pub fn find(&self, route: &str) -> Option<&Experiment> {
+        self.experiments
+            .iter()
+            .find(|experiment| experiment.route == route)
+    }
+
+    pub fn get(&self, experiment_id: i64) -> Option<&Experiment> {
+        self.experiments
+            .iter()
+            .find(|experiment| experiment.id == experiment_id)
+    }
+
+    // Experiments converted by inserts into a custom data table
+    pub fn converted_by<'a>(&'a self, table: &'a str) -> impl Iterator<Item = &'a Experiment> {
+        self.experiments
+            .iter()
+            .filter(move |experiment| experiment.conversion_table.as_deref() == Some(table))
+    }
+
+    // Assignments of running experiments, keeping the cookie small
+    pub fn retain_running(&self, assignments: &mut Vec<ExperimentAssignment>) {
+        assignments.retain(|assignment| {
+            self.get(assignment.e)
+                .is_some_and(|experiment| experiment.variant(assignment.v).is_some())
+        });
+    }
+}
+
+// A visitor's variant of an experiment. Visitors without a variant, or whose variant was
+// removed, are assigned one with `draw`. Returns the variant, and whether it is new
+pub fn assign_variant<'a>(
+    experiment: &'a Experiment,
+    assignments: &mut Vec<ExperimentAssignment>,
+    draw: u64,
+) -> Option<(&'a ExperimentVariant, bool)> {
+    let assigned = assignments
+        .iter()
+        .find(|assignment| assignment.e == experiment.id)
+        .and_then(|assignment| experiment.variant(assignment.v));
+    if let Some(variant) = assigned {
+        return Some((variant, false));
+    }
+    let variant = experiment.pick(draw)?;
+    assignments.retain(|assignment| assignment.e != experiment.id);
+    assignments.push(ExperimentAssignment {
+        e: experiment.id,
+        v: variant.id,
+        x: false,
+        c: false,
+    });
+    Some((variant, true))
+}
+
+// Mark the visitor's first view of an experiment route. Returns the variant to count an
+// exposure for, None when already counted or not assigned
+pub fn mark_exposed(assignments: &mut [ExperimentAssignment], experiment_id: i64) -> Option<i64> {
+    let assignment = assignments
+        .iter_mut()
+        .find(|assignment| assignment.e == experiment_id && !assignment.x)?;
+    assignment.x = true;
+    Some(assignment.v)
+}
+
+// Mark the visitor's first conversion. Only exposed visitors convert, so conversion rates
+// are relative to exposures
+pub fn mark_converted(assignments: &mut [ExperimentAssignment], experiment_id: i64) -> Option<i64> {
+    let assignment = assignments
+        .iter_mut()
+        .find(|assignment| assignment.e == experiment_id && assignment.x && !assignment.c)?;
+    assignment.c = true;
+    Some(assignment.v)
+}
+
+pub fn experiment_cookie(token: &str, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        EXPERIMENT_COOKIE,
+        token,
+        ASSIGNMENT_TTL_MINUTES * 60,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+// Variants are (name, page_route). The route and each variant are pages of the site, and
+// each variant renders a different page
+pub fn validate_experiment(
+    route: &str,
+    variants: &[(&str, &str)],
+    pages: &HashSet<String>,
+) -> Result<(), String> {
+    if !pages.contains(route) {
+        return Err(format!("Experiment route {} is not a page", route));
+    }
+    for (index, (name, page_route)) in variants.iter().enumerate() {
+        if !pages.contains(*page_route) {
+            return Err(format!("Variant page {} does not exist", page_route));
+        }
+        let earlier = &variants[..index];
+        if earlier.iter().any(|(other, _)| other == name) {
+            return Err(format!("Duplicate variant name {}", name));
+        }
+        if earlier.iter().any(|(_, other)| other == page_route) {
+            return Err(format!(
+                "Variants must render different pages, {}",
+                page_route
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn conversion_rate(exposures: i64, conversions: i64) -> f64 {
+    if exposures <= 0 {
+        return 0.0;
+    }
+    conversions as f64 / exposures as f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantComparison {
+    // None when the control has no conversions
+    pub lift: Option<f64>,
+    pub z_score: f64,
+    pub p_value: f64,
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to 1.5e-7
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+// Two proportion z-test of a variant against the control, as (exposures, conversions).
+// None when there is nothing to compare, e.g. no exposures or no conversions at all
+pub fn compare_variant(control: (i64, i64), variant: (i64, i64)) -> Option<VariantComparison> {
+    let (control_n, control_c) = control;
+    let (variant_n, variant_c) = variant;
+    if control_n <= 0 || variant_n <= 0 {
+        return None;
+    }
+    let control_rate = conversion_rate(control_n, control_c);
+    let variant_rate = conversion_rate(variant_n, variant_c);
+    let pooled = (control_c + variant_c) as f64 / (control_n + variant_n) as f64;
+    let std_error =
+        (pooled * (1.0 - pooled) * (1.0 / control_n as f64 + 1.0 / variant_n as f64)).sqrt();
+    if std_error == 0.0 || !std_error.is_finite() {
+        return None;
+    }
+    let z_score = (variant_rate - control_rate) / std_error;
+    Some(VariantComparison {
+        lift: (control_rate > 0.0).then(|| (variant_rate - control_rate) / control_rate),
+        z_score,
+        p_value: 2.0 * (1.0 - normal_cdf(z_score.abs())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment() -> Experiment {
+        Experiment {
+            id: 1,
+            route: "/landing".into(),
+            conversion_table: Some("signups".into()),
+            variants: vec![
+                ExperimentVariant {
+                    id: 10,
+                    page_route: "/landing".into(),
+                    weight: 1,
+                },
+                ExperimentVariant {
+                    id: 11,
+                    page_route: "/landing-b".into(),
+                    weight: 3,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn assigns_sticky_weighted_variants() {
+        let experiment = experiment();
+        let picks: Vec<i64> = (0..8)
+            .map(|draw| experiment.pick(draw).unwrap().id)
+            .collect();
+        assert_eq!(picks, vec![10, 11, 11, 11, 10, 11, 11, 11]);
+
+        let mut assignments = vec![];
+        let (variant, new) = assign_variant(&experiment, &mut assignments, 1).unwrap();
+        assert_eq!((variant.id, new), (11, true));
+        // The cookie's variant wins over later draws
+        let (variant, new) = assign_variant(&experiment, &mut assignments, 0).unwrap();
+        assert_eq!((variant.id, new), (11, false));
+        assert_eq!(assignments.len(), 1);
+
+        // Conversions count once, after exposure
+        assert_eq!(mark_converted(&mut assignments, 1), None);
+        assert_eq!(mark_exposed(&mut assignments, 1), Some(11));
+        assert_eq!(mark_exposed(&mut assignments, 1), None);
+        assert_eq!(mark_converted(&mut assignments, 1), Some(11));
+        assert_eq!(mark_converted(&mut assignments, 1), None);
+
+        // Removed variants are assigned again
+        assignments[0].v = 99;
+        let (variant, new) = assign_variant(&experiment, &mut assignments, 0).unwrap();
+        assert_eq!((variant.id, new), (10, true));
+        assert_eq!(assignments.len(), 1);
+        assert!(!assignments[0].x);
+
+        let experiments = SiteExperiments::new(vec![experiment.clone()]);
+        assignments.push(ExperimentAssignment {
+            e: 2,
+            v: 20,
+            x: false,
+            c: false,
+        });
+        experiments.retain_running(&mut assignments);
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(experiments.converted_by("signups").count(), 1);
+    }
+
+    #[test]
+    fn compares_variants() {
+        let comparison = compare_variant((1000, 100), (1000, 150)).unwrap();
+        assert!((comparison.z_score - 3.3806).abs() < 0.001);
+        assert!((comparison.p_value - 0.000723).abs() < 0.00001);
+        assert!((comparison.lift.unwrap() - 0.5).abs() < 1e-9);
+
+        let even = compare_variant((200, 20), (200, 20)).unwrap();
+        assert!((even.p_value - 1.0).abs() < 1e-6);
+
+        assert_eq!(compare_variant((0, 0), (100, 5)), None);
+        assert_eq!(compare_variant((100, 0), (100, 0)), None);
+        assert_eq!(
+            compare_variant((100, 0), (100, 5)).map(|c| c.lift),
+            Some(None)
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::fs;
 
 use lazy_static::lazy_static;
 
+use super::compression::{compress_brotli, compress_gzip, ContentEncoding};
+
 lazy_static! {
     static ref SITE_HTML_V1: String = fs::read_to_string("index_v1.html").unwrap();
     static ref SITE_HTML: String = fs::read_to_string("index.html").unwrap();
@@ -28,6 +30,8 @@ lazy_static! {
     // worth degrading for -- serving 404s here would leave generated pages
     // rendered but never hydrated, which is invisible until someone clicks.
     static ref SITE_JS: String = fs::read_to_string("site.js").unwrap();
+    static ref SITE_JS_BR: Vec<u8> = compress_brotli(SITE_JS.as_bytes());
+    static ref SITE_JS_GZIP: Vec<u8> = compress_gzip(SITE_JS.as_bytes());
 }
 
 pub fn get_site_js_dev() -> String {
@@ -37,3 +41,12 @@ pub fn get_site_js_dev() -> String {
 pub fn get_site_js() -> &'static str {
     return &SITE_JS;
 }
+
+// site.js compressed for the negotiated encoding, compressed once on first use
+pub fn get_site_js_encoded(encoding: ContentEncoding) -> &'static [u8] {
+    match encoding {
+        ContentEncoding::Identity => SITE_JS.as_bytes(),
+        ContentEncoding::Gzip => &SITE_JS_GZIP,
+        ContentEncoding::Brotli => &SITE_JS_BR,
+    }
+}
@@ -0,0 +1,164 @@
+use axum::http::{
+    header::{CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderMap, HeaderValue,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use super::compression::ContentEncoding;
+
+// Served pages are revalidated on every visit unless the site configures otherwise
+pub const DEFAULT_CACHE_CONTROL: &str = "no-cache";
+
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+pub fn http_date(date: DateTime<Utc>) -> String {
+    date.format(HTTP_DATE_FORMAT).to_string()
+}
+
+pub fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), HTTP_DATE_FORMAT)
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+// Owner configured Cache-Control, limited to plain directives. Falls back to the default
+pub fn site_cache_control(value: Option<&str>) -> String {
+    match value.map(str::trim) {
+        Some(value)
+            if !value.is_empty()
+                && value.len() <= 200
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-=, ".contains(c)) =>
+        {
+            value.to_string()
+        }
+        _ => DEFAULT_CACHE_CONTROL.to_string(),
+    }
+}
+
+// Validators of a served response body
+#[derive(Debug, Clone)]
+pub struct HttpValidators {
+    // Strong ETag, a hash of the body
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl HttpValidators {
+    pub fn new(body: &str, content_updated_at: i64) -> Self {
+        let hash = format!("{:x}", Sha256::digest(body.as_bytes()));
+        HttpValidators {
+            etag: format!("\"{}\"", &hash[..32]),
+            last_modified: DateTime::from_timestamp_millis(content_updated_at)
+                .filter(|_| content_updated_at > 0),
+        }
+    }
+
+    // Each content coding of a page is a separate representation with its own strong ETag
+    pub fn with_encoding(mut self, encoding: ContentEncoding) -> Self {
+        if let Some(coding) = encoding.header_value() {
+            let tag = self.etag.trim_end_matches('"');
+            self.etag = format!("{}-{}\"", tag, coding);
+        }
+        self
+    }
+
+    // Whether a conditional GET can be answered with 304. If-None-Match takes precedence
+    // over If-Modified-Since, as in RFC 9110
+    pub fn is_not_modified(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = headers.get(IF_NONE_MATCH) {
+            let Ok(if_none_match) = if_none_match.to_str() else {
+                return false;
+            };
+            return if_none_match.split(',').any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag.trim_start_matches("W/") == self.etag
+            });
+        }
+        let (Some(last_modified), Some(since)) = (
+            self.last_modified,
+            headers
+                .get(IF_MODIFIED_SINCE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_http_date),
+        ) else {
+            return false;
+        };
+        // HTTP dates have second precision
+        last_modified.timestamp() <= since.timestamp()
+    }
+
+    pub fn headers(&self, cache_control: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(ETAG, etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            if let Ok(value) = HeaderValue::from_str(&http_date(last_modified)) {
+                headers.insert(LAST_MODIFIED, value);
+            }
+        }
+        if let Ok(value) = HeaderValue::from_str(cache_control) {
+            headers.insert(CACHE_CONTROL, value);
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn matches_conditional_requests() {
+        let validators = HttpValidators::new("<html></html>", 1_782_864_000_500);
+        let etag = validators.etag.clone();
+
+        assert!(validators.is_not_modified(&request("if-none-match", &etag)));
+        assert!(
+            validators.is_not_modified(&request("if-none-match", &format!("\"a\", W/{}", etag)))
+        );
+        assert!(!validators.is_not_modified(&request("if-none-match", "\"other\"")));
+        assert!(!validators.is_not_modified(&HeaderMap::new()));
+
+        let brotli = validators.clone().with_encoding(ContentEncoding::Brotli);
+        assert!(brotli.etag.ends_with("-br\""));
+        assert!(!brotli.is_not_modified(&request("if-none-match", &etag)));
+
+        assert!(validators.is_not_modified(&request(
+            "if-modified-since",
+            "Wed, 01 Jul 2026 00:00:00 GMT"
+        )));
+        assert!(!validators.is_not_modified(&request(
+            "if-modified-since",
+            "Tue, 30 Jun 2026 23:59:59 GMT"
+        )));
+    }
+
+    #[test]
+    fn formats_headers() {
+        let validators = HttpValidators::new("body", 1_782_864_000_000);
+        let headers = validators.headers(&site_cache_control(Some("max-age=60\r\nX: y")));
+
+        assert_eq!(
+            headers.get(LAST_MODIFIED).unwrap(),
+            "Wed, 01 Jul 2026 00:00:00 GMT"
+        );
+        assert_eq!(headers.get(CACHE_CONTROL).unwrap(), DEFAULT_CACHE_CONTROL);
+        assert_eq!(
+            site_cache_control(Some("public, max-age=300")),
+            "public, max-age=300"
+        );
+    }
+}
@@ -1,10 +1,15 @@
 use axum::{
-    extract::rejection::JsonRejection::{self, JsonDataError, JsonSyntaxError},
+    extract::{
+        rejection::JsonRejection::{self, JsonDataError, JsonSyntaxError},
+        OptionalFromRequest, Request,
+    },
     http::StatusCode,
+    Json,
 };
 use axum_macros::FromRequest;
 use lib_shared_types::error::api_error::ApiErrorCode;
 use regex::Regex;
+use serde::de::DeserializeOwned;
 use std::error::Error;
 
 use crate::error::api_error::ApiError;
@@ -14,6 +19,21 @@ use crate::error::api_error::ApiError;
 #[from_request(via(axum::Json), rejection(ApiError))]
 pub struct PsJson<T>(pub T);
 
+// Allows `Option<PsJson<T>>` for endpoints where the body is optional.
+// Resolves to `None` when the request has no Content-Type header
+impl<T, S> OptionalFromRequest<S> for PsJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let json = <Json<T> as OptionalFromRequest<S>>::from_request(req, state).await?;
+        Ok(json.map(|Json(value)| PsJson(value)))
+    }
+}
+
 fn field_from_error(err: &str) -> Option<String> {
     if let Ok(regex) = Regex::new(r"missing field `([a-zA-Z0-9]+)`.*") {
         if let Some(captures) = regex.captures(err) {
@@ -0,0 +1,72 @@
+use json_patch::Patch;
+use lib_shared_types::error::api_error::ApiErrorCode;
+use serde_json::Value;
+
+use crate::error::api_error::ApiError;
+
+use super::site_document::{encode_site_document, parse_site_document};
+
+fn invalid_patch(field: &str, message: String) -> ApiError {
+    ApiError::bad_request()
+        .code(ApiErrorCode::InvalidPatch)
+        .message(format!("Failed to patch {}: {}", field, message))
+}
+
+/// Applies an RFC 6902 JSON Patch to a stored site document. The builder stores site data as a
+/// JSON encoded string, in which case the patch is applied to the decoded document and the
+/// result is encoded again.
+pub fn apply_site_patch(field: &str, stored: &str, patch: &Patch) -> Result<Value, ApiError> {
+    let (mut doc, encoded) =
+        parse_site_document(stored).map_err(|e| invalid_patch(field, e.to_string()))?;
+
+    json_patch::patch(&mut doc, patch).map_err(|e| invalid_patch(field, e.to_string()))?;
+
+    Ok(encode_site_document(doc, encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn make_patch(value: Value) -> Patch {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_patch_encoded_document() {
+        let stored = json!(r#"{"home":{"name":"Home","route":"/"}}"#).to_string();
+        let patch = make_patch(json!([
+            { "op": "replace", "path": "/home/name", "value": "Index" },
+            { "op": "add", "path": "/about", "value": { "name": "About", "route": "/about" } },
+        ]));
+
+        let result = apply_site_patch("pages", &stored, &patch).unwrap();
+        let Value::String(encoded) = result else {
+            panic!("Expected an encoded document");
+        };
+        let doc: Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(doc["home"]["name"], "Index");
+        assert_eq!(doc["about"]["route"], "/about");
+    }
+
+    #[test]
+    fn test_patch_plain_document() {
+        let stored = json!({ "theme": { "colors": [] } }).to_string();
+        let patch =
+            make_patch(json!([{ "op": "add", "path": "/theme/colors/-", "value": "#fff" }]));
+
+        let result = apply_site_patch("context", &stored, &patch).unwrap();
+        assert_eq!(result, json!({ "theme": { "colors": ["#fff"] } }));
+    }
+
+    #[test]
+    fn test_patch_failed_test_op() {
+        let stored = json!({ "a": 1 }).to_string();
+        let patch = make_patch(json!([{ "op": "test", "path": "/a", "value": 2 }]));
+
+        let result = apply_site_patch("context", &stored, &patch);
+        assert!(result.is_err_and(|e| e.code == ApiErrorCode::InvalidPatch));
+    }
+}
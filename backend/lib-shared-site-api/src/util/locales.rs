@@ -0,0 +1,145 @@
+use lib_shared_types::domain::site_defaults::SiteLocale;
+use serde_json::Value;
+
+use super::site_document::decode_site_document;
+
+const MAX_LOCALES: usize = 20;
+
+// `en`, `zh-TW`, `sr-Latn-RS`
+fn is_language_tag(code: &str) -> bool {
+    let mut subtags = code.split('-');
+    let language = subtags.next().unwrap_or_default();
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+pub fn validate_locales(locales: &[SiteLocale]) -> Result<(), String> {
+    if locales.len() > MAX_LOCALES {
+        return Err(format!("A site can have at most {} locales", MAX_LOCALES));
+    }
+    for (index, locale) in locales.iter().enumerate() {
+        if !is_language_tag(&locale.code) {
+            return Err(format!("Invalid locale {}", locale.code));
+        }
+        if locales[..index]
+            .iter()
+            .any(|other| other.code.eq_ignore_ascii_case(&locale.code))
+        {
+            return Err(format!("Duplicate locale {}", locale.code));
+        }
+    }
+    Ok(())
+}
+
+// Validate the locales of a site defaults document sent by the builder
+pub fn validate_site_locales(defaults: &Value) -> Result<(), String> {
+    let (defaults, _) =
+        decode_site_document(defaults.clone()).map_err(|_| "Invalid site defaults".to_string())?;
+    let Some(locales) = defaults.get("locales").filter(|locales| !locales.is_null()) else {
+        return Ok(());
+    };
+    let locales: Vec<SiteLocale> =
+        serde_json::from_value(locales.clone()).map_err(|_| "Invalid locales".to_string())?;
+    validate_locales(&locales)
+}
+
+// Language ranges of an Accept-Language header, by descending quality
+fn language_ranges(accept_language: &str) -> Vec<&str> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let range = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!range.is_empty() && quality > 0.0).then_some((range, quality))
+        })
+        .collect();
+    // Stable, so equal qualities keep header order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+// Locale for a visitor's Accept-Language. An exact match wins, then a locale of the same
+// language, `zh` matches `zh-TW`. Falls back to the default locale
+pub fn negotiate_locale<'a>(
+    locales: &'a [SiteLocale],
+    accept_language: Option<&str>,
+) -> Option<&'a SiteLocale> {
+    for range in language_ranges(accept_language.unwrap_or_default()) {
+        if range == "*" {
+            break;
+        }
+        let exact = locales
+            .iter()
+            .find(|locale| locale.code.eq_ignore_ascii_case(range));
+        let language = locales.iter().find(|locale| {
+            primary_language(&locale.code).eq_ignore_ascii_case(primary_language(range))
+        });
+        if let Some(locale) = exact.or(language) {
+            return Some(locale);
+        }
+    }
+    locales.first()
+}
+
+#[cfg(test)]
+mod tests {
+    use lib_shared_types::domain::site_defaults::canonical_locale_path;
+
+    use super::*;
+
+    fn locale(code: &str) -> SiteLocale {
+        SiteLocale {
+            code: code.into(),
+            title: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn negotiates_accept_language() {
+        let locales = vec![locale("zh-TW"), locale("en")];
+        let negotiate =
+            |header: Option<&str>| negotiate_locale(&locales, header).unwrap().code.clone();
+
+        assert_eq!(negotiate(None), "zh-TW");
+        assert_eq!(negotiate(Some("en-US,en;q=0.9")), "en");
+        assert_eq!(negotiate(Some("fr;q=0.9, en;q=0.5, zh;q=0.8")), "zh-TW");
+        assert_eq!(negotiate(Some("zh-tw")), "zh-TW");
+        assert_eq!(negotiate(Some("fr, *;q=0.1")), "zh-TW");
+        assert_eq!(negotiate(Some("en;q=0")), "zh-TW");
+        assert!(negotiate_locale(&[], Some("en")).is_none());
+    }
+
+    #[test]
+    fn validates_and_splits_locales() {
+        assert!(validate_locales(&[locale("en"), locale("zh-TW"), locale("sr-Latn-RS")]).is_ok());
+        assert!(validate_locales(&[locale("english")]).is_err());
+        assert!(validate_locales(&[locale("en/x")]).is_err());
+        assert!(validate_locales(&[locale("en"), locale("EN")]).is_err());
+
+        let zh = locale("zh-TW");
+        assert_eq!(zh.strip_prefix("/zh-tw/about"), Some("/about"));
+        assert_eq!(zh.strip_prefix("/zh-TW"), Some("/"));
+        assert_eq!(zh.strip_prefix("/zh-TWabout"), None);
+        assert_eq!(zh.localize("/"), "/zh-TW");
+        assert_eq!(zh.localize("/about"), "/zh-TW/about");
+
+        let locales = vec![locale("en"), zh];
+        assert_eq!(
+            canonical_locale_path(&locales, "/zh-tw/about"),
+            "/zh-TW/about"
+        );
+        assert_eq!(canonical_locale_path(&locales, "/EN"), "/en");
+        assert_eq!(canonical_locale_path(&locales, "/about"), "/about");
+    }
+}
@@ -0,0 +1,273 @@
+use std::net::IpAddr;
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use lib_shared_types::entity::site_api::maintenance_entity::{MaintenanceEntity, MaintenancePage};
+
+use super::{site_access::get_cookie, site_seo::escape_xml};
+
+// Visiting this path with `?token=` sets the bypass cookie for previewers
+pub const MAINTENANCE_BYPASS_PATH: &str = "/_ps/maintenance";
+pub const BYPASS_COOKIE: &str = "ps_maintenance";
+// Bypass cookies outlive a typical maintenance window
+pub const BYPASS_MAX_AGE_SECS: i64 = 7 * 24 * 60 * 60;
+// Sent as Retry-After when maintenance has no end time
+pub const DEFAULT_RETRY_AFTER_SECS: i64 = 60 * 60;
+
+// Maintenance settings of a site, None fields mean the site has never enabled it
+#[derive(Debug, Clone, Default)]
+pub struct SiteMaintenance {
+    pub enabled: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub page: MaintenancePage,
+    pub message: Option<String>,
+    pub custom_page: Option<String>,
+    pub allowed_ips: Vec<IpAddr>,
+    pub bypass_token: String,
+}
+
+impl From<MaintenanceEntity> for SiteMaintenance {
+    fn from(entity: MaintenanceEntity) -> Self {
+        SiteMaintenance {
+            enabled: entity.enabled,
+            starts_at: entity.starts_at,
+            ends_at: entity.ends_at,
+            page: entity.page,
+            message: entity.message,
+            custom_page: entity.custom_page,
+            // Validated when saved
+            allowed_ips: entity
+                .allowed_ips
+                .iter()
+                .filter_map(|ip| ip.parse().ok())
+                .collect(),
+            bypass_token: entity.bypass_token,
+        }
+    }
+}
+
+impl SiteMaintenance {
+    // Enabled, and inside the start/end window when set
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.enabled
+            && self.starts_at.is_none_or(|starts_at| starts_at <= now)
+            && self.ends_at.is_none_or(|ends_at| now < ends_at)
+    }
+
+    // Seconds until the end of maintenance, a default when it has no end time
+    pub fn retry_after(&self, now: DateTime<Utc>) -> i64 {
+        match self.ends_at {
+            Some(ends_at) => (ends_at - now).num_seconds().max(1),
+            None => DEFAULT_RETRY_AFTER_SECS,
+        }
+    }
+
+    // Allowlisted IPs and previewers holding the bypass cookie see the site
+    pub fn is_allowed(&self, client_ip: Option<IpAddr>, headers: &HeaderMap) -> bool {
+        client_ip.is_some_and(|ip| self.allowed_ips.contains(&ip))
+            || get_cookie(headers, BYPASS_COOKIE)
+                .is_some_and(|token| !self.bypass_token.is_empty() && token == self.bypass_token)
+    }
+
+    // Whether a visitor gets the holding page
+    pub fn blocks(
+        &self,
+        now: DateTime<Utc>,
+        client_ip: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> bool {
+        self.is_active(now) && !self.is_allowed(client_ip, headers)
+    }
+
+    pub fn render_page(&self, title: &str) -> String {
+        match (self.page, &self.custom_page) {
+            (MaintenancePage::Custom, Some(custom_page)) => custom_page.clone(),
+            _ => render_maintenance_page(title, self.message.as_deref()),
+        }
+    }
+}
+
+// Client address of a request. Forwarding headers are only read when the peer is a trusted
+// proxy, and X-Forwarded-For is read from the right, since clients can prepend any address
+pub fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    if !peer.is_some_and(|ip| trusted_proxies.contains(&ip)) {
+        return peer;
+    }
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if forwarded.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|ip| ip.trim().parse().ok())
+            .or(peer);
+    }
+    // The right-most hop not added by a trusted proxy. An unreadable hop stops the walk
+    let mut client = peer;
+    for hop in forwarded.iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(ip) if trusted_proxies.contains(&ip) => client = Some(ip),
+            Ok(ip) => return Some(ip),
+            Err(_) => break,
+        }
+    }
+    client
+}
+
+pub fn validate_maintenance(
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    page: MaintenancePage,
+    custom_page: Option<&str>,
+    allowed_ips: &[String],
+) -> Result<(), String> {
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        if ends_at <= starts_at {
+            return Err("Maintenance must end after it starts".into());
+        }
+    }
+    if page == MaintenancePage::Custom && custom_page.is_none_or(|html| html.trim().is_empty()) {
+        return Err("A custom maintenance page requires custom_page HTML".into());
+    }
+    if let Some(ip) = allowed_ips.iter().find(|ip| ip.parse::<IpAddr>().is_err()) {
+        return Err(format!("Invalid IP address {}", ip));
+    }
+    Ok(())
+}
+
+pub fn bypass_cookie(token: &str, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        BYPASS_COOKIE,
+        token,
+        BYPASS_MAX_AGE_SECS,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+pub fn render_maintenance_page(title: &str, message: Option<&str>) -> String {
+    let message = message
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or("We're making some improvements. Please check back soon.");
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <meta name="viewport" content="width=device-width,initial-scale=1.0" />
+    <meta name="robots" content="noindex" />
+    <title>{title}</title>
+    <style>
+      body {{ font-family: sans-serif; text-align: center; padding: 15vh 24px 0; color: #333; }}
+    </style>
+  </head>
+  <body>
+    <h1>{title}</h1>
+    <p>{message}</p>
+  </body>
+</html>
+"#,
+        title = escape_xml(title),
+        message = escape_xml(message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderValue};
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn applies_maintenance_window() {
+        let now = Utc::now();
+        let mut maintenance = SiteMaintenance {
+            enabled: true,
+            ends_at: Some(now + Duration::minutes(10)),
+            ..Default::default()
+        };
+        assert!(maintenance.is_active(now));
+        assert_eq!(maintenance.retry_after(now), 600);
+        assert!(!maintenance.is_active(now + Duration::minutes(10)));
+
+        maintenance.starts_at = Some(now + Duration::minutes(5));
+        assert!(!maintenance.is_active(now));
+        assert!(maintenance.is_active(now + Duration::minutes(5)));
+
+        maintenance.ends_at = None;
+        assert_eq!(maintenance.retry_after(now), DEFAULT_RETRY_AFTER_SECS);
+        maintenance.enabled = false;
+        assert!(!maintenance.is_active(now + Duration::minutes(5)));
+
+        assert!(
+            validate_maintenance(Some(now), Some(now), MaintenancePage::Builtin, None, &[])
+                .is_err()
+        );
+        assert!(validate_maintenance(None, None, MaintenancePage::Custom, None, &[]).is_err());
+        assert!(
+            validate_maintenance(None, None, MaintenancePage::Builtin, None, &["::1".into()])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn allows_ips_and_bypass_cookie() {
+        let maintenance = SiteMaintenance {
+            enabled: true,
+            allowed_ips: vec!["203.0.113.7".parse().unwrap()],
+            bypass_token: "token".into(),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let mut headers = HeaderMap::new();
+        assert!(maintenance.blocks(now, None, &headers));
+
+        let proxies: Vec<IpAddr> = vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.2"),
+        );
+        let ip = client_ip(&headers, Some("10.0.0.1".parse().unwrap()), &proxies);
+        assert_eq!(ip, Some("203.0.113.7".parse().unwrap()));
+        assert!(!maintenance.blocks(now, ip, &headers));
+
+        // Addresses prepended by the client, or sent straight to the API, are not trusted
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 198.51.100.1"),
+        );
+        let ip = client_ip(&headers, Some("10.0.0.1".parse().unwrap()), &proxies);
+        assert_eq!(ip, Some("198.51.100.1".parse().unwrap()));
+        let peer = Some("198.51.100.2".parse().unwrap());
+        assert_eq!(client_ip(&headers, peer, &proxies), peer);
+        assert!(maintenance.blocks(now, peer, &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("203.0.113.7"));
+        let ip = client_ip(&headers, Some("10.0.0.1".parse().unwrap()), &proxies);
+        assert_eq!(ip, Some("203.0.113.7".parse().unwrap()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("ps_maintenance=token"),
+        );
+        assert!(!maintenance.blocks(now, None, &headers));
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("ps_maintenance=other"),
+        );
+        assert!(maintenance.blocks(now, None, &headers));
+    }
+}
@@ -1,5 +1,24 @@
+pub mod acme;
+pub mod analytics;
+pub mod compression;
 pub mod conversion;
+pub mod domain_verification;
 pub mod domains;
+pub mod experiments;
 pub mod get_site_html;
+pub mod http_cache;
 pub mod json_extractor;
+pub mod json_patch;
+pub mod locales;
 pub mod log_format;
+pub mod maintenance;
+pub mod page_head;
+pub mod password;
+pub mod redirects;
+pub mod site_access;
+pub mod site_archive;
+pub mod site_document;
+pub mod site_merge;
+pub mod site_seo;
+pub mod ssg_hash;
+pub mod unique_visitors;
@@ -0,0 +1,279 @@
+use lib_shared_types::cache::site_data::CachedPageHead;
+use serde_json::{json, Map, Value};
+
+use super::site_seo::escape_xml;
+
+// Placeholders in the head of the SPA shell (index.html)
+const TITLE_PLACEHOLDER: &str = "Pub Studio";
+const DESCRIPTION_PLACEHOLDER: &str = "DESCRIPTION";
+const URL_PLACEHOLDER: &str = "https://pubstud.io";
+const LANG_PLACEHOLDER: &str = r#"<html lang="en">"#;
+const HEAD_END: &str = "</head>";
+// Defaults of the shell, replaced when the page meta sets its own
+const OG_TYPE_META: &str = r#"<meta property="og:type" content="pubstudio:site" />"#;
+const TWITTER_CARD_META: &str = r#"<meta property="twitter:card" content="summary_large_image" />"#;
+
+// Meta tags rendered from the page head fields, page meta entries with these keys are skipped
+const RENDERED_META: [&str; 11] = [
+    "description",
+    "og:title",
+    "og:description",
+    "og:url",
+    "og:image",
+    "og:type",
+    "twitter:title",
+    "twitter:description",
+    "twitter:url",
+    "twitter:image",
+    "twitter:card",
+];
+
+// Replace placeholders in a single pass, so inserted values are never replaced again
+fn replace_placeholders(template: &str, replacements: &[(&str, &str)]) -> String {
+    let mut html = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some((index, placeholder, value)) = replacements
+        .iter()
+        .filter_map(|(placeholder, value)| {
+            rest.find(placeholder)
+                .map(|index| (index, *placeholder, *value))
+        })
+        .min_by_key(|(index, _, _)| *index)
+    {
+        html.push_str(&rest[..index]);
+        html.push_str(value);
+        rest = &rest[index + placeholder.len()..];
+    }
+    html.push_str(rest);
+    html
+}
+
+fn meta_tag(attribute: &str, key: &str, content: &str) -> String {
+    format!(
+        r#"<meta {}="{}" content="{}" />"#,
+        attribute,
+        escape_xml(key),
+        escape_xml(content)
+    )
+}
+
+// Content of a page meta entry, by property or name
+fn page_meta<'a>(head: &'a CachedPageHead, key: &str) -> Option<&'a str> {
+    head.meta
+        .iter()
+        .find(|meta| meta.property.as_deref().or(meta.name.as_deref()) == Some(key))
+        .and_then(|meta| meta.content.as_deref())
+}
+
+fn absolute_url(base_url: Option<&str>, href: &str) -> String {
+    match base_url {
+        Some(base_url) if href.starts_with('/') => {
+            format!("{}{}", base_url.trim_end_matches('/'), href)
+        }
+        _ => href.to_string(),
+    }
+}
+
+// schema.org WebPage. `<` is escaped so page values cannot close the script element
+fn json_ld(head: &CachedPageHead, url: Option<&str>) -> String {
+    let mut page = Map::new();
+    page.insert("@context".into(), json!("https://schema.org"));
+    page.insert("@type".into(), json!("WebPage"));
+    page.insert("name".into(), json!(head.title));
+    page.insert("description".into(), json!(head.description));
+    if let Some(url) = url {
+        page.insert("url".into(), json!(url));
+    }
+    if let Some(image) = &head.image {
+        page.insert("image".into(), json!(image));
+    }
+    Value::Object(page)
+        .to_string()
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+}
+
+fn head_tags(head: &CachedPageHead, url: Option<&str>, base_url: Option<&str>) -> Vec<String> {
+    let mut tags = vec![meta_tag(
+        "property",
+        "twitter:description",
+        &head.description,
+    )];
+    if let Some(image) = &head.image {
+        let image = absolute_url(base_url, image);
+        tags.push(meta_tag("property", "og:image", &image));
+        tags.push(meta_tag("property", "twitter:image", &image));
+    }
+    for meta in &head.meta {
+        let (attribute, key) = match (&meta.property, &meta.name) {
+            (Some(property), _) => ("property", property),
+            (None, Some(name)) => ("name", name),
+            _ => continue,
+        };
+        if let Some(content) = &meta.content {
+            if !RENDERED_META.contains(&key.as_str()) {
+                tags.push(meta_tag(attribute, key, content));
+            }
+        }
+    }
+    if let Some(url) = url {
+        tags.push(format!(
+            r#"<link rel="canonical" href="{}" />"#,
+            escape_xml(url)
+        ));
+    }
+    for link in &head.alternates {
+        if let (Some(hreflang), Some(href)) = (&link.hreflang, &link.href) {
+            tags.push(format!(
+                r#"<link rel="alternate" hreflang="{}" href="{}" />"#,
+                escape_xml(hreflang),
+                escape_xml(&absolute_url(base_url, href))
+            ));
+        }
+    }
+    tags.push(format!(
+        r#"<script type="application/ld+json">{}</script>"#,
+        json_ld(head, url)
+    ));
+    tags
+}
+
+// Render the head of a page into the SPA shell. Values are HTML escaped, and the canonical URL
+// is only set when the site has a domain to build it from.
+pub fn render_page_head(template: &str, head: &CachedPageHead, base_url: Option<&str>) -> String {
+    let Some(head_end) = template.find(HEAD_END) else {
+        return template.to_string();
+    };
+    let url = base_url.map(|base_url| format!("{}{}", base_url.trim_end_matches('/'), head.route));
+    let title = escape_xml(&head.title);
+    let description = escape_xml(&head.description);
+    let url_value = url.as_deref().map(escape_xml);
+    let lang = head
+        .lang
+        .as_deref()
+        .map(|lang| format!(r#"<html lang="{}">"#, escape_xml(lang)));
+
+    let mut replacements = vec![
+        (TITLE_PLACEHOLDER, title.as_str()),
+        (DESCRIPTION_PLACEHOLDER, description.as_str()),
+    ];
+    if let Some(url_value) = &url_value {
+        replacements.push((URL_PLACEHOLDER, url_value.as_str()));
+    }
+    if let Some(lang) = &lang {
+        replacements.push((LANG_PLACEHOLDER, lang.as_str()));
+    }
+    let og_type =
+        page_meta(head, "og:type").map(|og_type| meta_tag("property", "og:type", og_type));
+    if let Some(og_type) = &og_type {
+        replacements.push((OG_TYPE_META, og_type.as_str()));
+    }
+    let twitter_card =
+        page_meta(head, "twitter:card").map(|card| meta_tag("property", "twitter:card", card));
+    if let Some(twitter_card) = &twitter_card {
+        replacements.push((TWITTER_CARD_META, twitter_card.as_str()));
+    }
+    let head_html = &template[..head_end];
+    // Indentation of the closing head tag, tags are inserted one level deeper
+    let indent = &head_html[head_html.trim_end_matches([' ', '\t']).len()..];
+    let mut html = replace_placeholders(head_html, &replacements);
+    for tag in head_tags(head, url.as_deref(), base_url) {
+        html.push_str("  ");
+        html.push_str(&tag);
+        html.push('\n');
+        html.push_str(indent);
+    }
+    html.push_str(&template[head_end..]);
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use lib_shared_types::domain::site_defaults::SiteHeadMeta;
+
+    use super::*;
+
+    const TEMPLATE: &str = r#"<html lang="en"><head><title>Pub Studio</title><meta name="description" content="DESCRIPTION" /><meta property="og:url" content="https://pubstud.io" /><meta property="og:type" content="pubstudio:site" /><meta property="twitter:card" content="summary_large_image" />
+</head><body>Pub Studio</body>"#;
+
+    fn property_meta(property: &str, content: &str) -> SiteHeadMeta {
+        SiteHeadMeta {
+            content: Some(content.into()),
+            http_equiv: None,
+            name: None,
+            property: Some(property.into()),
+        }
+    }
+
+    #[test]
+    fn renders_escaped_head() {
+        let head = CachedPageHead {
+            route: "/about".into(),
+            title: "Tom & Jerry </title><script>DESCRIPTION".into(),
+            description: r#"Say "hi""#.into(),
+            image: Some("/img/a.png".into()),
+            meta: vec![SiteHeadMeta {
+                content: Some("cats".into()),
+                http_equiv: None,
+                name: Some("keywords".into()),
+                property: None,
+            }],
+            alternates: vec![],
+            lang: Some("zh-TW".into()),
+            ..Default::default()
+        };
+        let html = render_page_head(TEMPLATE, &head, Some("https://a.com"));
+
+        assert!(
+            html.contains("<title>Tom &amp; Jerry &lt;/title&gt;&lt;script&gt;DESCRIPTION</title>")
+        );
+        assert!(html.contains(r#"content="Say &quot;hi&quot;""#));
+        assert!(html.contains(r#"<meta property="og:url" content="https://a.com/about" />"#));
+        assert!(html.contains(r#"<link rel="canonical" href="https://a.com/about" />"#));
+        assert!(html.contains(r#"<meta property="og:image" content="https://a.com/img/a.png" />"#));
+        assert!(html.contains(r#"<meta name="keywords" content="cats" />"#));
+        assert!(html.contains(r#""name":"Tom \u0026 Jerry \u003c/title\u003e"#));
+        assert!(html.starts_with(r#"<html lang="zh-TW"><head>"#));
+        // The body is not part of the head template
+        assert!(html.ends_with("</head><body>Pub Studio</body>"));
+    }
+
+    #[test]
+    fn renders_without_base_url() {
+        let head = CachedPageHead {
+            route: "/".into(),
+            title: "Site".into(),
+            description: "Desc".into(),
+            ..Default::default()
+        };
+        let html = render_page_head(TEMPLATE, &head, None);
+        assert!(!html.contains("canonical"));
+        assert!(html.contains(r#"content="https://pubstud.io""#));
+        assert!(!html.contains("og:image"));
+        assert!(html.starts_with(r#"<html lang="en">"#));
+    }
+
+    #[test]
+    fn replaces_shell_meta_defaults() {
+        let mut head = CachedPageHead {
+            route: "/".into(),
+            title: "Site".into(),
+            description: "Desc".into(),
+            ..Default::default()
+        };
+        let html = render_page_head(TEMPLATE, &head, None);
+        assert_eq!(html.matches(OG_TYPE_META).count(), 1);
+        assert_eq!(html.matches(TWITTER_CARD_META).count(), 1);
+
+        head.meta = vec![
+            property_meta("og:type", "article"),
+            property_meta("twitter:card", "summary"),
+        ];
+        let html = render_page_head(TEMPLATE, &head, None);
+        assert_eq!(html.matches("og:type").count(), 1);
+        assert_eq!(html.matches("twitter:card").count(), 1);
+        assert!(html.contains(r#"<meta property="og:type" content="article" />"#));
+        assert!(html.contains(r#"<meta property="twitter:card" content="summary" />"#));
+    }
+}
@@ -0,0 +1,45 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+use crate::error::api_error::ApiError;
+
+// Hash a password into a PHC string, suitable for storing in the database
+pub fn hash_password(password: &str) -> Result<String, ApiError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| ApiError::internal_error().message(e))?
+        .to_string())
+}
+
+// Returns false if the password does not match, or the stored hash is malformed
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+// Argon2 takes tens of milliseconds, so it runs off the async runtime
+pub async fn verify_password_blocking(password: String, password_hash: String) -> bool {
+    tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+        .await
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_hashed_password() {
+        let hash = hash_password("hunter22").unwrap();
+        assert!(verify_password("hunter22", &hash));
+        assert!(!verify_password("hunter23", &hash));
+        assert!(!verify_password("hunter22", "not-a-hash"));
+    }
+}
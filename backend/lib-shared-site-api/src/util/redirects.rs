@@ -0,0 +1,445 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+
+use lib_shared_types::domain::site_defaults::TrailingSlashPolicy;
+
+// Source patterns are exact paths (`/old`), prefixes ending in `/*` (`/blog/*`), and may
+// contain `:param` segments (`/posts/:slug`). Targets are a site path or an absolute URL, and
+// may reference the source's params, and `*` for the remainder of a prefix match.
+
+const MAX_SEGMENTS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct RedirectPattern {
+    segments: Vec<Segment>,
+    prefix: bool,
+}
+
+fn path_segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+fn is_param_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl RedirectPattern {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if !source.starts_with('/') {
+            return Err("Redirect source must start with /".into());
+        }
+        if source.contains(['?', '#']) {
+            return Err("Redirect source must be a path without query or fragment".into());
+        }
+        let mut segments = path_segments(source);
+        let prefix = segments.last() == Some(&"*");
+        if prefix {
+            segments.pop();
+        }
+        if segments.len() > MAX_SEGMENTS {
+            return Err("Redirect source has too many segments".into());
+        }
+        let mut parsed = Vec::with_capacity(segments.len());
+        for segment in segments {
+            if segment.contains('*') {
+                return Err("Wildcard is only allowed as the last segment, e.g. /blog/*".into());
+            }
+            match segment.strip_prefix(':') {
+                Some(name) if is_param_name(name) => {
+                    if parsed.contains(&Segment::Param(name.to_string())) {
+                        return Err(format!("Duplicate redirect param :{}", name));
+                    }
+                    parsed.push(Segment::Param(name.to_string()))
+                }
+                Some(name) => return Err(format!("Invalid redirect param :{}", name)),
+                None => parsed.push(Segment::Literal(segment.to_string())),
+            }
+        }
+        Ok(RedirectPattern {
+            segments: parsed,
+            prefix,
+        })
+    }
+
+    // Exact patterns have no params or wildcard, and take priority when matching
+    pub fn is_exact(&self) -> bool {
+        !self.prefix
+            && self
+                .segments
+                .iter()
+                .all(|s| matches!(s, Segment::Literal(_)))
+    }
+
+    // Captured params of a matching path, with the prefix remainder under `*`.
+    // Trailing slashes are ignored.
+    pub fn matches(&self, path: &str) -> Option<HashMap<String, String>> {
+        let segments = path_segments(path);
+        if segments.len() < self.segments.len()
+            || (!self.prefix && segments.len() != self.segments.len())
+        {
+            return None;
+        }
+        let mut captures = HashMap::new();
+        for (pattern, segment) in self.segments.iter().zip(&segments) {
+            match pattern {
+                Segment::Literal(literal) if literal == segment => {}
+                Segment::Literal(_) => return None,
+                Segment::Param(name) => {
+                    captures.insert(name.clone(), segment.to_string());
+                }
+            }
+        }
+        if self.prefix {
+            captures.insert("*".into(), segments[self.segments.len()..].join("/"));
+        }
+        Some(captures)
+    }
+
+    fn has_param(&self, name: &str) -> bool {
+        self.segments.contains(&Segment::Param(name.to_string()))
+    }
+}
+
+// Parse and validate a redirect rule. Targets may only reference params of the source
+pub fn validate_redirect(source: &str, target: &str) -> Result<RedirectPattern, String> {
+    let pattern = RedirectPattern::parse(source)?;
+    let is_url = target.starts_with("https://") || target.starts_with("http://");
+    if !is_url && !target.starts_with('/') {
+        return Err("Redirect target must be a path starting with / or an http(s) URL".into());
+    }
+    // Sent as the Location header value
+    if !target.chars().all(|c| c.is_ascii_graphic()) {
+        return Err("Redirect target must be URL encoded, without spaces".into());
+    }
+    for name in target_params(target) {
+        if !pattern.has_param(name) {
+            return Err(format!(
+                "Redirect target references unknown param :{}",
+                name
+            ));
+        }
+    }
+    if target.contains('*') && !pattern.prefix {
+        return Err("Redirect target uses * but the source has no wildcard".into());
+    }
+    if pattern.is_exact() && path_segments(source) == path_segments(target) {
+        return Err("Redirect source and target are the same".into());
+    }
+    Ok(pattern)
+}
+
+// `:name` references in a target. Names start with a letter, so URL ports are not params
+fn target_params(target: &str) -> Vec<&str> {
+    let mut params = vec![];
+    let mut rest = target;
+    while let Some(index) = rest.find(':') {
+        rest = &rest[index + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if is_param_name(name) {
+            params.push(name);
+        }
+        rest = &rest[end..];
+    }
+    params
+}
+
+// Location of a matched redirect. The request query is kept when the target has none
+pub fn resolve_target(
+    target: &str,
+    captures: &HashMap<String, String>,
+    query: Option<&str>,
+) -> String {
+    let mut location = target.to_string();
+    let mut names: Vec<&str> = target_params(target);
+    // Replace longer names first, so `:id` does not clobber `:idx`
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    for name in names {
+        if let Some(value) = captures.get(name) {
+            location = location.replace(&format!(":{}", name), value);
+        }
+    }
+    if let Some(rest) = captures.get("*") {
+        location = location.replace('*', rest);
+    }
+    match query {
+        Some(query) if !query.is_empty() && !location.contains('?') => {
+            format!("{}?{}", location, query)
+        }
+        _ => location,
+    }
+}
+
+// Location of the canonical trailing slash form of `path`, None when it already is. Leading
+// slashes are collapsed, browsers read `//host` and `/\host` locations as another site
+pub fn trailing_slash_redirect(
+    policy: TrailingSlashPolicy,
+    path: &str,
+    query: Option<&str>,
+) -> Option<String> {
+    let path = &format!("/{}", path.trim_start_matches(['/', '\\']));
+    let location = match policy {
+        TrailingSlashPolicy::Ignore => return None,
+        TrailingSlashPolicy::Remove if path.len() > 1 && path.ends_with('/') => {
+            let trimmed = path.trim_end_matches('/');
+            if trimmed.is_empty() {
+                "/".to_string()
+            } else {
+                trimmed.to_string()
+            }
+        }
+        TrailingSlashPolicy::Add
+            if !path.ends_with('/')
+                && !path
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|file| file.contains('.')) =>
+        {
+            format!("{}/", path)
+        }
+        _ => return None,
+    };
+    Some(match query {
+        Some(query) if !query.is_empty() => format!("{}?{}", location, query),
+        _ => location,
+    })
+}
+
+// Enabled redirect rule of a site, with its pattern parsed
+#[derive(Debug, Clone)]
+pub struct RedirectRule {
+    pub id: i64,
+    pub pattern: RedirectPattern,
+    pub target: String,
+    pub status_code: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchedRedirect {
+    pub id: i64,
+    pub location: String,
+    pub status_code: u16,
+}
+
+// The redirect rules of a site in match order: exact sources first, then patterns in
+// creation order
+#[derive(Debug, Clone, Default)]
+pub struct SiteRedirects {
+    rules: Vec<RedirectRule>,
+}
+
+impl SiteRedirects {
+    pub fn new(mut rules: Vec<RedirectRule>) -> Self {
+        rules.sort_by_key(|rule| (!rule.pattern.is_exact(), rule.id));
+        SiteRedirects { rules }
+    }
+
+    pub fn find(&self, path: &str, query: Option<&str>) -> Option<MatchedRedirect> {
+        self.rules.iter().find_map(|rule| {
+            let captures = rule.pattern.matches(path)?;
+            Some(MatchedRedirect {
+                id: rule.id,
+                location: resolve_target(&rule.target, &captures, query),
+                status_code: rule.status_code,
+            })
+        })
+    }
+}
+
+// Hits of a redirect since the last persist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedirectHitCount {
+    pub hits: i64,
+    pub last_hit_at: DateTime<Utc>,
+}
+
+impl RedirectHitCount {
+    fn add(&mut self, other: RedirectHitCount) {
+        self.hits += other.hits;
+        self.last_hit_at = self.last_hit_at.max(other.last_hit_at);
+    }
+}
+
+// Redirect hits counted in memory, until they are persisted. Keyed by site and redirect ID
+#[derive(Debug, Clone, Default)]
+pub struct RedirectHits {
+    counts: Arc<Mutex<HashMap<(String, i64), RedirectHitCount>>>,
+}
+
+impl RedirectHits {
+    pub fn record(&self, site_id: &str, redirect_id: i64, now: DateTime<Utc>) {
+        self.add(
+            (site_id.to_string(), redirect_id),
+            RedirectHitCount {
+                hits: 1,
+                last_hit_at: now,
+            },
+        );
+    }
+
+    fn add(&self, key: (String, i64), count: RedirectHitCount) {
+        let mut counts = self.counts.lock().unwrap();
+        counts
+            .entry(key)
+            .and_modify(|existing| existing.add(count))
+            .or_insert(count);
+    }
+
+    // Hits of a site's redirects that are not persisted yet
+    pub fn pending(&self, site_id: &str) -> HashMap<i64, RedirectHitCount> {
+        let counts = self.counts.lock().unwrap();
+        counts
+            .iter()
+            .filter(|((id, _), _)| id == site_id)
+            .map(|((_, redirect_id), count)| (*redirect_id, *count))
+            .collect()
+    }
+
+    // Take the hits to persist, grouped by site. Hits recorded meanwhile are counted again
+    pub fn drain(&self) -> HashMap<String, Vec<(i64, RedirectHitCount)>> {
+        let mut sites: HashMap<String, Vec<(i64, RedirectHitCount)>> = HashMap::new();
+        for ((site_id, redirect_id), count) in self.counts.lock().unwrap().drain() {
+            sites.entry(site_id).or_default().push((redirect_id, count));
+        }
+        sites
+    }
+
+    // Put back hits that failed to persist, to retry on the next persist
+    pub fn restore(&self, site_id: &str, counts: Vec<(i64, RedirectHitCount)>) {
+        for (redirect_id, count) in counts {
+            self.add((site_id.to_string(), redirect_id), count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: i64, source: &str, target: &str) -> RedirectRule {
+        RedirectRule {
+            id,
+            pattern: validate_redirect(source, target).unwrap(),
+            target: target.to_string(),
+            status_code: 301,
+        }
+    }
+
+    #[test]
+    fn matches_patterns() {
+        let redirects = SiteRedirects::new(vec![
+            rule(1, "/blog/*", "https://blog.example.com/*"),
+            rule(2, "/posts/:year/:slug", "/articles/:slug-:year"),
+            rule(3, "/blog/old", "/blog/new"),
+            rule(4, "/about", "/company/about"),
+        ]);
+
+        let matched = redirects.find("/about/", None).unwrap();
+        assert_eq!(
+            (matched.id, matched.location.as_str()),
+            (4, "/company/about")
+        );
+        // Exact rules win over earlier prefix rules
+        assert_eq!(redirects.find("/blog/old", None).unwrap().id, 3);
+        assert_eq!(
+            redirects
+                .find("/blog/2024/post", Some("a=1"))
+                .unwrap()
+                .location,
+            "https://blog.example.com/2024/post?a=1"
+        );
+        assert_eq!(
+            redirects.find("/posts/2024/hello", None).unwrap().location,
+            "/articles/hello-2024"
+        );
+        assert!(redirects.find("/posts/2024", None).is_none());
+        assert!(redirects.find("/aboutus", None).is_none());
+    }
+
+    #[test]
+    fn applies_trailing_slash_policy() {
+        use TrailingSlashPolicy::*;
+
+        assert_eq!(trailing_slash_redirect(Ignore, "/about/", None), None);
+        assert_eq!(
+            trailing_slash_redirect(Remove, "/about//", Some("a=1")).as_deref(),
+            Some("/about?a=1")
+        );
+        assert_eq!(trailing_slash_redirect(Remove, "/", None), None);
+        assert_eq!(
+            trailing_slash_redirect(Add, "/about", None).as_deref(),
+            Some("/about/")
+        );
+        assert_eq!(trailing_slash_redirect(Add, "/logo.png", None), None);
+        assert_eq!(trailing_slash_redirect(Add, "/", None), None);
+        // Never an off-site location
+        assert_eq!(
+            trailing_slash_redirect(Remove, "//evil.example/", None).as_deref(),
+            Some("/evil.example")
+        );
+        assert_eq!(
+            trailing_slash_redirect(Add, "//evil.example/login", None).as_deref(),
+            Some("/evil.example/login/")
+        );
+        assert_eq!(
+            trailing_slash_redirect(Add, "/\\evil.example/login", None).as_deref(),
+            Some("/evil.example/login/")
+        );
+    }
+
+    #[test]
+    fn validates_rules() {
+        assert!(validate_redirect("old", "/new").is_err());
+        assert!(validate_redirect("/a/*/b", "/new").is_err());
+        assert!(validate_redirect("/a/:1", "/new").is_err());
+        assert!(validate_redirect("/a/:id", "/b/:slug").is_err());
+        assert!(validate_redirect("/a", "/b/*").is_err());
+        assert!(validate_redirect("/a/", "/a").is_err());
+        assert!(validate_redirect("/a", "javascript:alert(1)").is_err());
+        assert!(validate_redirect("/a", "/b\r\nSet-Cookie: x").is_err());
+        assert!(validate_redirect("/a", "http://example.com:8080/a").is_ok());
+        assert!(validate_redirect("/a/:id", "/b?id=:id").is_ok());
+    }
+
+    #[test]
+    fn counts_redirect_hits() {
+        let first = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let last = DateTime::from_timestamp(1_800_000_060, 0).unwrap();
+        let hits = RedirectHits::default();
+        hits.record("site", 1, last);
+        hits.record("site", 1, first);
+        hits.record("other", 1, first);
+
+        let pending = hits.pending("site");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[&1],
+            RedirectHitCount {
+                hits: 2,
+                last_hit_at: last
+            }
+        );
+
+        // Failed persists are retried with hits recorded meanwhile
+        let mut drained = hits.drain();
+        assert!(hits.pending("site").is_empty());
+        hits.record("site", 1, first);
+        hits.restore("site", drained.remove("site").unwrap());
+        assert_eq!(hits.pending("site")[&1].hits, 3);
+        assert_eq!(hits.pending("site")[&1].last_hit_at, last);
+    }
+}
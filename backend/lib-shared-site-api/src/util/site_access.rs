@@ -0,0 +1,370 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::auth::types::AccessGrant;
+
+use super::site_seo::escape_xml;
+
+// Login form target, served on the site's own domain like the hydration runtime
+pub const SITE_LOGIN_PATH: &str = "/_ps/login";
+pub const ACCESS_COOKIE: &str = "ps_access";
+// Visitors stay logged in for a week
+pub const ACCESS_TTL_MINUTES: i64 = 7 * 24 * 60;
+// Unlocked preview link, sent as a cookie, or as a header by API clients
+pub const PREVIEW_COOKIE: &str = "ps_preview";
+pub const PREVIEW_TOKEN_HEADER: &str = "x-preview-token";
+pub const PREVIEW_TTL_MINUTES: i64 = 24 * 60;
+// Visitors are locked out of a site's logins after this many failed passwords in a row, until
+// LOGIN_LOCKOUT_MINUTES pass without another failure
+pub const MAX_LOGIN_FAILURES: u32 = 5;
+pub const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+// Bounds the memory used by visitors that never come back
+const MAX_TRACKED_LOGINS: usize = 10_000;
+
+// A password protected route prefix of a site
+#[derive(Debug, Clone)]
+pub struct ProtectedRoute {
+    pub id: i64,
+    pub route: String,
+    pub password_hash: String,
+}
+
+impl ProtectedRoute {
+    pub fn grant(&self) -> AccessGrant {
+        AccessGrant {
+            id: self.id,
+            fp: password_fingerprint(&self.password_hash),
+        }
+    }
+}
+
+// Short digest of a password hash, stored in access cookies instead of the hash
+pub fn password_fingerprint(password_hash: &str) -> String {
+    format!("{:x}", Sha256::digest(password_hash.as_bytes()))[..16].to_string()
+}
+
+// `/members` covers `/members` and `/members/a`, but not `/membership`
+fn route_covers(prefix: &str, route: &str) -> bool {
+    prefix == "/"
+        || route == prefix
+        || route
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+// Whether a page route is public, locked, or unlocked by the visitor's cookie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageAccess {
+    Public,
+    Locked,
+    Unlocked,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SiteProtections {
+    routes: Vec<ProtectedRoute>,
+}
+
+impl SiteProtections {
+    pub fn new(mut routes: Vec<ProtectedRoute>) -> Self {
+        // The longest prefix protects a route, so nested areas can have their own password
+        routes.sort_by_key(|route| std::cmp::Reverse(route.route.len()));
+        SiteProtections { routes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn find(&self, route: &str) -> Option<&ProtectedRoute> {
+        self.routes.iter().find(|p| route_covers(&p.route, route))
+    }
+
+    pub fn access(&self, route: &str, grants: &[AccessGrant]) -> PageAccess {
+        match self.find(route) {
+            None => PageAccess::Public,
+            Some(protected) if grants.contains(&protected.grant()) => PageAccess::Unlocked,
+            Some(_) => PageAccess::Locked,
+        }
+    }
+}
+
+// Protected routes are page routes or prefixes of them, without query or wildcards
+pub fn validate_protected_route(route: &str) -> Result<(), String> {
+    if !route.starts_with('/') {
+        return Err("Protected route must start with /".into());
+    }
+    if route.contains(['?', '#', '*', ':']) || route.contains("//") {
+        return Err("Protected route must be a plain path, e.g. /members".into());
+    }
+    if !route.chars().all(|c| c.is_ascii_graphic()) {
+        return Err("Protected route must be URL encoded, without spaces".into());
+    }
+    Ok(())
+}
+
+pub fn get_cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            (key == name).then_some(value)
+        })
+}
+
+pub fn access_cookie(token: &str, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        ACCESS_COOKIE,
+        token,
+        ACCESS_TTL_MINUTES * 60,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+pub fn preview_cookie(token: &str, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        PREVIEW_COOKIE,
+        token,
+        PREVIEW_TTL_MINUTES * 60,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+// Preview token of a request, from the header or the cookie
+pub fn preview_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(PREVIEW_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| get_cookie(headers, PREVIEW_COOKIE))
+}
+
+// Only same site paths are accepted after login, `//host` would leave the site
+pub fn safe_redirect_path(redirect: Option<&str>) -> String {
+    match redirect {
+        Some(path)
+            if path.starts_with('/')
+                && !path.starts_with("//")
+                && !path.contains('\\')
+                && path.chars().all(|c| c.is_ascii_graphic()) =>
+        {
+            path.to_string()
+        }
+        _ => "/".into(),
+    }
+}
+
+// Login page of protected routes, and of password protected preview links
+pub fn render_login_page(
+    title: &str,
+    redirect: &str,
+    preview: Option<&str>,
+    error: Option<&str>,
+) -> String {
+    let error = match error {
+        Some(error) => format!(r#"<p class="error">{}</p>"#, escape_xml(error)),
+        None => String::new(),
+    };
+    let preview = match preview {
+        Some(preview) => format!(
+            r#"<input type="hidden" name="preview" value="{}" />"#,
+            escape_xml(preview)
+        ),
+        None => String::new(),
+    };
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <meta name="viewport" content="width=device-width,initial-scale=1.0" />
+    <meta name="robots" content="noindex" />
+    <title>{title}</title>
+    <style>
+      body {{ font-family: sans-serif; display: flex; justify-content: center; padding-top: 15vh; }}
+      form {{ display: flex; flex-direction: column; gap: 12px; width: 280px; }}
+      input, button {{ padding: 8px; font-size: 16px; }}
+      .error {{ color: #c62828; margin: 0; }}
+    </style>
+  </head>
+  <body>
+    <form method="post" action="{action}">
+      <h1>{title}</h1>
+      <p>This page is password protected.</p>
+      {error}
+      <input type="hidden" name="redirect" value="{redirect}" />
+      {preview}
+      <input type="password" name="password" placeholder="Password" autofocus required />
+      <button type="submit">Enter</button>
+    </form>
+  </body>
+</html>
+"#,
+        title = escape_xml(title),
+        action = SITE_LOGIN_PATH,
+        error = error,
+        redirect = escape_xml(redirect),
+        preview = preview,
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LoginFailures {
+    count: u32,
+    last_failure_at: DateTime<Utc>,
+}
+
+impl LoginFailures {
+    fn is_current(&self, now: DateTime<Utc>) -> bool {
+        now - self.last_failure_at < Duration::minutes(LOGIN_LOCKOUT_MINUTES)
+    }
+}
+
+// Failed site and preview logins counted in memory, keyed by site and client address. Checked
+// before hashing a password, so guessing is throttled and can't tie up the blocking pool
+#[derive(Debug, Clone, Default)]
+pub struct LoginAttempts {
+    failures: Arc<Mutex<HashMap<(String, IpAddr), LoginFailures>>>,
+}
+
+impl LoginAttempts {
+    pub fn is_locked(&self, site_id: &str, ip: IpAddr, now: DateTime<Utc>) -> bool {
+        let failures = self.failures.lock().unwrap();
+        failures
+            .get(&(site_id.to_string(), ip))
+            .is_some_and(|f| f.count >= MAX_LOGIN_FAILURES && f.is_current(now))
+    }
+
+    pub fn record_failure(&self, site_id: &str, ip: IpAddr, now: DateTime<Utc>) {
+        let mut failures = self.failures.lock().unwrap();
+        if failures.len() >= MAX_TRACKED_LOGINS {
+            failures.retain(|_, f| f.is_current(now));
+        }
+        let entry = failures
+            .entry((site_id.to_string(), ip))
+            .or_insert(LoginFailures {
+                count: 0,
+                last_failure_at: now,
+            });
+        if !entry.is_current(now) {
+            entry.count = 0;
+        }
+        entry.count += 1;
+        entry.last_failure_at = now;
+    }
+
+    // A successful login starts the count over
+    pub fn clear(&self, site_id: &str, ip: IpAddr) {
+        let mut failures = self.failures.lock().unwrap();
+        failures.remove(&(site_id.to_string(), ip));
+    }
+
+    pub fn clear_all(&self) {
+        self.failures.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn protected(id: i64, route: &str) -> ProtectedRoute {
+        ProtectedRoute {
+            id,
+            route: route.into(),
+            password_hash: format!("hash-{}", id),
+        }
+    }
+
+    #[test]
+    fn matches_longest_prefix() {
+        let protections =
+            SiteProtections::new(vec![protected(1, "/members"), protected(2, "/members/vip")]);
+        assert_eq!(protections.find("/members").unwrap().id, 1);
+        assert_eq!(protections.find("/members/a").unwrap().id, 1);
+        assert_eq!(protections.find("/members/vip/b").unwrap().id, 2);
+        assert!(protections.find("/membership").is_none());
+
+        let grants = vec![protections.find("/members").unwrap().grant()];
+        assert_eq!(protections.access("/about", &grants), PageAccess::Public);
+        assert_eq!(
+            protections.access("/members/a", &grants),
+            PageAccess::Unlocked
+        );
+        assert_eq!(
+            protections.access("/members/vip", &grants),
+            PageAccess::Locked
+        );
+
+        // A changed password invalidates existing grants
+        let changed = SiteProtections::new(vec![ProtectedRoute {
+            password_hash: "new".into(),
+            ..protected(1, "/members")
+        }]);
+        assert_eq!(changed.access("/members", &grants), PageAccess::Locked);
+
+        let site = SiteProtections::new(vec![protected(3, "/")]);
+        assert_eq!(site.find("/home").unwrap().id, 3);
+    }
+
+    #[test]
+    fn parses_cookies_and_redirects() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("a=1; ps_access=token; b=2"),
+        );
+        assert_eq!(get_cookie(&headers, ACCESS_COOKIE), Some("token"));
+        assert_eq!(get_cookie(&headers, "c"), None);
+
+        assert_eq!(safe_redirect_path(Some("/members?a=1")), "/members?a=1");
+        assert_eq!(safe_redirect_path(Some("//evil.com")), "/");
+        assert_eq!(safe_redirect_path(Some("https://evil.com")), "/");
+        assert_eq!(safe_redirect_path(None), "/");
+
+        assert!(validate_protected_route("/members").is_ok());
+        assert!(validate_protected_route("members").is_err());
+        assert!(validate_protected_route("/members/*").is_err());
+    }
+
+    #[test]
+    fn locks_out_repeated_login_failures() {
+        let attempts = LoginAttempts::default();
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "203.0.113.8".parse().unwrap();
+        let now = Utc::now();
+
+        for _ in 0..MAX_LOGIN_FAILURES {
+            assert!(!attempts.is_locked("site", ip, now));
+            attempts.record_failure("site", ip, now);
+        }
+        assert!(attempts.is_locked("site", ip, now));
+        assert!(!attempts.is_locked("site", other, now));
+        assert!(!attempts.is_locked("other-site", ip, now));
+
+        let later = now + Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+        assert!(!attempts.is_locked("site", ip, later));
+        attempts.record_failure("site", ip, later);
+        assert!(!attempts.is_locked("site", ip, later));
+
+        attempts.record_failure("site", other, now);
+        attempts.clear("site", other);
+        for _ in 1..MAX_LOGIN_FAILURES {
+            attempts.record_failure("site", other, now);
+        }
+        assert!(!attempts.is_locked("site", other, now));
+    }
+}
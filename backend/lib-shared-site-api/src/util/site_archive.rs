@@ -0,0 +1,367 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read, Write},
+};
+
+use lib_shared_types::{
+    dto::site_api::site_archive_dto::{
+        SiteArchive, SiteArchiveManifest, SiteArchiveStaticPage, SiteArchiveTable,
+        SiteArchiveVersion, SITE_ARCHIVE_FORMAT_VERSION,
+    },
+    error::api_error::ApiErrorCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::api_error::ApiError;
+
+// Number of custom data rows stored in each rows file
+pub const SITE_ARCHIVE_ROWS_PER_FILE: usize = 500;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SITE_VERSIONS_DIR: &str = "site_versions";
+const CUSTOM_TABLES_DIR: &str = "custom_tables";
+const TABLE_FILE: &str = "table.json";
+const ROWS_DIR: &str = "rows";
+const STATIC_PAGES_DIR: &str = "static_pages";
+
+// A file in a site archive. Versions, tables, row chunks and static pages are numbered in the
+// order they are written
+enum ArchiveEntry {
+    Manifest,
+    Version(usize),
+    Table(usize),
+    Rows(usize, usize),
+    StaticPage(usize),
+}
+
+fn parse_index(file: &str) -> Option<usize> {
+    file.strip_suffix(".json")?.parse().ok()
+}
+
+impl ArchiveEntry {
+    fn path(&self) -> String {
+        match self {
+            Self::Manifest => MANIFEST_FILE.to_string(),
+            Self::Version(i) => format!("{}/{}.json", SITE_VERSIONS_DIR, i),
+            Self::Table(i) => format!("{}/{}/{}", CUSTOM_TABLES_DIR, i, TABLE_FILE),
+            Self::Rows(table, i) => {
+                format!("{}/{}/{}/{}.json", CUSTOM_TABLES_DIR, table, ROWS_DIR, i)
+            }
+            Self::StaticPage(i) => format!("{}/{}.json", STATIC_PAGES_DIR, i),
+        }
+    }
+
+    fn parse(path: &str) -> Option<Self> {
+        let parts: Vec<&str> = path.split('/').collect();
+        match parts.as_slice() {
+            [MANIFEST_FILE] => Some(Self::Manifest),
+            [SITE_VERSIONS_DIR, file] => parse_index(file).map(Self::Version),
+            [CUSTOM_TABLES_DIR, table, TABLE_FILE] => table.parse().ok().map(Self::Table),
+            [CUSTOM_TABLES_DIR, table, ROWS_DIR, file] => {
+                Some(Self::Rows(table.parse().ok()?, parse_index(file)?))
+            }
+            [STATIC_PAGES_DIR, file] => parse_index(file).map(Self::StaticPage),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_archive<T: std::fmt::Display>(message: T) -> ApiError {
+    ApiError::bad_request()
+        .code(ApiErrorCode::InvalidSiteArchive)
+        .message(message)
+}
+
+fn append_json<T: Serialize, W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    value: &T,
+) -> Result<(), ApiError> {
+    let data =
+        serde_json::to_vec_pretty(value).map_err(|e| ApiError::internal_error().message(e))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    builder
+        .append_data(&mut header, path, data.as_slice())
+        .map_err(|e| ApiError::internal_error().message(e))
+}
+
+// Writes a site archive as an uncompressed tar of JSON files, one entry at a time, so an export
+// only holds the version, rows chunk or static page being written
+pub struct SiteArchiveWriter<W: Write> {
+    builder: tar::Builder<W>,
+    versions: usize,
+    tables: usize,
+    rows: usize,
+    static_pages: usize,
+}
+
+impl<W: Write> SiteArchiveWriter<W> {
+    pub fn new(writer: W, manifest: &SiteArchiveManifest) -> Result<Self, ApiError> {
+        let mut builder = tar::Builder::new(writer);
+        append_json(&mut builder, &ArchiveEntry::Manifest.path(), manifest)?;
+        Ok(Self {
+            builder,
+            versions: 0,
+            tables: 0,
+            rows: 0,
+            static_pages: 0,
+        })
+    }
+
+    // Versions are written oldest first: the published version (if any) followed by the draft
+    pub fn append_version(&mut self, version: &SiteArchiveVersion) -> Result<(), ApiError> {
+        append_json(
+            &mut self.builder,
+            &ArchiveEntry::Version(self.versions).path(),
+            version,
+        )?;
+        self.versions += 1;
+        Ok(())
+    }
+
+    // Write the table definition. Its rows follow with `append_rows`
+    pub fn append_table(&mut self, table: &SiteArchiveTable) -> Result<(), ApiError> {
+        append_json(
+            &mut self.builder,
+            &ArchiveEntry::Table(self.tables).path(),
+            table,
+        )?;
+        self.tables += 1;
+        self.rows = 0;
+        Ok(())
+    }
+
+    // Write a chunk of rows of the last appended table
+    pub fn append_rows(&mut self, rows: &[BTreeMap<String, String>]) -> Result<(), ApiError> {
+        let table = self
+            .tables
+            .checked_sub(1)
+            .ok_or(ApiError::internal_error().message("Rows written before their table"))?;
+        append_json(
+            &mut self.builder,
+            &ArchiveEntry::Rows(table, self.rows).path(),
+            &rows,
+        )?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn append_static_page(&mut self, page: &SiteArchiveStaticPage) -> Result<(), ApiError> {
+        append_json(
+            &mut self.builder,
+            &ArchiveEntry::StaticPage(self.static_pages).path(),
+            page,
+        )?;
+        self.static_pages += 1;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<W, ApiError> {
+        self.builder
+            .into_inner()
+            .map_err(|e| ApiError::internal_error().message(e))
+    }
+}
+
+impl SiteArchiveWriter<Vec<u8>> {
+    // Take the bytes written since the last call, so they can be sent before the next entry
+    pub fn take_written(&mut self) -> Vec<u8> {
+        std::mem::take(self.builder.get_mut())
+    }
+}
+
+// Write an archive that is already in memory
+pub fn write_site_archive<W: Write>(archive: &SiteArchive, writer: W) -> Result<W, ApiError> {
+    let mut writer = SiteArchiveWriter::new(writer, &archive.manifest)?;
+    for version in archive.versions.iter() {
+        writer.append_version(version)?;
+    }
+    for table in archive.custom_tables.iter() {
+        writer.append_table(table)?;
+        for rows in table.rows.chunks(SITE_ARCHIVE_ROWS_PER_FILE) {
+            writer.append_rows(rows)?;
+        }
+    }
+    for page in archive.static_pages.iter() {
+        writer.append_static_page(page)?;
+    }
+    writer.finish()
+}
+
+fn parse_json<T: DeserializeOwned>(path: &str, data: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(data).map_err(|e| invalid_archive(format!("Invalid {}: {}", path, e)))
+}
+
+// Read and validate the structure of a site archive. Content limits are checked by the caller
+pub fn read_site_archive(data: &[u8]) -> Result<SiteArchive, ApiError> {
+    let mut manifest: Option<SiteArchiveManifest> = None;
+    let mut versions = vec![];
+    let mut custom_tables: Vec<SiteArchiveTable> = vec![];
+    let mut static_pages = vec![];
+
+    let mut tar = tar::Archive::new(Cursor::new(data));
+    let entries = tar.entries().map_err(invalid_archive)?;
+    for entry in entries {
+        let mut entry = entry.map_err(invalid_archive)?;
+        let path = entry
+            .path()
+            .map_err(invalid_archive)?
+            .to_string_lossy()
+            .to_string();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(invalid_archive)?;
+
+        // Entries must be in the order they are written, starting with the manifest
+        match ArchiveEntry::parse(&path) {
+            Some(ArchiveEntry::Manifest) if manifest.is_none() => {
+                let value: SiteArchiveManifest = parse_json(&path, &content)?;
+                if value.format_version != SITE_ARCHIVE_FORMAT_VERSION {
+                    return Err(invalid_archive(format!(
+                        "Unsupported archive format version {}",
+                        value.format_version
+                    )));
+                }
+                manifest = Some(value);
+            }
+            _ if manifest.is_none() => {
+                return Err(invalid_archive(format!(
+                    "Archive must start with {}",
+                    MANIFEST_FILE
+                )))
+            }
+            Some(ArchiveEntry::Version(i)) if i == versions.len() => {
+                versions.push(parse_json(&path, &content)?);
+            }
+            Some(ArchiveEntry::Table(i)) if i == custom_tables.len() => {
+                custom_tables.push(parse_json(&path, &content)?);
+            }
+            Some(ArchiveEntry::Rows(table, _)) if table + 1 == custom_tables.len() => {
+                let rows: Vec<BTreeMap<String, String>> = parse_json(&path, &content)?;
+                custom_tables[table].rows.extend(rows);
+            }
+            Some(ArchiveEntry::StaticPage(i)) if i == static_pages.len() => {
+                static_pages.push(parse_json(&path, &content)?);
+            }
+            _ => return Err(invalid_archive(format!("Unexpected file {}", path))),
+        }
+    }
+
+    let manifest = manifest.ok_or(invalid_archive(format!(
+        "Archive is missing {}",
+        MANIFEST_FILE
+    )))?;
+    let archive = SiteArchive {
+        manifest,
+        versions,
+        custom_tables,
+        static_pages,
+    };
+
+    // At most one published version, followed by at most one draft
+    let valid_versions = match archive.versions.as_slice() {
+        [_] => true,
+        [published, _] => published.published,
+        _ => false,
+    };
+    if !valid_versions {
+        return Err(invalid_archive(
+            "Archive must contain the published version and/or the draft",
+        ));
+    }
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+
+    fn site_version(published: bool) -> SiteArchiveVersion {
+        SiteArchiveVersion {
+            name: "Site".into(),
+            version: "0.1".into(),
+            context: "\"{}\"".into(),
+            defaults: "\"{}\"".into(),
+            editor: "\"{}\"".into(),
+            history: "\"{}\"".into(),
+            pages: "\"{}\"".into(),
+            page_order: "\"[]\"".into(),
+            published,
+            content_updated_at: 1,
+        }
+    }
+
+    fn site_archive(versions: Vec<SiteArchiveVersion>) -> SiteArchive {
+        SiteArchive {
+            manifest: SiteArchiveManifest {
+                format_version: SITE_ARCHIVE_FORMAT_VERSION,
+                schema_version: 20230414104517,
+                site_id: "6d2c8359-6094-402c-bcbb-37202fd7c336".into(),
+                name: "Site".into(),
+                exported_at: Utc::now(),
+            },
+            versions,
+            custom_tables: vec![SiteArchiveTable {
+                name: "contact".into(),
+                columns: json!({}),
+                events: json!([]),
+                // Spans two rows files
+                rows: (0..=SITE_ARCHIVE_ROWS_PER_FILE)
+                    .map(|i| BTreeMap::from([("id".to_string(), i.to_string())]))
+                    .collect(),
+            }],
+            static_pages: vec![SiteArchiveStaticPage {
+                route: "/".into(),
+                body: "<html></html>".into(),
+                content_type: "text/html".into(),
+                content_updated_at: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn round_trips_archive() {
+        let data = write_site_archive(
+            &site_archive(vec![site_version(true), site_version(false)]),
+            vec![],
+        )
+        .unwrap();
+        let archive = read_site_archive(&data).unwrap();
+
+        assert_eq!(archive.versions.len(), 2);
+        assert_eq!(archive.custom_tables[0].name, "contact");
+        assert_eq!(
+            archive.custom_tables[0].rows.len(),
+            SITE_ARCHIVE_ROWS_PER_FILE + 1
+        );
+        assert_eq!(archive.custom_tables[0].rows[1]["id"], "1");
+        assert_eq!(archive.static_pages[0].route, "/");
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        for versions in [
+            vec![],
+            vec![site_version(false), site_version(false)],
+            vec![site_version(true), site_version(false), site_version(false)],
+        ] {
+            let data = write_site_archive(&site_archive(versions), vec![]).unwrap();
+            assert!(read_site_archive(&data).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_format() {
+        let mut archive = site_archive(vec![site_version(true)]);
+        archive.manifest.format_version = SITE_ARCHIVE_FORMAT_VERSION + 1;
+        let data = write_site_archive(&archive, vec![]).unwrap();
+
+        assert!(read_site_archive(&data).is_err());
+        assert!(read_site_archive(b"not a tar file").is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use serde_json::Value;
+
+// The builder sends site data (`pages`, `context`, `defaults`) as JSON encoded strings, which
+// are stored as JSON string values. These helpers unwrap and re-wrap the encoded document.
+
+/// Decode a site document value. Returns the document, and whether it was JSON encoded
+pub fn decode_site_document(value: Value) -> Result<(Value, bool), serde_json::Error> {
+    match value {
+        Value::String(inner) => Ok((serde_json::from_str(&inner)?, true)),
+        value => Ok((value, false)),
+    }
+}
+
+/// Parse and decode a site document stored in a site_versions column
+pub fn parse_site_document(stored: &str) -> Result<(Value, bool), serde_json::Error> {
+    decode_site_document(serde_json::from_str(stored)?)
+}
+
+/// Reverse `decode_site_document`
+pub fn encode_site_document(doc: Value, encoded: bool) -> Value {
+    if encoded {
+        Value::String(doc.to_string())
+    } else {
+        doc
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use lib_shared_types::dto::site_api::site_merge_conflict_viewmodel::SiteMergeConflict;
+use serde_json::{Map, Value};
+
+// Three-way merge of site documents. `base` is the revision the editor session started from,
+// `mine` is the incoming update, and `theirs` is the latest stored version.
+
+/// Three-way merge of a single value. `None` means the value is absent (deleted).
+/// Returns `Err` if both sides changed the value differently
+fn merge_value(
+    base: Option<&Value>,
+    mine: Option<&Value>,
+    theirs: Option<&Value>,
+) -> Result<Option<Value>, ()> {
+    if mine == theirs || theirs == base {
+        Ok(mine.cloned())
+    } else if mine == base {
+        Ok(theirs.cloned())
+    } else {
+        Err(())
+    }
+}
+
+fn conflict(field: &str, route: Option<&str>, component_id: Option<&str>) -> SiteMergeConflict {
+    SiteMergeConflict {
+        field: field.to_string(),
+        route: route.map(|r| r.to_string()),
+        component_id: component_id.map(|c| c.to_string()),
+    }
+}
+
+/// Merge a whole document, such as `context` or `defaults`
+pub fn merge_document(
+    field: &str,
+    base: &Value,
+    mine: &Value,
+    theirs: &Value,
+) -> Result<Value, Vec<SiteMergeConflict>> {
+    match merge_value(Some(base), Some(mine), Some(theirs)) {
+        Ok(value) => Ok(value.unwrap_or(Value::Null)),
+        Err(()) => Err(vec![conflict(field, None, None)]),
+    }
+}
+
+fn key_union(maps: [&Map<String, Value>; 3]) -> BTreeSet<&String> {
+    maps.into_iter().flat_map(|m| m.keys()).collect()
+}
+
+/// Merge `pages`, a map of route to page. Pages changed on both sides are merged per component
+pub fn merge_pages(
+    base: &Value,
+    mine: &Value,
+    theirs: &Value,
+) -> Result<Value, Vec<SiteMergeConflict>> {
+    let (Some(base_map), Some(mine_map), Some(theirs_map)) =
+        (base.as_object(), mine.as_object(), theirs.as_object())
+    else {
+        return merge_document("pages", base, mine, theirs);
+    };
+
+    let mut merged = Map::new();
+    let mut conflicts = vec![];
+
+    for route in key_union([base_map, mine_map, theirs_map]) {
+        let (b, m, t) = (
+            base_map.get(route),
+            mine_map.get(route),
+            theirs_map.get(route),
+        );
+        match merge_value(b, m, t) {
+            Ok(Some(page)) => {
+                merged.insert(route.clone(), page);
+            }
+            Ok(None) => {}
+            Err(()) => match (b, m, t) {
+                (Some(b), Some(m), Some(t)) => match merge_page(route, b, m, t) {
+                    Ok(page) => {
+                        merged.insert(route.clone(), page);
+                    }
+                    Err(mut page_conflicts) => conflicts.append(&mut page_conflicts),
+                },
+                // Deleted on one side and modified on the other
+                _ => conflicts.push(conflict("pages", Some(route), None)),
+            },
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(Value::Object(merged))
+    } else {
+        Err(conflicts)
+    }
+}
+
+// A component without its children, and the IDs of its children
+struct FlatComponent {
+    props: Value,
+    children: Option<Value>,
+}
+
+fn flatten_component(component: &Value, out: &mut HashMap<String, FlatComponent>) {
+    let Some(id) = component.get("id").and_then(|id| id.as_str()) else {
+        return;
+    };
+    let mut props = component.clone();
+    let children = props
+        .as_object_mut()
+        .and_then(|p| p.remove("children"))
+        .and_then(|c| match c {
+            Value::Array(children) => Some(children),
+            _ => None,
+        });
+
+    let child_ids = children.as_ref().map(|children| {
+        for child in children.iter() {
+            flatten_component(child, out);
+        }
+        Value::Array(
+            children
+                .iter()
+                .filter_map(|c| c.get("id").cloned())
+                .collect(),
+        )
+    });
+    out.insert(
+        id.to_string(),
+        FlatComponent {
+            props,
+            children: child_ids,
+        },
+    );
+}
+
+fn flatten_page(page: &Value) -> HashMap<String, FlatComponent> {
+    let mut components = HashMap::new();
+    if let Some(root) = page.get("root") {
+        flatten_component(root, &mut components);
+    }
+    components
+}
+
+// Parent ID by component ID
+fn parent_ids(components: &HashMap<String, FlatComponent>) -> HashMap<&str, &str> {
+    components
+        .iter()
+        .flat_map(|(id, component)| {
+            component
+                .children
+                .iter()
+                .flat_map(id_list)
+                .map(move |child_id| (child_id, id.as_str()))
+        })
+        .collect()
+}
+
+fn id_list(value: &Value) -> Vec<&str> {
+    value
+        .as_array()
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Merge child lists changed on both sides. Takes the order from `theirs`, removes children
+/// removed in `mine`, and inserts children added in `mine` after their preceding sibling.
+/// Returns `None` if `mine` reordered existing children
+fn merge_children(base: &Value, mine: &Value, theirs: &Value) -> Option<Value> {
+    let (base, mine, theirs) = (id_list(base), id_list(mine), id_list(theirs));
+    let base_set: HashSet<&str> = base.iter().copied().collect();
+    let mine_set: HashSet<&str> = mine.iter().copied().collect();
+
+    let base_kept: Vec<&str> = base
+        .iter()
+        .copied()
+        .filter(|id| mine_set.contains(id))
+        .collect();
+    let mine_kept: Vec<&str> = mine
+        .iter()
+        .copied()
+        .filter(|id| base_set.contains(id))
+        .collect();
+    if base_kept != mine_kept {
+        return None;
+    }
+
+    let mut merged: Vec<&str> = theirs
+        .iter()
+        .copied()
+        .filter(|id| !base_set.contains(id) || mine_set.contains(id))
+        .collect();
+    for (i, id) in mine.iter().enumerate() {
+        if base_set.contains(id) || merged.contains(id) {
+            continue;
+        }
+        let position = mine[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| merged.iter().position(|m| m == prev))
+            .map(|p| p + 1)
+            .unwrap_or(0);
+        merged.insert(position, id);
+    }
+    Some(Value::Array(
+        merged
+            .into_iter()
+            .map(|id| Value::String(id.into()))
+            .collect(),
+    ))
+}
+
+fn build_component(
+    id: &str,
+    components: &HashMap<String, (Value, Option<Value>)>,
+    visited: &mut HashSet<String>,
+) -> Option<Value> {
+    if !visited.insert(id.to_string()) {
+        return None;
+    }
+    let (props, children) = components.get(id)?;
+    let mut component = props.clone();
+    if let (Some(children), Some(obj)) = (children, component.as_object_mut()) {
+        let built: Vec<Value> = id_list(children)
+            .into_iter()
+            .filter_map(|child_id| build_component(child_id, components, visited))
+            .collect();
+        obj.insert("children".into(), Value::Array(built));
+    }
+    Some(component)
+}
+
+fn merge_page(
+    route: &str,
+    base: &Value,
+    mine: &Value,
+    theirs: &Value,
+) -> Result<Value, Vec<SiteMergeConflict>> {
+    let (Some(base_obj), Some(mine_obj), Some(theirs_obj)) =
+        (base.as_object(), mine.as_object(), theirs.as_object())
+    else {
+        return Err(vec![conflict("pages", Some(route), None)]);
+    };
+    let mut conflicts = vec![];
+    let mut merged = Map::new();
+
+    // Page level properties
+    for key in key_union([base_obj, mine_obj, theirs_obj]) {
+        if key == "root" {
+            continue;
+        }
+        match merge_value(base_obj.get(key), mine_obj.get(key), theirs_obj.get(key)) {
+            Ok(Some(value)) => {
+                merged.insert(key.clone(), value);
+            }
+            Ok(None) => {}
+            Err(()) => conflicts.push(conflict("pages", Some(route), None)),
+        }
+    }
+
+    // Components
+    let (base_c, mine_c, theirs_c) = (flatten_page(base), flatten_page(mine), flatten_page(theirs));
+    let ids: BTreeSet<&String> = base_c
+        .keys()
+        .chain(mine_c.keys())
+        .chain(theirs_c.keys())
+        .collect();
+    let (base_p, mine_p, theirs_p) = (
+        parent_ids(&base_c),
+        parent_ids(&mine_c),
+        parent_ids(&theirs_c),
+    );
+    let mut merged_components: HashMap<String, (Value, Option<Value>)> = HashMap::new();
+
+    for id in ids {
+        // Moved to different parents, or moved on one side and removed on the other
+        let (pb, pm, pt) = (
+            base_p.get(id.as_str()),
+            mine_p.get(id.as_str()),
+            theirs_p.get(id.as_str()),
+        );
+        if pm != pt && pm != pb && pt != pb {
+            conflicts.push(conflict("pages", Some(route), Some(id)));
+            continue;
+        }
+        let (b, m, t) = (base_c.get(id), mine_c.get(id), theirs_c.get(id));
+        let props = match merge_value(
+            b.map(|c| &c.props),
+            m.map(|c| &c.props),
+            t.map(|c| &c.props),
+        ) {
+            Ok(Some(props)) => props,
+            Ok(None) => continue,
+            Err(()) => {
+                conflicts.push(conflict("pages", Some(route), Some(id)));
+                continue;
+            }
+        };
+        let (bc, mc, tc) = (
+            b.and_then(|c| c.children.as_ref()),
+            m.and_then(|c| c.children.as_ref()),
+            t.and_then(|c| c.children.as_ref()),
+        );
+        let children = match merge_value(bc, mc, tc) {
+            Ok(children) => children,
+            Err(()) => {
+                let empty = Value::Array(vec![]);
+                let merged_children = merge_children(
+                    bc.unwrap_or(&empty),
+                    mc.unwrap_or(&empty),
+                    tc.unwrap_or(&empty),
+                );
+                if merged_children.is_none() {
+                    conflicts.push(conflict("pages", Some(route), Some(id)));
+                }
+                merged_children
+            }
+        };
+        merged_components.insert(id.clone(), (props, children));
+    }
+
+    if !conflicts.is_empty() {
+        let mut seen = HashSet::new();
+        conflicts.retain(|c| seen.insert((c.route.clone(), c.component_id.clone())));
+        return Err(conflicts);
+    }
+
+    let root_id = [theirs, mine, base]
+        .iter()
+        .find_map(|page| page.get("root")?.get("id")?.as_str());
+    let root = match root_id {
+        Some(root_id) => {
+            let mut visited = HashSet::new();
+            let root = build_component(root_id, &merged_components, &mut visited);
+            // Components left out of the tree, e.g. added under a parent removed on the other side
+            let mut orphans: Vec<&String> = merged_components
+                .keys()
+                .filter(|id| !visited.contains(*id))
+                .collect();
+            if !orphans.is_empty() {
+                orphans.sort();
+                return Err(orphans
+                    .into_iter()
+                    .map(|id| conflict("pages", Some(route), Some(id)))
+                    .collect());
+            }
+            root
+        }
+        // Pages without a component tree are merged as a whole
+        None => merge_value(base.get("root"), mine.get("root"), theirs.get("root"))
+            .map_err(|_| vec![conflict("pages", Some(route), None)])?,
+    };
+    if let Some(root) = root {
+        merged.insert("root".into(), root);
+    }
+    Ok(Value::Object(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn page(title: &str, children: Value) -> Value {
+        json!({
+            "name": "Home",
+            "route": "/",
+            "root": {
+                "id": "root",
+                "name": title,
+                "children": children,
+            },
+        })
+    }
+
+    fn text(id: &str, content: &str) -> Value {
+        json!({ "id": id, "content": content })
+    }
+
+    #[test]
+    fn test_merge_separate_pages() {
+        let base = json!({ "/": page("Root", json!([])), "/about": page("About", json!([])) });
+        let mut mine = base.clone();
+        mine["/"] = page("Root 2", json!([]));
+        let mut theirs = base.clone();
+        theirs["/about"] = page("About 2", json!([]));
+
+        let merged = merge_pages(&base, &mine, &theirs).unwrap();
+        assert_eq!(merged["/"]["root"]["name"], "Root 2");
+        assert_eq!(merged["/about"]["root"]["name"], "About 2");
+    }
+
+    #[test]
+    fn test_merge_separate_components() {
+        let base = json!({ "/": page("Root", json!([text("a", "A"), text("b", "B")])) });
+        let mine = json!({ "/": page("Root", json!([text("a", "A2"), text("b", "B")])) });
+        let theirs = json!({
+            "/": page("Root", json!([text("a", "A"), text("b", "B2"), text("c", "C")]))
+        });
+
+        let merged = merge_pages(&base, &mine, &theirs).unwrap();
+        assert_eq!(
+            merged["/"]["root"]["children"],
+            json!([text("a", "A2"), text("b", "B2"), text("c", "C")])
+        );
+    }
+
+    #[test]
+    fn test_merge_added_children() {
+        let base = json!({ "/": page("Root", json!([text("a", "A")])) });
+        let mine = json!({ "/": page("Root", json!([text("m", "M"), text("a", "A")])) });
+        let theirs = json!({ "/": page("Root", json!([text("a", "A"), text("t", "T")])) });
+
+        let merged = merge_pages(&base, &mine, &theirs).unwrap();
+        assert_eq!(
+            merged["/"]["root"]["children"],
+            json!([text("m", "M"), text("a", "A"), text("t", "T")])
+        );
+    }
+
+    #[test]
+    fn test_merge_component_conflict() {
+        let base = json!({ "/": page("Root", json!([text("a", "A"), text("b", "B")])) });
+        let mine = json!({ "/": page("Root", json!([text("a", "A2"), text("b", "B")])) });
+        let theirs = json!({ "/": page("Root", json!([text("a", "A3")])) });
+
+        let conflicts = merge_pages(&base, &mine, &theirs).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].route.as_deref(), Some("/"));
+        assert_eq!(conflicts[0].component_id.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_merge_moved_component() {
+        let base = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [text("a", "A")] },
+            { "id": "q", "children": [] },
+        ])) });
+        let mine = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [] },
+            { "id": "q", "children": [text("a", "A")] },
+        ])) });
+        let theirs = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [text("a", "A2")] },
+            { "id": "q", "children": [] },
+            text("t", "T"),
+        ])) });
+
+        let merged = merge_pages(&base, &mine, &theirs).unwrap();
+        assert_eq!(
+            merged["/"]["root"]["children"],
+            json!([
+                { "id": "p", "children": [] },
+                { "id": "q", "children": [text("a", "A2")] },
+                text("t", "T"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_moved_component_conflict() {
+        let base = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [text("a", "A")] },
+            { "id": "q", "children": [] },
+        ])) });
+        let mine = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [] },
+            { "id": "q", "children": [text("a", "A")] },
+        ])) });
+        let theirs = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [] },
+            { "id": "q", "children": [] },
+            text("a", "A"),
+        ])) });
+
+        let conflicts = merge_pages(&base, &mine, &theirs).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].component_id.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_merge_added_child_of_deleted_parent_conflict() {
+        let base = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [] },
+            text("b", "B"),
+        ])) });
+        let mine = json!({ "/": page("Root", json!([
+            { "id": "p", "children": [text("m", "M")] },
+            text("b", "B"),
+        ])) });
+        let theirs = json!({ "/": page("Root", json!([text("b", "B2")])) });
+
+        let conflicts = merge_pages(&base, &mine, &theirs).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].component_id.as_deref(), Some("m"));
+    }
+
+    #[test]
+    fn test_merge_deleted_page_conflict() {
+        let base = json!({ "/about": page("About", json!([])) });
+        let mine = json!({ "/about": page("About 2", json!([])) });
+        let theirs = json!({});
+
+        let conflicts = merge_pages(&base, &mine, &theirs).unwrap_err();
+        assert_eq!(conflicts[0].route.as_deref(), Some("/about"));
+        assert_eq!(conflicts[0].component_id, None);
+    }
+}
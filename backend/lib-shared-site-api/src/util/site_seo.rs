@@ -0,0 +1,285 @@
+use chrono::{DateTime, SecondsFormat};
+use lib_shared_types::{
+    domain::site_defaults::{SiteHeadDefaults, SiteLocale, SiteRobotsRule},
+    entity::site_api::site_entity::SiteEntity,
+};
+use serde_json::Value;
+
+use super::{site_access::SiteProtections, site_document::parse_site_document};
+
+pub const SITEMAP_PATH: &str = "/sitemap.xml";
+pub const ROBOTS_PATH: &str = "/robots.txt";
+
+// Sitemap and robots.txt of a published site version
+#[derive(Debug, Clone)]
+pub struct SiteSeoFiles {
+    // None when the site has no verified domain to build absolute URLs from
+    pub sitemap: Option<String>,
+    pub robots: String,
+}
+
+impl SiteSeoFiles {
+    // Body and content type of the file served at `path`
+    pub fn get(&self, path: &str) -> Option<(String, &'static str)> {
+        match path {
+            SITEMAP_PATH => self
+                .sitemap
+                .clone()
+                .map(|sitemap| (sitemap, "application/xml")),
+            ROBOTS_PATH => Some((self.robots.clone(), "text/plain")),
+            _ => None,
+        }
+    }
+}
+
+pub fn is_seo_file_path(path: &str) -> bool {
+    path == SITEMAP_PATH || path == ROBOTS_PATH
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+// Robots values are owner provided, and must not start new directives
+fn robots_value(value: &str) -> String {
+    value.replace(['\r', '\n'], "").trim().to_string()
+}
+
+// Public page routes in page order, with the home page served at `/`. Password protected
+// pages are left out
+fn sitemap_routes(
+    pages: &Value,
+    page_order: Vec<String>,
+    home_page: &str,
+    protections: &SiteProtections,
+) -> Vec<String> {
+    let Value::Object(pages) = pages else {
+        return vec![];
+    };
+    // `pageOrder` is missing from older sites, fall back to the stored page order
+    let order = if page_order.is_empty() {
+        pages.keys().cloned().collect()
+    } else {
+        page_order
+    };
+    order
+        .into_iter()
+        .filter(|route| {
+            let public = pages
+                .get(route)
+                .and_then(|page| page.get("public"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            public && route != "/not-found" && protections.find(route).is_none()
+        })
+        .map(|route| {
+            if route == home_page {
+                "/".into()
+            } else {
+                route
+            }
+        })
+        .collect()
+}
+
+// hreflang alternates of a route, every locale and `x-default` for the unprefixed route
+fn sitemap_alternates(origin: &str, route: &str, locales: &[SiteLocale]) -> String {
+    locales
+        .iter()
+        .map(|locale| (locale.code.as_str(), locale.localize(route)))
+        .chain([("x-default", route.to_string())])
+        .map(|(hreflang, href)| {
+            format!(
+                r#"<xhtml:link rel="alternate" hreflang="{}" href="{}"/>"#,
+                escape_xml(hreflang),
+                escape_xml(&format!("{}{}", origin, href))
+            )
+        })
+        .collect()
+}
+
+// Multi-language sites list each route once per locale, with hreflang alternates
+pub fn emit_sitemap(
+    routes: &[String],
+    base_url: &str,
+    content_updated_at: i64,
+    locales: &[SiteLocale],
+) -> String {
+    let origin = base_url.trim_end_matches('/');
+    let lastmod = DateTime::from_timestamp_millis(content_updated_at)
+        .filter(|_| content_updated_at > 0)
+        .map(|date| {
+            format!(
+                "<lastmod>{}</lastmod>",
+                date.to_rfc3339_opts(SecondsFormat::Millis, true)
+            )
+        })
+        .unwrap_or_default();
+    let url = |path: &str, alternates: &str| {
+        format!(
+            "<url><loc>{}</loc>{}{}</url>",
+            escape_xml(&format!("{}{}", origin, path)),
+            lastmod,
+            alternates
+        )
+    };
+    let (urls, urlset): (Vec<String>, _) = if locales.is_empty() {
+        (
+            routes.iter().map(|route| url(route, "")).collect(),
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        )
+    } else {
+        (
+            routes
+                .iter()
+                .flat_map(|route| {
+                    let alternates = sitemap_alternates(origin, route, locales);
+                    locales
+                        .iter()
+                        .map(move |locale| url(&locale.localize(route), &alternates))
+                })
+                .collect(),
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#,
+        )
+    };
+    [
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        urlset,
+        &urls.join("\n"),
+        "</urlset>",
+        "",
+    ]
+    .join("\n")
+}
+
+pub fn emit_robots(rules: &[SiteRobotsRule], base_url: Option<&str>) -> String {
+    let mut groups: Vec<String> = rules
+        .iter()
+        .map(|rule| {
+            let mut lines = vec![format!("User-agent: {}", robots_value(&rule.user_agent))];
+            for allow in &rule.allow {
+                lines.push(format!("Allow: {}", robots_value(allow)));
+            }
+            for disallow in &rule.disallow {
+                lines.push(format!("Disallow: {}", robots_value(disallow)));
+            }
+            if let Some(delay) = rule.crawl_delay {
+                lines.push(format!("Crawl-delay: {}", delay));
+            }
+            lines.join("\n")
+        })
+        .collect();
+    if groups.is_empty() {
+        groups.push("User-agent: *\nAllow: /".into());
+    }
+    if let Some(base_url) = base_url {
+        groups.push(format!(
+            "Sitemap: {}{}",
+            base_url.trim_end_matches('/'),
+            SITEMAP_PATH
+        ));
+    }
+    format!("{}\n", groups.join("\n\n"))
+}
+
+pub fn build_site_seo_files(
+    site: &SiteEntity,
+    base_url: Option<&str>,
+    protections: &SiteProtections,
+) -> Result<SiteSeoFiles, serde_json::Error> {
+    let (defaults, _) = parse_site_document(&site.defaults)?;
+    let defaults: SiteHeadDefaults = serde_json::from_value(defaults)?;
+    let (pages, _) = parse_site_document(&site.pages)?;
+    // Page order may be stored as an empty string
+    let page_order: Vec<String> = parse_site_document(&site.page_order)
+        .ok()
+        .and_then(|(order, _)| serde_json::from_value(order).ok())
+        .unwrap_or_default();
+
+    let sitemap = base_url.map(|base_url| {
+        let routes = sitemap_routes(&pages, page_order, &defaults.home_page, protections);
+        emit_sitemap(
+            &routes,
+            base_url,
+            site.content_updated_at,
+            &defaults.locales,
+        )
+    });
+    Ok(SiteSeoFiles {
+        sitemap,
+        robots: emit_robots(&defaults.robots, base_url),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::site_access::ProtectedRoute;
+    use serde_json::json;
+
+    #[test]
+    fn emits_sitemap_in_page_order() {
+        let pages = json!({
+            "/home": { "public": true },
+            "/about": { "public": true },
+            "/draft": { "public": false },
+            "/not-found": { "public": true },
+            "/members": { "public": true },
+        });
+        let order = vec![
+            "/about".into(),
+            "/home".into(),
+            "/draft".into(),
+            "/members".into(),
+        ];
+        let protections = SiteProtections::new(vec![ProtectedRoute {
+            id: 1,
+            route: "/members".into(),
+            password_hash: "hash".into(),
+        }]);
+        let routes = sitemap_routes(&pages, order, "/home", &protections);
+        assert_eq!(routes, vec!["/about", "/"]);
+
+        let sitemap = emit_sitemap(&routes, "https://a.com/", 1_782_864_000_000, &[]);
+        assert!(sitemap.contains(
+            "<url><loc>https://a.com/about</loc><lastmod>2026-07-01T00:00:00.000Z</lastmod></url>"
+        ));
+        assert!(sitemap.contains("<loc>https://a.com/</loc>"));
+
+        let locales: Vec<SiteLocale> = ["en", "zh-TW"]
+            .map(|code| SiteLocale {
+                code: code.into(),
+                title: None,
+                description: None,
+            })
+            .into();
+        let sitemap = emit_sitemap(&routes, "https://a.com", 0, &locales);
+        assert!(sitemap.contains(r#"xmlns:xhtml="http://www.w3.org/1999/xhtml""#));
+        assert!(sitemap.contains(
+            r#"<url><loc>https://a.com/zh-TW/about</loc><xhtml:link rel="alternate" hreflang="en" href="https://a.com/en/about"/><xhtml:link rel="alternate" hreflang="zh-TW" href="https://a.com/zh-TW/about"/><xhtml:link rel="alternate" hreflang="x-default" href="https://a.com/about"/></url>"#
+        ));
+        assert!(sitemap.contains("<loc>https://a.com/en</loc>"));
+        assert!(!sitemap.contains("<loc>https://a.com/about</loc>"));
+    }
+
+    #[test]
+    fn emits_robots_rules() {
+        assert_eq!(emit_robots(&[], None), "User-agent: *\nAllow: /\n");
+
+        let rules = vec![SiteRobotsRule {
+            user_agent: "GPTBot".into(),
+            allow: vec![],
+            disallow: vec!["/\nUser-agent: *".into()],
+            crawl_delay: Some(10),
+        }];
+        assert_eq!(
+            emit_robots(&rules, Some("https://a.com")),
+            "User-agent: GPTBot\nDisallow: /User-agent: *\nCrawl-delay: 10\n\nSitemap: https://a.com/sitemap.xml\n"
+        );
+    }
+}
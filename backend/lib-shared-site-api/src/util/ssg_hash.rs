@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use lib_shared_types::entity::site_api::site_entity::SiteEntity;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::site_document::parse_site_document;
+
+// Content hashes of a site version, used to skip SSG regeneration of unchanged pages
+#[derive(Debug)]
+pub struct SiteContentHashes {
+    // Data every page depends on: site name/version, defaults, context (theme, styles,
+    // components), page order, and the generator options
+    pub shared: String,
+    // Page document hash by static route. The home page is also keyed by `/`, and each
+    // locale's pages by their prefixed route, e.g. `/zh-TW/about` and `/zh-TW`
+    pub pages: HashMap<String, String>,
+    pub home_page: Option<String>,
+    // Locale codes of the site
+    pub locales: Vec<String>,
+}
+
+impl SiteContentHashes {
+    // Page route to render for a static route
+    pub fn page_route<'a>(&'a self, static_route: &'a str) -> &'a str {
+        let static_route = self
+            .locales
+            .iter()
+            .find_map(
+                |code| match static_route.strip_prefix(&format!("/{}", code))? {
+                    "" => Some("/"),
+                    route if route.starts_with('/') => Some(route),
+                    _ => None,
+                },
+            )
+            .unwrap_or(static_route);
+        match (static_route, &self.home_page) {
+            ("/", Some(home_page)) => home_page,
+            _ => static_route,
+        }
+    }
+}
+
+fn sha256_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.len().to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+// `options_key` identifies generator options that affect every page, e.g. the base URL
+pub fn hash_site_content(
+    site: &SiteEntity,
+    options_key: &str,
+) -> Result<SiteContentHashes, serde_json::Error> {
+    let shared = sha256_hex(&[
+        &site.name,
+        &site.version,
+        &site.defaults,
+        &site.context,
+        &site.page_order,
+        options_key,
+    ]);
+
+    let (defaults, _) = parse_site_document(&site.defaults)?;
+    let home_page = defaults
+        .get("homePage")
+        .and_then(Value::as_str)
+        .map(String::from);
+    let locales: Vec<String> = defaults
+        .get("locales")
+        .and_then(Value::as_array)
+        .map(|locales| {
+            locales
+                .iter()
+                .filter_map(|locale| locale.get("code")?.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (pages_doc, _) = parse_site_document(&site.pages)?;
+    let mut pages = HashMap::new();
+    if let Value::Object(page_map) = pages_doc {
+        for (route, page) in page_map {
+            pages.insert(route, sha256_hex(&[&page.to_string()]));
+        }
+    }
+    if let Some(home_hash) = home_page.as_ref().and_then(|home| pages.get(home)) {
+        pages.insert("/".into(), home_hash.clone());
+    }
+    let localized: Vec<(String, String)> = locales
+        .iter()
+        .flat_map(|code| {
+            pages.iter().map(move |(route, hash)| {
+                let route = if route == "/" { "" } else { route };
+                (format!("/{}{}", code, route), hash.clone())
+            })
+        })
+        .collect();
+    pages.extend(localized);
+
+    Ok(SiteContentHashes {
+        shared,
+        pages,
+        home_page,
+        locales,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn encoded(value: Value) -> String {
+        Value::String(value.to_string()).to_string()
+    }
+
+    fn site(about_text: &str, theme: &str) -> SiteEntity {
+        SiteEntity {
+            id: 1,
+            name: "Site".into(),
+            version: "2".into(),
+            context: encoded(json!({ "theme": theme })),
+            defaults: encoded(json!({ "homePage": "/home" })),
+            editor: "null".into(),
+            history: "null".into(),
+            pages: encoded(json!({
+                "/home": { "route": "/home", "text": "Welcome" },
+                "/about": { "route": "/about", "text": about_text },
+            })),
+            page_order: encoded(json!(["/home", "/about"])),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            content_updated_at: 0,
+            published: true,
+            preview_id: None,
+            revision_id: None,
+        }
+    }
+
+    #[test]
+    fn detects_changed_pages() {
+        let before = hash_site_content(&site("About", "light"), "").unwrap();
+        let after = hash_site_content(&site("About us", "light"), "").unwrap();
+
+        assert_eq!(before.shared, after.shared);
+        assert_eq!(before.pages["/home"], after.pages["/home"]);
+        assert_eq!(before.pages["/"], before.pages["/home"]);
+        assert_ne!(before.pages["/about"], after.pages["/about"]);
+        assert_eq!(before.page_route("/"), "/home");
+
+        let mut localized = site("About", "light");
+        localized.defaults = encoded(json!({
+            "homePage": "/home",
+            "locales": [{ "code": "en" }, { "code": "zh-TW" }],
+        }));
+        let localized = hash_site_content(&localized, "").unwrap();
+        assert_eq!(localized.pages["/zh-TW/about"], before.pages["/about"]);
+        assert_eq!(localized.pages["/en"], before.pages["/home"]);
+        assert_eq!(localized.page_route("/zh-TW"), "/home");
+        assert_eq!(localized.page_route("/en/about"), "/about");
+        assert_eq!(localized.page_route("/english"), "/english");
+    }
+
+    #[test]
+    fn detects_shared_changes() {
+        let before = hash_site_content(&site("About", "light"), "").unwrap();
+        let theme = hash_site_content(&site("About", "dark"), "").unwrap();
+        let options = hash_site_content(&site("About", "light"), "https://a.com").unwrap();
+
+        assert_ne!(before.shared, theme.shared);
+        assert_ne!(before.shared, options.shared);
+        assert_eq!(before.pages, theme.pages);
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+
+// 2^12 registers, estimates are within about 1.6% of the real count
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+// Sketches held in memory between persists, 4 KB each. Once full, visitors of other
+// sites and days are not counted until the next persist
+const MAX_SKETCHES: usize = 5_000;
+
+// HyperLogLog sketch of visitor hashes. Counts distinct visitors in fixed memory, and
+// sketches of several days merge into the count of the whole range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        HyperLogLog {
+            registers: vec![0; REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Guard bit, so the rank is at most 64 - PRECISION + 1
+        let rest = (hash << PRECISION) | (1 << (PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-(rank as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        // Linear counting is more accurate for small counts
+        if estimate <= 2.5 * m && zeros > 0 {
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+
+    // Serialized as the precision followed by the registers
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(REGISTERS + 1);
+        bytes.push(PRECISION as u8);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<HyperLogLog> {
+        let (&precision, registers) = bytes.split_first()?;
+        if precision as u32 != PRECISION || registers.len() != REGISTERS {
+            return None;
+        }
+        Some(HyperLogLog {
+            registers: registers.to_vec(),
+        })
+    }
+}
+
+// Anonymous visitor hash. The salt rotates daily and is deleted after its day, so hashes can't
+// be linked to an IP address, or to the same visitor on another day or site
+pub fn visitor_hash(salt: &[u8], site_id: &str, ip: IpAddr, user_agent: &str) -> u64 {
+    let mut hasher = Sha256::new();
+    for part in [
+        salt,
+        site_id.as_bytes(),
+        ip.to_string().as_bytes(),
+        user_agent.as_bytes(),
+    ] {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    let digest = hasher.finalize();
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+#[derive(Debug, Default)]
+struct VisitorSketches {
+    // Day of the salt, and the salt. Replaced by the next day's salt
+    salt: Option<(NaiveDate, Vec<u8>)>,
+    sketches: HashMap<(String, NaiveDate), HyperLogLog>,
+}
+
+// Daily unique visitor sketches of each site, until they are persisted. The salt of the day
+// is loaded from the metadata DB, which shares it between servers and restarts
+#[derive(Debug, Clone, Default)]
+pub struct UniqueVisitors {
+    state: Arc<Mutex<VisitorSketches>>,
+}
+
+impl UniqueVisitors {
+    // Salt of `day`, when it is already loaded
+    pub fn salt(&self, day: NaiveDate) -> Option<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        match &state.salt {
+            Some((salt_day, salt)) if *salt_day == day => Some(salt.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn set_salt(&self, day: NaiveDate, salt: Vec<u8>) {
+        self.state.lock().unwrap().salt = Some((day, salt));
+    }
+
+    pub fn record(&self, site_id: &str, day: NaiveDate, salt: &[u8], ip: IpAddr, user_agent: &str) {
+        let mut state = self.state.lock().unwrap();
+        let key = (site_id.to_string(), day);
+        if state.sketches.len() >= MAX_SKETCHES && !state.sketches.contains_key(&key) {
+            return;
+        }
+        state
+            .sketches
+            .entry(key)
+            .or_default()
+            .insert(visitor_hash(salt, site_id, ip, user_agent));
+    }
+
+    // Visitors of a day that are not persisted yet
+    pub fn sketch(&self, site_id: &str, day: NaiveDate) -> Option<HyperLogLog> {
+        let state = self.state.lock().unwrap();
+        state.sketches.get(&(site_id.to_string(), day)).cloned()
+    }
+
+    // Take the sketches to persist. Visitors recorded meanwhile go to new sketches
+    pub fn drain(&self) -> Vec<(String, NaiveDate, HyperLogLog)> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .sketches
+            .drain()
+            .map(|((site_id, day), sketch)| (site_id, day, sketch))
+            .collect()
+    }
+
+    // Put back sketches that failed to persist, to retry on the next persist
+    pub fn restore(&self, sketches: Vec<(String, NaiveDate, HyperLogLog)>) {
+        let mut state = self.state.lock().unwrap();
+        for (site_id, day, sketch) in sketches {
+            state
+                .sketches
+                .entry((site_id, day))
+                .or_default()
+                .merge(&sketch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch_of(ids: std::ops::Range<u32>) -> HyperLogLog {
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let mut sketch = HyperLogLog::default();
+        for id in ids {
+            sketch.insert(visitor_hash(b"salt", "site", ip, &format!("agent {}", id)));
+        }
+        sketch
+    }
+
+    fn assert_near(estimate: u64, count: u64, tolerance: f64) {
+        let error = (estimate as f64 - count as f64).abs() / count as f64;
+        assert!(error <= tolerance, "estimate {estimate} for {count}");
+    }
+
+    #[test]
+    fn estimates_unique_visitors() {
+        assert_eq!(HyperLogLog::default().estimate(), 0);
+        assert_near(sketch_of(0..100).estimate(), 100, 0.02);
+        assert_near(sketch_of(0..50_000).estimate(), 50_000, 0.05);
+
+        // Repeat visits are counted once
+        let mut sketch = sketch_of(0..1000);
+        sketch.merge(&sketch_of(0..1000));
+        assert_eq!(sketch, sketch_of(0..1000));
+
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_ne!(
+            visitor_hash(b"day 1", "site", ip, "agent"),
+            visitor_hash(b"day 2", "site", ip, "agent")
+        );
+        assert_ne!(
+            visitor_hash(b"salt", "site 1", ip, "agent"),
+            visitor_hash(b"salt", "site 2", ip, "agent")
+        );
+    }
+
+    #[test]
+    fn merges_and_serializes_sketches() {
+        let mut month = sketch_of(0..3000);
+        month.merge(&sketch_of(2000..5000));
+        assert_near(month.estimate(), 5000, 0.05);
+
+        let bytes = month.to_bytes();
+        assert_eq!(bytes.len(), REGISTERS + 1);
+        assert_eq!(HyperLogLog::from_bytes(&bytes), Some(month));
+        assert_eq!(HyperLogLog::from_bytes(&bytes[1..]), None);
+        assert_eq!(HyperLogLog::from_bytes(&[]), None);
+
+        let day = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let visitors = UniqueVisitors::default();
+        visitors.record("site", day, b"salt", ip, "agent");
+        visitors.record("site", day, b"salt", ip, "agent");
+        visitors.record("site", day, b"salt", ip, "other agent");
+        assert_eq!(visitors.sketch("site", day).unwrap().estimate(), 2);
+
+        // Failed persists are retried with visitors recorded meanwhile
+        let drained = visitors.drain();
+        assert_eq!(visitors.sketch("site", day), None);
+        visitors.record(
+            "site",
+            day,
+            b"salt",
+            "203.0.113.8".parse().unwrap(),
+            "agent",
+        );
+        visitors.restore(drained);
+        assert_eq!(visitors.sketch("site", day).unwrap().estimate(), 3);
+
+        // Only the salt of the current day is kept
+        visitors.set_salt(day, b"salt".to_vec());
+        assert_eq!(visitors.salt(day), Some(b"salt".to_vec()));
+        visitors.set_salt(day.succ_opt().unwrap(), b"next".to_vec());
+        assert_eq!(visitors.salt(day), None);
+    }
+}
@@ -1,11 +1,126 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::dto::site_api::get_current_site_dto::GetCurrentSiteResponse;
+use crate::{
+    domain::site_defaults::{
+        split_locale_path, SiteHeadLink, SiteHeadMeta, SiteLocale, TrailingSlashPolicy,
+    },
+    dto::site_api::get_current_site_dto::GetCurrentSiteResponse,
+};
+
+// Head of a single page, with site values filled in where the page has none
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedPageHead {
+    // Served route, the home page is `/`
+    pub route: String,
+    pub title: String,
+    pub description: String,
+    // OpenGraph/Twitter card image
+    pub image: Option<String>,
+    // Other meta tags of the page, e.g. keywords or og:type
+    #[serde(default)]
+    pub meta: Vec<SiteHeadMeta>,
+    // `alternate` links with hreflang
+    #[serde(default)]
+    pub alternates: Vec<SiteHeadLink>,
+    // Language of the served page, for multi-language sites
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    // Locale code -> localized head of the page
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localized: HashMap<String, LocalizedHead>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalizedHead {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedSiteHead {
     pub title: String,
     pub description: String,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub home_page: String,
+    // Page route -> head of public pages
+    #[serde(default)]
+    pub pages: HashMap<String, CachedPageHead>,
+    // Owner configured Cache-Control for served pages
+    #[serde(default)]
+    pub cache_control: Option<String>,
+    #[serde(default)]
+    pub trailing_slash: TrailingSlashPolicy,
+    // The first locale is the default
+    #[serde(default)]
+    pub locales: Vec<SiteLocale>,
+}
+
+fn trim_route(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        route => route,
+    }
+}
+
+impl CachedSiteHead {
+    // Page route served at `path`, without its locale prefix. The home page is served at `/`
+    pub fn page_route(&self, path: &str) -> String {
+        let (_, route) = split_locale_path(&self.locales, path);
+        match trim_route(route) {
+            "/" if !self.home_page.is_empty() => self.home_page.clone(),
+            route => route.to_string(),
+        }
+    }
+
+    // Head served at `path`. Unknown routes get the site head, the SPA renders its not found page
+    pub fn page_head(&self, path: &str) -> CachedPageHead {
+        let (locale, route) = split_locale_path(&self.locales, path);
+        let route = trim_route(route);
+        let page_route = if route == "/" { &self.home_page } else { route };
+        let mut head = match self.pages.get(page_route) {
+            Some(head) => head.clone(),
+            None => CachedPageHead {
+                title: self.title.clone(),
+                description: self.description.clone(),
+                image: self.image.clone(),
+                ..Default::default()
+            },
+        };
+        let Some(locale) = locale else {
+            head.route = route.to_string();
+            head.localized.clear();
+            return head;
+        };
+        // Page values of the locale, then the locale's site head
+        let localized = head.localized.remove(&locale.code).unwrap_or_default();
+        if let Some(title) = localized.title.or_else(|| locale.title.clone()) {
+            head.title = title;
+        }
+        if let Some(description) = localized.description.or_else(|| locale.description.clone()) {
+            head.description = description;
+        }
+        // Unprefixed paths are canonical under the default locale's prefix
+        head.route = locale.localize(route);
+        head.lang = Some(locale.code.clone());
+        head.localized.clear();
+        head.alternates = self
+            .locales
+            .iter()
+            .map(|locale| (locale.code.clone(), locale.localize(route)))
+            .chain([("x-default".to_string(), route.to_string())])
+            .map(|(hreflang, href)| SiteHeadLink {
+                href: Some(href),
+                rel: Some("alternate".into()),
+                hreflang: Some(hreflang),
+                ..Default::default()
+            })
+            .collect();
+        head
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
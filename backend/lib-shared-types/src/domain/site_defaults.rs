@@ -5,6 +5,97 @@ use serde::{Deserialize, Serialize};
 pub struct SiteHeadDefaults {
     pub head: SiteHead,
     pub home_page: String,
+    // robots.txt rule groups. An empty list allows all crawlers
+    #[serde(default)]
+    pub robots: Vec<SiteRobotsRule>,
+    // Cache-Control header for served pages
+    pub cache_control: Option<String>,
+    #[serde(default)]
+    pub trailing_slash: TrailingSlashPolicy,
+    // Language versions of the site. The first locale is the default
+    #[serde(default)]
+    pub locales: Vec<SiteLocale>,
+}
+
+// A language version of the site, served under its route prefix, e.g. `/zh-TW/about`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteLocale {
+    // BCP 47 language tag, also used as the route prefix
+    pub code: String,
+    // Head of the locale, used by pages without a localized head
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+impl SiteLocale {
+    // Route of a localized path, `/zh-TW/about` -> `/about`. Prefixes match case-insensitively
+    pub fn strip_prefix<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let rest = path.strip_prefix('/')?;
+        let prefix = rest.get(..self.code.len())?;
+        if !prefix.eq_ignore_ascii_case(&self.code) {
+            return None;
+        }
+        match &rest[self.code.len()..] {
+            "" => Some("/"),
+            route if route.starts_with('/') => Some(route),
+            _ => None,
+        }
+    }
+
+    // Served path of a page route in this locale, the home page is `/zh-TW`
+    pub fn localize(&self, route: &str) -> String {
+        match route {
+            "/" => format!("/{}", self.code),
+            route => format!("/{}{}", self.code, route),
+        }
+    }
+}
+
+// The locale of a served path and the path without its prefix. Unprefixed paths are served in
+// the default locale, None when the site has no locales
+pub fn split_locale_path<'a, 'b>(
+    locales: &'a [SiteLocale],
+    path: &'b str,
+) -> (Option<&'a SiteLocale>, &'b str) {
+    for locale in locales {
+        if let Some(route) = locale.strip_prefix(path) {
+            return (Some(locale), route);
+        }
+    }
+    (locales.first(), path)
+}
+
+// A served path with its locale prefix in the locale's case, `/zh-tw/about` -> `/zh-TW/about`.
+// Static pages are stored under the locale's code
+pub fn canonical_locale_path(locales: &[SiteLocale], path: &str) -> String {
+    locales
+        .iter()
+        .find_map(|locale| Some(locale.localize(locale.strip_prefix(path)?)))
+        .unwrap_or_else(|| path.to_string())
+}
+
+// Whether served paths are redirected to a canonical trailing slash form
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrailingSlashPolicy {
+    #[default]
+    Ignore,
+    // `/about/` redirects to `/about`
+    Remove,
+    // `/about` redirects to `/about/`, except for file paths like `/logo.png`
+    Add,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteRobotsRule {
+    pub user_agent: String,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub disallow: Vec<String>,
+    pub crawl_delay: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,7 +104,7 @@ pub struct SiteHeadBase {
     pub target: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SiteHeadLink {
     pub href: Option<String>,
     pub rel: Option<String>,
@@ -28,6 +119,8 @@ pub struct SiteHeadLink {
     pub head_type: Option<String>,
     pub blocking: Option<String>,
     pub crossorigin: Option<String>,
+    // Language of an `alternate` link, e.g. `en-US` or `x-default`
+    pub hreflang: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,23 @@
+use crate::type_util::REGEX_VERSION_TAG;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::entity::site_api::site_info_entity::SiteVersionLabel;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CreateDraftDto {
+    #[validate(length(max = 500))]
+    pub message: Option<String>,
+    #[validate(regex(path = "*REGEX_VERSION_TAG"))]
+    pub tag: Option<String>,
+}
+
+impl CreateDraftDto {
+    pub fn to_label(&self) -> SiteVersionLabel {
+        SiteVersionLabel {
+            message: self.message.clone(),
+            tag: self.tag.clone(),
+        }
+    }
+}
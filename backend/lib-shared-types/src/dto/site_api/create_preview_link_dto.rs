@@ -0,0 +1,13 @@
+use crate::type_util::REGEX_DATE;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreatePreviewLinkDto {
+    pub version_id: i64,
+    #[validate(regex(path = "*REGEX_DATE"))]
+    pub expires_at: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub password: Option<String>,
+}
@@ -0,0 +1,10 @@
+use crate::type_util::REGEX_DATE;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreatePublishScheduleDto {
+    #[validate(regex(path = "*REGEX_DATE"))]
+    pub publish_at: String,
+}
@@ -19,13 +19,22 @@ pub struct CreateSiteDto {
     pub owner_email: String,
     #[validate(regex(path = "*REGEX_SITE_NAME"))]
     pub name: String,
+    // Site content may be omitted when starting from a template
+    #[validate(regex(path = "*REGEX_UUID"))]
+    pub template_id: Option<String>,
+    #[serde(default)]
     pub version: String,
+    #[serde(default)]
     pub context: serde_json::Value,
+    #[serde(default)]
     pub defaults: serde_json::Value,
+    #[serde(default)]
     pub editor: serde_json::Value,
+    #[serde(default)]
     pub history: serde_json::Value,
+    #[serde(default)]
     pub pages: serde_json::Value,
-    #[serde(rename = "pageOrder")]
+    #[serde(rename = "pageOrder", default)]
     pub page_order: serde_json::Value,
     pub published: bool,
     pub domains: Vec<CustomDomainViewModel>,
@@ -36,3 +45,15 @@ pub struct CreateSiteDto {
 pub struct CreateSiteResponse {
     pub id: String,
 }
+
+impl CreateSiteDto {
+    pub fn has_content(&self) -> bool {
+        !self.version.is_empty()
+            && !self.context.is_null()
+            && !self.defaults.is_null()
+            && !self.editor.is_null()
+            && !self.history.is_null()
+            && !self.pages.is_null()
+            && !self.page_order.is_null()
+    }
+}
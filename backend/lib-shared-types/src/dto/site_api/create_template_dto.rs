@@ -0,0 +1,38 @@
+use crate::type_util::REGEX_UUID;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TemplatePreviewUpload {
+    #[validate(length(min = 1, max = 100))]
+    pub content_type: String,
+    #[validate(range(min = 1, max = 5242880))]
+    pub size: i64,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateTemplateDto {
+    // Site to snapshot
+    #[validate(regex(path = "*REGEX_UUID"))]
+    pub site_id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 50))]
+    pub category: String,
+    // Request a presigned URL for uploading the preview image
+    #[validate(nested)]
+    pub preview: Option<TemplatePreviewUpload>,
+    #[serde(default)]
+    pub include_custom_tables: bool,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ListTemplatesQuery {
+    #[validate(length(min = 1, max = 50))]
+    pub category: Option<String>,
+}
@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::domain_verification_entity::{
+        DomainVerificationEntity, DomainVerificationStatus,
+    },
+    shared::js_date::JsDate,
+};
+
+#[derive(Serialize)]
+pub struct DomainVerificationViewModel {
+    pub domain: String,
+    // Owners add a TXT record named `txt_name`, with `token` as its value
+    pub txt_name: String,
+    pub token: String,
+    pub status: DomainVerificationStatus,
+    pub verified: bool,
+    pub last_checked_at: Option<JsDate>,
+    pub last_result: Option<String>,
+    pub last_error: Option<String>,
+    pub failing_since: Option<JsDate>,
+}
+
+pub fn to_api_response(
+    entity: DomainVerificationEntity,
+    txt_name: String,
+) -> DomainVerificationViewModel {
+    DomainVerificationViewModel {
+        domain: entity.domain,
+        txt_name,
+        token: entity.token,
+        status: entity.status,
+        verified: entity.verified,
+        last_checked_at: entity.last_checked_at.map(|timestamp| JsDate { timestamp }),
+        last_result: entity.last_result,
+        last_error: entity.last_error,
+        failing_since: entity.failing_since.map(|timestamp| JsDate { timestamp }),
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    shared::site::SiteType,
+    type_util::{REGEX_SITE_NAME, REGEX_UUID},
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct DuplicateSiteDto {
+    #[validate(regex(path = "*REGEX_UUID"))]
+    pub owner_id: String,
+    // Defaults to the owner email of the source site. Only admins may set another email
+    #[validate(email)]
+    pub owner_email: Option<String>,
+    // Defaults to the name of the source site
+    #[validate(regex(path = "*REGEX_SITE_NAME"))]
+    pub name: Option<String>,
+    // Defaults to the site type of the source site. Only admins may set another type
+    pub site_type: Option<SiteType>,
+    // Copy custom table schemas, without their rows
+    #[serde(default)]
+    pub copy_custom_tables: bool,
+}
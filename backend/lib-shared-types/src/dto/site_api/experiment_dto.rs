@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateExperimentVariantDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    // Page of the site rendered for the variant, the experiment route itself for the control
+    #[validate(length(min = 1, max = 500))]
+    pub page_route: String,
+    #[validate(range(min = 1, max = 1000))]
+    pub weight: i64,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateExperimentDto {
+    #[validate(length(min = 1, max = 500))]
+    pub route: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub enabled: Option<bool>,
+    // Custom data table whose inserts count as conversions
+    #[validate(length(min = 1, max = 100))]
+    pub conversion_table: Option<String>,
+    // The first variant is the control
+    #[validate(length(min = 2, max = 10), nested)]
+    pub variants: Vec<CreateExperimentVariantDto>,
+}
+
+// Variants are fixed once an experiment is created, so results stay comparable
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateExperimentDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    // An empty string removes the conversion table
+    #[validate(length(max = 100))]
+    pub conversion_table: Option<String>,
+}
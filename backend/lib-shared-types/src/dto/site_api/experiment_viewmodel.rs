@@ -0,0 +1,86 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::experiment_entity::{ExperimentEntity, ExperimentVariantEntity},
+    shared::js_date::JsDate,
+};
+
+#[derive(Serialize)]
+pub struct ExperimentVariantViewModel {
+    pub id: i64,
+    pub name: String,
+    pub page_route: String,
+    pub weight: i64,
+    pub exposures: i64,
+    pub conversions: i64,
+}
+
+#[derive(Serialize)]
+pub struct ExperimentViewModel {
+    pub id: i64,
+    pub route: String,
+    pub name: String,
+    pub enabled: bool,
+    pub conversion_table: Option<String>,
+    pub variants: Vec<ExperimentVariantViewModel>,
+    pub created_at: JsDate,
+    pub updated_at: JsDate,
+}
+
+fn to_variant_response(entity: ExperimentVariantEntity) -> ExperimentVariantViewModel {
+    ExperimentVariantViewModel {
+        id: entity.id,
+        name: entity.name,
+        page_route: entity.page_route,
+        weight: entity.weight,
+        exposures: entity.exposures,
+        conversions: entity.conversions,
+    }
+}
+
+pub fn to_api_response(entity: ExperimentEntity) -> ExperimentViewModel {
+    ExperimentViewModel {
+        id: entity.id,
+        route: entity.route,
+        name: entity.name,
+        enabled: entity.enabled,
+        conversion_table: entity.conversion_table,
+        variants: entity
+            .variants
+            .into_iter()
+            .map(to_variant_response)
+            .collect(),
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+        updated_at: JsDate {
+            timestamp: entity.updated_at,
+        },
+    }
+}
+
+// Results of a variant, compared with the control. Comparison values are None for the
+// control, and when either variant has no exposures
+#[derive(Serialize)]
+pub struct ExperimentVariantResultViewModel {
+    pub id: i64,
+    pub name: String,
+    pub exposures: i64,
+    pub conversions: i64,
+    pub conversion_rate: f64,
+    // Relative change of the conversion rate, 0.1 is 10% better than the control
+    pub lift: Option<f64>,
+    pub z_score: Option<f64>,
+    // Two-sided p-value of a two proportion z-test
+    pub p_value: Option<f64>,
+    // p_value below 0.05
+    pub significant: bool,
+}
+
+#[derive(Serialize)]
+pub struct ExperimentResultsViewModel {
+    pub id: i64,
+    pub name: String,
+    pub control_id: i64,
+    pub variants: Vec<ExperimentVariantResultViewModel>,
+}
@@ -8,6 +8,13 @@ pub struct GetCurrentSiteQuery {
     pub p: Option<String>,
 }
 
+#[derive(Deserialize, Validate)]
+pub struct GetSiteHeadQuery {
+    pub p: Option<String>,
+    // Page route, defaults to the home page
+    pub route: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetCurrentSiteResponse {
     pub id: String,
@@ -20,6 +27,9 @@ pub struct GetCurrentSiteResponse {
     // Freshness key for generated static pages (see static_pages)
     #[serde(default)]
     pub content_updated_at: i64,
+    // Subdomain of the request host, when the site is served on a wildcard domain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdomain: Option<String>,
 }
 
 pub fn to_api_response(site: &SiteEntity, site_id: &str) -> GetCurrentSiteResponse {
@@ -32,6 +42,7 @@ pub fn to_api_response(site: &SiteEntity, site_id: &str) -> GetCurrentSiteRespon
         pages: site.pages.clone(),
         published: site.published,
         content_updated_at: site.content_updated_at,
+        subdomain: None,
     }
 }
 
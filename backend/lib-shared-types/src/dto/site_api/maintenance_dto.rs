@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{entity::site_api::maintenance_entity::MaintenancePage, type_util::REGEX_DATE};
+
+// Replaces the site's maintenance settings
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateMaintenanceDto {
+    pub enabled: bool,
+    #[validate(regex(path = "*REGEX_DATE"))]
+    pub starts_at: Option<String>,
+    #[validate(regex(path = "*REGEX_DATE"))]
+    pub ends_at: Option<String>,
+    #[serde(default)]
+    pub page: MaintenancePage,
+    #[validate(length(max = 2000))]
+    pub message: Option<String>,
+    #[validate(length(max = 200000))]
+    pub custom_page: Option<String>,
+    #[serde(default)]
+    #[validate(length(max = 50))]
+    pub allowed_ips: Vec<String>,
+    // Invalidates bypass cookies given to previewers
+    #[serde(default)]
+    pub reset_bypass_token: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MaintenanceBypassQuery {
+    pub token: String,
+}
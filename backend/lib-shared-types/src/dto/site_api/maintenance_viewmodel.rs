@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::maintenance_entity::{MaintenanceEntity, MaintenancePage},
+    shared::js_date::JsDate,
+};
+
+#[derive(Serialize)]
+pub struct MaintenanceViewModel {
+    pub enabled: bool,
+    // Whether visitors currently get the holding page
+    pub active: bool,
+    pub starts_at: Option<JsDate>,
+    pub ends_at: Option<JsDate>,
+    pub page: MaintenancePage,
+    pub message: Option<String>,
+    pub custom_page: Option<String>,
+    pub allowed_ips: Vec<String>,
+    // Shared with previewers, visiting the bypass path with it sets the bypass cookie
+    pub bypass_token: String,
+    pub updated_at: JsDate,
+}
+
+pub fn to_api_response(entity: MaintenanceEntity, active: bool) -> MaintenanceViewModel {
+    MaintenanceViewModel {
+        enabled: entity.enabled,
+        active,
+        starts_at: entity.starts_at.map(|timestamp| JsDate { timestamp }),
+        ends_at: entity.ends_at.map(|timestamp| JsDate { timestamp }),
+        page: entity.page,
+        message: entity.message,
+        custom_page: entity.custom_page,
+        allowed_ips: entity.allowed_ips,
+        bypass_token: entity.bypass_token,
+        updated_at: JsDate {
+            timestamp: entity.updated_at,
+        },
+    }
+}
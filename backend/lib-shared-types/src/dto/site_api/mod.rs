@@ -1,18 +1,42 @@
 pub mod create_backup_dto;
+pub mod create_draft_dto;
 pub mod create_metadata_dto;
+pub mod create_preview_link_dto;
+pub mod create_publish_schedule_dto;
 pub mod create_site_dto;
 pub mod create_site_from_backup_dto;
+pub mod create_template_dto;
+pub mod domain_verification_viewmodel;
+pub mod duplicate_site_dto;
+pub mod experiment_dto;
+pub mod experiment_viewmodel;
 pub mod get_current_site_dto;
 pub mod get_site_domains_dto;
 pub mod get_site_version_dto;
+pub mod maintenance_dto;
+pub mod maintenance_viewmodel;
+pub mod patch_site_dto;
+pub mod patch_site_viewmodel;
+pub mod preview_link_viewmodel;
+pub mod protected_route_dto;
+pub mod protected_route_viewmodel;
+pub mod publish_schedule_viewmodel;
 pub mod publish_site_dto;
 pub mod record_page_view_dto;
+pub mod redirect_dto;
+pub mod redirect_viewmodel;
 pub mod reset_all_dto;
+pub mod site_analytics_dto;
+pub mod site_archive_dto;
 pub mod site_info_viewmodel;
+pub mod site_merge_conflict_viewmodel;
 pub mod site_metadata_viewmodel;
 pub mod site_usage_viewmodel;
 pub mod site_viewmodel;
 pub mod ssg_dto;
+pub mod static_build_viewmodel;
+pub mod template_viewmodel;
+pub mod unlock_preview_link_dto;
 pub mod update_metadata_dto;
 pub mod update_site_dto;
 pub mod validate_domain_query;
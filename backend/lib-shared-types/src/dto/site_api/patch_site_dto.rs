@@ -0,0 +1,19 @@
+use crate::type_util::REGEX_DATE;
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PatchSiteDto {
+    // RFC 6902 JSON Patch documents, applied to the latest site version
+    pub context: Option<Patch>,
+    pub defaults: Option<Patch>,
+    pub pages: Option<Patch>,
+    // Replaced in full, as in `UpdateSiteDto`
+    pub editor: Option<serde_json::Value>,
+    pub history: Option<serde_json::Value>,
+    #[validate(regex(path = "*REGEX_DATE"))]
+    pub update_key: String,
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::site_entity::SiteEntity,
+    shared::js_date::{format_js_date, JsDate},
+};
+
+// Minimal response for incremental updates, so large sites aren't sent back on every save
+#[derive(Serialize)]
+pub struct PatchSiteViewModel {
+    pub id: i64,
+    pub update_key: String,
+    pub content_updated_at: i64,
+    pub updated_at: JsDate,
+}
+
+pub fn to_api_response(site_entity: &SiteEntity) -> PatchSiteViewModel {
+    PatchSiteViewModel {
+        id: site_entity.id,
+        update_key: format_js_date(site_entity.updated_at),
+        content_updated_at: site_entity.content_updated_at,
+        updated_at: JsDate {
+            timestamp: site_entity.updated_at,
+        },
+    }
+}
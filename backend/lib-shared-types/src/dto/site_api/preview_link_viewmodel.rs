@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+use crate::{entity::site_api::preview_link_entity::PreviewLinkEntity, shared::js_date::JsDate};
+
+#[derive(Serialize)]
+pub struct PreviewLinkViewModel {
+    pub id: String,
+    pub version_id: i64,
+    pub expires_at: Option<JsDate>,
+    pub has_password: bool,
+    pub view_count: i64,
+    pub created_at: JsDate,
+}
+
+pub fn to_api_response(entity: PreviewLinkEntity) -> PreviewLinkViewModel {
+    PreviewLinkViewModel {
+        id: entity.id,
+        version_id: entity.version_id,
+        expires_at: entity.expires_at.map(|timestamp| JsDate { timestamp }),
+        has_password: entity.password_hash.is_some(),
+        view_count: entity.view_count,
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+    }
+}
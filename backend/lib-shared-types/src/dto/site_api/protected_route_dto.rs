@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateProtectedRouteDto {
+    // Route prefix, `/` protects the whole site
+    #[validate(length(min = 1, max = 500))]
+    pub route: String,
+    #[validate(length(min = 1, max = 100))]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateProtectedRouteDto {
+    #[validate(length(min = 1, max = 500))]
+    pub route: Option<String>,
+    // Changing the password signs out visitors who unlocked the route
+    #[validate(length(min = 1, max = 100))]
+    pub password: Option<String>,
+}
+
+// Visitor login form, posted from the login page
+#[derive(Deserialize, Validate, Debug)]
+pub struct SiteLoginDto {
+    #[validate(length(min = 1, max = 100))]
+    pub password: String,
+    // Path to return to after login
+    pub redirect: Option<String>,
+    // Preview link to unlock, instead of a protected route
+    #[validate(length(min = 1, max = 100))]
+    pub preview: Option<String>,
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::protected_route_entity::ProtectedRouteEntity, shared::js_date::JsDate,
+};
+
+// The password hash is never returned
+#[derive(Serialize)]
+pub struct ProtectedRouteViewModel {
+    pub id: i64,
+    pub route: String,
+    pub created_at: JsDate,
+    pub updated_at: JsDate,
+}
+
+pub fn to_api_response(entity: ProtectedRouteEntity) -> ProtectedRouteViewModel {
+    ProtectedRouteViewModel {
+        id: entity.id,
+        route: entity.route,
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+        updated_at: JsDate {
+            timestamp: entity.updated_at,
+        },
+    }
+}
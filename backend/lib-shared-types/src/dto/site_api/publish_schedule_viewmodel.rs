@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+use crate::{
+    entity::site_api::publish_schedule_entity::{PublishScheduleEntity, PublishScheduleStatus},
+    shared::js_date::JsDate,
+};
+
+#[derive(Serialize)]
+pub struct PublishScheduleViewModel {
+    pub id: i64,
+    pub site_id: String,
+    pub publish_at: JsDate,
+    pub status: PublishScheduleStatus,
+    pub error: Option<String>,
+    pub completed_at: Option<JsDate>,
+    pub created_at: JsDate,
+}
+
+pub fn to_api_response(entity: PublishScheduleEntity) -> PublishScheduleViewModel {
+    PublishScheduleViewModel {
+        id: entity.id,
+        site_id: entity.site_id,
+        publish_at: JsDate {
+            timestamp: entity.publish_at,
+        },
+        status: entity.status,
+        error: entity.error,
+        completed_at: entity.completed_at.map(|timestamp| JsDate { timestamp }),
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+    }
+}
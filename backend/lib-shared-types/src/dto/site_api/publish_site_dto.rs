@@ -1,8 +1,42 @@
+use crate::type_util::REGEX_VERSION_TAG;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::entity::site_api::site_info_entity::SiteVersionLabel;
+
+// How publishing handles SSG blockers, i.e. interactive features that need the hydration runtime
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SsgBlockerPolicy {
+    #[default]
+    Ignore,
+    // Publish, and return the blockers in the response
+    Warn,
+    // Do not publish when the draft has blockers
+    Refuse,
+}
+
 #[derive(Serialize, Deserialize, Validate, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct PublishSiteDto {
     pub publish: bool,
+    #[validate(length(max = 500))]
+    pub message: Option<String>,
+    #[validate(regex(path = "*REGEX_VERSION_TAG"))]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub ssg_blockers: SsgBlockerPolicy,
+}
+
+#[derive(Serialize)]
+pub struct PublishSiteResponse {
+    pub ssg_blockers: Vec<String>,
+}
+
+impl PublishSiteDto {
+    pub fn to_label(&self) -> SiteVersionLabel {
+        SiteVersionLabel {
+            message: self.message.clone(),
+            tag: self.tag.clone(),
+        }
+    }
 }
@@ -3,6 +3,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Serialize, Clone)]
 pub struct RecordPageViewDto {
     pub route: String,
+    // `document.referrer` of the page, only its host is counted
+    #[serde(default)]
+    pub referrer: Option<String>,
+    // UTM parameters of the landing page URL
+    #[serde(default)]
+    pub utm_source: Option<String>,
+    #[serde(default)]
+    pub utm_medium: Option<String>,
+    #[serde(default)]
+    pub utm_campaign: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateRedirectDto {
+    #[validate(length(min = 1, max = 500))]
+    pub source: String,
+    #[validate(length(min = 1, max = 2000))]
+    pub target: String,
+    // 301, 302, 307 or 308. Defaults to 301
+    pub status_code: Option<u16>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateRedirectDto {
+    #[validate(length(min = 1, max = 500))]
+    pub source: Option<String>,
+    #[validate(length(min = 1, max = 2000))]
+    pub target: Option<String>,
+    pub status_code: Option<u16>,
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+use crate::{entity::site_api::redirect_entity::RedirectEntity, shared::js_date::JsDate};
+
+#[derive(Serialize)]
+pub struct RedirectViewModel {
+    pub id: i64,
+    pub source: String,
+    pub target: String,
+    pub status_code: i64,
+    pub enabled: bool,
+    pub hit_count: i64,
+    pub last_hit_at: Option<JsDate>,
+    pub created_at: JsDate,
+    pub updated_at: JsDate,
+}
+
+pub fn to_api_response(entity: RedirectEntity) -> RedirectViewModel {
+    RedirectViewModel {
+        id: entity.id,
+        source: entity.source,
+        target: entity.target,
+        status_code: entity.status_code,
+        enabled: entity.enabled,
+        hit_count: entity.hit_count,
+        last_hit_at: entity.last_hit_at.map(|timestamp| JsDate { timestamp }),
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+        updated_at: JsDate {
+            timestamp: entity.updated_at,
+        },
+    }
+}
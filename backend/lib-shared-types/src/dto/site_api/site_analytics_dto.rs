@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    entity::site_api::site_usage_hourly_entity::{
+        AnalyticsDimension, DeviceClass, DimensionViewsEntity,
+    },
+    shared::js_date::JsDate,
+};
+
+// Filters match dimensions exactly. An empty value matches views without the dimension,
+// e.g. `referrer=` for direct visits
+#[derive(Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct GetSiteAnalyticsQuery {
+    // Start of the range, defaults to 24 hours before `end`
+    pub start: Option<JsDate>,
+    // End of the range, exclusive. Defaults to now
+    pub end: Option<JsDate>,
+    #[validate(length(max = 500))]
+    pub route: Option<String>,
+    #[validate(length(max = 100))]
+    pub referrer: Option<String>,
+    #[validate(length(max = 100))]
+    pub utm_source: Option<String>,
+    #[validate(length(max = 100))]
+    pub utm_medium: Option<String>,
+    #[validate(length(max = 100))]
+    pub utm_campaign: Option<String>,
+    pub device: Option<DeviceClass>,
+    #[validate(length(max = 2))]
+    pub country: Option<String>,
+    // Break down the views in the range by a dimension
+    pub group_by: Option<AnalyticsDimension>,
+}
+
+#[derive(Serialize)]
+pub struct HourlyViewsViewModel {
+    pub hour: JsDate,
+    pub views: i64,
+}
+
+#[derive(Serialize)]
+pub struct DimensionViewsViewModel {
+    pub value: Option<String>,
+    pub views: i64,
+}
+
+#[derive(Serialize)]
+pub struct SiteAnalyticsViewModel {
+    pub start: JsDate,
+    pub end: JsDate,
+    pub total_views: i64,
+    // Every hour of the range, including hours without views
+    pub hours: Vec<HourlyViewsViewModel>,
+    // Views by the `group_by` dimension, most viewed first
+    pub breakdown: Vec<DimensionViewsViewModel>,
+}
+
+impl From<DimensionViewsEntity> for DimensionViewsViewModel {
+    fn from(value: DimensionViewsEntity) -> Self {
+        DimensionViewsViewModel {
+            value: value.value,
+            views: value.views,
+        }
+    }
+}
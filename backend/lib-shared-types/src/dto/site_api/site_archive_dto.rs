@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+use crate::{
+    entity::site_api::{site_entity::SiteEntity, static_page_entity::StaticPageEntity},
+    shared::site::SiteType,
+    type_util::REGEX_UUID,
+};
+
+use super::create_metadata_dto::CreateSiteMetadataDto;
+
+// Bumped when the layout of the archive changes in a way older readers cannot handle
+pub const SITE_ARCHIVE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteArchiveManifest {
+    pub format_version: u32,
+    // Latest site database migration applied when the archive was exported
+    pub schema_version: i64,
+    pub site_id: String,
+    pub name: String,
+    pub exported_at: DateTime<Utc>,
+}
+
+// Raw site_versions columns, as stored in the site database
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteArchiveVersion {
+    pub name: String,
+    pub version: String,
+    pub context: String,
+    pub defaults: String,
+    pub editor: String,
+    pub history: String,
+    pub pages: String,
+    pub page_order: String,
+    pub published: bool,
+    pub content_updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteArchiveTable {
+    pub name: String,
+    pub columns: Value,
+    pub events: Value,
+    // Stored in separate chunks after the table, so rows can be written as they are read
+    #[serde(skip)]
+    pub rows: Vec<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteArchiveStaticPage {
+    pub route: String,
+    pub body: String,
+    pub content_type: String,
+    pub content_updated_at: i64,
+}
+
+#[derive(Debug)]
+pub struct SiteArchive {
+    pub manifest: SiteArchiveManifest,
+    // Oldest first: the published version (if any) followed by the draft
+    pub versions: Vec<SiteArchiveVersion>,
+    pub custom_tables: Vec<SiteArchiveTable>,
+    pub static_pages: Vec<SiteArchiveStaticPage>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ImportSiteQuery {
+    #[validate(regex(path = "*REGEX_UUID"))]
+    pub site_id: String,
+    #[validate(regex(path = "*REGEX_UUID"))]
+    pub owner_id: String,
+    #[validate(email)]
+    pub owner_email: String,
+    pub site_type: SiteType,
+}
+
+impl From<&SiteEntity> for SiteArchiveVersion {
+    fn from(site: &SiteEntity) -> Self {
+        SiteArchiveVersion {
+            name: site.name.clone(),
+            version: site.version.clone(),
+            context: site.context.clone(),
+            defaults: site.defaults.clone(),
+            editor: site.editor.clone(),
+            history: site.history.clone(),
+            pages: site.pages.clone(),
+            page_order: site.page_order.clone(),
+            published: site.published,
+            content_updated_at: site.content_updated_at,
+        }
+    }
+}
+
+impl From<StaticPageEntity> for SiteArchiveStaticPage {
+    fn from(page: StaticPageEntity) -> Self {
+        SiteArchiveStaticPage {
+            route: page.route,
+            body: page.body,
+            content_type: page.content_type,
+            content_updated_at: page.content_updated_at,
+        }
+    }
+}
+
+impl From<SiteArchiveStaticPage> for StaticPageEntity {
+    fn from(page: SiteArchiveStaticPage) -> Self {
+        StaticPageEntity {
+            route: page.route,
+            body: page.body,
+            content_type: page.content_type,
+            content_updated_at: page.content_updated_at,
+            // Imported pages are regenerated on the next publish
+            page_hash: String::new(),
+            shared_hash: String::new(),
+            body_br: None,
+            body_gzip: None,
+        }
+    }
+}
+
+impl From<&ImportSiteQuery> for CreateSiteMetadataDto {
+    fn from(value: &ImportSiteQuery) -> Self {
+        CreateSiteMetadataDto {
+            site_id: value.site_id.clone(),
+            owner_id: value.owner_id.clone(),
+            owner_email: value.owner_email.clone(),
+            domains: vec![],
+            site_type: value.site_type,
+        }
+    }
+}
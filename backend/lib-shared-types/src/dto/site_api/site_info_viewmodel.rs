@@ -8,6 +8,8 @@ pub struct SiteInfoViewModel {
     pub name: String,
     pub updated_at: JsDate,
     pub published: bool,
+    pub message: Option<String>,
+    pub tag: Option<String>,
 }
 
 pub fn to_api_response(site_entity: SiteInfoEntity) -> SiteInfoViewModel {
@@ -18,5 +20,7 @@ pub fn to_api_response(site_entity: SiteInfoEntity) -> SiteInfoViewModel {
             timestamp: site_entity.updated_at,
         },
         published: site_entity.published,
+        message: site_entity.message,
+        tag: site_entity.tag,
     }
 }
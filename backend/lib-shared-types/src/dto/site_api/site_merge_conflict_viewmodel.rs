@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+use crate::error::api_error::ApiErrorCode;
+
+// A change that could not be merged. `route` and `component_id` are set when the
+// conflict is within a page or component
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SiteMergeConflict {
+    pub field: String,
+    pub route: Option<String>,
+    pub component_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SiteMergeConflictViewModel {
+    pub code: String,
+    pub message: String,
+    pub status: u16,
+    pub conflicts: Vec<SiteMergeConflict>,
+}
+
+pub fn to_api_response(conflicts: Vec<SiteMergeConflict>) -> SiteMergeConflictViewModel {
+    SiteMergeConflictViewModel {
+        code: ApiErrorCode::MergeConflict.to_string(),
+        message: "Update conflicts with changes made in another session".into(),
+        status: 409,
+        conflicts,
+    }
+}
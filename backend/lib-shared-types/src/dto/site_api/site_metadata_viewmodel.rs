@@ -1,13 +1,20 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    entity::site_api::custom_domain_entity::CustomDomainRelationEntity, shared::site::SiteType,
+    entity::site_api::custom_domain_entity::CustomDomainRelationEntity,
+    shared::site::{DomainKind, SiteType, WwwPolicy},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CustomDomainViewModel {
     pub domain: String,
     pub verified: bool,
+    // Canonical host of the site, other hosts redirect to it
+    #[serde(default)]
+    pub primary: bool,
+    // Serve the site, redirect to the canonical host, or serve every subdomain
+    #[serde(default)]
+    pub kind: DomainKind,
 }
 
 impl From<CustomDomainRelationEntity> for CustomDomainViewModel {
@@ -15,6 +22,8 @@ impl From<CustomDomainRelationEntity> for CustomDomainViewModel {
         CustomDomainViewModel {
             domain: value.domain,
             verified: value.verified,
+            primary: value.primary,
+            kind: value.kind,
         }
     }
 }
@@ -32,4 +41,5 @@ pub struct SiteMetadataViewModel {
     pub disabled: bool,
     pub site_type: SiteType,
     pub custom_domains: Vec<CustomDomainViewModel>,
+    pub www_policy: WwwPolicy,
 }
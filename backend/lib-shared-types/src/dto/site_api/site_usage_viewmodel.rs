@@ -7,6 +7,14 @@ use crate::{
     shared::{js_date::JsDate, site::SiteType},
 };
 
+// Estimated unique visitors of the current UTC day, and visitor-days of the month so far.
+// Visitors can't be linked across days, so a visitor is counted once per day they visited
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniqueVisitorCounts {
+    pub daily: u64,
+    pub monthly_visitor_days: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SiteUsageViewModel {
     pub site_size: u64,
@@ -17,6 +25,8 @@ pub struct SiteUsageViewModel {
     pub total_request_count: u64,
     pub site_view_count: u64,
     pub total_site_view_count: u64,
+    pub daily_unique_visitors: u64,
+    pub monthly_visitor_days: u64,
     pub page_views: HashMap<String, u64>,
     pub total_page_views: HashMap<String, u64>,
     pub request_error_count: u64,
@@ -33,6 +43,7 @@ pub fn from_usage_data(
     site_type: SiteType,
     custom_data_usage: i64,
     custom_data_allowance: i64,
+    unique_visitors: UniqueVisitorCounts,
 ) -> SiteUsageViewModel {
     SiteUsageViewModel {
         site_size: data.site_size,
@@ -43,6 +54,8 @@ pub fn from_usage_data(
         total_request_count: data.total_request_count,
         site_view_count: data.site_view_count,
         total_site_view_count: data.total_site_view_count,
+        daily_unique_visitors: unique_visitors.daily,
+        monthly_visitor_days: unique_visitors.monthly_visitor_days,
         page_views: data.page_views,
         total_page_views: data.total_page_views,
         request_error_count: data.request_error_count,
@@ -58,16 +71,21 @@ pub fn from_usage_data(
 #[derive(Debug, Clone, Serialize)]
 pub struct PublicSiteUsageViewModel {
     pub total_site_view_count: u64,
+    pub daily_unique_visitors: u64,
+    pub monthly_visitor_days: u64,
     pub total_page_views: HashMap<String, u64>,
     pub last_updated: JsDate,
 }
 
-impl From<SiteUsageData> for PublicSiteUsageViewModel {
-    fn from(value: SiteUsageData) -> Self {
-        PublicSiteUsageViewModel {
-            total_site_view_count: value.total_site_view_count,
-            total_page_views: value.total_page_views,
-            last_updated: value.last_updated,
-        }
+pub fn to_public_usage(
+    data: SiteUsageData,
+    unique_visitors: UniqueVisitorCounts,
+) -> PublicSiteUsageViewModel {
+    PublicSiteUsageViewModel {
+        total_site_view_count: data.total_site_view_count,
+        daily_unique_visitors: unique_visitors.daily,
+        monthly_visitor_days: unique_visitors.monthly_visitor_days,
+        total_page_views: data.total_page_views,
+        last_updated: data.last_updated,
     }
 }
@@ -19,6 +19,8 @@ pub struct SiteViewModel {
     pub updated_at: JsDate,
     pub published: bool,
     pub preview_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<i64>,
 }
 
 pub fn to_api_response(site_entity: SiteEntity) -> SiteViewModel {
@@ -41,5 +43,6 @@ pub fn to_api_response(site_entity: SiteEntity) -> SiteViewModel {
         },
         published: site_entity.published,
         preview_id: site_entity.preview_id,
+        revision_id: site_entity.revision_id,
     }
 }
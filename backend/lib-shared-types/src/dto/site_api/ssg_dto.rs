@@ -12,6 +12,10 @@ pub struct SsgSiteInput {
     pub pages: String,
     #[serde(rename = "pageOrder")]
     pub page_order: String,
+    // Pages behind a protected route, encoded like `pages`. Kept out of `pages` so they never
+    // reach the hydration payload of other pages
+    #[serde(rename = "protectedPages", skip_serializing_if = "Option::is_none")]
+    pub protected_pages: Option<String>,
     // Millisecond timestamp used for sitemap lastmod
     pub updated_at: i64,
 }
@@ -22,6 +26,9 @@ pub struct SsgOptions {
     pub base_url: Option<String>,
     #[serde(rename = "runtimeSrc")]
     pub runtime_src: String,
+    // Only render these page routes. All public pages are rendered when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -71,6 +78,7 @@ pub fn site_to_ssg_input(site: &SiteEntity, site_id: &str) -> SsgSiteInput {
         context: site.context.clone(),
         pages: site.pages.clone(),
         page_order: site.page_order.clone(),
+        protected_pages: None,
         updated_at: site.content_updated_at,
     }
 }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    entity::site_api::static_build_entity::{
+        StaticBuildEntity, StaticBuildStatus, StaticBuildTrigger,
+    },
+    shared::js_date::JsDate,
+};
+
+#[derive(Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ListStaticBuildsQuery {
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct StaticBuildViewModel {
+    pub id: i64,
+    pub trigger: StaticBuildTrigger,
+    pub status: StaticBuildStatus,
+    pub content_updated_at: i64,
+    pub generator: Option<String>,
+    pub page_count: i64,
+    pub incremental: bool,
+    pub warnings: Vec<String>,
+    pub blockers: Vec<String>,
+    pub error: Option<String>,
+    pub started_at: JsDate,
+    pub finished_at: Option<JsDate>,
+}
+
+pub fn to_api_response(entity: StaticBuildEntity) -> StaticBuildViewModel {
+    StaticBuildViewModel {
+        id: entity.id,
+        trigger: entity.trigger,
+        status: entity.status,
+        content_updated_at: entity.content_updated_at,
+        generator: entity.generator,
+        page_count: entity.page_count,
+        incremental: entity.incremental,
+        warnings: entity.warnings,
+        blockers: entity.blockers,
+        error: entity.error,
+        started_at: JsDate {
+            timestamp: entity.started_at,
+        },
+        finished_at: entity.finished_at.map(|timestamp| JsDate { timestamp }),
+    }
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+
+use crate::{entity::site_api::template_entity::TemplateInfoEntity, shared::js_date::JsDate};
+
+#[derive(Serialize)]
+pub struct TemplateViewModel {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: String,
+    // Object key in the template-previews bucket
+    pub preview_key: Option<String>,
+    pub created_at: JsDate,
+}
+
+#[derive(Serialize)]
+pub struct CreateTemplateResponse {
+    #[serde(flatten)]
+    pub template: TemplateViewModel,
+    // Presigned PUT URL for the preview image, when requested
+    pub preview_upload_url: Option<String>,
+}
+
+pub fn to_api_response(entity: TemplateInfoEntity) -> TemplateViewModel {
+    TemplateViewModel {
+        id: entity.id,
+        name: entity.name,
+        description: entity.description,
+        category: entity.category,
+        preview_key: entity.preview_key,
+        created_at: JsDate {
+            timestamp: entity.created_at,
+        },
+    }
+}
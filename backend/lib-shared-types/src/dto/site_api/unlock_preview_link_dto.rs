@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UnlockPreviewLinkDto {
+    #[validate(length(min = 1, max = 100))]
+    pub password: String,
+}
+
+// The token is also set as a cookie. API clients send it in the `X-Preview-Token` header
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UnlockPreviewLinkResponse {
+    pub token: String,
+}
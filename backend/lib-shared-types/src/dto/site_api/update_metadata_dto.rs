@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::shared::site::SiteType;
+use crate::shared::site::{SiteType, WwwPolicy};
 
 use super::site_metadata_viewmodel::CustomDomainViewModel;
 
@@ -11,4 +11,5 @@ pub struct UpdateSiteMetadataDto {
     pub domains: Option<Vec<CustomDomainViewModel>>,
     pub owner_email: Option<String>,
     pub owner_id: Option<String>,
+    pub www_policy: Option<WwwPolicy>,
 }
@@ -21,9 +21,14 @@ pub struct UpdateSiteDto {
     #[validate(regex(path = "*REGEX_DATE"))]
     pub update_key: Option<String>,
     pub enable_preview: Option<bool>,
+    // `revision_id` of the site the editor session started from. When set and the site changed
+    // since, `pages`, `context`, and `defaults` are merged with the latest version
+    pub base_revision_id: Option<i64>,
 }
 
 pub struct UpdateSiteDtoWithContentUpdatedAt {
     pub dto: UpdateSiteDto,
     pub content_updated_at: Option<i64>,
+    // Only update if the latest version's `content_updated_at` matches
+    pub expected_content_updated_at: Option<i64>,
 }
@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::dto::site_api::site_metadata_viewmodel::CustomDomainViewModel;
+use crate::{
+    dto::site_api::site_metadata_viewmodel::CustomDomainViewModel, shared::site::DomainKind,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CustomDomainEntity {
@@ -15,6 +17,10 @@ pub struct CustomDomainEntity {
 pub struct CustomDomainRelationEntity {
     pub domain: String,
     pub verified: bool,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(default)]
+    pub kind: DomainKind,
 }
 
 pub fn vec_from_viewmodel(domains: Vec<CustomDomainViewModel>) -> Vec<CustomDomainRelationEntity> {
@@ -23,6 +29,8 @@ pub fn vec_from_viewmodel(domains: Vec<CustomDomainViewModel>) -> Vec<CustomDoma
         .map(|d| CustomDomainRelationEntity {
             domain: d.domain,
             verified: d.verified,
+            primary: d.primary,
+            kind: d.kind,
         })
         .collect()
 }
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, Display, sqlx::Type,
+)]
+pub enum DomainVerificationStatus {
+    // Not verified yet
+    Pending,
+    Verified,
+    // Verified before, and failing the latest checks
+    Failing,
+    // Failed checks for too long, the domain is no longer served
+    Suspended,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DomainVerificationEntity {
+    pub domain: String,
+    pub site_id: String,
+    // Expected in a TXT record of `_pubstudio.{domain}`
+    pub token: String,
+    pub status: DomainVerificationStatus,
+    pub verified: bool,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    // Record that verified the domain: TXT, CNAME or A
+    pub last_result: Option<String>,
+    pub last_error: Option<String>,
+    pub failing_since: Option<DateTime<Utc>>,
+    // Checks failed in a row
+    pub failed_checks: i64,
+    pub created_at: DateTime<Utc>,
+}
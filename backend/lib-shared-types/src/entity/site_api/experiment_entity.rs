@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExperimentVariantEntity {
+    pub id: i64,
+    pub experiment_id: i64,
+    pub name: String,
+    // Page of the site rendered for visitors assigned to the variant
+    pub page_route: String,
+    pub weight: i64,
+    pub exposures: i64,
+    pub conversions: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExperimentEntity {
+    pub id: i64,
+    pub route: String,
+    pub name: String,
+    pub enabled: bool,
+    pub conversion_table: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // In creation order, the first variant is the control
+    pub variants: Vec<ExperimentVariantEntity>,
+}
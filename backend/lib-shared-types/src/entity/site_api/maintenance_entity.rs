@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+// Holding page served during maintenance
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumString,
+    Display,
+    sqlx::Type,
+)]
+pub enum MaintenancePage {
+    // Generated page with the site title and the owner's message
+    #[default]
+    Builtin,
+    // The owner's HTML document
+    Custom,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MaintenanceEntity {
+    pub enabled: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub page: MaintenancePage,
+    pub message: Option<String>,
+    pub custom_page: Option<String>,
+    pub allowed_ips: Vec<String>,
+    pub bypass_token: String,
+    pub updated_at: DateTime<Utc>,
+}
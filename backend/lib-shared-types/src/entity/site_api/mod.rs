@@ -1,8 +1,19 @@
 pub mod backup_entity;
 pub mod custom_domain_entity;
+pub mod domain_verification_entity;
+pub mod experiment_entity;
+pub mod maintenance_entity;
+pub mod preview_link_entity;
+pub mod protected_route_entity;
+pub mod publish_schedule_entity;
+pub mod redirect_entity;
 pub mod site_custom_data_info_entity;
 pub mod site_entity;
 pub mod site_info_entity;
 pub mod site_metadata_entity;
+pub mod site_revision_entity;
 pub mod site_usage_entity;
+pub mod site_usage_hourly_entity;
+pub mod static_build_entity;
 pub mod static_page_entity;
+pub mod template_entity;
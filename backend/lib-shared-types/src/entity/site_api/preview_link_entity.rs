@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct PreviewLinkEntity {
+    pub id: String,
+    pub version_id: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub password_hash: Option<String>,
+    pub view_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PreviewLinkEntity {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
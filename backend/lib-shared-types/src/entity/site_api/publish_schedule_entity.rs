@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, Display, sqlx::Type,
+)]
+pub enum PublishScheduleStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PublishScheduleEntity {
+    pub id: i64,
+    pub site_id: String,
+    pub publish_at: DateTime<Utc>,
+    pub status: PublishScheduleStatus,
+    pub error: Option<String>,
+    // Set when an instance claims the schedule, identifies the claim
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RedirectEntity {
+    pub id: i64,
+    pub source: String,
+    pub target: String,
+    pub status_code: i64,
+    pub enabled: bool,
+    pub hit_count: i64,
+    pub last_hit_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -17,6 +17,8 @@ pub struct SiteEntity {
     pub content_updated_at: i64,
     pub published: bool,
     pub preview_id: Option<String>,
+    // ID of the recorded revision of the content, used as the merge base of updates
+    pub revision_id: Option<i64>,
 }
 
 impl SiteEntity {
@@ -7,4 +7,19 @@ pub struct SiteInfoEntity {
     pub name: String,
     pub updated_at: DateTime<Utc>,
     pub published: bool,
+    pub message: Option<String>,
+    pub tag: Option<String>,
+}
+
+// Optional message and tag attached to a site version
+#[derive(Clone, Debug, Default)]
+pub struct SiteVersionLabel {
+    pub message: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl SiteVersionLabel {
+    pub fn is_empty(&self) -> bool {
+        self.message.is_none() && self.tag.is_none()
+    }
 }
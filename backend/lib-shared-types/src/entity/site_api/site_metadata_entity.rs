@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{dto::site_api::update_metadata_dto::UpdateSiteMetadataDto, shared::site::SiteType};
+use crate::{
+    dto::site_api::update_metadata_dto::UpdateSiteMetadataDto,
+    shared::site::{SiteType, WwwPolicy},
+};
 
 use super::custom_domain_entity::{vec_from_viewmodel, CustomDomainRelationEntity};
 
@@ -14,6 +17,7 @@ pub struct SiteMetadataEntity {
     pub site_type: SiteType,
     pub disabled: bool,
     pub custom_data_usage: i64,
+    pub www_policy: WwwPolicy,
 }
 
 pub struct UpdateSiteMetadataEntity {
@@ -23,6 +27,7 @@ pub struct UpdateSiteMetadataEntity {
     pub disabled: Option<bool>,
     pub domains: Option<Vec<CustomDomainRelationEntity>>,
     pub custom_data_usage: Option<i64>,
+    pub www_policy: Option<WwwPolicy>,
 }
 
 impl UpdateSiteMetadataEntity {
@@ -34,6 +39,7 @@ impl UpdateSiteMetadataEntity {
             disabled: None,
             domains: None,
             custom_data_usage: Some(usage),
+            www_policy: None,
         }
     }
 }
@@ -47,6 +53,7 @@ impl From<UpdateSiteMetadataDto> for UpdateSiteMetadataEntity {
             disabled: value.disabled,
             domains: value.domains.and_then(|d| Some(vec_from_viewmodel(d))),
             custom_data_usage: None,
+            www_policy: value.www_policy,
         }
     }
 }
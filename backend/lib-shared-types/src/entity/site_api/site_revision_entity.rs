@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct SiteRevisionEntity {
+    pub id: i64,
+    pub content_updated_at: i64,
+    pub context: String,
+    pub defaults: String,
+    pub pages: String,
+}
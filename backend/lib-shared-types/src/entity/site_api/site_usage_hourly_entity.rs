@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+// Device class of a page view, parsed from the User-Agent header
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    Display,
+    sqlx::Type,
+)]
+pub enum DeviceClass {
+    Desktop,
+    Mobile,
+    Tablet,
+    // Crawlers and headless browsers that run the site's scripts
+    Bot,
+    #[default]
+    Unknown,
+}
+
+// Dimension that hourly page views can be broken down by
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AnalyticsDimension {
+    Route,
+    Referrer,
+    UtmSource,
+    UtmMedium,
+    UtmCampaign,
+    Device,
+    Country,
+}
+
+impl AnalyticsDimension {
+    // Column of the dimension in `site_usage_hourly`
+    pub fn column(&self) -> &'static str {
+        match self {
+            AnalyticsDimension::Route => "route",
+            AnalyticsDimension::Referrer => "referrer",
+            AnalyticsDimension::UtmSource => "utm_source",
+            AnalyticsDimension::UtmMedium => "utm_medium",
+            AnalyticsDimension::UtmCampaign => "utm_campaign",
+            AnalyticsDimension::Device => "device",
+            AnalyticsDimension::Country => "country",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HourlyViewsEntity {
+    pub hour: DateTime<Utc>,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DimensionViewsEntity {
+    // None for views without the dimension, e.g. direct visits when broken down by referrer
+    pub value: Option<String>,
+    pub views: i64,
+}
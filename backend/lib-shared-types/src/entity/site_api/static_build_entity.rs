@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+// What started an SSG run
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, Display, sqlx::Type,
+)]
+pub enum StaticBuildTrigger {
+    Publish,
+    Update,
+    Schedule,
+    Metadata,
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, Display, sqlx::Type,
+)]
+pub enum StaticBuildStatus {
+    Running,
+    Succeeded,
+    Failed,
+    // Static pages were removed because the site is unpublished or disabled
+    Cleared,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StaticBuildEntity {
+    pub id: i64,
+    pub trigger: StaticBuildTrigger,
+    pub status: StaticBuildStatus,
+    pub content_updated_at: i64,
+    pub generator: Option<String>,
+    pub page_count: i64,
+    pub incremental: bool,
+    pub warnings: Vec<String>,
+    pub blockers: Vec<String>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
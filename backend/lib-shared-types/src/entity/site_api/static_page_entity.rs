@@ -5,4 +5,11 @@ pub struct StaticPageEntity {
     pub body: String,
     pub content_type: String,
     pub content_updated_at: i64,
+    // Content hashes at generation time, empty when unknown (e.g. imported pages)
+    pub page_hash: String,
+    pub shared_hash: String,
+    // Brotli and gzip variants of `body`, compressed by the repo when the page is stored.
+    // None for pages stored before compression was added
+    pub body_br: Option<Vec<u8>>,
+    pub body_gzip: Option<Vec<u8>>,
 }
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::dto::site_api::site_archive_dto::SiteArchiveVersion;
+
+// Template listing data, without the site content
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TemplateInfoEntity {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: String,
+    pub preview_key: Option<String>,
+    pub source_site_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TemplateEntity {
+    pub info: TemplateInfoEntity,
+    pub version: String,
+    pub context: String,
+    pub defaults: String,
+    pub editor: String,
+    pub history: String,
+    pub pages: String,
+    pub page_order: String,
+    // CreateTable data for each custom table schema
+    pub custom_tables: Vec<Value>,
+}
+
+impl TemplateEntity {
+    // First version of a site started from the template
+    pub fn to_site_version(&self, name: &str, published: bool) -> SiteArchiveVersion {
+        SiteArchiveVersion {
+            name: name.to_string(),
+            version: self.version.clone(),
+            context: self.context.clone(),
+            defaults: self.defaults.clone(),
+            editor: self.editor.clone(),
+            history: self.history.clone(),
+            pages: self.pages.clone(),
+            page_order: self.page_order.clone(),
+            published,
+            content_updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
@@ -58,6 +58,19 @@ pub enum ApiErrorCode {
     CustomDataMaxLengthFail,
     CustomDataUniqueFail,
     CustomDataUsageExceeded,
+    InvalidPatch,
+    MergeConflict,
+    PreviewExpired,
+    PreviewPasswordRequired,
+    InvalidSiteArchive,
+    SiteCapacityExceeded,
+    SsgBlocked,
+    RedirectSourceExists,
+    ProtectedRouteExists,
+    SitePasswordRequired,
+    SiteMaintenance,
+    ExperimentRouteExists,
+    LoginLocked,
     None,
 }
 
@@ -32,6 +32,55 @@ impl SitePaymentPeriod {
     }
 }
 
+// How the primary domain's www subdomain is handled. Also lets the www/apex variant of a
+// site's domains resolve to the site, so it can be redirected to the canonical host.
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumString,
+    Display,
+    sqlx::Type,
+)]
+pub enum WwwPolicy {
+    // The primary domain is canonical as entered
+    #[default]
+    Keep,
+    // `example.com` -> `www.example.com`
+    Www,
+    // `www.example.com` -> `example.com`
+    Apex,
+}
+
+// How requests to a custom domain are handled
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumString,
+    Display,
+    sqlx::Type,
+)]
+pub enum DomainKind {
+    // The site is served on the domain
+    #[default]
+    Serve,
+    // Alias, permanently redirected to the site's canonical host
+    Redirect,
+    // `*.events.example.com`, the site is served on every subdomain
+    Wildcard,
+}
+
 #[derive(
     Debug,
     Serialize,
@@ -77,6 +126,16 @@ impl SiteType {
             SiteType::Paid3 => 50 * MB,
         }
     }
+    // Number of recent site versions kept by the retention cron, in addition to
+    // tagged versions and one version per day for the last 30 days
+    pub fn get_max_versions(&self) -> u32 {
+        match self {
+            SiteType::Free => 10,
+            SiteType::Paid1 => 25,
+            SiteType::Paid2 => 50,
+            SiteType::Paid3 => 100,
+        }
+    }
     pub fn get_bandwidth_allowance(&self, exec_env: ExecEnv) -> u64 {
         if exec_env == ExecEnv::Dev || exec_env == ExecEnv::Ci {
             match self {
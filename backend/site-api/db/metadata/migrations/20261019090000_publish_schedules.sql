@@ -0,0 +1,25 @@
+-- Publish jobs scheduled by site owners. Rows are claimed atomically by setting
+-- `status = 'Running'`, so only one instance runs a schedule when several share the DB.
+CREATE TABLE IF NOT EXISTS publish_schedules
+(
+    id           INTEGER PRIMARY KEY NOT NULL,
+    site_id      TEXT                NOT NULL,
+    publish_at   TIMESTAMP           NOT NULL,
+    status       TEXT                NOT NULL DEFAULT 'Pending',
+    error        TEXT                DEFAULT NULL,
+    claimed_at   TIMESTAMP           DEFAULT NULL,
+    completed_at TIMESTAMP           DEFAULT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_site_id FOREIGN KEY (site_id) REFERENCES sites(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS publish_schedules_status_publish_at ON publish_schedules(status, publish_at);
+
+CREATE TRIGGER IF NOT EXISTS publish_schedules_update_timestamp
+BEFORE UPDATE ON publish_schedules
+BEGIN
+  UPDATE publish_schedules
+  SET updated_at = CURRENT_TIMESTAMP
+  WHERE id = old.id;
+END;
@@ -0,0 +1,31 @@
+-- Site templates published by admins. Content columns are copied verbatim from the
+-- source site's site_versions row. `custom_tables` is a JSON array of CreateTable data.
+CREATE TABLE IF NOT EXISTS templates
+(
+    id             TEXT PRIMARY KEY    NOT NULL,
+    name           TEXT UNIQUE         NOT NULL,
+    description    TEXT                DEFAULT NULL,
+    category       TEXT                NOT NULL,
+    preview_key    TEXT                DEFAULT NULL,
+    source_site_id TEXT                NOT NULL,
+    version        TEXT                NOT NULL,
+    context        TEXT                NOT NULL,
+    defaults       TEXT                NOT NULL,
+    editor         TEXT                NOT NULL,
+    history        TEXT                NOT NULL,
+    pages          TEXT                NOT NULL,
+    page_order     TEXT                NOT NULL,
+    custom_tables  TEXT                NOT NULL DEFAULT '[]',
+    created_at     TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at     TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS templates_category ON templates(category);
+
+CREATE TRIGGER IF NOT EXISTS templates_update_timestamp
+BEFORE UPDATE ON templates
+BEGIN
+  UPDATE templates
+  SET updated_at = CURRENT_TIMESTAMP
+  WHERE id = old.id;
+END;
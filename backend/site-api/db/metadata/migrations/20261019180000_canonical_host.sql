@@ -0,0 +1,4 @@
+-- The primary domain is the site's canonical host, other hosts are redirected to it.
+-- `www_policy` is applied to the primary domain: Keep, Www or Apex.
+ALTER TABLE domains ADD COLUMN is_primary BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE sites ADD COLUMN www_policy TEXT NOT NULL DEFAULT 'Keep';
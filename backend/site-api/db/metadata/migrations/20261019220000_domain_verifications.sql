@@ -0,0 +1,17 @@
+-- DNS verification of custom domains. Rows are kept by domain, since site domains are
+-- replaced on every metadata update. `verified` is copied to `domains.verified` once checked.
+-- Status is one of Pending, Verified, Failing or Suspended. Suspended domains are not served.
+CREATE TABLE IF NOT EXISTS domain_verifications
+(
+    domain          TEXT PRIMARY KEY NOT NULL,
+    site_id         TEXT NOT NULL,
+    token           TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'Pending',
+    verified        BOOLEAN NOT NULL DEFAULT FALSE,
+    last_checked_at TIMESTAMP,
+    last_result     TEXT,
+    last_error      TEXT,
+    failing_since   TIMESTAMP,
+    created_at      TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT      fk_site_id FOREIGN KEY (site_id) REFERENCES sites(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- How a domain is handled: Serve, Redirect (to the canonical host), or Wildcard.
+-- Wildcard domains are stored as `*.example.com`, and serve every subdomain.
+ALTER TABLE domains ADD COLUMN kind TEXT NOT NULL DEFAULT 'Serve';
@@ -0,0 +1,21 @@
+-- Page views per site and hour, by route, referrer host, UTM parameters, device and country.
+-- Missing dimensions are stored as '' so rows of the same bucket match the unique index,
+-- and persisting the same bucket again adds to its views.
+CREATE TABLE IF NOT EXISTS site_usage_hourly
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    site_id      TEXT NOT NULL,
+    hour         TIMESTAMP NOT NULL,
+    route        TEXT NOT NULL,
+    referrer     TEXT NOT NULL DEFAULT '',
+    utm_source   TEXT NOT NULL DEFAULT '',
+    utm_medium   TEXT NOT NULL DEFAULT '',
+    utm_campaign TEXT NOT NULL DEFAULT '',
+    device       TEXT NOT NULL DEFAULT 'Unknown',
+    country      TEXT NOT NULL DEFAULT '',
+    views        INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT   fk_site_id FOREIGN KEY (site_id) REFERENCES sites(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS site_usage_hourly_bucket ON site_usage_hourly
+    (site_id, hour, route, referrer, utm_source, utm_medium, utm_campaign, device, country);
@@ -0,0 +1,10 @@
+-- Daily HyperLogLog sketches of anonymous visitor hashes. Sketches of a month merge into
+-- its unique visitor count, no visitor data is stored.
+CREATE TABLE IF NOT EXISTS site_visitor_sketches
+(
+    site_id      TEXT NOT NULL,
+    day          DATE NOT NULL,
+    sketch       BLOB NOT NULL,
+    PRIMARY KEY  (site_id, day),
+    CONSTRAINT   fk_site_id FOREIGN KEY (site_id) REFERENCES sites(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Lease of a claimed schedule. The running instance renews `heartbeat_at` while it publishes,
+-- and a schedule is only re-claimed once its heartbeat is stale. `claimed_at` identifies the
+-- claim, so an instance that lost its lease can't finish the schedule.
+ALTER TABLE publish_schedules ADD COLUMN heartbeat_at TIMESTAMP DEFAULT NULL;
@@ -0,0 +1,3 @@
+-- Checks failed in a row since the domain last passed. Owners are only notified of a failing
+-- domain after several, so a single DNS hiccup doesn't send mail.
+ALTER TABLE domain_verifications ADD COLUMN failed_checks INTEGER NOT NULL DEFAULT 0;
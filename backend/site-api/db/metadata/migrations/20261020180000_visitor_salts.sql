@@ -0,0 +1,8 @@
+-- Salt of the current day's anonymous visitor hashes, shared by every server. A salt is
+-- deleted once it expires, so hashes of past days can't be recomputed.
+CREATE TABLE IF NOT EXISTS visitor_salts
+(
+    day          DATE PRIMARY KEY NOT NULL,
+    salt         BLOB NOT NULL,
+    expires_at   DATETIME NOT NULL
+);
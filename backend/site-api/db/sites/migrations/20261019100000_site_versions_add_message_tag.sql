@@ -0,0 +1,11 @@
+-- Optional label for a version, set when publishing or creating a draft.
+-- Tagged versions are never removed by the retention cron.
+ALTER TABLE
+  site_versions
+ADD COLUMN
+  message TEXT DEFAULT NULL;
+
+ALTER TABLE
+  site_versions
+ADD COLUMN
+  tag TEXT DEFAULT NULL;
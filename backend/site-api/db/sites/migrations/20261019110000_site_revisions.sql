@@ -0,0 +1,9 @@
+-- Recent content snapshots, keyed by the `content_updated_at` of the save that produced them.
+-- Used as the merge base when an editor session saves against an older revision.
+CREATE TABLE IF NOT EXISTS site_revisions (
+    content_updated_at INTEGER PRIMARY KEY NOT NULL,
+    context TEXT NOT NULL,
+    defaults TEXT NOT NULL,
+    pages TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
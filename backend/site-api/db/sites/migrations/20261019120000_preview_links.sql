@@ -0,0 +1,10 @@
+-- Shareable preview links, each bound to a specific site version.
+-- `password_hash` is an Argon2 PHC string, NULL when the link is unprotected.
+CREATE TABLE IF NOT EXISTS preview_links (
+    id TEXT PRIMARY KEY NOT NULL,
+    version_id INTEGER NOT NULL REFERENCES site_versions(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP,
+    password_hash TEXT,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,4 @@
+-- Content hashes used to regenerate only the SSG pages that changed. `page_hash` covers the
+-- page document, `shared_hash` covers data every page depends on (defaults, context, etc).
+ALTER TABLE static_pages ADD COLUMN page_hash TEXT NOT NULL DEFAULT '';
+ALTER TABLE static_pages ADD COLUMN shared_hash TEXT NOT NULL DEFAULT '';
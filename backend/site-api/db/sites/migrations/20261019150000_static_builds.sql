@@ -0,0 +1,16 @@
+-- History of SSG runs for the site. `warnings` and `blockers` are JSON string arrays as
+-- reported by the generator. Only the latest runs are kept.
+CREATE TABLE IF NOT EXISTS static_builds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    trigger TEXT NOT NULL,
+    status TEXT NOT NULL,
+    content_updated_at INTEGER NOT NULL,
+    generator TEXT DEFAULT NULL,
+    page_count INTEGER NOT NULL DEFAULT 0,
+    incremental BOOLEAN NOT NULL DEFAULT 0,
+    warnings TEXT NOT NULL DEFAULT '[]',
+    blockers TEXT NOT NULL DEFAULT '[]',
+    error TEXT DEFAULT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP DEFAULT NULL
+);
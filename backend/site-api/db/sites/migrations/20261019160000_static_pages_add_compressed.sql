@@ -0,0 +1,4 @@
+-- Brotli and gzip variants of `body`, compressed when pages are stored and picked by
+-- Accept-Encoding when served. NULL for pages stored before compression was added.
+ALTER TABLE static_pages ADD COLUMN body_br BLOB;
+ALTER TABLE static_pages ADD COLUMN body_gzip BLOB;
@@ -0,0 +1,13 @@
+-- Owner defined redirects, matched against request paths before static or SPA serving.
+-- `source` is an exact path, a `/*` prefix, and may contain `:param` segments.
+CREATE TABLE IF NOT EXISTS redirects (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL UNIQUE,
+    target TEXT NOT NULL,
+    status_code INTEGER NOT NULL DEFAULT 301,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    hit_count INTEGER NOT NULL DEFAULT 0,
+    last_hit_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Password protected route prefixes. Visitors unlock a route with its password, `/` protects
+-- the whole site. The password is stored as an Argon2 PHC string.
+CREATE TABLE IF NOT EXISTS protected_routes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    route TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,15 @@
+-- Owner controlled maintenance mode, a single row per site. Visitors get a 503 holding page
+-- while enabled and inside the optional start/end window, except allowlisted IPs and
+-- previewers holding the bypass token cookie.
+CREATE TABLE IF NOT EXISTS maintenance (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    starts_at TIMESTAMP DEFAULT NULL,
+    ends_at TIMESTAMP DEFAULT NULL,
+    page TEXT NOT NULL DEFAULT 'Builtin',
+    message TEXT DEFAULT NULL,
+    custom_page TEXT DEFAULT NULL,
+    allowed_ips TEXT NOT NULL DEFAULT '[]',
+    bypass_token TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,26 @@
+-- Owner defined A/B experiments. Visitors of `route` are assigned a weighted variant, each
+-- rendering a page of the site. `conversion_table` counts custom data inserts as conversions.
+CREATE TABLE IF NOT EXISTS experiments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    route TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    conversion_table TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Exposures count assigned visitors who viewed the experiment route, conversions count exposed
+-- visitors who converted. Each visitor is counted once per experiment.
+CREATE TABLE IF NOT EXISTS experiment_variants (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    experiment_id INTEGER NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    page_route TEXT NOT NULL,
+    weight INTEGER NOT NULL,
+    exposures INTEGER NOT NULL DEFAULT 0,
+    conversions INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS experiment_variants_experiment_id
+    ON experiment_variants(experiment_id);
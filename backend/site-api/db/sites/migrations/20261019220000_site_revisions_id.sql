@@ -0,0 +1,17 @@
+-- Identify content snapshots by their own id. Editor sessions send the id of the revision they
+-- started from as the merge base.
+CREATE TABLE site_revisions_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    content_updated_at INTEGER UNIQUE NOT NULL,
+    context TEXT NOT NULL,
+    defaults TEXT NOT NULL,
+    pages TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO site_revisions_new (content_updated_at, context, defaults, pages, created_at)
+SELECT content_updated_at, context, defaults, pages, created_at FROM site_revisions
+ORDER BY content_updated_at;
+
+DROP TABLE site_revisions;
+ALTER TABLE site_revisions_new RENAME TO site_revisions;
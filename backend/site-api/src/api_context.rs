@@ -1,12 +1,19 @@
+use hickory_resolver::TokioAsyncResolver;
 use lib_shared_site_api::{cache::cache::AppCache, clients::s3_client::S3Client};
 
 use crate::{
     config::Config,
     db::{
         backup_repo::DynBackupRepo, custom_data_info_repo::DynCustomDataInfoRepo,
-        custom_data_repo::DynCustomDataRepo, site_repo::DynSiteRepo,
-        sites_metadata_repo::DynSitesMetadataRepo, usage_repo::DynUsageRepo,
+        custom_data_repo::DynCustomDataRepo, domain_verification_repo::DynDomainVerificationRepo,
+        experiment_repo::DynExperimentRepo, maintenance_repo::DynMaintenanceRepo,
+        preview_link_repo::DynPreviewLinkRepo, protected_route_repo::DynProtectedRouteRepo,
+        publish_schedule_repo::DynPublishScheduleRepo, redirect_repo::DynRedirectRepo,
+        site_repo::DynSiteRepo, sites_metadata_repo::DynSitesMetadataRepo,
+        static_build_repo::DynStaticBuildRepo, template_repo::DynTemplateRepo,
+        usage_repo::DynUsageRepo,
     },
+    util::{acme::AcmeClient, certificates::CertificateStore},
 };
 use std::sync::Arc;
 
@@ -20,5 +27,18 @@ pub struct ApiContext {
     pub usage_repo: DynUsageRepo,
     pub custom_data_info_repo: DynCustomDataInfoRepo,
     pub custom_data_repo: DynCustomDataRepo,
+    pub publish_schedule_repo: DynPublishScheduleRepo,
+    pub preview_link_repo: DynPreviewLinkRepo,
+    pub template_repo: DynTemplateRepo,
+    pub static_build_repo: DynStaticBuildRepo,
+    pub redirect_repo: DynRedirectRepo,
+    pub protected_route_repo: DynProtectedRouteRepo,
+    pub maintenance_repo: DynMaintenanceRepo,
+    pub experiment_repo: DynExperimentRepo,
+    pub domain_verification_repo: DynDomainVerificationRepo,
+    pub dns_resolver: TokioAsyncResolver,
+    pub certificate_store: CertificateStore,
+    // Set when the TLS listener is enabled
+    pub acme_client: Option<Arc<AcmeClient>>,
     pub cache: AppCache,
 }
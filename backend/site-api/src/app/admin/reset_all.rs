@@ -51,6 +51,15 @@ async fn reset_all_helper(context: ApiContext, dto: ResetAllDto) -> Result<(), A
     context.cache.domain_cache.run_pending_tasks().await;
     context.cache.site_data_cache.invalidate_all();
     context.cache.site_data_cache.run_pending_tasks().await;
+    context.cache.seo_cache.invalidate_all();
+    context.cache.redirect_cache.invalidate_all();
+    context.cache.protection_cache.invalidate_all();
+    context.cache.maintenance_cache.invalidate_all();
+    context.cache.experiment_cache.invalidate_all();
+    context.cache.hourly_usage.drain();
+    context.cache.unique_visitors.drain();
+    context.cache.redirect_hits.drain();
+    context.cache.login_attempts.clear_all();
 
     // Seed new sites
     let seed_data = sites_seed_data(context.config.exec_env);
@@ -1,9 +1,13 @@
 use crate::api_context::ApiContext;
 
-use crate::app::{custom, health, publish, site, usage};
+use crate::app::{
+    certificate, custom, domain_verification, experiment, health, maintenance, protected_route,
+    publish, redirect, site, ssg, template, usage,
+};
 use crate::middleware::auth::{
     auth_admin, auth_admin_owner, auth_admin_owner_anonymous, error_cache,
 };
+use axum::extract::DefaultBodyLimit;
 use axum::handler::Handler;
 
 use axum::http::StatusCode;
@@ -11,16 +15,28 @@ use axum::middleware::from_fn_with_state;
 use axum::response::IntoResponse;
 use axum::routing::{delete, patch, post};
 use axum::{routing::get, Router};
+use lib_shared_site_api::util::{
+    acme::ACME_CHALLENGE_PATH, maintenance::MAINTENANCE_BYPASS_PATH, site_access::SITE_LOGIN_PATH,
+};
 
 use super::admin;
 use super::backup;
-use super::serve::serve_web_site;
+use super::serve::{maintenance_bypass, serve_web_site, site_login};
 
 pub fn app_router(context: &ApiContext) -> Router<ApiContext> {
     let api_router = api_router(context);
 
     Router::new()
         .nest("/api", api_router)
+        .route(
+            ACME_CHALLENGE_PATH,
+            get(certificate::acme_challenge::acme_challenge),
+        )
+        .route(SITE_LOGIN_PATH, post(site_login::site_login))
+        .route(
+            MAINTENANCE_BYPASS_PATH,
+            get(maintenance_bypass::maintenance_bypass),
+        )
         .fallback(get(serve_web_site::serve_web_site)) // Handle all non-api/* routes and render the frontend
 }
 
@@ -60,6 +76,14 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
                     .layer(from_fn_with_state(context.clone(), auth_admin)),
             ),
         )
+        .route(
+            "/sites/{site_id}/actions/patch",
+            post(
+                site::patch_site::patch_site
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner))
+                    .layer(from_fn_with_state(context.clone(), error_cache)),
+            ),
+        )
         .route(
             "/sites/{site_id}/head",
             get(site::get_site_head::get_site_head),
@@ -78,6 +102,28 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
             "/sites/actions/create_from_backup",
             post(site::create_site_from_backup::create_site_from_backup),
         )
+        .route(
+            "/sites/actions/import",
+            post(site::import_site::import_site)
+                .route_layer(DefaultBodyLimit::max(
+                    site::import_site::MAX_SITE_ARCHIVE_SIZE,
+                ))
+                .route_layer(from_fn_with_state(context.clone(), auth_admin)),
+        )
+        .route(
+            "/templates",
+            get(template::list_templates::list_templates).post(
+                template::create_template::create_template
+                    .layer(from_fn_with_state(context.clone(), auth_admin)),
+            ),
+        )
+        .route(
+            "/templates/{template_id}",
+            delete(
+                template::delete_template::delete_template
+                    .layer(from_fn_with_state(context.clone(), auth_admin)),
+            ),
+        )
         .route(
             "/sites/validate_domain",
             get(site::validate_domain::validate_domain),
@@ -96,6 +142,11 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
             get(site::list_site_versions::list_site_versions)
                 .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
         )
+        .route(
+            "/sites/{site_id}/export",
+            get(site::export_site::export_site)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
         .route(
             "/sites/{site_id}/domains",
             get(site::get_site_domains::get_site_domains
@@ -106,6 +157,16 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
             post(publish::publish_site::publish_site)
                 .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
         )
+        .route(
+            "/sites/{site_id}/static_builds",
+            get(ssg::list_static_builds::list_static_builds)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/actions/duplicate",
+            post(site::duplicate_site::duplicate_site)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
         .route(
             "/sites/{site_id}/actions/create_draft",
             post(publish::create_draft::create_draft)
@@ -116,6 +177,136 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
             delete(publish::delete_draft::delete_draft)
                 .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
         )
+        .route(
+            "/sites/{site_id}/publish_schedules",
+            post(
+                publish::create_publish_schedule::create_publish_schedule
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .get(
+                publish::list_publish_schedules::list_publish_schedules
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/publish_schedules/{schedule_id}",
+            delete(publish::cancel_publish_schedule::cancel_publish_schedule)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/preview_links",
+            post(
+                publish::create_preview_link::create_preview_link
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .get(
+                publish::list_preview_links::list_preview_links
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/preview_links/{link_id}",
+            delete(publish::revoke_preview_link::revoke_preview_link)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/preview_links/{link_id}/unlock",
+            post(publish::unlock_preview_link::unlock_preview_link),
+        )
+        .route(
+            "/sites/{site_id}/redirects",
+            post(
+                redirect::create_redirect::create_redirect
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .get(
+                redirect::list_redirects::list_redirects
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/redirects/{redirect_id}",
+            patch(
+                redirect::update_redirect::update_redirect
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .delete(
+                redirect::delete_redirect::delete_redirect
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/maintenance",
+            get(maintenance::get_maintenance::get_maintenance
+                .layer(from_fn_with_state(context.clone(), auth_admin_owner)))
+            .put(
+                maintenance::update_maintenance::update_maintenance
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/protected_routes",
+            post(
+                protected_route::create_protected_route::create_protected_route
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .get(
+                protected_route::list_protected_routes::list_protected_routes
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/protected_routes/{route_id}",
+            patch(
+                protected_route::update_protected_route::update_protected_route
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .delete(
+                protected_route::delete_protected_route::delete_protected_route
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/domain_verifications",
+            get(domain_verification::list_domain_verifications::list_domain_verifications)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/domain_verifications/actions/check",
+            post(domain_verification::check_domain_verifications::check_domain_verifications)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/experiments",
+            post(
+                experiment::create_experiment::create_experiment
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .get(
+                experiment::list_experiments::list_experiments
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/experiments/{experiment_id}",
+            patch(
+                experiment::update_experiment::update_experiment
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            )
+            .delete(
+                experiment::delete_experiment::delete_experiment
+                    .layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+            ),
+        )
+        .route(
+            "/sites/{site_id}/experiments/{experiment_id}/results",
+            get(experiment::get_experiment_results::get_experiment_results)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
+        .route(
+            "/sites/{site_id}/experiments/{experiment_id}/actions/convert",
+            post(experiment::record_conversion::record_conversion),
+        )
         .route(
             "/sites/{site_id}/custom_data",
             post(custom::custom_data::custom_data).route_layer(from_fn_with_state(
@@ -149,6 +340,11 @@ fn api_router(context: &ApiContext) -> Router<ApiContext> {
             get(site::get_site_usage::get_site_usage)
                 .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
         )
+        .route(
+            "/sites/{site_id}/analytics",
+            get(site::get_site_analytics::get_site_analytics)
+                .route_layer(from_fn_with_state(context.clone(), auth_admin_owner)),
+        )
         .route(
             "/sites/{site_id}/public_usage",
             get(site::get_site_usage::get_public_site_usage),
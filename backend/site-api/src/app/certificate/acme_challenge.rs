@@ -0,0 +1,15 @@
+use axum::extract::{Path, State};
+use lib_shared_site_api::error::api_error::ApiError;
+
+use crate::api_context::ApiContext;
+
+// Key authorization of a pending HTTP-01 challenge, requested by the ACME server
+pub async fn acme_challenge(
+    Path(token): Path<String>,
+    State(context): State<ApiContext>,
+) -> Result<String, ApiError> {
+    context
+        .certificate_store
+        .challenge(&token)
+        .ok_or_else(ApiError::not_found)
+}
@@ -0,0 +1,2 @@
+pub mod acme_challenge;
+pub mod renew_certificates;
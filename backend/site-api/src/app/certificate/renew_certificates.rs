@@ -0,0 +1,48 @@
+use chrono::{Duration, Utc};
+use lib_shared_site_api::util::{acme::needs_renewal, domain_verification::is_local_domain};
+use tracing::{error, info};
+
+use crate::api_context::ApiContext;
+
+// Obtain certificates for verified domains without one, and renew expiring certificates.
+// Does nothing when the TLS listener is disabled
+pub async fn renew_certificates_helper(context: ApiContext) {
+    let Some(acme_client) = context.acme_client.clone() else {
+        return;
+    };
+    let Some(_renewal) = acme_client.start_renewal() else {
+        return;
+    };
+    let domains = match context.metadata_repo.list_verified_domains().await {
+        Ok(domains) => domains,
+        Err(e) => {
+            error!("Failed to list verified domains: {}", e);
+            return;
+        }
+    };
+    let domains: Vec<String> = domains
+        .into_iter()
+        .filter(|domain| !is_local_domain(domain))
+        .collect();
+    let store = &context.certificate_store;
+    store.retain_domains(&domains).await;
+
+    let renew_before = Duration::days(context.config.acme_renew_days.into());
+    let mut count = 0;
+    for domain in domains {
+        if !needs_renewal(store.not_after(&domain), Utc::now(), renew_before) {
+            continue;
+        }
+        let result = match acme_client.issue_certificate(&domain, store).await {
+            Ok((certificate, key)) => store.save(&domain, &certificate, &key).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => count += 1,
+            Err(e) => error!("Failed to obtain a certificate for {}: {}", domain, e),
+        }
+    }
+    if count > 0 {
+        info!("Obtained {} TLS certificates", count);
+    }
+}
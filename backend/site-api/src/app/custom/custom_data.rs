@@ -1,9 +1,10 @@
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Extension, Json,
 };
+use axum_extra::extract::Host;
 use lib_shared_site_api::{
     error::{api_error::ApiError, helpers::check_bad_form},
     util::json_extractor::PsJson,
@@ -13,10 +14,14 @@ use lib_shared_types::{
     shared::user::{RequestUser, UserType},
 };
 use serde::de::DeserializeOwned;
+use tracing::warn;
 
 use validator::Validate;
 
-use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+use crate::{
+    api_context::ApiContext, app::experiment::helpers::VisitorExperiments,
+    middleware::auth::verify_site_owner,
+};
 
 use super::{
     add_column::add_column, add_row::add_row, create_table::create_table,
@@ -33,6 +38,8 @@ pub async fn custom_data(
     Path(id): Path<String>,
     State(context): State<ApiContext>,
     Extension(user): Extension<RequestUser>,
+    Host(hostname): Host,
+    headers: HeaderMap,
     PsJson(dto): PsJson<CustomDataDto>,
 ) -> Result<(StatusCode, Response), ApiError> {
     check_bad_form(dto.validate())?;
@@ -68,9 +75,32 @@ pub async fn custom_data(
             ))
         }
         Action::AddRow => {
+            let table_name = dto
+                .data
+                .get("table_name")
+                .and_then(|table| table.as_str())
+                .map(|table| table.to_string());
+            // Loaded before the insert, so a failure can't report an added row as an error
+            let mut visitor = VisitorExperiments::load(&context, &id, &headers).await?;
             let response = add_row(&context, &id, dto.data).await?;
 
-            Ok((StatusCode::OK, Json(response).into_response()))
+            // Rows added to an experiment's conversion table count as conversions
+            if let Some(table_name) = table_name {
+                visitor
+                    .record_table_conversions(&context, &id, &table_name)
+                    .await;
+            }
+            let response_headers = visitor
+                .response_headers(&context, &id, &hostname)
+                .unwrap_or_else(|e| {
+                    warn!("Failed to update experiment cookie for site {}: {}", id, e);
+                    HeaderMap::new()
+                });
+
+            Ok((
+                StatusCode::OK,
+                (response_headers, Json(response)).into_response(),
+            ))
         }
         Action::RemoveRow => {
             remove_row(&context, &id, dto.data).await?;
@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::api_error::ApiError, util::domain_verification::verification_record_name,
+};
+use lib_shared_types::{
+    dto::site_api::domain_verification_viewmodel::{to_api_response, DomainVerificationViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::{check_domain, map_verification_error};
+
+// Check the site's custom domains now, e.g. after the owner added DNS records
+pub async fn check_domain_verifications(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<DomainVerificationViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    context
+        .domain_verification_repo
+        .sync_verifications()
+        .await
+        .map_err(map_verification_error)?;
+    let verifications = context
+        .domain_verification_repo
+        .list_verifications_by_site_id(&site_id)
+        .await
+        .map_err(map_verification_error)?;
+
+    let mut checked = vec![];
+    for verification in verifications {
+        let verification = check_domain(&context, verification).await?;
+        let txt_name = verification_record_name(&verification.domain);
+        checked.push(to_api_response(verification, txt_name));
+    }
+    Ok(Json(checked))
+}
@@ -0,0 +1,115 @@
+use chrono::{Duration, Utc};
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::domain_verification::{
+        next_verification_state, notify_verification, verify_records, VerificationState,
+        VerificationTargets,
+    },
+};
+use lib_shared_types::entity::site_api::{
+    domain_verification_entity::DomainVerificationEntity, static_build_entity::StaticBuildTrigger,
+};
+use tracing::error;
+
+use crate::{
+    api_context::ApiContext,
+    app::{
+        certificate::renew_certificates::renew_certificates_helper,
+        ssg::generate_static::spawn_regenerate_static_pages,
+    },
+    db::domain_verification_repo::DomainCheckResult,
+    util::{dns::resolve_domain_records, mail_helpers::make_mail_params},
+};
+
+use super::notify::notify_domain_status;
+
+pub fn map_verification_error(e: sqlx::Error) -> ApiError {
+    ApiError::internal_error().message(format!("Failed to verify domains: {}", e))
+}
+
+// Issue tokens for saved custom domains. The verification cron syncs again, so a failure is
+// only logged
+pub async fn sync_domain_verifications(context: &ApiContext) {
+    if let Err(e) = context.domain_verification_repo.sync_verifications().await {
+        error!("Failed to sync domain verifications: {}", e);
+    }
+}
+
+fn verification_targets(context: &ApiContext) -> VerificationTargets {
+    VerificationTargets {
+        cname: context.config.domain_cname_target.clone(),
+        ips: context.config.domain_target_ips.clone(),
+    }
+}
+
+// Check a domain's DNS records and save the result. Owners are notified when the status
+// changes, of failures only once they repeat
+pub async fn check_domain(
+    context: &ApiContext,
+    verification: DomainVerificationEntity,
+) -> Result<DomainVerificationEntity, ApiError> {
+    let domain = &verification.domain;
+    let outcome = resolve_domain_records(&context.dns_resolver, domain)
+        .await
+        .and_then(|records| {
+            verify_records(
+                domain,
+                &verification.token,
+                &records,
+                &verification_targets(context),
+            )
+        });
+
+    let current = VerificationState {
+        status: verification.status,
+        verified: verification.verified,
+        failing_since: verification.failing_since,
+        failed_checks: verification.failed_checks,
+    };
+    let now = Utc::now();
+    let suspend_days = context.config.domain_suspend_days;
+    let state = next_verification_state(
+        &current,
+        outcome.is_ok(),
+        now,
+        Duration::days(suspend_days.into()),
+    );
+    let checked = context
+        .domain_verification_repo
+        .save_check(
+            domain,
+            DomainCheckResult {
+                state: &state,
+                checked_at: now,
+                last_result: outcome.as_ref().ok().copied(),
+                last_error: outcome.as_ref().err().map(String::as_str),
+            },
+        )
+        .await
+        .map_err(map_verification_error)?;
+
+    let site_id = &checked.site_id;
+    if state.status != current.status || state.verified != current.verified {
+        // Served domains, and the canonical URL of the site's pages depend on verification
+        context.cache.remove_domain_mapping(site_id).await;
+        context.cache.remove_metadata(site_id).await;
+        context.cache.remove_seo_files(site_id).await;
+        if state.verified != current.verified {
+            spawn_regenerate_static_pages(context, site_id, StaticBuildTrigger::Metadata, None);
+        }
+        // Newly verified domains get a certificate without waiting for the renewal cron
+        if state.verified && !current.verified {
+            tokio::spawn(renew_certificates_helper(context.clone()));
+        }
+    }
+    if notify_verification(&current, &state) {
+        match context.metadata_repo.get_site_metadata(site_id).await {
+            Ok(meta) => {
+                let params = make_mail_params(&context.config, &meta.owner_email);
+                notify_domain_status(params, &checked, suspend_days).await;
+            }
+            Err(e) => error!("Failed to get metadata for domain {}: {}", domain, e),
+        }
+    }
+    Ok(checked)
+}
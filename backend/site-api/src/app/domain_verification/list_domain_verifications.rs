@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::api_error::ApiError, util::domain_verification::verification_record_name,
+};
+use lib_shared_types::{
+    dto::site_api::domain_verification_viewmodel::{to_api_response, DomainVerificationViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_verification_error;
+
+// Verification tokens and check results of the site's custom domains. Tokens are issued when
+// domains are saved
+pub async fn list_domain_verifications(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<DomainVerificationViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let verifications = context
+        .domain_verification_repo
+        .list_verifications_by_site_id(&site_id)
+        .await
+        .map_err(map_verification_error)?;
+
+    Ok(Json(
+        verifications
+            .into_iter()
+            .map(|verification| {
+                let txt_name = verification_record_name(&verification.domain);
+                to_api_response(verification, txt_name)
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,5 @@
+pub mod check_domain_verifications;
+pub mod helpers;
+pub mod list_domain_verifications;
+pub mod notify;
+pub mod run_domain_verifications;
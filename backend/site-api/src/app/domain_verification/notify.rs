@@ -0,0 +1,56 @@
+use lib_shared_site_api::mail::{send_mail, ApiMailParams};
+use lib_shared_types::entity::site_api::domain_verification_entity::{
+    DomainVerificationEntity, DomainVerificationStatus,
+};
+use tracing::error;
+
+pub async fn notify_domain_status(
+    params: ApiMailParams,
+    verification: &DomainVerificationEntity,
+    suspend_days: u32,
+) {
+    let error = verification.last_error.as_deref().unwrap_or_default();
+    let (subject, text) = match verification.status {
+        DomainVerificationStatus::Pending => return,
+        DomainVerificationStatus::Verified => (
+            "PubStudio domain verified",
+            format!(
+                "
+      Your domain \"{}\" was verified, and serves your site.\n\n
+      View your site settings by clicking the link below:\n\n
+      {}/build/{}",
+                verification.domain, params.frontend_url, verification.site_id
+            ),
+        ),
+        DomainVerificationStatus::Failing => (
+            "PubStudio domain verification failing",
+            format!(
+                "
+      Verification of your domain \"{}\" failed: {}\n\n
+      The domain stops serving your site after {} days of failed checks.
+      Check its DNS records by clicking the link below:\n\n
+      {}/build/{}",
+                verification.domain, error, suspend_days, params.frontend_url, verification.site_id
+            ),
+        ),
+        DomainVerificationStatus::Suspended => (
+            "PubStudio domain suspended",
+            format!(
+                "
+      Your domain \"{}\" failed verification for {} days, and no longer serves your site: {}\n\n
+      The domain is served again once its DNS records are fixed. Check them by clicking the
+      link below:\n\n
+      {}/build/{}",
+                verification.domain, suspend_days, error, params.frontend_url, verification.site_id
+            ),
+        ),
+    };
+
+    let result = send_mail(params.params, subject, Some(text), None).await;
+    if let Err(e) = result {
+        error!(
+            err = e.to_string(),
+            "Failed to notify domain status: {}", verification.domain
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use tracing::{error, info};
+
+use crate::api_context::ApiContext;
+
+use super::helpers::check_domain;
+
+// Check the DNS records of all custom domains
+pub async fn run_domain_verifications_helper(context: ApiContext) {
+    if let Err(e) = context.domain_verification_repo.sync_verifications().await {
+        error!("Failed to sync domain verifications: {}", e);
+        return;
+    }
+    let verifications = match context.domain_verification_repo.list_verifications().await {
+        Ok(verifications) => verifications,
+        Err(e) => {
+            error!("Failed to list domain verifications: {}", e);
+            return;
+        }
+    };
+
+    let count = verifications.len();
+    for verification in verifications {
+        let domain = verification.domain.clone();
+        if let Err(e) = check_domain(&context, verification).await {
+            error!("Failed to check domain {}: {}", domain, e);
+        }
+    }
+    if count > 0 {
+        info!("Checked {} custom domains", count);
+    }
+}
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        experiment_dto::CreateExperimentDto,
+        experiment_viewmodel::{to_api_response, ExperimentViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::{check_experiment, map_experiment_error};
+
+pub async fn create_experiment(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<CreateExperimentDto>,
+) -> Result<(StatusCode, Json<ExperimentViewModel>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let fields = check_experiment(&context, &site_id, dto).await?;
+    let experiment = context
+        .experiment_repo
+        .create_experiment(&site_id, fields)
+        .await
+        .map_err(map_experiment_error)?;
+    context.cache.remove_experiments(&site_id).await;
+
+    Ok((StatusCode::CREATED, Json(to_api_response(experiment))))
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::shared::user::RequestUser;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_experiment_error;
+
+pub async fn delete_experiment(
+    Path((site_id, experiment_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<(), ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    context
+        .experiment_repo
+        .delete_experiment(&site_id, experiment_id)
+        .await
+        .map_err(map_experiment_error)?;
+    context.cache.remove_experiments(&site_id).await;
+    Ok(())
+}
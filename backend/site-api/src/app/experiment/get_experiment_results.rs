@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::experiments::{compare_variant, conversion_rate, SIGNIFICANCE_LEVEL},
+};
+use lib_shared_types::{
+    dto::site_api::experiment_viewmodel::{
+        ExperimentResultsViewModel, ExperimentVariantResultViewModel,
+    },
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_experiment_error;
+
+// Conversion rates of each variant, tested against the control for significance
+pub async fn get_experiment_results(
+    Path((site_id, experiment_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<ExperimentResultsViewModel>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let experiment = context
+        .experiment_repo
+        .get_experiment(&site_id, experiment_id)
+        .await
+        .map_err(map_experiment_error)?;
+    let control = experiment
+        .variants
+        .first()
+        .ok_or_else(|| ApiError::internal_error().message("Experiment has no variants"))?;
+    let control_id = control.id;
+    let control_counts = (control.exposures, control.conversions);
+
+    let variants = experiment
+        .variants
+        .into_iter()
+        .map(|variant| {
+            let comparison = (variant.id != control_id)
+                .then(|| compare_variant(control_counts, (variant.exposures, variant.conversions)))
+                .flatten();
+            ExperimentVariantResultViewModel {
+                conversion_rate: conversion_rate(variant.exposures, variant.conversions),
+                lift: comparison.as_ref().and_then(|c| c.lift),
+                z_score: comparison.as_ref().map(|c| c.z_score),
+                p_value: comparison.as_ref().map(|c| c.p_value),
+                significant: comparison.is_some_and(|c| c.p_value < SIGNIFICANCE_LEVEL),
+                id: variant.id,
+                name: variant.name,
+                exposures: variant.exposures,
+                conversions: variant.conversions,
+            }
+        })
+        .collect();
+
+    Ok(Json(ExperimentResultsViewModel {
+        id: experiment.id,
+        name: experiment.name,
+        control_id,
+        variants,
+    }))
+}
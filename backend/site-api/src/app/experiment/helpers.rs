@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+
+use axum::http::{header, HeaderMap, HeaderValue};
+use lib_shared_site_api::{
+    auth::{
+        generate_jwt::generate_experiment_token, types::ExperimentAssignment, util::derive_secret,
+        verify_jwt::verify_experiment_token,
+    },
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::{
+        domains::{base_url_from_domain, domain_without_port},
+        experiments::{
+            assign_variant, experiment_cookie, mark_converted, mark_exposed, validate_experiment,
+            ExperimentVariant, SiteExperiments, ASSIGNMENT_TTL_MINUTES, EXPERIMENT_COOKIE,
+        },
+        site_access::get_cookie,
+        site_document::{encode_site_document, parse_site_document},
+    },
+};
+use lib_shared_types::{
+    dto::site_api::{
+        experiment_dto::CreateExperimentDto, get_current_site_dto::GetCurrentSiteResponse,
+    },
+    error::api_error::ApiErrorCode,
+};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    api_context::ApiContext,
+    app::{custom::helpers::validate_table_name, ssg::static_serve::normalize_route},
+    db::{
+        db_cache_layer::get_experiments_from_cache_or_repo,
+        experiment_repo::{ExperimentFields, ExperimentVariantFields},
+    },
+};
+
+// Experiment pages differ per visitor, and must not be stored by shared caches
+pub const EXPERIMENT_CACHE_CONTROL: &str = "private, no-cache";
+
+pub fn map_experiment_error(e: DbError) -> ApiError {
+    match e {
+        DbError::Unique(_) => ApiError::bad_request()
+            .code(ApiErrorCode::ExperimentRouteExists)
+            .message("An experiment already runs on this route"),
+        DbError::EntityNotFound() | DbError::NoDb(_) => ApiError::not_found(),
+        _ => ApiError::internal_error().message(e),
+    }
+}
+
+fn invalid_experiment(message: String) -> ApiError {
+    ApiError::bad_request()
+        .code(ApiErrorCode::InvalidFormData)
+        .message(message)
+}
+
+// An empty table name removes the conversion table
+pub fn check_conversion_table(table: Option<String>) -> Result<Option<String>, ApiError> {
+    let Some(table) = table.filter(|table| !table.is_empty()) else {
+        return Ok(None);
+    };
+    validate_table_name(&table)?;
+    Ok(Some(table))
+}
+
+// Public page routes of the latest site version. Variants are served to visitors in place of
+// the experiment route, so they must be public
+async fn public_page_routes(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<HashSet<String>, ApiError> {
+    let site = context
+        .site_repo
+        .get_site_latest_version(site_id, false)
+        .await
+        .map_err(map_experiment_error)?;
+    let (pages, _) =
+        parse_site_document(&site.pages).map_err(|e| ApiError::internal_error().message(e))?;
+    let Value::Object(pages) = pages else {
+        return Ok(HashSet::new());
+    };
+    Ok(pages
+        .into_iter()
+        .filter(|(_, page)| page.get("public").and_then(Value::as_bool) == Some(true))
+        .map(|(route, _)| route)
+        .collect())
+}
+
+// Validate a new experiment against the site's pages. Routes are stored without a trailing
+// slash
+pub async fn check_experiment(
+    context: &ApiContext,
+    site_id: &str,
+    dto: CreateExperimentDto,
+) -> Result<ExperimentFields, ApiError> {
+    let route = normalize_route(&dto.route);
+    let variants: Vec<ExperimentVariantFields> = dto
+        .variants
+        .into_iter()
+        .map(|variant| ExperimentVariantFields {
+            name: variant.name,
+            page_route: normalize_route(&variant.page_route),
+            weight: variant.weight,
+        })
+        .collect();
+    let pages = public_page_routes(context, site_id).await?;
+    let named_routes: Vec<(&str, &str)> = variants
+        .iter()
+        .map(|variant| (variant.name.as_str(), variant.page_route.as_str()))
+        .collect();
+    validate_experiment(&route, &named_routes, &pages).map_err(invalid_experiment)?;
+
+    Ok(ExperimentFields {
+        route,
+        name: dto.name,
+        enabled: dto.enabled.unwrap_or(true),
+        conversion_table: check_conversion_table(dto.conversion_table)?,
+        variants,
+    })
+}
+
+// A visitor's experiment variants, kept in a signed cookie
+pub struct VisitorExperiments {
+    experiments: SiteExperiments,
+    assignments: Vec<ExperimentAssignment>,
+    // Assignments differ from the request cookie
+    changed: bool,
+}
+
+// Derived from the site access secret, so access and experiment tokens can't be swapped
+fn experiment_secret(context: &ApiContext) -> String {
+    let secret = context.config.site_access_secret();
+    derive_secret(secret, "experiments")
+}
+
+impl VisitorExperiments {
+    pub async fn load(
+        context: &ApiContext,
+        site_id: &str,
+        headers: &HeaderMap,
+    ) -> Result<Self, ApiError> {
+        let experiments = get_experiments_from_cache_or_repo(context, site_id).await?;
+        let mut assignments = get_cookie(headers, EXPERIMENT_COOKIE)
+            .map(|token| verify_experiment_token(&experiment_secret(context), site_id, token))
+            .unwrap_or_default();
+        let count = assignments.len();
+        experiments.retain_running(&mut assignments);
+        Ok(VisitorExperiments {
+            changed: assignments.len() != count,
+            experiments,
+            assignments,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.experiments.is_empty()
+    }
+
+    // Variant of the experiment running on a page route. New visitors are assigned one
+    pub fn variant(&mut self, route: &str) -> Option<ExperimentVariant> {
+        let experiment = self.experiments.find(route)?;
+        let draw = Uuid::new_v4().as_u128() as u64;
+        let (variant, new) = assign_variant(experiment, &mut self.assignments, draw)?;
+        self.changed |= new;
+        Some(variant.clone())
+    }
+
+    // Variants of every running experiment by route, the SPA may navigate to any of them
+    pub fn variants(&mut self) -> Vec<(String, ExperimentVariant)> {
+        let routes: Vec<String> = self
+            .experiments
+            .iter()
+            .map(|experiment| experiment.route.clone())
+            .collect();
+        routes
+            .into_iter()
+            .filter_map(|route| {
+                let variant = self.variant(&route)?;
+                Some((route, variant))
+            })
+            .collect()
+    }
+
+    // Count the visitor's first view of an experiment route
+    pub async fn record_exposure(&mut self, context: &ApiContext, site_id: &str, route: &str) {
+        let Some(experiment_id) = self.experiments.find(route).map(|e| e.id) else {
+            return;
+        };
+        let Some(variant_id) = mark_exposed(&mut self.assignments, experiment_id) else {
+            return;
+        };
+        self.changed = true;
+        if let Err(e) = context
+            .experiment_repo
+            .increment_exposures(site_id, variant_id)
+            .await
+        {
+            warn!("Failed to record exposure for site {}: {}", site_id, e);
+        }
+    }
+
+    // Count the visitor's first conversion. Returns false for unknown experiments
+    pub async fn record_conversion(
+        &mut self,
+        context: &ApiContext,
+        site_id: &str,
+        experiment_id: i64,
+    ) -> bool {
+        if self.experiments.get(experiment_id).is_none() {
+            return false;
+        }
+        let Some(variant_id) = mark_converted(&mut self.assignments, experiment_id) else {
+            return true;
+        };
+        self.changed = true;
+        if let Err(e) = context
+            .experiment_repo
+            .increment_conversions(site_id, variant_id)
+            .await
+        {
+            warn!("Failed to record conversion for site {}: {}", site_id, e);
+        }
+        true
+    }
+
+    // Conversions of experiments counting inserts into a custom data table
+    pub async fn record_table_conversions(
+        &mut self,
+        context: &ApiContext,
+        site_id: &str,
+        table: &str,
+    ) {
+        let experiment_ids: Vec<i64> = self
+            .experiments
+            .converted_by(table)
+            .map(|experiment| experiment.id)
+            .collect();
+        for experiment_id in experiment_ids {
+            self.record_conversion(context, site_id, experiment_id)
+                .await;
+        }
+    }
+
+    // Headers to send the updated cookie, empty when the assignments did not change. Responses
+    // to experiment visitors are not stored by shared caches
+    pub fn response_headers(
+        &self,
+        context: &ApiContext,
+        site_id: &str,
+        domain: &str,
+    ) -> Result<HeaderMap, ApiError> {
+        let mut headers = HeaderMap::new();
+        if !self.changed {
+            return Ok(headers);
+        }
+        let token = generate_experiment_token(
+            site_id,
+            self.assignments.clone(),
+            ASSIGNMENT_TTL_MINUTES,
+            &experiment_secret(context),
+        )?;
+        let domain = domain_without_port(domain.to_string());
+        let secure = base_url_from_domain(&domain).starts_with("https://");
+        let cookie = HeaderValue::from_str(&experiment_cookie(&token, secure))
+            .map_err(|e| ApiError::internal_error().message(e))?;
+        headers.insert(header::SET_COOKIE, cookie);
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(EXPERIMENT_CACHE_CONTROL),
+        );
+        Ok(headers)
+    }
+}
+
+// Serve each experiment route with the page of the visitor's variant
+pub fn apply_variants(
+    mut site: GetCurrentSiteResponse,
+    variants: &[(String, ExperimentVariant)],
+) -> Result<GetCurrentSiteResponse, ApiError> {
+    if variants
+        .iter()
+        .all(|(route, variant)| *route == variant.page_route)
+    {
+        return Ok(site);
+    }
+    let (mut pages, encoded) =
+        parse_site_document(&site.pages).map_err(|e| ApiError::internal_error().message(e))?;
+    if let Value::Object(pages) = &mut pages {
+        for (route, variant) in variants {
+            let Some(mut page) = pages.get(&variant.page_route).cloned() else {
+                continue;
+            };
+            if let Some(page) = page.as_object_mut() {
+                page.insert("route".into(), Value::String(route.clone()));
+            }
+            pages.insert(route.clone(), page);
+        }
+    }
+    site.pages = encode_site_document(pages, encoded).to_string();
+    Ok(site)
+}
@@ -0,0 +1,29 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::experiment_viewmodel::{to_api_response, ExperimentViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_experiment_error;
+
+pub async fn list_experiments(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<ExperimentViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let experiments = context
+        .experiment_repo
+        .list_experiments(&site_id)
+        .await
+        .map_err(map_experiment_error)?;
+
+    Ok(Json(experiments.into_iter().map(to_api_response).collect()))
+}
@@ -0,0 +1,7 @@
+pub mod create_experiment;
+pub mod delete_experiment;
+pub mod get_experiment_results;
+pub mod helpers;
+pub mod list_experiments;
+pub mod record_conversion;
+pub mod update_experiment;
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use axum_extra::extract::Host;
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::error::api_error::ApiErrorCode;
+
+use crate::{api_context::ApiContext, db::db_cache_layer::get_site_id_by_host_or_origin};
+
+use super::helpers::VisitorExperiments;
+
+// Count a conversion for the visitor's variant. Only the first conversion after the visitor
+// saw the experiment route is counted
+pub async fn record_conversion(
+    Path((id, experiment_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Host(hostname): Host,
+    headers: HeaderMap,
+) -> Result<(StatusCode, HeaderMap), ApiError> {
+    let site_id = get_site_id_by_host_or_origin(&context, hostname.clone(), &headers).await?;
+    if id != site_id {
+        return Err(ApiError::bad_request().code(ApiErrorCode::InvalidFormData));
+    }
+    let mut visitor = VisitorExperiments::load(&context, &site_id, &headers).await?;
+    if !visitor
+        .record_conversion(&context, &site_id, experiment_id)
+        .await
+    {
+        return Err(ApiError::not_found());
+    }
+
+    let headers = visitor.response_headers(&context, &site_id, &hostname)?;
+    Ok((StatusCode::NO_CONTENT, headers))
+}
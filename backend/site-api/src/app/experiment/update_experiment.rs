@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        experiment_dto::UpdateExperimentDto,
+        experiment_viewmodel::{to_api_response, ExperimentViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, db::experiment_repo::ExperimentUpdateFields,
+    middleware::auth::verify_site_owner,
+};
+
+use super::helpers::{check_conversion_table, map_experiment_error};
+
+pub async fn update_experiment(
+    Path((site_id, experiment_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<UpdateExperimentDto>,
+) -> Result<Json<ExperimentViewModel>, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let existing = context
+        .experiment_repo
+        .get_experiment(&site_id, experiment_id)
+        .await
+        .map_err(map_experiment_error)?;
+    let conversion_table = match dto.conversion_table {
+        Some(table) => check_conversion_table(Some(table))?,
+        None => existing.conversion_table,
+    };
+    let experiment = context
+        .experiment_repo
+        .update_experiment(
+            &site_id,
+            experiment_id,
+            ExperimentUpdateFields {
+                name: dto.name.unwrap_or(existing.name),
+                enabled: dto.enabled.unwrap_or(existing.enabled),
+                conversion_table,
+            },
+        )
+        .await
+        .map_err(map_experiment_error)?;
+    context.cache.remove_experiments(&site_id).await;
+
+    Ok(Json(to_api_response(experiment)))
+}
@@ -0,0 +1,33 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use lib_shared_site_api::{error::api_error::ApiError, util::maintenance::SiteMaintenance};
+use lib_shared_types::{
+    dto::site_api::maintenance_viewmodel::{to_api_response, MaintenanceViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_maintenance_error;
+
+// 404 when the site has never configured maintenance
+pub async fn get_maintenance(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<MaintenanceViewModel>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let maintenance = context
+        .maintenance_repo
+        .get_maintenance(&site_id)
+        .await
+        .map_err(map_maintenance_error)?
+        .ok_or_else(|| ApiError::not_found().message("Maintenance is not configured"))?;
+    let active = SiteMaintenance::from(maintenance.clone()).is_active(Utc::now());
+
+    Ok(Json(to_api_response(maintenance, active)))
+}
@@ -0,0 +1,105 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::ConnectInfo,
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Extension,
+};
+use chrono::Utc;
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::maintenance::{client_ip, SiteMaintenance},
+};
+use lib_shared_types::error::api_error::ApiErrorCode;
+use tracing::error;
+
+use crate::{
+    api_context::ApiContext,
+    db::db_cache_layer::{get_maintenance_from_cache_or_repo, get_site_from_cache_or_repo},
+};
+
+// Peer address of the connection, set by the server's connect info
+pub type PeerAddr = Option<Extension<ConnectInfo<SocketAddr>>>;
+
+pub fn peer_ip(peer: PeerAddr) -> Option<IpAddr> {
+    peer.map(|Extension(ConnectInfo(addr))| addr.ip())
+}
+
+// Visitor address, read from forwarding headers of the configured trusted proxies
+pub fn visitor_ip(
+    context: &ApiContext,
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+) -> Option<IpAddr> {
+    client_ip(headers, peer, &context.config.trusted_proxies)
+}
+
+// Visitor address of a request that another server, such as platform-api, makes on the
+// visitor's behalf. None when the caller is not a trusted proxy forwarding the address, since
+// the caller's own address would be counted for every visitor
+pub fn forwarded_visitor_ip(
+    context: &ApiContext,
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+) -> Option<IpAddr> {
+    let visitor = visitor_ip(context, headers, peer);
+    if visitor == peer {
+        error!(
+            "Request from {:?} did not forward the visitor address, add the server to TRUSTED_PROXIES and set X-Forwarded-For. Its visitors are not counted",
+            peer
+        );
+        return None;
+    }
+    visitor
+}
+
+pub fn map_maintenance_error(e: DbError) -> ApiError {
+    match e {
+        DbError::EntityNotFound() | DbError::NoDb(_) => ApiError::not_found(),
+        _ => ApiError::internal_error().message(e),
+    }
+}
+
+// Maintenance settings when the visitor gets the holding page, None when they see the site
+pub async fn blocking_maintenance(
+    context: &ApiContext,
+    site_id: &str,
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+) -> Result<Option<SiteMaintenance>, ApiError> {
+    let maintenance = get_maintenance_from_cache_or_repo(context, site_id).await?;
+    let blocked = maintenance.blocks(Utc::now(), visitor_ip(context, headers, peer), headers);
+    Ok(blocked.then_some(maintenance))
+}
+
+pub fn maintenance_error() -> ApiError {
+    ApiError::service_unavailable()
+        .code(ApiErrorCode::SiteMaintenance)
+        .message("The site is under maintenance")
+}
+
+// Holding page, titled with the site name
+pub async fn maintenance_response(
+    context: &ApiContext,
+    site_id: &str,
+    maintenance: &SiteMaintenance,
+) -> Response {
+    let title = match get_site_from_cache_or_repo(context, site_id).await {
+        Ok(site) => site.meta.title,
+        Err(_) => String::new(),
+    };
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [
+            (
+                header::RETRY_AFTER,
+                maintenance.retry_after(Utc::now()).to_string(),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        Html(maintenance.render_page(&title)),
+    )
+        .into_response()
+}
@@ -0,0 +1,3 @@
+pub mod get_maintenance;
+pub mod helpers;
+pub mod update_maintenance;
@@ -0,0 +1,96 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{
+        json_extractor::PsJson,
+        maintenance::{validate_maintenance, SiteMaintenance},
+    },
+};
+use lib_shared_types::{
+    dto::site_api::{
+        maintenance_dto::UpdateMaintenanceDto,
+        maintenance_viewmodel::{to_api_response, MaintenanceViewModel},
+    },
+    error::api_error::ApiErrorCode,
+    shared::user::RequestUser,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, db::maintenance_repo::MaintenanceFields,
+    middleware::auth::verify_site_owner,
+};
+
+use super::helpers::map_maintenance_error;
+
+fn parse_date(date: Option<String>, field: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+    date.map(|date| {
+        date.parse::<DateTime<Utc>>()
+            .map_err(|_| ApiError::bad_request().message(format!("Failed to parse {}", field)))
+    })
+    .transpose()
+}
+
+// Replaces the maintenance settings. The bypass token is created on first save, and kept
+// until the owner resets it.
+pub async fn update_maintenance(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<UpdateMaintenanceDto>,
+) -> Result<Json<MaintenanceViewModel>, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let starts_at = parse_date(dto.starts_at, "starts_at")?;
+    let ends_at = parse_date(dto.ends_at, "ends_at")?;
+    validate_maintenance(
+        starts_at,
+        ends_at,
+        dto.page,
+        dto.custom_page.as_deref(),
+        &dto.allowed_ips,
+    )
+    .map_err(|e| {
+        ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(e)
+    })?;
+
+    let existing = context
+        .maintenance_repo
+        .get_maintenance(&site_id)
+        .await
+        .map_err(map_maintenance_error)?;
+    let bypass_token = match existing {
+        Some(existing) if !dto.reset_bypass_token => existing.bypass_token,
+        _ => Uuid::new_v4().simple().to_string(),
+    };
+
+    let maintenance = context
+        .maintenance_repo
+        .save_maintenance(
+            &site_id,
+            MaintenanceFields {
+                enabled: dto.enabled,
+                starts_at,
+                ends_at,
+                page: dto.page,
+                message: dto.message,
+                custom_page: dto.custom_page,
+                allowed_ips: dto.allowed_ips,
+                bypass_token,
+            },
+        )
+        .await
+        .map_err(map_maintenance_error)?;
+    context.cache.remove_maintenance(&site_id).await;
+    let active = SiteMaintenance::from(maintenance.clone()).is_active(Utc::now());
+
+    Ok(Json(to_api_response(maintenance, active)))
+}
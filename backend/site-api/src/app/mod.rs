@@ -1,10 +1,17 @@
 pub mod admin;
 pub mod app_router;
 pub mod backup;
+pub mod certificate;
 pub mod custom;
+pub mod domain_verification;
+pub mod experiment;
 pub mod health;
+pub mod maintenance;
+pub mod protected_route;
 pub mod publish;
+pub mod redirect;
 pub mod serve;
 pub mod site;
 pub mod ssg;
+pub mod template;
 pub mod usage;
@@ -0,0 +1,42 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{json_extractor::PsJson, password::hash_password},
+};
+use lib_shared_types::{
+    dto::site_api::{
+        protected_route_dto::CreateProtectedRouteDto,
+        protected_route_viewmodel::{to_api_response, ProtectedRouteViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::{check_protected_route, map_protected_route_error, protections_changed};
+
+pub async fn create_protected_route(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<CreateProtectedRouteDto>,
+) -> Result<(StatusCode, Json<ProtectedRouteViewModel>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let route = check_protected_route(&dto.route)?;
+    let password_hash = hash_password(&dto.password)?;
+    let protected_route = context
+        .protected_route_repo
+        .create_protected_route(&site_id, &route, &password_hash)
+        .await
+        .map_err(map_protected_route_error)?;
+    protections_changed(&context, &site_id).await;
+
+    Ok((StatusCode::CREATED, Json(to_api_response(protected_route))))
+}
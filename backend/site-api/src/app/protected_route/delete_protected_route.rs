@@ -0,0 +1,26 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::shared::user::RequestUser;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::{map_protected_route_error, protections_changed};
+
+pub async fn delete_protected_route(
+    Path((site_id, route_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<(), ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    context
+        .protected_route_repo
+        .delete_protected_route(&site_id, route_id)
+        .await
+        .map_err(map_protected_route_error)?;
+    protections_changed(&context, &site_id).await;
+    Ok(())
+}
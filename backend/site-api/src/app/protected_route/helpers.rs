@@ -0,0 +1,149 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use axum::http::HeaderMap;
+use chrono::Utc;
+use lib_shared_site_api::{
+    auth::{types::AccessGrant, verify_jwt::verify_site_access_token},
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::{
+        password::verify_password_blocking,
+        site_access::{get_cookie, validate_protected_route, PageAccess, ACCESS_COOKIE},
+        site_document::{encode_site_document, parse_site_document},
+    },
+};
+use lib_shared_types::{
+    dto::site_api::get_current_site_dto::GetCurrentSiteResponse,
+    entity::site_api::static_build_entity::StaticBuildTrigger, error::api_error::ApiErrorCode,
+};
+use serde_json::Value;
+
+use crate::{
+    api_context::ApiContext,
+    app::ssg::{generate_static::spawn_regenerate_static_pages, static_serve::normalize_route},
+    db::db_cache_layer::{get_protections_from_cache_or_repo, get_site_from_cache_or_repo},
+};
+
+// Protections decide the sitemap, and which pages static pages may embed in their payload
+pub async fn protections_changed(context: &ApiContext, site_id: &str) {
+    context.cache.remove_protections(site_id).await;
+    context.cache.remove_seo_files(site_id).await;
+    spawn_regenerate_static_pages(context, site_id, StaticBuildTrigger::Metadata, None);
+}
+
+// Unlocked pages differ per visitor, and must not be stored by shared caches
+pub const PROTECTED_CACHE_CONTROL: &str = "private, no-cache";
+
+// Validate a protected route before it is saved, stored without a trailing slash
+pub fn check_protected_route(route: &str) -> Result<String, ApiError> {
+    let route = normalize_route(route);
+    validate_protected_route(&route).map_err(|e| {
+        ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(e)
+    })?;
+    Ok(route)
+}
+
+pub fn map_protected_route_error(e: DbError) -> ApiError {
+    match e {
+        DbError::Unique(_) => ApiError::bad_request()
+            .code(ApiErrorCode::ProtectedRouteExists)
+            .message("This route is already protected"),
+        DbError::EntityNotFound() | DbError::NoDb(_) => ApiError::not_found(),
+        _ => ApiError::internal_error().message(e),
+    }
+}
+
+pub fn password_required() -> ApiError {
+    ApiError::unauthorized()
+        .code(ApiErrorCode::SitePasswordRequired)
+        .message("This page is password protected")
+}
+
+// Check a visitor's site or preview password. Failures are counted per site and client
+// address, and a locked out client is refused before its password is hashed
+pub async fn verify_visitor_password(
+    context: &ApiContext,
+    site_id: &str,
+    ip: Option<IpAddr>,
+    password: String,
+    password_hash: String,
+) -> Result<bool, ApiError> {
+    let attempts = &context.cache.login_attempts;
+    let ip = ip.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    if attempts.is_locked(site_id, ip, Utc::now()) {
+        return Err(ApiError::too_many_requests()
+            .code(ApiErrorCode::LoginLocked)
+            .message("Too many failed attempts, try again later"));
+    }
+    let verified = verify_password_blocking(password, password_hash).await;
+    if verified {
+        attempts.clear(site_id, ip);
+    } else {
+        attempts.record_failure(site_id, ip, Utc::now());
+    }
+    Ok(verified)
+}
+
+// Protected routes unlocked by the visitor's access cookie
+pub fn site_access_grants(
+    context: &ApiContext,
+    site_id: &str,
+    headers: &HeaderMap,
+) -> Vec<AccessGrant> {
+    let secret = context.config.site_access_secret();
+    get_cookie(headers, ACCESS_COOKIE)
+        .map(|token| verify_site_access_token(secret, site_id, token))
+        .unwrap_or_default()
+}
+
+// Page route served at a request path, without its locale prefix. The home page is served
+// at `/`
+pub async fn page_route(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+) -> Result<String, ApiError> {
+    let site = get_site_from_cache_or_repo(context, site_id).await?;
+    Ok(site.meta.page_route(path))
+}
+
+pub async fn page_access(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+    headers: &HeaderMap,
+) -> Result<PageAccess, ApiError> {
+    let protections = get_protections_from_cache_or_repo(context, site_id).await?;
+    if protections.is_empty() {
+        return Ok(PageAccess::Public);
+    }
+    let route = page_route(context, site_id, path).await?;
+    let grants = site_access_grants(context, site_id, headers);
+    Ok(protections.access(&route, &grants))
+}
+
+// Remove pages the visitor has not unlocked from the site sent to the SPA. A protected home
+// page, or `/`, locks the whole site
+pub async fn filter_protected_pages(
+    context: &ApiContext,
+    mut site: GetCurrentSiteResponse,
+    headers: &HeaderMap,
+) -> Result<GetCurrentSiteResponse, ApiError> {
+    let protections = get_protections_from_cache_or_repo(context, &site.id).await?;
+    if protections.is_empty() {
+        return Ok(site);
+    }
+    if page_access(context, &site.id, "/", headers).await? == PageAccess::Locked {
+        return Err(password_required());
+    }
+    let grants = site_access_grants(context, &site.id, headers);
+    let (mut pages, encoded) =
+        parse_site_document(&site.pages).map_err(|e| ApiError::internal_error().message(e))?;
+    if let Value::Object(pages) = &mut pages {
+        pages.retain(|route, _| protections.access(route, &grants) != PageAccess::Locked);
+    }
+    site.pages = encode_site_document(pages, encoded).to_string();
+    Ok(site)
+}
@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::protected_route_viewmodel::{to_api_response, ProtectedRouteViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_protected_route_error;
+
+pub async fn list_protected_routes(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<ProtectedRouteViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let protected_routes = context
+        .protected_route_repo
+        .list_protected_routes(&site_id)
+        .await
+        .map_err(map_protected_route_error)?;
+
+    Ok(Json(
+        protected_routes.into_iter().map(to_api_response).collect(),
+    ))
+}
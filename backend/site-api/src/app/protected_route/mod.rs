@@ -0,0 +1,5 @@
+pub mod create_protected_route;
+pub mod delete_protected_route;
+pub mod helpers;
+pub mod list_protected_routes;
+pub mod update_protected_route;
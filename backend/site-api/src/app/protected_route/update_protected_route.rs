@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{json_extractor::PsJson, password::hash_password},
+};
+use lib_shared_types::{
+    dto::site_api::{
+        protected_route_dto::UpdateProtectedRouteDto,
+        protected_route_viewmodel::{to_api_response, ProtectedRouteViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::{check_protected_route, map_protected_route_error, protections_changed};
+
+pub async fn update_protected_route(
+    Path((site_id, route_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<UpdateProtectedRouteDto>,
+) -> Result<Json<ProtectedRouteViewModel>, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let existing = context
+        .protected_route_repo
+        .get_protected_route(&site_id, route_id)
+        .await
+        .map_err(map_protected_route_error)?;
+    let route = check_protected_route(dto.route.as_deref().unwrap_or(&existing.route))?;
+    let password_hash = match dto.password {
+        Some(password) => hash_password(&password)?,
+        None => existing.password_hash,
+    };
+    let protected_route = context
+        .protected_route_repo
+        .update_protected_route(&site_id, route_id, &route, &password_hash)
+        .await
+        .map_err(map_protected_route_error)?;
+    protections_changed(&context, &site_id).await;
+
+    Ok(Json(to_api_response(protected_route)))
+}
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::publish_schedule_viewmodel::{to_api_response, PublishScheduleViewModel},
+    entity::site_api::publish_schedule_entity::PublishScheduleStatus,
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn cancel_publish_schedule(
+    Path((site_id, schedule_id)): Path<(String, String)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<PublishScheduleViewModel>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let schedule_id_int = schedule_id
+        .parse::<i64>()
+        .map_err(|_| ApiError::bad_request().message("Failed to parse schedule ID"))?;
+
+    let schedule = context
+        .publish_schedule_repo
+        .get_schedule(schedule_id_int)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+
+    if schedule.site_id != site_id {
+        return Err(ApiError::not_found());
+    }
+    if schedule.status != PublishScheduleStatus::Pending {
+        return Err(ApiError::bad_request().message("Only pending schedules can be cancelled"));
+    }
+
+    // The schedule may have been claimed between the check and the update
+    let schedule = context
+        .publish_schedule_repo
+        .cancel_schedule(schedule_id_int)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                ApiError::bad_request().message("Only pending schedules can be cancelled")
+            }
+            _ => ApiError::internal_error().message(e),
+        })?;
+
+    Ok(Json(to_api_response(schedule)))
+}
@@ -2,16 +2,25 @@ use axum::{
     extract::{Path, State},
     Extension, Json,
 };
-use lib_shared_site_api::{db::db_error::DbError, error::api_error::ApiError};
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
 use lib_shared_types::{
     dto::{
         query_dto::ListQuery,
-        site_api::site_info_viewmodel::{to_api_response, SiteInfoViewModel},
+        site_api::{
+            create_draft_dto::CreateDraftDto,
+            site_info_viewmodel::{to_api_response, SiteInfoViewModel},
+        },
     },
     entity::site_api::site_info_entity::SiteInfoEntity,
     shared::user::RequestUser,
 };
 
+use validator::Validate;
+
 use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
 
 pub async fn list_versions(
@@ -32,7 +41,10 @@ pub async fn create_draft(
     Path(id): Path<String>,
     State(context): State<ApiContext>,
     Extension(user): Extension<RequestUser>,
+    dto: Option<PsJson<CreateDraftDto>>,
 ) -> Result<Json<Vec<SiteInfoViewModel>>, ApiError> {
+    let dto = dto.map(|PsJson(dto)| dto).unwrap_or_default();
+    check_bad_form(dto.validate())?;
     verify_site_owner(&context, &user, &id).await?;
 
     let mut versions = list_versions(&context, &id).await?;
@@ -43,7 +55,7 @@ pub async fn create_draft(
         if let Some(prev_version) = versions.get(0) {
             context
                 .site_repo
-                .create_draft(&id, prev_version.id)
+                .create_draft(&id, prev_version.id, dto.to_label())
                 .await
                 .map_err(|e| match e {
                     DbError::NoDb(_) => ApiError::not_found(),
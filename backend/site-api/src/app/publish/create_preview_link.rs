@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{json_extractor::PsJson, password::hash_password},
+};
+use lib_shared_types::{
+    dto::site_api::{
+        create_preview_link_dto::CreatePreviewLinkDto,
+        preview_link_viewmodel::{to_api_response, PreviewLinkViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn create_preview_link(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<CreatePreviewLinkDto>,
+) -> Result<(StatusCode, Json<PreviewLinkViewModel>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let expires_at = match &dto.expires_at {
+        Some(expires_at) => {
+            let expires_at = expires_at
+                .parse::<DateTime<Utc>>()
+                .map_err(|_| ApiError::bad_request().message("Failed to parse expires_at"))?;
+            if expires_at <= Utc::now() {
+                return Err(ApiError::bad_request().message("expires_at must be in the future"));
+            }
+            Some(expires_at)
+        }
+        None => None,
+    };
+
+    context
+        .site_repo
+        .get_site_by_version(&site_id, &dto.version_id.to_string())
+        .await
+        .map_err(|_| ApiError::not_found().message("Site version not found"))?;
+
+    let password_hash = match &dto.password {
+        Some(password) => Some(hash_password(password)?),
+        None => None,
+    };
+
+    let link = context
+        .preview_link_repo
+        .create_link(&site_id, dto.version_id, expires_at, password_hash)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok((StatusCode::CREATED, Json(to_api_response(link))))
+}
@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        create_publish_schedule_dto::CreatePublishScheduleDto,
+        publish_schedule_viewmodel::{to_api_response, PublishScheduleViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn create_publish_schedule(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<CreatePublishScheduleDto>,
+) -> Result<(StatusCode, Json<PublishScheduleViewModel>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let publish_at = dto
+        .publish_at
+        .parse::<DateTime<Utc>>()
+        .map_err(|_| ApiError::bad_request().message("Failed to parse publish_at"))?;
+
+    if publish_at <= Utc::now() {
+        return Err(ApiError::bad_request().message("publish_at must be in the future"));
+    }
+
+    let schedule = context
+        .publish_schedule_repo
+        .create_schedule(&site_id, publish_at)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok((StatusCode::CREATED, Json(to_api_response(schedule))))
+}
@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::preview_link_viewmodel::{to_api_response, PreviewLinkViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn list_preview_links(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<PreviewLinkViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let links = context
+        .preview_link_repo
+        .list_links(&site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(Json(links.into_iter().map(to_api_response).collect()))
+}
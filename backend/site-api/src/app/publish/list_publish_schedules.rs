@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::publish_schedule_viewmodel::{to_api_response, PublishScheduleViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn list_publish_schedules(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<PublishScheduleViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let schedules = context
+        .publish_schedule_repo
+        .list_schedules_by_site_id(&site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(Json(schedules.into_iter().map(to_api_response).collect()))
+}
@@ -1,3 +1,12 @@
+pub mod cancel_publish_schedule;
 pub mod create_draft;
+pub mod create_preview_link;
+pub mod create_publish_schedule;
 pub mod delete_draft;
+pub mod list_preview_links;
+pub mod list_publish_schedules;
+pub mod notify;
 pub mod publish_site;
+pub mod revoke_preview_link;
+pub mod run_publish_schedules;
+pub mod unlock_preview_link;
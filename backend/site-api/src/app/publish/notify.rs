@@ -0,0 +1,40 @@
+use lib_shared_site_api::mail::{send_mail, ApiMailParams};
+use lib_shared_types::entity::site_api::publish_schedule_entity::PublishScheduleEntity;
+use tracing::error;
+
+pub async fn notify_scheduled_publish(
+    params: ApiMailParams,
+    schedule: &PublishScheduleEntity,
+    error: Option<&str>,
+) {
+    let (subject, text) = match error {
+        None => (
+            "PubStudio scheduled publish completed",
+            format!(
+                "
+      Your site \"{}\" was published as scheduled.\n\n
+      View it in the builder by clicking the link below:\n\n
+      {}/build/{}",
+                schedule.site_id, params.frontend_url, schedule.site_id
+            ),
+        ),
+        Some(e) => (
+            "PubStudio scheduled publish failed",
+            format!(
+                "
+      The scheduled publish for site \"{}\" failed: {}\n\n
+      Publish manually by clicking the link below:\n\n
+      {}/build/{}",
+                schedule.site_id, e, params.frontend_url, schedule.site_id
+            ),
+        ),
+    };
+
+    let result = send_mail(params.params, subject, Some(text), None).await;
+    if let Err(e) = result {
+        error!(
+            err = e.to_string(),
+            "Failed to notify scheduled publish: {}", schedule.site_id
+        );
+    }
+}
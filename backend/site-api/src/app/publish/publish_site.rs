@@ -2,7 +2,7 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    Extension,
+    Extension, Json,
 };
 use lib_shared_site_api::{
     db::db_error::DbError,
@@ -10,63 +10,113 @@ use lib_shared_site_api::{
     util::json_extractor::PsJson,
 };
 use lib_shared_types::{
-    dto::{query_dto::ListQuery, site_api::publish_site_dto::PublishSiteDto},
+    dto::{
+        query_dto::ListQuery,
+        site_api::publish_site_dto::{PublishSiteDto, PublishSiteResponse, SsgBlockerPolicy},
+    },
+    entity::site_api::{
+        site_info_entity::SiteVersionLabel, static_build_entity::StaticBuildTrigger,
+    },
+    error::api_error::ApiErrorCode,
     shared::user::RequestUser,
 };
+use tracing::warn;
 use validator::Validate;
 
 use crate::{
-    api_context::ApiContext, app::ssg::generate_static::spawn_regenerate_static_pages,
+    api_context::ApiContext,
+    app::ssg::generate_static::{check_ssg_blockers, spawn_regenerate_static_pages},
     middleware::auth::verify_site_owner,
 };
 
-pub async fn publish_site(
-    Path(id): Path<String>,
-    State(context): State<ApiContext>,
-    Extension(user): Extension<RequestUser>,
-    PsJson(dto): PsJson<PublishSiteDto>,
-) -> Result<Response, ApiError> {
-    check_bad_form(dto.validate())?;
-    verify_site_owner(&context, &user, &id).await?;
-
+// Publish (or unpublish) a site and reset its cache. Static pages are not regenerated here
+pub async fn publish_site_helper(
+    context: &ApiContext,
+    id: &str,
+    publish: bool,
+    label: SiteVersionLabel,
+) -> Result<(), ApiError> {
     let versions = context
         .site_repo
-        .list_site_versions(&id, ListQuery::default())
+        .list_site_versions(id, ListQuery::default())
         .await
         .map_err(|e| match e {
             DbError::NoDb(_) => ApiError::not_found(),
             _ => ApiError::internal_error().message(e),
         })?;
 
-    if versions.len() == 1 || !dto.publish {
+    if versions.len() == 1 || !publish {
         context
             .site_repo
-            .publish_all_versions(&id, dto.publish)
+            .publish_all_versions(id, publish)
             .await
             .map_err(|e| match e {
                 DbError::NoDb(_) => ApiError::not_found(),
                 _ => ApiError::internal_error().message(e),
             })?;
+        // Label the single version that was just published
+        if let Some(version) = versions.first() {
+            if publish && !label.is_empty() {
+                context
+                    .site_repo
+                    .set_version_label(id, version.id, label)
+                    .await
+                    .map_err(|e| ApiError::internal_error().message(e))?;
+            }
+        }
+    } else {
+        context
+            .site_repo
+            .publish_site(id, label)
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?;
+    }
 
-        // Reset cache
-        context.cache.remove_site(&id).await;
+    // Reset cache
+    context.cache.remove_site(id).await;
 
-        // Regenerate (or clear, when unpublishing) static pages
-        spawn_regenerate_static_pages(&context, &id, None);
-        return Ok(StatusCode::NO_CONTENT.into_response());
-    }
+    Ok(())
+}
 
-    context
-        .site_repo
-        .publish_site(&id)
-        .await
-        .map_err(|e| ApiError::internal_error().message(e))?;
+pub async fn publish_site(
+    Path(id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<PublishSiteDto>,
+) -> Result<Response, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &id).await?;
 
-    // Reset cache
-    context.cache.remove_site(&id).await;
+    let blockers = match (dto.publish, dto.ssg_blockers) {
+        (false, _) | (_, SsgBlockerPolicy::Ignore) => vec![],
+        (true, SsgBlockerPolicy::Warn) => {
+            check_ssg_blockers(&context, &id).await.unwrap_or_else(|e| {
+                warn!("SSG blocker check failed for site {}: {}", id, e);
+                vec![]
+            })
+        }
+        (true, SsgBlockerPolicy::Refuse) => check_ssg_blockers(&context, &id).await?,
+    };
+    if dto.ssg_blockers == SsgBlockerPolicy::Refuse && !blockers.is_empty() {
+        return Err(ApiError::bad_request()
+            .code(ApiErrorCode::SsgBlocked)
+            .message(format!(
+                "Static generation blocked: {}",
+                blockers.join("; ")
+            )));
+    }
+
+    publish_site_helper(&context, &id, dto.publish, dto.to_label()).await?;
 
-    // Regenerate static pages for the newly published content
-    spawn_regenerate_static_pages(&context, &id, None);
+    // Regenerate static pages for the newly published content, or clear them when unpublishing
+    spawn_regenerate_static_pages(&context, &id, StaticBuildTrigger::Publish, None);
 
-    Ok(StatusCode::NO_CONTENT.into_response())
+    if blockers.is_empty() {
+        Ok(StatusCode::NO_CONTENT.into_response())
+    } else {
+        Ok(Json(PublishSiteResponse {
+            ssg_blockers: blockers,
+        })
+        .into_response())
+    }
 }
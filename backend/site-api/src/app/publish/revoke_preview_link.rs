@@ -0,0 +1,25 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use lib_shared_site_api::{db::db_error::DbError, error::api_error::ApiError};
+use lib_shared_types::shared::user::RequestUser;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+pub async fn revoke_preview_link(
+    Path((site_id, link_id)): Path<(String, String)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<(), ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    context
+        .preview_link_repo
+        .delete_link(&site_id, &link_id)
+        .await
+        .map_err(|e| match e {
+            DbError::EntityNotFound() | DbError::NoDb(_) => ApiError::not_found(),
+            _ => ApiError::internal_error().message(e),
+        })
+}
@@ -0,0 +1,138 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use lib_shared_types::entity::site_api::{
+    publish_schedule_entity::{PublishScheduleEntity, PublishScheduleStatus},
+    site_info_entity::SiteVersionLabel,
+    static_build_entity::StaticBuildTrigger,
+};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::{
+    api_context::ApiContext, app::ssg::generate_static::regenerate_static_pages,
+    util::mail_helpers::make_mail_params,
+};
+
+use super::{notify::notify_scheduled_publish, publish_site::publish_site_helper};
+
+// Schedules without a heartbeat for longer than this are assumed abandoned by a crashed
+// instance
+const STALE_CLAIM_MINUTES: i64 = 10;
+// Running schedules renew their lease well within the stale timeout
+const HEARTBEAT_SECONDS: u64 = 60;
+
+// Renew the lease of a claimed schedule until aborted, or until it is lost
+fn spawn_heartbeat(context: ApiContext, id: i64, claimed_at: DateTime<Utc>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(StdDuration::from_secs(HEARTBEAT_SECONDS));
+        // The first tick completes immediately, the claim was just made
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match context
+                .publish_schedule_repo
+                .renew_claim(id, claimed_at)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("Lost the lease of publish schedule {}", id);
+                    return;
+                }
+                Err(e) => warn!("Failed to renew publish schedule {}: {}", id, e),
+            }
+        }
+    })
+}
+
+async fn run_publish_schedule(context: &ApiContext, schedule: &PublishScheduleEntity) {
+    let Some(claimed_at) = schedule.claimed_at else {
+        return;
+    };
+    let heartbeat = spawn_heartbeat(context.clone(), schedule.id, claimed_at);
+    let result = publish_site_helper(
+        context,
+        &schedule.site_id,
+        true,
+        SiteVersionLabel::default(),
+    )
+    .await;
+
+    let (status, error) = match result {
+        Ok(()) => {
+            if let Err(e) =
+                regenerate_static_pages(context, &schedule.site_id, StaticBuildTrigger::Schedule)
+                    .await
+            {
+                warn!(
+                    "Static generation failed after scheduled publish {}: {}",
+                    schedule.id, e
+                );
+            }
+            (PublishScheduleStatus::Completed, None)
+        }
+        Err(e) => (PublishScheduleStatus::Failed, Some(e.to_string())),
+    };
+    heartbeat.abort();
+
+    match context
+        .publish_schedule_repo
+        .finish_schedule(schedule.id, claimed_at, status, error.clone())
+        .await
+    {
+        Ok(true) => {}
+        // Another instance re-claimed the schedule, and reports its result
+        Ok(false) => {
+            warn!(
+                "Publish schedule {} was re-claimed before it finished",
+                schedule.id
+            );
+            return;
+        }
+        Err(e) => error!("Failed to update publish schedule {}: {}", schedule.id, e),
+    }
+
+    match context
+        .metadata_repo
+        .get_site_metadata(&schedule.site_id)
+        .await
+    {
+        Ok(meta) => {
+            let params = make_mail_params(&context.config, &meta.owner_email);
+            notify_scheduled_publish(params, schedule, error.as_deref()).await;
+        }
+        Err(e) => error!(
+            "Failed to get metadata for scheduled publish {}: {}",
+            schedule.id, e
+        ),
+    }
+}
+
+// Publish all sites with a due schedule. Also picks up schedules missed while the server was down.
+// Schedules are claimed one at a time, so a claim is never left without a heartbeat while
+// earlier schedules run
+pub async fn run_publish_schedules_helper(context: ApiContext) {
+    let mut count = 0;
+    loop {
+        let now = Utc::now();
+        let stale_before = now - Duration::minutes(STALE_CLAIM_MINUTES);
+        let schedule = match context
+            .publish_schedule_repo
+            .claim_due_schedule(now, stale_before)
+            .await
+        {
+            Ok(Some(schedule)) => schedule,
+            Ok(None) => break,
+            Err(e) => {
+                error!("Failed to claim publish schedules: {}", e);
+                break;
+            }
+        };
+        run_publish_schedule(&context, &schedule).await;
+        count += 1;
+    }
+    if count > 0 {
+        info!("Ran {} scheduled publishes", count);
+    }
+}
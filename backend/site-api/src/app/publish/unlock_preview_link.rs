@@ -0,0 +1,90 @@
+use std::net::IpAddr;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::Host;
+use chrono::Utc;
+use lib_shared_site_api::{
+    auth::generate_jwt::generate_preview_token,
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{
+        domains::{base_url_from_domain, domain_without_port},
+        json_extractor::PsJson,
+        site_access::{password_fingerprint, preview_cookie, PREVIEW_TTL_MINUTES},
+    },
+};
+use lib_shared_types::{
+    dto::site_api::unlock_preview_link_dto::{UnlockPreviewLinkDto, UnlockPreviewLinkResponse},
+    error::api_error::ApiErrorCode,
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext,
+    app::{
+        maintenance::helpers::{peer_ip, visitor_ip, PeerAddr},
+        protected_route::helpers::verify_visitor_password,
+    },
+};
+
+// Check the password of a protected preview link, and issue a token that unlocks it. The
+// password is checked once, instead of on every preview request
+pub async fn unlock_preview(
+    context: &ApiContext,
+    site_id: &str,
+    link_id: &str,
+    ip: Option<IpAddr>,
+    password: String,
+) -> Result<String, ApiError> {
+    let link = context
+        .preview_link_repo
+        .get_link(site_id, link_id)
+        .await
+        .map_err(|_| ApiError::not_found().message("Site preview not found"))?;
+    if link.is_expired(Utc::now()) {
+        return Err(ApiError::not_found()
+            .code(ApiErrorCode::PreviewExpired)
+            .message("Site preview has expired"));
+    }
+    let Some(password_hash) = link.password_hash else {
+        return Err(ApiError::bad_request().message("Site preview is not password protected"));
+    };
+    if !verify_visitor_password(context, site_id, ip, password, password_hash.clone()).await? {
+        return Err(ApiError::unauthorized()
+            .code(ApiErrorCode::InvalidAuth)
+            .message("Invalid preview password"));
+    }
+
+    let secret = context.config.site_access_secret();
+    generate_preview_token(
+        site_id,
+        link_id,
+        password_fingerprint(&password_hash),
+        PREVIEW_TTL_MINUTES,
+        secret,
+    )
+}
+
+pub async fn unlock_preview_link(
+    Path((site_id, link_id)): Path<(String, String)>,
+    State(context): State<ApiContext>,
+    Host(hostname): Host,
+    headers: HeaderMap,
+    peer: PeerAddr,
+    PsJson(dto): PsJson<UnlockPreviewLinkDto>,
+) -> Result<Response, ApiError> {
+    check_bad_form(dto.validate())?;
+    let ip = visitor_ip(&context, &headers, peer_ip(peer));
+    let token = unlock_preview(&context, &site_id, &link_id, ip, dto.password).await?;
+    let secure = base_url_from_domain(&domain_without_port(hostname)).starts_with("https://");
+
+    Ok((
+        [(header::SET_COOKIE, preview_cookie(&token, secure))],
+        Json(UnlockPreviewLinkResponse { token }),
+    )
+        .into_response())
+}
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        redirect_dto::CreateRedirectDto,
+        redirect_viewmodel::{to_api_response, RedirectViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, db::redirect_repo::RedirectFields, middleware::auth::verify_site_owner,
+};
+
+use super::helpers::{check_redirect, map_redirect_error, DEFAULT_REDIRECT_STATUS};
+
+pub async fn create_redirect(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<CreateRedirectDto>,
+) -> Result<(StatusCode, Json<RedirectViewModel>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let fields = check_redirect(RedirectFields {
+        source: dto.source,
+        target: dto.target,
+        status_code: dto.status_code.unwrap_or(DEFAULT_REDIRECT_STATUS),
+        enabled: dto.enabled.unwrap_or(true),
+    })?;
+    let redirect = context
+        .redirect_repo
+        .create_redirect(&site_id, fields)
+        .await
+        .map_err(map_redirect_error)?;
+    context.cache.remove_redirects(&site_id).await;
+
+    Ok((StatusCode::CREATED, Json(to_api_response(redirect))))
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::shared::user::RequestUser;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_redirect_error;
+
+pub async fn delete_redirect(
+    Path((site_id, redirect_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<(), ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    context
+        .redirect_repo
+        .delete_redirect(&site_id, redirect_id)
+        .await
+        .map_err(map_redirect_error)?;
+    context.cache.remove_redirects(&site_id).await;
+    Ok(())
+}
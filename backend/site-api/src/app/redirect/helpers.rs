@@ -0,0 +1,36 @@
+use lib_shared_site_api::{
+    db::db_error::DbError, error::api_error::ApiError, util::redirects::validate_redirect,
+};
+use lib_shared_types::error::api_error::ApiErrorCode;
+
+use crate::{app::ssg::static_serve::normalize_route, db::redirect_repo::RedirectFields};
+
+pub const DEFAULT_REDIRECT_STATUS: u16 = 301;
+const REDIRECT_STATUS_CODES: [u16; 4] = [301, 302, 307, 308];
+
+// Validate a redirect before it is saved. Sources are stored without a trailing slash,
+// since matching ignores it.
+pub fn check_redirect(mut fields: RedirectFields) -> Result<RedirectFields, ApiError> {
+    if !REDIRECT_STATUS_CODES.contains(&fields.status_code) {
+        return Err(ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message("status_code must be one of 301, 302, 307, 308"));
+    }
+    fields.source = normalize_route(&fields.source);
+    validate_redirect(&fields.source, &fields.target).map_err(|e| {
+        ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(e)
+    })?;
+    Ok(fields)
+}
+
+pub fn map_redirect_error(e: DbError) -> ApiError {
+    match e {
+        DbError::Unique(_) => ApiError::bad_request()
+            .code(ApiErrorCode::RedirectSourceExists)
+            .message("A redirect with this source already exists"),
+        DbError::EntityNotFound() | DbError::NoDb(_) => ApiError::not_found(),
+        _ => ApiError::internal_error().message(e),
+    }
+}
@@ -0,0 +1,42 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_types::{
+    dto::site_api::redirect_viewmodel::{to_api_response, RedirectViewModel},
+    shared::user::RequestUser,
+};
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::helpers::map_redirect_error;
+
+pub async fn list_redirects(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Json<Vec<RedirectViewModel>>, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let redirects = context
+        .redirect_repo
+        .list_redirects(&site_id)
+        .await
+        .map_err(map_redirect_error)?;
+
+    // Include hits that are not persisted yet
+    let pending = context.cache.redirect_hits.pending(&site_id);
+    Ok(Json(
+        redirects
+            .into_iter()
+            .map(|mut redirect| {
+                if let Some(count) = pending.get(&redirect.id) {
+                    redirect.hit_count += count.hits;
+                    redirect.last_hit_at = Some(count.last_hit_at);
+                }
+                to_api_response(redirect)
+            })
+            .collect(),
+    ))
+}
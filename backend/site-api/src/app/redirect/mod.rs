@@ -0,0 +1,5 @@
+pub mod create_redirect;
+pub mod delete_redirect;
+pub mod helpers;
+pub mod list_redirects;
+pub mod update_redirect;
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        redirect_dto::UpdateRedirectDto,
+        redirect_viewmodel::{to_api_response, RedirectViewModel},
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, db::redirect_repo::RedirectFields, middleware::auth::verify_site_owner,
+};
+
+use super::helpers::{check_redirect, map_redirect_error};
+
+pub async fn update_redirect(
+    Path((site_id, redirect_id)): Path<(String, i64)>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<UpdateRedirectDto>,
+) -> Result<Json<RedirectViewModel>, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let existing = context
+        .redirect_repo
+        .get_redirect(&site_id, redirect_id)
+        .await
+        .map_err(map_redirect_error)?;
+    let fields = check_redirect(RedirectFields {
+        source: dto.source.unwrap_or(existing.source),
+        target: dto.target.unwrap_or(existing.target),
+        status_code: dto.status_code.unwrap_or(existing.status_code as u16),
+        enabled: dto.enabled.unwrap_or(existing.enabled),
+    })?;
+    let redirect = context
+        .redirect_repo
+        .update_redirect(&site_id, redirect_id, fields)
+        .await
+        .map_err(map_redirect_error)?;
+    context.cache.remove_redirects(&site_id).await;
+
+    Ok(Json(to_api_response(redirect)))
+}
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::Host;
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::{
+        domains::{base_url_from_domain, domain_without_port},
+        maintenance::bypass_cookie,
+    },
+};
+use lib_shared_types::dto::site_api::maintenance_dto::MaintenanceBypassQuery;
+
+use crate::{
+    api_context::ApiContext,
+    db::db_cache_layer::{
+        get_maintenance_from_cache_or_repo, get_site_id_by_domain_from_cache_or_repo,
+    },
+};
+
+// Link shared with previewers. A valid token sets the bypass cookie, so the previewer sees
+// the site during maintenance
+pub async fn maintenance_bypass(
+    State(context): State<ApiContext>,
+    Host(hostname): Host,
+    Query(query): Query<MaintenanceBypassQuery>,
+) -> Result<Response, ApiError> {
+    let domain = domain_without_port(hostname);
+    let site_id = get_site_id_by_domain_from_cache_or_repo(&context, domain.clone()).await?;
+    let maintenance = get_maintenance_from_cache_or_repo(&context, &site_id).await?;
+    if maintenance.bypass_token.is_empty() || query.token != maintenance.bypass_token {
+        return Err(ApiError::forbidden().message("Invalid maintenance bypass token"));
+    }
+    let secure = base_url_from_domain(&domain).starts_with("https://");
+
+    Ok((
+        StatusCode::SEE_OTHER,
+        [
+            (header::SET_COOKIE, bypass_cookie(&query.token, secure)),
+            (header::LOCATION, "/".to_string()),
+        ],
+    )
+        .into_response())
+}
@@ -1 +1,3 @@
+pub mod maintenance_bypass;
 pub mod serve_web_site;
+pub mod site_login;
@@ -1,48 +1,98 @@
+use std::{borrow::Cow, net::IpAddr};
+
 use axum::{
     extract::{OriginalUri, Query, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
     response::{Html, IntoResponse, Response},
 };
 use axum_extra::extract::Host;
+use chrono::Utc;
 use lib_shared_site_api::{
+    cache::cache::SiteMetadata,
     db::sites_seed_data::SITE_SEED_VERSION,
     error::api_error::ApiError,
     util::{
-        domains::domain_without_port,
-        get_site_html::{get_site_html, get_site_html_dev, get_site_js, get_site_js_dev},
+        compression::{negotiate_encoding, ContentEncoding},
+        domains::{canonical_redirect_url, domain_without_port, match_host, HostMatch},
+        get_site_html::{get_site_html, get_site_html_dev, get_site_js_dev, get_site_js_encoded},
+        http_cache::{site_cache_control, HttpValidators},
+        locales::negotiate_locale,
+        page_head::render_page_head,
+        redirects::trailing_slash_redirect,
+        site_access::PageAccess,
+        site_seo::is_seo_file_path,
     },
 };
 use lib_shared_types::{
-    dto::site_api::get_current_site_dto::GetCurrentSiteQuery,
-    entity::site_api::static_page_entity::StaticPageEntity, shared::core::ExecEnv,
+    dto::site_api::get_current_site_dto::GetCurrentSiteQuery, error::api_error::ApiErrorCode,
+    shared::core::ExecEnv,
 };
 
 use crate::{
     api_context::ApiContext,
-    app::ssg::static_serve::serve_static_page,
-    db::db_cache_layer::{get_site_id_by_domain_from_cache_or_repo, get_site_or_preview},
+    app::{
+        experiment::helpers::{VisitorExperiments, EXPERIMENT_CACHE_CONTROL},
+        maintenance::helpers::{
+            blocking_maintenance, maintenance_response, peer_ip, visitor_ip, PeerAddr,
+        },
+        protected_route::helpers::{page_access, page_route, PROTECTED_CACHE_CONTROL},
+        serve::site_login::login_page_response,
+        ssg::static_serve::{serve_seo_file, serve_static_page, ServedStaticPage},
+    },
+    db::db_cache_layer::{
+        get_metadata_from_cache_or_repo, get_redirects_from_cache_or_repo,
+        get_site_from_cache_or_repo, get_site_id_by_domain_from_cache_or_repo, get_site_or_preview,
+    },
 };
 
 // Path of the shared hydration runtime referenced by generated static pages
 pub const SITE_JS_PATH: &str = "/_ps/site.js";
 
-fn static_page_response(page: StaticPageEntity, status: StatusCode) -> Response {
+// Vary and Content-Encoding for a response negotiated from Accept-Encoding
+fn encoding_headers(encoding: ContentEncoding) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if let Some(coding) = encoding.header_value() {
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(coding));
+    }
+    headers
+}
+
+fn static_page_response(served: ServedStaticPage) -> Response {
+    let mut headers = served.validators.headers(&served.cache_control);
+    if served.not_modified {
+        // A 304 has no body to encode, only Vary applies
+        headers.extend(encoding_headers(ContentEncoding::Identity));
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+    headers.extend(encoding_headers(served.encoding));
+    let status = if served.not_found {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::OK
+    };
     (
         status,
-        [(header::CONTENT_TYPE, page.content_type)],
-        page.body,
+        headers,
+        [(header::CONTENT_TYPE, served.page.content_type)],
+        served.body,
     )
         .into_response()
 }
 
-fn serve_site_js(context: &ApiContext) -> Response {
-    let js = if context.config.exec_env == ExecEnv::Dev {
-        get_site_js_dev()
+fn serve_site_js(context: &ApiContext, headers: &HeaderMap) -> Response {
+    let (encoding, js) = if context.config.exec_env == ExecEnv::Dev {
+        (
+            ContentEncoding::Identity,
+            Cow::Owned(get_site_js_dev().into_bytes()),
+        )
     } else {
-        get_site_js().to_string()
+        let encoding = negotiate_encoding(headers);
+        (encoding, Cow::Borrowed(get_site_js_encoded(encoding)))
     };
     (
         StatusCode::OK,
+        encoding_headers(encoding),
         [
             (header::CONTENT_TYPE, "application/javascript".to_string()),
             (
@@ -55,59 +105,277 @@ fn serve_site_js(context: &ApiContext) -> Response {
         .into_response()
 }
 
+async fn serve_seo_file_response(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+) -> Result<Response, ApiError> {
+    let file = serve_seo_file(context, site_id, path)
+        .await?
+        .ok_or_else(ApiError::not_found)?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, file.content_type),
+            (header::CACHE_CONTROL, "public, max-age=3600".to_string()),
+        ],
+        file.body,
+    )
+        .into_response())
+}
+
 // Serve a statically generated page when a fresh one exists for the current
 // published content. Returns None to fall back to the SPA shell.
 async fn try_serve_static(
     context: &ApiContext,
     site_id: &str,
     path: &str,
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    subdomain: Option<&str>,
 ) -> Result<Option<Response>, ApiError> {
-    let Some(served) = serve_static_page(context, site_id, path).await? else {
+    let encoding = negotiate_encoding(headers);
+    let Some(served) = serve_static_page(
+        context,
+        site_id,
+        path,
+        headers,
+        visitor_ip(context, headers, peer),
+        encoding,
+        subdomain,
+    )
+    .await?
+    else {
         return Ok(None);
     };
-    let status = if served.not_found {
-        StatusCode::NOT_FOUND
-    } else {
-        StatusCode::OK
+    Ok(Some(static_page_response(served)))
+}
+
+fn redirect_response(status_code: u16, location: &str) -> Result<Response, ApiError> {
+    let status =
+        StatusCode::from_u16(status_code).map_err(|e| ApiError::internal_error().message(e))?;
+    let location =
+        HeaderValue::from_str(location).map_err(|e| ApiError::internal_error().message(e))?;
+    Ok((status, [(header::LOCATION, location)]).into_response())
+}
+
+// The site's redirect rules, then its trailing slash policy. Evaluated before static or
+// SPA serving, so renamed pages keep working for visitors and crawlers.
+async fn try_redirect(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+    query: Option<&str>,
+) -> Result<Option<Response>, ApiError> {
+    let redirects = get_redirects_from_cache_or_repo(context, site_id).await?;
+    if let Some(matched) = redirects.find(path, query) {
+        context
+            .cache
+            .redirect_hits
+            .record(site_id, matched.id, Utc::now());
+        return redirect_response(matched.status_code, &matched.location).map(Some);
+    }
+    let site = get_site_from_cache_or_repo(context, site_id).await?;
+    trailing_slash_redirect(site.meta.trailing_slash, path, query)
+        .map(|location| redirect_response(StatusCode::MOVED_PERMANENTLY.as_u16(), &location))
+        .transpose()
+}
+
+// Multi-language sites serve `/` in the visitor's preferred locale. The redirect depends on
+// Accept-Language, so it is not stored by shared caches without Vary
+async fn try_locale_redirect(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+    query: Option<&str>,
+    headers: &HeaderMap,
+) -> Result<Option<Response>, ApiError> {
+    if path != "/" {
+        return Ok(None);
+    }
+    let site = get_site_from_cache_or_repo(context, site_id).await?;
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let Some(locale) = negotiate_locale(&site.meta.locales, accept_language) else {
+        return Ok(None);
+    };
+    let location = match query {
+        Some(query) if !query.is_empty() => format!("{}?{}", locale.localize("/"), query),
+        _ => locale.localize("/"),
+    };
+    let mut response = redirect_response(StatusCode::FOUND.as_u16(), &location)?;
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::VARY, HeaderValue::from_static("accept-language"));
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Ok(Some(response))
+}
+
+// Other domains of the site, and plain http behind a TLS terminating proxy, permanently
+// redirect to the canonical host once the owner sets a primary domain. Redirect aliases
+// always redirect, and subdomains matched by a wildcard domain are served as is
+fn try_canonical_redirect(
+    meta: &SiteMetadata,
+    domain: &str,
+    uri: &Uri,
+    headers: &HeaderMap,
+) -> Result<Option<Response>, ApiError> {
+    let target = match match_host(&meta.domains, domain) {
+        HostMatch::Serve => &meta.canonical_host,
+        HostMatch::Redirect => &meta.alias_host,
+        HostMatch::Wildcard(_) => return Ok(None),
     };
-    Ok(Some(static_page_response(served.page, status)))
+    let Some(canonical_host) = target else {
+        return Ok(None);
+    };
+    let forwarded_proto = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok());
+    let path_and_query = uri.path_and_query().map_or("/", |p| p.as_str());
+    canonical_redirect_url(canonical_host, domain, forwarded_proto, path_and_query)
+        .map(|location| redirect_response(StatusCode::MOVED_PERMANENTLY.as_u16(), &location))
+        .transpose()
+}
+
+// Unlocked protected pages are only cached by the visitor's browser
+fn protect_response(mut response: Response, access: PageAccess) -> Response {
+    if access == PageAccess::Unlocked {
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(PROTECTED_CACHE_CONTROL),
+        );
+    }
+    response
+}
+
+// Pages of experiment routes differ per visitor. The response sends the visitor's assignment
+fn experiment_response(mut response: Response, experiment: Option<&HeaderMap>) -> Response {
+    if let Some(experiment_headers) = experiment {
+        let headers = response.headers_mut();
+        headers.extend(experiment_headers.clone());
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(EXPERIMENT_CACHE_CONTROL),
+        );
+    }
+    response
 }
 
 pub async fn serve_web_site_helper(
     context: &ApiContext,
     hostname: String,
-    path: &str,
+    uri: &Uri,
     query: GetCurrentSiteQuery,
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
 ) -> Result<Response, ApiError> {
+    let path = uri.path();
     let domain = domain_without_port(hostname);
 
     // domain -> site_id from cache
-    let site_id = get_site_id_by_domain_from_cache_or_repo(&context, domain).await?;
+    let site_id = get_site_id_by_domain_from_cache_or_repo(&context, domain.clone()).await?;
 
-    // Statically generated pages take priority (previews are always dynamic)
+    let site_meta = get_metadata_from_cache_or_repo(context, &site_id).await?;
+    if let Some(response) = try_canonical_redirect(&site_meta, &domain, uri, headers)? {
+        return Ok(response);
+    }
+
+    // Maintenance takes priority over all site content. Preview links are not affected, and
+    // holding page requests are not counted as views
+    if query.p.is_none() {
+        if let Some(maintenance) = blocking_maintenance(context, &site_id, headers, peer).await? {
+            return Ok(maintenance_response(context, &site_id, &maintenance).await);
+        }
+    }
+
+    if is_seo_file_path(path) {
+        return serve_seo_file_response(context, &site_id, path).await;
+    }
+
+    // Redirects, then password protection and statically generated pages take priority
+    // (previews are always dynamic)
+    let mut access = PageAccess::Public;
+    let mut experiment = None;
+    let mut variant_route = None;
     if query.p.is_none() {
-        if let Some(response) = try_serve_static(context, &site_id, path).await? {
+        if let Some(response) = try_redirect(context, &site_id, path, uri.query()).await? {
+            return Ok(response);
+        }
+        if let Some(response) =
+            try_locale_redirect(context, &site_id, path, uri.query(), headers).await?
+        {
             return Ok(response);
         }
+        access = page_access(context, &site_id, path, headers).await?;
+        if access == PageAccess::Locked {
+            let redirect = uri.path_and_query().map_or(path, |p| p.as_str());
+            return login_page_response(context, &site_id, redirect, None, None).await;
+        }
+        // Visitors keep their variant of an experiment. Static pages hydrate from the control's
+        // page data, so other variants are rendered by the SPA
+        let mut visitor = VisitorExperiments::load(context, &site_id, headers).await?;
+        if !visitor.is_empty() {
+            let route = page_route(context, &site_id, path).await?;
+            if let Some(variant) = visitor.variant(&route) {
+                experiment = Some(visitor.response_headers(context, &site_id, &domain)?);
+                variant_route = Some(variant.page_route).filter(|variant| *variant != route);
+            }
+        }
+        if variant_route.is_none() {
+            let subdomain = match match_host(&site_meta.domains, &domain) {
+                HostMatch::Wildcard(subdomain) => Some(subdomain),
+                _ => None,
+            };
+            if let Some(response) =
+                try_serve_static(context, &site_id, path, headers, peer, subdomain.as_deref())
+                    .await?
+            {
+                let response = protect_response(response, access);
+                return Ok(experiment_response(response, experiment.as_ref()));
+            }
+        }
     }
 
     // TODO -- either cache  `subdomain -> server.address` or the whole HTML response
 
-    let site = get_site_or_preview(&context, &site_id, query.p).await?;
+    let is_preview = query.p.is_some();
+    let site = match get_site_or_preview(&context, &site_id, query.p.clone(), headers).await {
+        Err(e) if e.code == ApiErrorCode::PreviewPasswordRequired => {
+            let redirect = uri.path_and_query().map_or(path, |p| p.as_str());
+            let preview = query.p.as_deref();
+            return login_page_response(context, &site_id, redirect, preview, None).await;
+        }
+        site => site?,
+    };
 
     let html = if context.config.exec_env == ExecEnv::Dev {
         &get_site_html_dev()
     } else {
         get_site_html(&site.site.version)
     };
-    Ok(
-        Html(html.replacen("Pub Studio", &site.meta.title, 3).replacen(
-            "DESCRIPTION",
-            &site.meta.description,
-            2,
-        ))
-        .into_response(),
-    )
+    let mut head = site.meta.page_head(path);
+    // Variants are served at the experiment route, with the head of the variant's page
+    if let Some(variant_head) = variant_route.and_then(|route| site.meta.pages.get(&route)) {
+        head.title = variant_head.title.clone();
+        head.description = variant_head.description.clone();
+        head.image = variant_head.image.clone();
+        head.meta = variant_head.meta.clone();
+    }
+    let html = render_page_head(html, &head, site_meta.base_url.as_deref());
+
+    // Previews must never be stored by browsers or shared caches
+    if is_preview {
+        return Ok(([(header::CACHE_CONTROL, "no-store")], Html(html)).into_response());
+    }
+    let validators = HttpValidators::new(&html, site.site.content_updated_at);
+    let cache_headers = validators.headers(&site_cache_control(site.meta.cache_control.as_deref()));
+    if validators.is_not_modified(headers) {
+        let response = (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+        let response = protect_response(response, access);
+        return Ok(experiment_response(response, experiment.as_ref()));
+    }
+    let response = protect_response((cache_headers, Html(html)).into_response(), access);
+    Ok(experiment_response(response, experiment.as_ref()))
 }
 
 pub async fn serve_web_site(
@@ -115,16 +383,21 @@ pub async fn serve_web_site(
     Host(hostname): Host,
     OriginalUri(uri): OriginalUri,
     Query(query): Query<GetCurrentSiteQuery>,
+    headers: HeaderMap,
+    peer: PeerAddr,
 ) -> Response {
     let path = uri.path();
     if path == SITE_JS_PATH {
-        return serve_site_js(&context);
+        return serve_site_js(&context, &headers);
     }
 
-    let result = serve_web_site_helper(&context, hostname, path, query).await;
+    let result =
+        serve_web_site_helper(&context, hostname, &uri, query, &headers, peer_ip(peer)).await;
 
     match result {
         Ok(response) => response,
+        // Crawlers must not receive the SPA shell for the sitemap or robots.txt
+        Err(e) if is_seo_file_path(path) => e.into_response(),
         Err(e) => {
             tracing::warn!("serve_web_site fallback for {}: {:?}", path, e);
             Html(get_site_html(SITE_SEED_VERSION).to_string()).into_response()
@@ -0,0 +1,120 @@
+use axum::{
+    extract::{Form, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+use axum_extra::extract::Host;
+use lib_shared_site_api::{
+    auth::generate_jwt::generate_site_access_token,
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{
+        domains::{base_url_from_domain, domain_without_port},
+        site_access::{
+            access_cookie, preview_cookie, render_login_page, safe_redirect_path,
+            ACCESS_TTL_MINUTES,
+        },
+    },
+};
+use lib_shared_types::{
+    dto::site_api::protected_route_dto::SiteLoginDto, error::api_error::ApiErrorCode,
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext,
+    app::{
+        maintenance::helpers::{peer_ip, visitor_ip, PeerAddr},
+        protected_route::helpers::{page_route, site_access_grants, verify_visitor_password},
+        publish::unlock_preview_link::unlock_preview,
+    },
+    db::db_cache_layer::{
+        get_protections_from_cache_or_repo, get_site_from_cache_or_repo,
+        get_site_id_by_domain_from_cache_or_repo,
+    },
+};
+
+// Login page shown in place of a locked page or protected preview, titled with the site name.
+// A failed login is shown with its error
+pub async fn login_page_response(
+    context: &ApiContext,
+    site_id: &str,
+    redirect: &str,
+    preview: Option<&str>,
+    error: Option<&ApiError>,
+) -> Result<Response, ApiError> {
+    let site = get_site_from_cache_or_repo(context, site_id).await?;
+    Ok((
+        error.map_or(StatusCode::UNAUTHORIZED, |e| e.status),
+        [(header::CACHE_CONTROL, "no-store")],
+        Html(render_login_page(
+            &site.meta.title,
+            redirect,
+            preview,
+            error.map(|e| e.message.as_str()),
+        )),
+    )
+        .into_response())
+}
+
+// Visitor login to a password protected route. The route is taken from the page the visitor
+// returns to, and unlocking it is added to routes unlocked before. Protected preview links are
+// unlocked with a preview cookie instead
+pub async fn site_login(
+    State(context): State<ApiContext>,
+    Host(hostname): Host,
+    headers: HeaderMap,
+    peer: PeerAddr,
+    Form(dto): Form<SiteLoginDto>,
+) -> Result<Response, ApiError> {
+    check_bad_form(dto.validate())?;
+    let ip = visitor_ip(&context, &headers, peer_ip(peer));
+    let domain = domain_without_port(hostname);
+    let site_id = get_site_id_by_domain_from_cache_or_repo(&context, domain.clone()).await?;
+    let redirect = safe_redirect_path(dto.redirect.as_deref());
+    let secure = base_url_from_domain(&domain).starts_with("https://");
+    let see_other = [(header::LOCATION, redirect.clone())];
+
+    if let Some(preview) = dto.preview.as_deref() {
+        return match unlock_preview(&context, &site_id, preview, ip, dto.password).await {
+            Ok(token) => Ok((
+                StatusCode::SEE_OTHER,
+                [(header::SET_COOKIE, preview_cookie(&token, secure))],
+                see_other,
+            )
+                .into_response()),
+            Err(e) if [ApiErrorCode::InvalidAuth, ApiErrorCode::LoginLocked].contains(&e.code) => {
+                login_page_response(&context, &site_id, &redirect, Some(preview), Some(&e)).await
+            }
+            Err(e) => Err(e),
+        };
+    }
+
+    let path = redirect.split(['?', '#']).next().unwrap_or("/");
+    let route = page_route(&context, &site_id, path).await?;
+    let protections = get_protections_from_cache_or_repo(&context, &site_id).await?;
+    let Some(protected) = protections.find(&route) else {
+        return Ok((StatusCode::SEE_OTHER, see_other).into_response());
+    };
+    let hash = protected.password_hash.clone();
+    let error = match verify_visitor_password(&context, &site_id, ip, dto.password, hash).await {
+        Ok(true) => None,
+        Ok(false) => Some(ApiError::unauthorized().message("Incorrect password")),
+        Err(e) => Some(e),
+    };
+    if let Some(e) = error {
+        return login_page_response(&context, &site_id, &redirect, None, Some(&e)).await;
+    }
+
+    let mut grants = site_access_grants(&context, &site_id, &headers);
+    grants.retain(|grant| grant.id != protected.id);
+    grants.push(protected.grant());
+    let secret = context.config.site_access_secret();
+    let token = generate_site_access_token(&site_id, grants, ACCESS_TTL_MINUTES, secret)?;
+
+    Ok((
+        StatusCode::SEE_OTHER,
+        [(header::SET_COOKIE, access_cookie(&token, secure))],
+        see_other,
+    )
+        .into_response())
+}
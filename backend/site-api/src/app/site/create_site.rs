@@ -2,43 +2,112 @@ use axum::http::StatusCode;
 use axum::{extract::State, Json};
 use lib_shared_site_api::error::api_error::ApiError;
 use lib_shared_site_api::error::helpers::{
-    check_bad_form, domain_strings, validate_custom_domains,
+    check_bad_form, domain_strings, validate_custom_domains, validate_domain_kinds,
 };
 use lib_shared_site_api::util::json_extractor::PsJson;
 use lib_shared_site_api::validator::site_data_len_validator::SiteDataValidator;
 use lib_shared_types::dto::site_api::create_metadata_dto::CreateSiteMetadataDto;
 use lib_shared_types::dto::site_api::create_site_dto::{CreateSiteDto, CreateSiteResponse};
+use lib_shared_types::entity::site_api::site_entity::SiteEntity;
+use lib_shared_types::error::api_error::ApiErrorCode;
+use lib_shared_types::shared::site::DomainKind;
+use serde_json::Value;
 use validator::Validate;
 
 use crate::api_context::ApiContext;
+use crate::app::custom::create_table::create_custom_table_helper;
+use crate::app::domain_verification::helpers::sync_domain_verifications;
 
 fn to_api_response(site_id: String) -> Json<CreateSiteResponse> {
     return Json(CreateSiteResponse { id: site_id });
 }
 
+fn parse_template_field(value: &str) -> Result<Value, ApiError> {
+    serde_json::from_str(value).map_err(|e| ApiError::internal_error().message(e))
+}
+
+// Create the site database from a template snapshot, including its custom table schemas
+async fn create_site_from_template(
+    context: &ApiContext,
+    dto: &CreateSiteDto,
+    template_id: &str,
+) -> Result<SiteEntity, ApiError> {
+    let template = context
+        .template_repo
+        .get_template(template_id)
+        .await
+        .map_err(|_| ApiError::not_found().message("Template not found"))?;
+
+    let validator = SiteDataValidator::new(dto.site_type);
+    validator.validate_context(&parse_template_field(&template.context)?)?;
+    validator.validate_history(&parse_template_field(&template.history)?)?;
+    validator.validate_pages(&parse_template_field(&template.pages)?)?;
+
+    context
+        .site_repo
+        .create_db(&dto.id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    let result = async {
+        context
+            .site_repo
+            .insert_site_version(&dto.id, &template.to_site_version(&dto.name, dto.published))
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?;
+        for table in &template.custom_tables {
+            create_custom_table_helper(context, &dto.id, table.clone()).await?;
+        }
+        context
+            .site_repo
+            .get_site_latest_version(&dto.id, false)
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))
+    }
+    .await;
+
+    if result.is_err() {
+        if let Err(e) = context.site_repo.delete_site(&dto.id).await {
+            tracing::warn!("Failed to remove partial site {}: {}", dto.id, e);
+        }
+    }
+    result
+}
+
 pub async fn create_site_helper(
     context: &ApiContext,
     dto: CreateSiteDto,
 ) -> Result<String, ApiError> {
-    let site_type = dto.site_type.clone();
+    let site_type = dto.site_type;
 
-    let validator = SiteDataValidator::new(site_type);
-    validator.validate_context(&dto.context)?;
-    validator.validate_history(&dto.history)?;
-    validator.validate_pages(&dto.pages)?;
+    if dto.template_id.is_none() {
+        if !dto.has_content() {
+            return Err(ApiError::bad_request()
+                .code(ApiErrorCode::InvalidFormData)
+                .message("Site content or template_id is required"));
+        }
+        let validator = SiteDataValidator::new(site_type);
+        validator.validate_context(&dto.context)?;
+        validator.validate_history(&dto.history)?;
+        validator.validate_pages(&dto.pages)?;
+    }
 
     let domains = &dto.domains.clone();
+    validate_domain_kinds(domains)?;
     validate_custom_domains(&domain_strings(domains))?;
 
     let site_id = dto.id.clone();
     let metadata_dto: CreateSiteMetadataDto = (&dto).into();
 
     // Create and run migrations on a new site database
-    let site = context
-        .site_repo
-        .create_site(dto)
-        .await
-        .map_err(|e| ApiError::internal_error().message(e))?;
+    let site = match dto.template_id.clone() {
+        Some(template_id) => create_site_from_template(context, &dto, &template_id).await?,
+        None => context
+            .site_repo
+            .create_site(dto)
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?,
+    };
 
     // Add site metadata to sites_metadata database
     let _ = context
@@ -46,9 +115,10 @@ pub async fn create_site_helper(
         .create_site(metadata_dto)
         .await
         .map_err(|e| ApiError::internal_error().message(e))?;
+    sync_domain_verifications(context).await;
 
-    // Add an in-memory cache to the app
-    for domain in domains {
+    // Add an in-memory cache to the app. Wildcard domains are cached by pattern when requested
+    for domain in domains.iter().filter(|d| d.kind != DomainKind::Wildcard) {
         context
             .cache
             .insert_domain_mapping(&site_id, &domain.domain)
@@ -2,16 +2,18 @@ use axum::http::StatusCode;
 use axum::{extract::State, Json};
 use lib_shared_site_api::error::api_error::ApiError;
 use lib_shared_site_api::error::helpers::{
-    check_bad_form, domain_strings, validate_custom_domains,
+    check_bad_form, domain_strings, validate_custom_domains, validate_domain_kinds,
 };
 use lib_shared_site_api::util::json_extractor::PsJson;
 use lib_shared_types::dto::site_api::create_metadata_dto::CreateSiteMetadataDto;
 use lib_shared_types::dto::site_api::create_site_dto::CreateSiteResponse;
 use lib_shared_types::dto::site_api::create_site_from_backup_dto::CreateSiteFromBackupDto;
+use lib_shared_types::shared::site::DomainKind;
 use validator::Validate;
 
 use crate::api_context::ApiContext;
 use crate::app::backup::helpers::get_backup_from_r2;
+use crate::app::domain_verification::helpers::sync_domain_verifications;
 
 fn to_api_response(site_id: String) -> Json<CreateSiteResponse> {
     return Json(CreateSiteResponse { id: site_id });
@@ -24,6 +26,7 @@ async fn create_site_from_backup_helper(
     let site_type = dto.site_type.clone();
 
     let domains = &dto.domains.clone();
+    validate_domain_kinds(domains)?;
     validate_custom_domains(&domain_strings(domains))?;
 
     let site_id = dto.site_id.clone();
@@ -45,6 +48,7 @@ async fn create_site_from_backup_helper(
         .create_site(metadata_dto)
         .await
         .map_err(|e| ApiError::internal_error().message(e))?;
+    sync_domain_verifications(context).await;
 
     let site = context
         .site_repo
@@ -52,8 +56,8 @@ async fn create_site_from_backup_helper(
         .await
         .map_err(|e| ApiError::not_found().message(e))?;
 
-    // Add an in-memory cache to the app
-    for domain in domains {
+    // Add an in-memory cache to the app. Wildcard domains are cached by pattern when requested
+    for domain in domains.iter().filter(|d| d.kind != DomainKind::Wildcard) {
         context
             .cache
             .insert_domain_mapping(&site_id, &domain.domain)
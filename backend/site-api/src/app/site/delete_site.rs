@@ -34,6 +34,10 @@ pub async fn delete_site(
     // Remove cache
     context.cache.remove_domain_mapping(&id).await;
     context.cache.remove_metadata(&id).await;
+    context.cache.remove_redirects(&id).await;
+    context.cache.remove_protections(&id).await;
+    context.cache.remove_maintenance(&id).await;
+    context.cache.remove_experiments(&id).await;
 
     Ok(())
 }
@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::Utc;
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::{
+        custom_data::list_tables_query::ListTablesQuery,
+        site_api::{
+            create_metadata_dto::CreateSiteMetadataDto, create_site_dto::CreateSiteResponse,
+            duplicate_site_dto::DuplicateSiteDto, site_archive_dto::SiteArchiveVersion,
+        },
+    },
+    error::api_error::ApiErrorCode,
+    shared::user::{RequestUser, UserType},
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, app::custom::create_table::create_custom_table_helper,
+    middleware::auth::verify_site_owner,
+};
+
+async fn copy_custom_table_schemas(
+    context: &ApiContext,
+    from_id: &str,
+    to_id: &String,
+) -> Result<(), ApiError> {
+    let all_tables = ListTablesQuery {
+        from: 1,
+        to: i32::MAX,
+    };
+    let tables = context
+        .custom_data_info_repo
+        .list_tables(from_id, all_tables)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    for table in tables.results {
+        let data = json!({
+            "table_name": table.name,
+            "columns": table.columns,
+            "events": table.events,
+        });
+        create_custom_table_helper(context, to_id, data).await?;
+    }
+    Ok(())
+}
+
+async fn copy_site_content(
+    context: &ApiContext,
+    from_id: &str,
+    to_id: &String,
+    dto: &DuplicateSiteDto,
+) -> Result<u64, ApiError> {
+    let site = context
+        .site_repo
+        .get_site_latest_version(from_id, false)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+
+    // The copy starts as a fresh, unpublished site without a preview link
+    let mut version = SiteArchiveVersion::from(&site);
+    if let Some(name) = &dto.name {
+        version.name = name.clone();
+    }
+    version.published = false;
+    version.content_updated_at = Utc::now().timestamp_millis();
+
+    context
+        .site_repo
+        .insert_site_version(to_id, &version)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    if dto.copy_custom_tables {
+        copy_custom_table_schemas(context, from_id, to_id).await?;
+    }
+    Ok(site.calculate_site_size())
+}
+
+// Remove the database of a copy that could not be completed
+async fn remove_partial_copy(context: &ApiContext, site_id: &str) {
+    if let Err(e) = context.site_repo.delete_site(site_id).await {
+        tracing::warn!("Failed to remove partial copy {}: {}", site_id, e);
+    }
+}
+
+pub async fn duplicate_site(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<DuplicateSiteDto>,
+) -> Result<(StatusCode, Json<CreateSiteResponse>), ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    // Owners may only duplicate sites into their own account
+    if user.user_type == UserType::Owner
+        && user.user_id.map(|id| id.to_string()) != Some(dto.owner_id.clone())
+    {
+        return Err(ApiError::forbidden());
+    }
+
+    let source = context
+        .metadata_repo
+        .get_site_metadata(&site_id)
+        .await
+        .map_err(|_| ApiError::not_found())?;
+    // Only admins may change the site type, owners keep the type of the source site
+    let site_type = match dto.site_type {
+        Some(site_type) if site_type != source.site_type && user.user_type != UserType::Admin => {
+            return Err(ApiError::forbidden().message("Only admins can change the site type"));
+        }
+        Some(site_type) => site_type,
+        None => source.site_type,
+    };
+    let owner_email = match dto.owner_email.clone() {
+        Some(email) if email != source.owner_email && user.user_type != UserType::Admin => {
+            return Err(ApiError::forbidden().message("Only admins can change the owner email"));
+        }
+        Some(email) => email,
+        None => source.owner_email,
+    };
+
+    let site_count = context
+        .metadata_repo
+        .count_owner_sites(&dto.owner_id, site_type)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+    if site_count >= site_type.get_capacity() as i64 {
+        return Err(ApiError::bad_request()
+            .code(ApiErrorCode::SiteCapacityExceeded)
+            .message("Site capacity reached for this site type"));
+    }
+
+    let new_site_id = Uuid::new_v4().to_string();
+
+    // Create and run migrations on a new site database
+    context
+        .site_repo
+        .create_db(&new_site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    let size = match copy_site_content(&context, &site_id, &new_site_id, &dto).await {
+        Ok(size) => size,
+        Err(e) => {
+            remove_partial_copy(&context, &new_site_id).await;
+            return Err(e);
+        }
+    };
+
+    // Add site metadata to sites_metadata database, without the source domains
+    let metadata_dto = CreateSiteMetadataDto {
+        site_id: new_site_id.clone(),
+        owner_id: dto.owner_id,
+        owner_email,
+        domains: vec![],
+        site_type,
+    };
+    if let Err(e) = context.metadata_repo.create_site(metadata_dto).await {
+        remove_partial_copy(&context, &new_site_id).await;
+        return Err(ApiError::internal_error().message(e));
+    }
+
+    context
+        .cache
+        .create_or_update_usage(&new_site_id, size, site_type)
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateSiteResponse { id: new_site_id }),
+    ))
+}
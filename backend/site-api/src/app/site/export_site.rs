@@ -0,0 +1,228 @@
+use std::io;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::Utc;
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::site_archive::{SiteArchiveWriter, SITE_ARCHIVE_ROWS_PER_FILE},
+};
+use lib_shared_types::{
+    dto::{
+        custom_data::{list_rows_query::ListRowsQuery, list_tables_query::ListTablesQuery},
+        query_dto::ListQuery,
+        site_api::site_archive_dto::{
+            SiteArchiveManifest, SiteArchiveTable, SiteArchiveVersion, SITE_ARCHIVE_FORMAT_VERSION,
+        },
+    },
+    shared::user::RequestUser,
+};
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+type BodySender = mpsc::Sender<Result<Bytes, io::Error>>;
+
+// Forward the archive entries written so far to the response body
+async fn send_written(
+    writer: &mut SiteArchiveWriter<Vec<u8>>,
+    sender: &BodySender,
+) -> Result<(), ApiError> {
+    let data = writer.take_written();
+    if data.is_empty() {
+        return Ok(());
+    }
+    sender
+        .send(Ok(Bytes::from(data)))
+        .await
+        .map_err(|_| ApiError::internal_error().message("Export download closed"))
+}
+
+async fn export_site_versions(
+    context: &ApiContext,
+    site_id: &str,
+    latest_id: i64,
+    writer: &mut SiteArchiveWriter<Vec<u8>>,
+    sender: &BodySender,
+) -> Result<(), ApiError> {
+    let published = context
+        .site_repo
+        .get_site_latest_version(site_id, true)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+    if published.published && published.id != latest_id {
+        writer.append_version(&SiteArchiveVersion::from(&published))?;
+        send_written(writer, sender).await?;
+    }
+    drop(published);
+
+    let latest = context
+        .site_repo
+        .get_site_latest_version(site_id, false)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+    writer.append_version(&SiteArchiveVersion::from(&latest))?;
+    send_written(writer, sender).await
+}
+
+async fn export_custom_tables(
+    context: &ApiContext,
+    site_id: &str,
+    writer: &mut SiteArchiveWriter<Vec<u8>>,
+    sender: &BodySender,
+) -> Result<(), ApiError> {
+    let all_tables = ListTablesQuery {
+        from: 1,
+        to: i32::MAX,
+    };
+    let tables = context
+        .custom_data_info_repo
+        .list_tables(site_id, all_tables)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    for table in tables.results {
+        writer.append_table(&SiteArchiveTable {
+            name: table.name.clone(),
+            columns: table.columns,
+            events: table.events,
+            rows: vec![],
+        })?;
+        send_written(writer, sender).await?;
+
+        // Rows are read one file at a time
+        let mut from = 1;
+        loop {
+            let page = ListRowsQuery {
+                table_name: table.name.clone(),
+                from,
+                to: from + SITE_ARCHIVE_ROWS_PER_FILE as i32 - 1,
+            };
+            let rows = context
+                .custom_data_repo
+                .list_rows(site_id, page)
+                .await
+                .map_err(|e| ApiError::internal_error().message(e))?;
+            if rows.results.is_empty() {
+                break;
+            }
+            writer.append_rows(&rows.results)?;
+            send_written(writer, sender).await?;
+            if rows.results.len() < SITE_ARCHIVE_ROWS_PER_FILE {
+                break;
+            }
+            from += SITE_ARCHIVE_ROWS_PER_FILE as i32;
+        }
+    }
+    Ok(())
+}
+
+async fn export_static_pages(
+    context: &ApiContext,
+    site_id: &str,
+    writer: &mut SiteArchiveWriter<Vec<u8>>,
+    sender: &BodySender,
+) -> Result<(), ApiError> {
+    let routes = context
+        .site_repo
+        .list_static_page_routes(site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    for route in routes {
+        let page = match context.site_repo.get_static_page(site_id, &route).await {
+            Ok(page) => page,
+            // Removed by a static build during the export
+            Err(DbError::EntityNotFound()) => continue,
+            Err(e) => return Err(ApiError::internal_error().message(e)),
+        };
+        writer.append_static_page(&page.into())?;
+        send_written(writer, sender).await?;
+    }
+    Ok(())
+}
+
+// Write the archive entry by entry, reading each version, rows chunk and static page from the
+// site database just before it is sent
+async fn stream_site_archive(
+    context: &ApiContext,
+    site_id: &str,
+    manifest: SiteArchiveManifest,
+    latest_id: i64,
+    sender: &BodySender,
+) -> Result<(), ApiError> {
+    let mut writer = SiteArchiveWriter::new(Vec::new(), &manifest)?;
+    send_written(&mut writer, sender).await?;
+
+    export_site_versions(context, site_id, latest_id, &mut writer, sender).await?;
+    export_custom_tables(context, site_id, &mut writer, sender).await?;
+    export_static_pages(context, site_id, &mut writer, sender).await?;
+
+    let data = writer.finish()?;
+    sender
+        .send(Ok(Bytes::from(data)))
+        .await
+        .map_err(|_| ApiError::internal_error().message("Export download closed"))
+}
+
+pub async fn export_site(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+) -> Result<Response, ApiError> {
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    // Fail before the response starts when the site cannot be exported
+    let latest = context
+        .site_repo
+        .list_site_versions(&site_id, ListQuery { from: 1, to: 1 })
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?
+        .pop()
+        .ok_or(ApiError::not_found().message("Site has no versions"))?;
+    let schema_version = context
+        .site_repo
+        .get_schema_version(&site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+    let manifest = SiteArchiveManifest {
+        format_version: SITE_ARCHIVE_FORMAT_VERSION,
+        schema_version,
+        site_id: site_id.clone(),
+        name: latest.name,
+        exported_at: Utc::now(),
+    };
+
+    // The tar is streamed as it is written, instead of being built in memory
+    let (sender, receiver) = mpsc::channel(4);
+    let export_site_id = site_id.clone();
+    tokio::spawn(async move {
+        let result =
+            stream_site_archive(&context, &export_site_id, manifest, latest.id, &sender).await;
+        // Abort the download, so a truncated archive is not mistaken for a complete one
+        if let Err(e) = result {
+            let _ = sender.send(Err(io::Error::other(e.message))).await;
+        }
+    });
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"site_{}.tar\"", site_id),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response())
+}
@@ -4,7 +4,10 @@ use axum::{
     Json,
 };
 use axum_extra::extract::Host;
-use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::domains::{domain_without_port, match_host, origin_domain, HostMatch},
+};
 use lib_shared_types::dto::site_api::get_current_site_dto::{
     GetCurrentSiteQuery, GetCurrentSiteResponse,
 };
@@ -12,6 +15,14 @@ use lib_shared_types::error::api_error::ApiErrorCode;
 
 use crate::{
     api_context::ApiContext,
+    app::{
+        experiment::helpers::{apply_variants, VisitorExperiments},
+        maintenance::helpers::{
+            blocking_maintenance, maintenance_error, peer_ip, visitor_ip, PeerAddr,
+        },
+        protected_route::helpers::filter_protected_pages,
+        usage::helpers::record_unique_visitor,
+    },
     db::db_cache_layer::{
         get_metadata_from_cache_or_repo, get_site_id_by_host_or_origin, get_site_or_preview,
     },
@@ -22,8 +33,10 @@ pub async fn get_current_site(
     Host(hostname): Host,
     headers: HeaderMap,
     Query(query): Query<GetCurrentSiteQuery>,
-) -> Result<Json<GetCurrentSiteResponse>, ApiError> {
-    let site_id = get_site_id_by_host_or_origin(&context, hostname, &headers).await?;
+    peer: PeerAddr,
+) -> Result<(HeaderMap, Json<GetCurrentSiteResponse>), ApiError> {
+    let peer = peer_ip(peer);
+    let site_id = get_site_id_by_host_or_origin(&context, hostname.clone(), &headers).await?;
 
     // Get metadata from cache
     let metadata = get_metadata_from_cache_or_repo(&context, &site_id).await?;
@@ -32,8 +45,39 @@ pub async fn get_current_site(
     if metadata.disabled {
         return Err(ApiError::forbidden().code(ApiErrorCode::SiteDisabled));
     }
+    if query.p.is_none()
+        && blocking_maintenance(&context, &site_id, &headers, peer)
+            .await?
+            .is_some()
+    {
+        return Err(maintenance_error());
+    }
 
-    let site = get_site_or_preview(&context, &site_id, query.p).await?.site;
+    let mut site = get_site_or_preview(&context, &site_id, query.p.clone(), &headers)
+        .await?
+        .site;
+    let mut response_headers = HeaderMap::new();
+    // Previews are shared by the owner, and include protected pages and no experiments
+    if query.p.is_none() {
+        site = filter_protected_pages(&context, site, &headers).await?;
+
+        // Exposures are counted by page views, when the visitor sees an experiment route
+        let mut visitor = VisitorExperiments::load(&context, &site_id, &headers).await?;
+        let variants = visitor.variants();
+        if !variants.is_empty() {
+            site = apply_variants(site, &variants)?;
+            response_headers = visitor.response_headers(&context, &site_id, &hostname)?;
+        }
+    }
+
+    // Pages of wildcard domains read the subdomain they are served on
+    site.subdomain = [Some(domain_without_port(hostname)), origin_domain(&headers)]
+        .into_iter()
+        .flatten()
+        .find_map(|host| match match_host(&metadata.domains, &host) {
+            HostMatch::Wildcard(subdomain) => Some(subdomain),
+            _ => None,
+        });
 
     // Check if the bandwidth usage exceeds the allowed limit
     let site_size = site.calculate_site_size();
@@ -46,6 +90,21 @@ pub async fn get_current_site(
         .cache
         .increase_view_count(&site_id, site_size, metadata.site_type)
         .await;
+    if query.p.is_none() {
+        let visitor = visitor_ip(&context, &headers, peer);
+        record_unique_visitor(&context, &site_id, &headers, visitor).await;
+    }
+
+    if let Some(preview_id) = query.p {
+        // No-op for legacy preview IDs, which are not stored as preview links
+        if let Err(e) = context
+            .preview_link_repo
+            .increment_view_count(&site_id, &preview_id)
+            .await
+        {
+            tracing::warn!("Failed to count preview link view: {:?}", e);
+        }
+    }
 
-    Ok(Json(site))
+    Ok((response_headers, Json(site)))
 }
@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{Duration, Utc};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::analytics::{fill_hours, hour_start},
+};
+use lib_shared_types::{
+    dto::site_api::site_analytics_dto::{
+        GetSiteAnalyticsQuery, HourlyViewsViewModel, SiteAnalyticsViewModel,
+    },
+    entity::site_api::site_usage_hourly_entity::AnalyticsDimension,
+    error::api_error::ApiErrorCode,
+    shared::{js_date::JsDate, user::RequestUser},
+};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext, db::usage_repo::HourlyUsageFilter, middleware::auth::verify_site_owner,
+};
+
+const DEFAULT_RANGE_HOURS: i64 = 24;
+// Longest range of a query, about three months
+const MAX_RANGE_DAYS: i64 = 92;
+const BREAKDOWN_LIMIT: u32 = 50;
+
+// Hourly page views of a site. Views are persisted every 10 minutes, so the latest views
+// may not be included yet
+pub async fn get_site_analytics(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    Query(query): Query<GetSiteAnalyticsQuery>,
+) -> Result<Json<SiteAnalyticsViewModel>, ApiError> {
+    check_bad_form(query.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let end = query.end.map_or_else(Utc::now, |end| end.timestamp);
+    let start = hour_start(
+        query
+            .start
+            .map_or(end - Duration::hours(DEFAULT_RANGE_HOURS), |start| {
+                start.timestamp
+            }),
+    );
+    if start >= end || end - start > Duration::days(MAX_RANGE_DAYS) {
+        return Err(ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(format!(
+                "Start must be before end, and at most {} days apart",
+                MAX_RANGE_DAYS
+            )));
+    }
+
+    let dimensions = [
+        (AnalyticsDimension::Route, query.route),
+        (AnalyticsDimension::Referrer, query.referrer),
+        (AnalyticsDimension::UtmSource, query.utm_source),
+        (AnalyticsDimension::UtmMedium, query.utm_medium),
+        (AnalyticsDimension::UtmCampaign, query.utm_campaign),
+        (
+            AnalyticsDimension::Device,
+            query.device.map(|d| d.to_string()),
+        ),
+        (
+            AnalyticsDimension::Country,
+            query.country.map(|c| c.to_ascii_uppercase()),
+        ),
+    ];
+    let filter = HourlyUsageFilter {
+        start,
+        end,
+        dimensions: dimensions
+            .into_iter()
+            .filter_map(|(dimension, value)| Some((dimension, value?)))
+            .collect(),
+    };
+
+    let views = context
+        .usage_repo
+        .list_hourly_views(&site_id, &filter)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+    let breakdown = match query.group_by {
+        Some(dimension) => context
+            .usage_repo
+            .list_dimension_views(&site_id, &filter, dimension, BREAKDOWN_LIMIT)
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?,
+        None => vec![],
+    };
+
+    let views: Vec<_> = views.into_iter().map(|v| (v.hour, v.views)).collect();
+    Ok(Json(SiteAnalyticsViewModel {
+        start: JsDate { timestamp: start },
+        end: JsDate { timestamp: end },
+        total_views: views.iter().map(|(_, views)| views).sum(),
+        hours: fill_hours(start, end, &views)
+            .into_iter()
+            .map(|(hour, views)| HourlyViewsViewModel {
+                hour: JsDate { timestamp: hour },
+                views,
+            })
+            .collect(),
+        breakdown: breakdown.into_iter().map(Into::into).collect(),
+    }))
+}
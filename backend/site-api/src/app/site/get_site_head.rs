@@ -1,22 +1,34 @@
 use axum::{
     extract::{Path, Query, State},
+    http::HeaderMap,
     Json,
 };
 use axum_macros::debug_handler;
-use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_site_api::{error::api_error::ApiError, util::site_access::PageAccess};
 use lib_shared_types::{
-    cache::site_data::CachedSiteHead, dto::site_api::get_current_site_dto::GetCurrentSiteQuery,
+    cache::site_data::CachedPageHead, dto::site_api::get_current_site_dto::GetSiteHeadQuery,
 };
 
-use crate::{api_context::ApiContext, db::db_cache_layer::get_site_or_preview};
+use crate::{
+    api_context::ApiContext,
+    app::protected_route::helpers::{page_access, password_required},
+    db::db_cache_layer::get_site_or_preview,
+};
 
 #[debug_handler]
 pub async fn get_site_head(
     Path(site_id): Path<String>,
     State(context): State<ApiContext>,
-    Query(query): Query<GetCurrentSiteQuery>,
-) -> Result<Json<CachedSiteHead>, ApiError> {
-    let cached_site = get_site_or_preview(&context, &site_id, query.p).await?;
+    Query(query): Query<GetSiteHeadQuery>,
+    headers: HeaderMap,
+) -> Result<Json<CachedPageHead>, ApiError> {
+    let route = query.route.as_deref().unwrap_or("/");
+    if query.p.is_none()
+        && page_access(&context, &site_id, route, &headers).await? == PageAccess::Locked
+    {
+        return Err(password_required());
+    }
+    let cached_site = get_site_or_preview(&context, &site_id, query.p, &headers).await?;
 
-    Ok(Json(cached_site.meta))
+    Ok(Json(cached_site.meta.page_head(route)))
 }
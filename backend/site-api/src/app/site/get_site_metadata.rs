@@ -27,5 +27,6 @@ pub async fn get_site_metadata(
         disabled: meta.disabled,
         site_type: meta.site_type,
         custom_domains: vec_from_entity(meta.domains),
+        www_policy: meta.www_policy,
     }))
 }
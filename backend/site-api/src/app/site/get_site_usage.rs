@@ -6,12 +6,15 @@ use axum_macros::debug_handler;
 use lib_shared_site_api::error::api_error::ApiError;
 use lib_shared_types::{
     dto::site_api::site_usage_viewmodel::{
-        from_usage_data, PublicSiteUsageViewModel, SiteUsageViewModel,
+        from_usage_data, to_public_usage, PublicSiteUsageViewModel, SiteUsageViewModel,
     },
     shared::user::RequestUser,
 };
 
-use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+use crate::{
+    api_context::ApiContext, app::usage::helpers::unique_visitor_counts,
+    middleware::auth::verify_site_owner,
+};
 
 #[debug_handler]
 pub async fn get_site_usage(
@@ -27,6 +30,7 @@ pub async fn get_site_usage(
         .get_site_metadata(&site_id)
         .await
         .map_err(|_| ApiError::not_found())?;
+    let unique_visitors = unique_visitor_counts(&context, &site_id).await?;
 
     return Ok(Json(from_usage_data(
         usage,
@@ -35,6 +39,7 @@ pub async fn get_site_usage(
         site_metadata
             .site_type
             .get_custom_data_allowance(context.config.exec_env),
+        unique_visitors,
     )));
 }
 
@@ -44,6 +49,7 @@ pub async fn get_public_site_usage(
     State(context): State<ApiContext>,
 ) -> Result<Json<PublicSiteUsageViewModel>, ApiError> {
     let usage = context.cache.get_usage(&site_id).await?;
+    let unique_visitors = unique_visitor_counts(&context, &site_id).await?;
 
-    return Ok(Json(usage.into()));
+    return Ok(Json(to_public_usage(usage, unique_visitors)));
 }
@@ -1,26 +1,83 @@
 use axum::{
     extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use lib_shared_site_api::error::api_error::ApiError;
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::{compression::ContentEncoding, site_access::PageAccess},
+};
 use lib_shared_types::dto::site_api::ssg_dto::{GetStaticPageQuery, StaticPageViewModel};
 
-use crate::{api_context::ApiContext, app::ssg::static_serve::serve_static_page};
+use crate::{
+    api_context::ApiContext,
+    app::{
+        maintenance::helpers::{
+            blocking_maintenance, forwarded_visitor_ip, maintenance_error, peer_ip, PeerAddr,
+        },
+        protected_route::helpers::{page_access, password_required, PROTECTED_CACHE_CONTROL},
+        ssg::static_serve::{serve_seo_file, serve_static_page},
+    },
+};
 
 // Returns the SSG page served for a request path (unknown routes fall back to the site's
 // /not-found page, signaled by `route`), or 404 when the site has no fresh static pages.
-// Usage tracking is applied here since the page is served to a visitor by the caller.
+// Usage tracking is applied here since the page is served to a visitor by the caller. Unique
+// visitors are only counted when the caller is in TRUSTED_PROXIES and forwards the visitor
+// address in X-Forwarded-For.
+// The sitemap and robots.txt generated by site-api take priority over SSG output.
+// The visitor's conditional headers are forwarded by the caller, a match responds with 304.
+// The JSON body carries the uncompressed page, the caller compresses its own response.
+// Protected pages require the visitor's access cookie, forwarded by the caller.
+// During maintenance the caller gets 503 SiteMaintenance, and serves its holding page.
 pub async fn get_static_page(
     Path(site_id): Path<String>,
     Query(query): Query<GetStaticPageQuery>,
     State(context): State<ApiContext>,
-) -> Result<Json<StaticPageViewModel>, ApiError> {
-    let served = serve_static_page(&context, &site_id, &query.path)
+    headers: HeaderMap,
+    peer: PeerAddr,
+) -> Result<Response, ApiError> {
+    let peer = peer_ip(peer);
+    if blocking_maintenance(&context, &site_id, &headers, peer)
         .await?
-        .ok_or_else(|| ApiError::not_found().message("No static page for route"))?;
-    Ok(Json(StaticPageViewModel {
-        route: served.page.route,
-        body: served.page.body,
-        content_type: served.page.content_type,
-    }))
+        .is_some()
+    {
+        return Err(maintenance_error());
+    }
+    if let Some(file) = serve_seo_file(&context, &site_id, &query.path).await? {
+        return Ok(Json(file).into_response());
+    }
+    let access = page_access(&context, &site_id, &query.path, &headers).await?;
+    if access == PageAccess::Locked {
+        return Err(password_required());
+    }
+    let served = serve_static_page(
+        &context,
+        &site_id,
+        &query.path,
+        &headers,
+        forwarded_visitor_ip(&context, &headers, peer),
+        ContentEncoding::Identity,
+        None,
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found().message("No static page for route"))?;
+    let cache_control = match access {
+        PageAccess::Unlocked => PROTECTED_CACHE_CONTROL,
+        _ => &served.cache_control,
+    };
+    let cache_headers = served.validators.headers(cache_control);
+    if served.not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+    Ok((
+        cache_headers,
+        Json(StaticPageViewModel {
+            route: served.page.route,
+            body: served.page.body,
+            content_type: served.page.content_type,
+        }),
+    )
+        .into_response())
 }
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::site_archive::read_site_archive,
+    validator::site_data_len_validator::SiteDataValidator,
+};
+use lib_shared_types::{
+    dto::{
+        custom_data::{add_row_dto::AddRow, create_table_dto::ColumnInfo},
+        site_api::{
+            create_metadata_dto::CreateSiteMetadataDto,
+            create_site_dto::CreateSiteResponse,
+            site_archive_dto::{ImportSiteQuery, SiteArchive},
+        },
+    },
+    error::api_error::ApiErrorCode,
+};
+use serde_json::{json, Value};
+use validator::Validate;
+
+use crate::{
+    api_context::ApiContext,
+    app::custom::{
+        create_table::create_custom_table_helper,
+        helpers::{validate_column_names, validate_table_name},
+    },
+    db::site_repo::site_schema_version,
+};
+
+// Upper bound on the request body accepted by the import endpoint
+pub const MAX_SITE_ARCHIVE_SIZE: usize = 50 * 1024 * 1024;
+
+fn invalid_archive<T: std::fmt::Display>(message: T) -> ApiError {
+    ApiError::bad_request()
+        .code(ApiErrorCode::InvalidSiteArchive)
+        .message(message)
+}
+
+fn parse_site_field(field: &str, value: &str) -> Result<Value, ApiError> {
+    serde_json::from_str(value).map_err(|e| invalid_archive(format!("Invalid {}: {}", field, e)))
+}
+
+// Check the archive content before anything is written
+fn validate_site_archive(archive: &SiteArchive, query: &ImportSiteQuery) -> Result<(), ApiError> {
+    if archive.manifest.schema_version > site_schema_version() {
+        return Err(invalid_archive(
+            "Archive was exported from a newer schema version",
+        ));
+    }
+
+    let validator = SiteDataValidator::new(query.site_type);
+    for version in archive.versions.iter() {
+        validator.validate_context(&parse_site_field("context", &version.context)?)?;
+        validator.validate_history(&parse_site_field("history", &version.history)?)?;
+        validator.validate_pages(&parse_site_field("pages", &version.pages)?)?;
+    }
+
+    for table in archive.custom_tables.iter() {
+        validate_table_name(&table.name)?;
+        let columns: HashMap<String, ColumnInfo> = serde_json::from_value(table.columns.clone())
+            .map_err(|e| invalid_archive(format!("Invalid columns for {}: {}", table.name, e)))?;
+        validate_column_names(columns.keys())?;
+
+        // Row keys are used as column names when inserting
+        let invalid_row = table.rows.iter().any(|row| {
+            row.keys()
+                .any(|key| key != "id" && !columns.contains_key(key))
+        });
+        if invalid_row {
+            return Err(invalid_archive(format!(
+                "Rows of {} do not match its columns",
+                table.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn import_site_content(
+    context: &ApiContext,
+    site_id: &String,
+    archive: SiteArchive,
+) -> Result<(), ApiError> {
+    for version in archive.versions.iter() {
+        context
+            .site_repo
+            .insert_site_version(site_id, version)
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?;
+    }
+
+    for table in archive.custom_tables {
+        let data = json!({
+            "table_name": table.name,
+            "columns": table.columns,
+            "events": table.events,
+        });
+        create_custom_table_helper(context, site_id, data).await?;
+
+        for row in table.rows {
+            let dto = AddRow {
+                table_name: table.name.clone(),
+                row: row.into_iter().collect(),
+            };
+            context
+                .custom_data_repo
+                .add_row(site_id, dto)
+                .await
+                .map_err(|e| invalid_archive(format!("Invalid row in {}: {}", table.name, e)))?;
+        }
+    }
+
+    let static_pages = archive.static_pages.into_iter().map(Into::into).collect();
+    context
+        .site_repo
+        .replace_static_pages(site_id, static_pages)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(())
+}
+
+// Create the site database with the archive content, then the site metadata
+async fn import_site_db(
+    context: &ApiContext,
+    query: &ImportSiteQuery,
+    archive: SiteArchive,
+) -> Result<(), ApiError> {
+    // Create and run migrations on a new site database
+    context
+        .site_repo
+        .create_db(&query.site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    import_site_content(context, &query.site_id, archive).await?;
+
+    // Add site metadata to sites_metadata database
+    let metadata_dto: CreateSiteMetadataDto = query.into();
+    context
+        .metadata_repo
+        .create_site(metadata_dto)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+    Ok(())
+}
+
+pub async fn import_site(
+    State(context): State<ApiContext>,
+    Query(query): Query<ImportSiteQuery>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<CreateSiteResponse>), ApiError> {
+    check_bad_form(query.validate())?;
+
+    let archive = read_site_archive(&body)?;
+    validate_site_archive(&archive, &query)?;
+
+    let site_id = query.site_id.clone();
+    if context
+        .metadata_repo
+        .get_site_metadata(&site_id)
+        .await
+        .is_ok()
+    {
+        return Err(ApiError::bad_request().message("Site already exists"));
+    }
+
+    // Remove the new site database if any step fails, so the site ID can be imported again
+    if let Err(e) = import_site_db(&context, &query, archive).await {
+        if let Err(delete_err) = context.site_repo.delete_site(&site_id).await {
+            tracing::warn!(
+                "Failed to remove partial import {}: {}",
+                site_id,
+                delete_err
+            );
+        }
+        return Err(e);
+    }
+
+    let site = context
+        .site_repo
+        .get_site_latest_version(&site_id, false)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+    let size = site.calculate_site_size();
+    context
+        .cache
+        .create_or_update_usage(&site_id, size, query.site_type)
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateSiteResponse { id: site_id }),
+    ))
+}
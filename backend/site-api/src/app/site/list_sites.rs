@@ -19,6 +19,7 @@ fn to_api_response(metadata: Vec<SiteMetadataEntity>) -> Json<Vec<SiteMetadataVi
                 disabled: meta.disabled,
                 site_type: meta.site_type,
                 custom_domains: vec_from_entity(meta.domains),
+                www_policy: meta.www_policy,
             })
             .collect(),
     )
@@ -0,0 +1,130 @@
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::{
+        site_document::{decode_site_document, encode_site_document, parse_site_document},
+        site_merge::{merge_document, merge_pages},
+    },
+};
+use lib_shared_types::{
+    dto::site_api::{
+        site_merge_conflict_viewmodel::SiteMergeConflict, update_site_dto::UpdateSiteDto,
+    },
+    entity::site_api::site_entity::SiteEntity,
+    error::api_error::ApiErrorCode,
+};
+use serde_json::Value;
+
+use crate::api_context::ApiContext;
+
+type MergeFn = fn(&Value, &Value, &Value) -> Result<Value, Vec<SiteMergeConflict>>;
+
+pub enum SiteMergeResult {
+    // The update can be written. The write must only succeed if the latest version's
+    // `content_updated_at` still matches `expected_content_updated_at`
+    Merged {
+        expected_content_updated_at: Option<i64>,
+    },
+    Conflicts(Vec<SiteMergeConflict>),
+}
+
+fn merge_field(
+    mine: Value,
+    base: &str,
+    theirs: &str,
+    merge: MergeFn,
+) -> Result<Result<Value, Vec<SiteMergeConflict>>, ApiError> {
+    let (mine, encoded) =
+        decode_site_document(mine).map_err(|e| ApiError::bad_request().message(e))?;
+    let (base, _) = parse_site_document(base).map_err(|e| ApiError::internal_error().message(e))?;
+    let (theirs, _) =
+        parse_site_document(theirs).map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(merge(&base, &mine, &theirs).map(|merged| encode_site_document(merged, encoded)))
+}
+
+// If the update was made from an older revision, merge `pages`, `context`, and `defaults` with
+// the latest version. The merged values replace the DTO fields
+pub async fn merge_site_update(
+    context: &ApiContext,
+    id: &str,
+    dto: &mut UpdateSiteDto,
+) -> Result<SiteMergeResult, ApiError> {
+    let Some(base_revision_id) = dto.base_revision_id else {
+        return Ok(SiteMergeResult::Merged {
+            expected_content_updated_at: None,
+        });
+    };
+
+    if dto.pages.is_none() && dto.context.is_none() && dto.defaults.is_none() {
+        return Ok(SiteMergeResult::Merged {
+            expected_content_updated_at: None,
+        });
+    }
+
+    let latest = context
+        .site_repo
+        .get_site_latest_version(id, false)
+        .await
+        .map_err(|e| match e {
+            DbError::NoDb(_) | DbError::EntityNotFound() => ApiError::not_found(),
+            _ => ApiError::internal_error().message(e),
+        })?;
+
+    if latest.revision_id != Some(base_revision_id) {
+        let conflicts = merge_with_latest(context, id, dto, base_revision_id, &latest).await?;
+        if !conflicts.is_empty() {
+            return Ok(SiteMergeResult::Conflicts(conflicts));
+        }
+    }
+
+    // Superseded by the `content_updated_at` check
+    dto.update_key = None;
+    Ok(SiteMergeResult::Merged {
+        expected_content_updated_at: Some(latest.content_updated_at),
+    })
+}
+
+// Merge the DTO fields with the latest version, from the base revision. Returns conflicts
+async fn merge_with_latest(
+    context: &ApiContext,
+    id: &str,
+    dto: &mut UpdateSiteDto,
+    base_revision_id: i64,
+    latest: &SiteEntity,
+) -> Result<Vec<SiteMergeConflict>, ApiError> {
+    let base = context
+        .site_repo
+        .get_site_revision(id, base_revision_id)
+        .await
+        .map_err(|e| match e {
+            DbError::EntityNotFound() => ApiError::bad_request()
+                .code(ApiErrorCode::UpdateStale)
+                .message("Base revision is no longer available"),
+            _ => ApiError::internal_error().message(e),
+        })?;
+
+    let mut conflicts = vec![];
+    if let Some(mine) = dto.pages.take() {
+        match merge_field(mine, &base.pages, &latest.pages, merge_pages)? {
+            Ok(merged) => dto.pages = Some(merged),
+            Err(mut c) => conflicts.append(&mut c),
+        }
+    }
+    if let Some(mine) = dto.context.take() {
+        let merge: MergeFn = |b, m, t| merge_document("context", b, m, t);
+        match merge_field(mine, &base.context, &latest.context, merge)? {
+            Ok(merged) => dto.context = Some(merged),
+            Err(mut c) => conflicts.append(&mut c),
+        }
+    }
+    if let Some(mine) = dto.defaults.take() {
+        let merge: MergeFn = |b, m, t| merge_document("defaults", b, m, t);
+        match merge_field(mine, &base.defaults, &latest.defaults, merge)? {
+            Ok(merged) => dto.defaults = Some(merged),
+            Err(mut c) => conflicts.append(&mut c),
+        }
+    }
+
+    Ok(conflicts)
+}
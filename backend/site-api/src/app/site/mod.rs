@@ -1,15 +1,22 @@
 pub mod create_site;
 pub mod create_site_from_backup;
 pub mod delete_site;
+pub mod duplicate_site;
+pub mod export_site;
 pub mod get_current_site;
+pub mod get_site_analytics;
 pub mod get_site_domains;
 pub mod get_site_head;
 pub mod get_site_metadata;
 pub mod get_site_usage;
 pub mod get_site_version;
 pub mod get_static_page;
+pub mod import_site;
 pub mod list_site_versions;
 pub mod list_sites;
+pub mod merge_site;
+pub mod patch_site;
+pub mod prune_site_versions;
 pub mod record_page_view;
 pub mod update_site;
 pub mod update_site_metadata;
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::{json_extractor::PsJson, json_patch::apply_site_patch},
+    validator::site_data_len_validator::SiteDataValidator,
+};
+use lib_shared_types::{
+    dto::site_api::{
+        patch_site_dto::PatchSiteDto,
+        patch_site_viewmodel::{to_api_response, PatchSiteViewModel},
+        update_site_dto::{UpdateSiteDto, UpdateSiteDtoWithContentUpdatedAt},
+    },
+    error::api_error::ApiErrorCode,
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+use super::update_site::{check_site_locales, get_updatable_site_metadata, update_site_helper};
+
+fn update_stale() -> ApiError {
+    ApiError::bad_request()
+        .code(ApiErrorCode::UpdateStale)
+        .message("update_key did not match")
+}
+
+// Apply JSON Patches to the latest site version. The patches are applied to the version
+// identified by `update_key`, and the write fails if the version changed in the meantime
+pub async fn patch_site(
+    Path(id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(dto): PsJson<PatchSiteDto>,
+) -> Result<Json<PatchSiteViewModel>, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &id).await?;
+
+    let site_metadata = get_updatable_site_metadata(&context, &user, &id).await?;
+
+    let site = context
+        .site_repo
+        .get_site_latest_version(&id, false)
+        .await
+        .map_err(|e| match e {
+            DbError::NoDb(_) | DbError::EntityNotFound() => ApiError::not_found(),
+            _ => ApiError::internal_error().message(e),
+        })?;
+
+    // `updated_at` is stored with second precision
+    let update_key = dto
+        .update_key
+        .parse::<DateTime<Utc>>()
+        .map_err(|_| ApiError::bad_request().code(ApiErrorCode::InvalidFormData))?;
+    if update_key.timestamp() != site.updated_at.timestamp() {
+        return Err(update_stale());
+    }
+
+    let context_value = match &dto.context {
+        Some(patch) => Some(apply_site_patch("context", &site.context, patch)?),
+        None => None,
+    };
+    let defaults = match &dto.defaults {
+        Some(patch) => Some(apply_site_patch("defaults", &site.defaults, patch)?),
+        None => None,
+    };
+    let pages = match &dto.pages {
+        Some(patch) => Some(apply_site_patch("pages", &site.pages, patch)?),
+        None => None,
+    };
+
+    // Validate the patched result
+    let validator = SiteDataValidator::new(site_metadata.site_type);
+    if let Some(value) = &context_value {
+        validator.validate_context(value)?;
+    }
+    if let Some(value) = &dto.history {
+        validator.validate_history(value)?;
+    }
+    if let Some(value) = &pages {
+        validator.validate_pages(value)?;
+    }
+    if let Some(value) = &defaults {
+        check_site_locales(value)?;
+    }
+
+    let content_updated = context_value.is_some() || defaults.is_some() || pages.is_some();
+    let content_updated_at = if content_updated {
+        Some(Utc::now().timestamp_millis())
+    } else {
+        None
+    };
+
+    let update = UpdateSiteDtoWithContentUpdatedAt {
+        dto: UpdateSiteDto {
+            name: None,
+            version: None,
+            context: context_value,
+            defaults,
+            editor: dto.editor,
+            history: dto.history,
+            pages,
+            page_order: None,
+            disabled: None,
+            update_key: Some(dto.update_key),
+            enable_preview: None,
+            base_revision_id: None,
+        },
+        content_updated_at,
+        // Guards against a concurrent write within the same second as `update_key`
+        expected_content_updated_at: Some(site.content_updated_at),
+    };
+
+    let updated_site = update_site_helper(&context, &id, &site_metadata, update).await?;
+
+    Ok(Json(to_api_response(&updated_site)))
+}
@@ -0,0 +1,34 @@
+use tracing::{error, info};
+
+use crate::api_context::ApiContext;
+
+// Days for which one version per day is kept, regardless of the site type limit
+const KEEP_DAILY_DAYS: u32 = 30;
+
+// Apply the version retention policy to all sites, and VACUUM site DBs that had versions removed
+pub async fn prune_site_versions_helper(context: ApiContext) {
+    let sites = match context.metadata_repo.list_sites().await {
+        Ok(sites) => sites,
+        Err(e) => {
+            error!("Failed to list sites for version pruning: {}", e);
+            return;
+        }
+    };
+
+    let mut total_deleted = 0;
+    for site in sites.iter() {
+        match context
+            .site_repo
+            .prune_site_versions(&site.id, site.site_type.get_max_versions(), KEEP_DAILY_DAYS)
+            .await
+        {
+            Ok(deleted) => total_deleted += deleted,
+            Err(e) => error!("Failed to prune versions for site {}: {}", site.id, e),
+        }
+    }
+    info!(
+        "Pruned {} site versions from {} sites",
+        total_deleted,
+        sites.len()
+    );
+}
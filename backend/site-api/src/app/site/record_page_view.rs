@@ -5,7 +5,13 @@ use axum::{
 };
 use axum_extra::extract::Host;
 use axum_macros::debug_handler;
-use lib_shared_site_api::{error::api_error::ApiError, util::json_extractor::PsJson};
+use lib_shared_site_api::{
+    error::api_error::ApiError,
+    util::{
+        domains::{domain_without_port, origin_domain},
+        json_extractor::PsJson,
+    },
+};
 use lib_shared_types::{
     dto::site_api::record_page_view_dto::{RecordPageViewDto, RecordPageViewResponse},
     error::api_error::ApiErrorCode,
@@ -13,6 +19,11 @@ use lib_shared_types::{
 
 use crate::{
     api_context::ApiContext,
+    app::{
+        experiment::helpers::VisitorExperiments,
+        maintenance::helpers::{blocking_maintenance, maintenance_error, peer_ip, PeerAddr},
+        usage::helpers::record_hourly_view,
+    },
     db::db_cache_layer::{get_pages_from_cache_or_repo, get_site_id_by_host_or_origin},
 };
 
@@ -22,24 +33,46 @@ pub async fn record_page_view(
     State(context): State<ApiContext>,
     Host(hostname): Host,
     headers: HeaderMap,
+    peer: PeerAddr,
     PsJson(dto): PsJson<RecordPageViewDto>,
-) -> Result<Json<RecordPageViewResponse>, ApiError> {
-    let site_id = get_site_id_by_host_or_origin(&context, hostname, &headers).await?;
+) -> Result<(HeaderMap, Json<RecordPageViewResponse>), ApiError> {
+    let site_id = get_site_id_by_host_or_origin(&context, hostname.clone(), &headers).await?;
     if id != site_id {
         return Err(ApiError::bad_request().code(ApiErrorCode::InvalidFormData));
     }
+    // Views are not counted while visitors get the maintenance page
+    if blocking_maintenance(&context, &site_id, &headers, peer_ip(peer))
+        .await?
+        .is_some()
+    {
+        return Err(maintenance_error());
+    }
     let page_routes = get_pages_from_cache_or_repo(&context, &site_id).await?;
 
     if !page_routes.contains(&dto.route) {
         return Err(ApiError::bad_request().code(ApiErrorCode::InvalidRoute));
     }
 
+    // The first view of an experiment route exposes the visitor to their variant
+    let mut visitor = VisitorExperiments::load(&context, &site_id, &headers).await?;
+    visitor
+        .record_exposure(&context, &site_id, &dto.route)
+        .await;
+    let response_headers = visitor.response_headers(&context, &site_id, &hostname)?;
+
     let page_views = context
         .cache
-        .increase_page_view_count(&site_id, dto.route)
+        .increase_page_view_count(&site_id, dto.route.clone())
         .await?;
 
-    return Ok(Json(RecordPageViewResponse {
-        view_count: page_views,
-    }));
+    // Referrers from the site's own pages are navigation, not traffic sources
+    let site_host = origin_domain(&headers).unwrap_or_else(|| domain_without_port(hostname));
+    record_hourly_view(&context, &site_id, &site_host, &headers, &dto);
+
+    return Ok((
+        response_headers,
+        Json(RecordPageViewResponse {
+            view_count: page_views,
+        }),
+    ));
 }
@@ -1,20 +1,26 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use chrono::Utc;
 use lib_shared_site_api::{
     db::db_error::DbError,
     error::{api_error::ApiError, helpers::check_bad_form},
-    util::json_extractor::PsJson,
+    util::{json_extractor::PsJson, locales::validate_site_locales},
     validator::site_data_len_validator::SiteDataValidator,
 };
 use lib_shared_types::{
     dto::site_api::{
-        site_viewmodel::{to_api_response, SiteViewModel},
+        site_merge_conflict_viewmodel,
+        site_viewmodel::to_api_response,
         update_site_dto::{UpdateSiteDto, UpdateSiteDtoWithContentUpdatedAt},
     },
+    entity::site_api::{
+        site_entity::SiteEntity, site_metadata_entity::SiteMetadataEntity,
+        static_build_entity::StaticBuildTrigger,
+    },
     error::api_error::ApiErrorCode,
     shared::user::{RequestUser, UserType},
 };
@@ -25,58 +31,52 @@ use crate::{
     middleware::auth::verify_site_owner,
 };
 
-pub async fn update_site(
-    Path(id): Path<String>,
-    State(context): State<ApiContext>,
-    Extension(user): Extension<RequestUser>,
-    PsJson(dto): PsJson<UpdateSiteDto>,
-) -> Result<(StatusCode, Json<SiteViewModel>), ApiError> {
-    check_bad_form(dto.validate())?;
-    verify_site_owner(&context, &user, &id).await?;
+use super::merge_site::{merge_site_update, SiteMergeResult};
 
+// Locales are used for routing, and are checked before the defaults are saved
+pub fn check_site_locales(defaults: &serde_json::Value) -> Result<(), ApiError> {
+    validate_site_locales(defaults).map_err(|e| {
+        ApiError::bad_request()
+            .code(ApiErrorCode::InvalidFormData)
+            .message(e)
+    })
+}
+
+// Get site metadata, and verify the site can be updated by the user
+pub async fn get_updatable_site_metadata(
+    context: &ApiContext,
+    user: &RequestUser,
+    id: &str,
+) -> Result<SiteMetadataEntity, ApiError> {
     let site_metadata = context
         .metadata_repo
-        .get_site_metadata(&id)
+        .get_site_metadata(id)
         .await
         .map_err(|e| ApiError::not_found().message(e))?;
 
-    let site_type = site_metadata.site_type;
     if site_metadata.disabled
         && user.user_type != UserType::Admin
         && user.user_type != UserType::Cron
     {
         return Err(ApiError::forbidden());
     }
+    Ok(site_metadata)
+}
 
-    // Validate
-    let validator = SiteDataValidator::new(site_type);
-    if let Some(value) = &dto.context {
-        validator.validate_context(value)?;
-    }
-    if let Some(value) = &dto.history {
-        validator.validate_history(value)?;
-    }
-    if let Some(value) = &dto.pages {
-        validator.validate_pages(value)?;
-    }
-    let has_update_key = dto.update_key.is_some();
-
-    // Update `content_updated_at` if any of `defaults`, `context`, or `pages` changes.
-    let mut content_updated_at: Option<i64> = None;
-
-    if dto.defaults.is_some() || dto.context.is_some() || dto.pages.is_some() {
-        content_updated_at = Some(Utc::now().timestamp_millis());
-    }
+// Update the latest site version, and refresh cache, usage, and static pages
+pub async fn update_site_helper(
+    context: &ApiContext,
+    id: &str,
+    site_metadata: &SiteMetadataEntity,
+    update: UpdateSiteDtoWithContentUpdatedAt,
+) -> Result<SiteEntity, ApiError> {
+    let has_update_key =
+        update.dto.update_key.is_some() || update.expected_content_updated_at.is_some();
+    let content_updated = update.content_updated_at.is_some();
 
     let site = context
         .site_repo
-        .update_site(
-            &id,
-            UpdateSiteDtoWithContentUpdatedAt {
-                dto,
-                content_updated_at,
-            },
-        )
+        .update_site(id, update)
         .await
         .map_err(|e| match e {
             DbError::NoUpdate => ApiError::bad_request()
@@ -99,18 +99,81 @@ pub async fn update_site(
     // Reset site data cache. This only needs to be done if there's no draft, but we would have to do
     // extra work to determine that, so it's easier and more efficient just to let get_current_site
     // take care of refreshing the cache
-    context.cache.remove_site(&id).await;
+    context.cache.remove_site(id).await;
     context.cache.sync().await;
     context
         .cache
-        .create_or_update_usage(&id, site.calculate_site_size(), site_type)
+        .create_or_update_usage(id, site.calculate_site_size(), site_metadata.site_type)
         .await;
 
     // A site with no separate draft version is published live on save, so
     // its static pages must be regenerated (debounced via content_updated_at)
-    if site.published && content_updated_at.is_some() {
-        spawn_regenerate_static_pages(&context, &id, Some(site.content_updated_at));
+    if site.published && content_updated {
+        spawn_regenerate_static_pages(
+            context,
+            id,
+            StaticBuildTrigger::Update,
+            Some(site.content_updated_at),
+        );
+    }
+
+    Ok(site)
+}
+
+pub async fn update_site(
+    Path(id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    PsJson(mut dto): PsJson<UpdateSiteDto>,
+) -> Result<Response, ApiError> {
+    check_bad_form(dto.validate())?;
+    verify_site_owner(&context, &user, &id).await?;
+
+    let site_metadata = get_updatable_site_metadata(&context, &user, &id).await?;
+
+    let expected_content_updated_at = match merge_site_update(&context, &id, &mut dto).await? {
+        SiteMergeResult::Merged {
+            expected_content_updated_at,
+        } => expected_content_updated_at,
+        SiteMergeResult::Conflicts(conflicts) => {
+            let body = site_merge_conflict_viewmodel::to_api_response(conflicts);
+            return Ok((StatusCode::CONFLICT, Json(body)).into_response());
+        }
+    };
+
+    // Validate
+    let validator = SiteDataValidator::new(site_metadata.site_type);
+    if let Some(value) = &dto.context {
+        validator.validate_context(value)?;
     }
+    if let Some(value) = &dto.history {
+        validator.validate_history(value)?;
+    }
+    if let Some(value) = &dto.pages {
+        validator.validate_pages(value)?;
+    }
+    if let Some(value) = &dto.defaults {
+        check_site_locales(value)?;
+    }
+
+    // Update `content_updated_at` if any of `defaults`, `context`, or `pages` changes.
+    let mut content_updated_at: Option<i64> = None;
+
+    if dto.defaults.is_some() || dto.context.is_some() || dto.pages.is_some() {
+        content_updated_at = Some(Utc::now().timestamp_millis());
+    }
+
+    let site = update_site_helper(
+        &context,
+        &id,
+        &site_metadata,
+        UpdateSiteDtoWithContentUpdatedAt {
+            dto,
+            content_updated_at,
+            expected_content_updated_at,
+        },
+    )
+    .await?;
 
-    Ok((StatusCode::OK, Json(to_api_response(site))))
+    Ok((StatusCode::OK, Json(to_api_response(site))).into_response())
 }
@@ -3,21 +3,28 @@ use axum::{
     Extension,
 };
 use lib_shared_site_api::{
-    cache::cache::SiteMetadata,
     error::{
         api_error::ApiError,
-        helpers::{domain_strings, validate_custom_domains},
+        helpers::{
+            domain_strings, validate_custom_domains, validate_domain_kinds, validate_primary_domain,
+        },
     },
-    util::json_extractor::PsJson,
+    util::{domain_verification::is_local_domain, json_extractor::PsJson},
 };
 use lib_shared_types::{
     dto::site_api::update_metadata_dto::UpdateSiteMetadataDto,
-    entity::site_api::custom_domain_entity::vec_from_viewmodel,
+    entity::site_api::{
+        custom_domain_entity::vec_from_viewmodel, static_build_entity::StaticBuildTrigger,
+    },
     shared::user::{RequestUser, UserType},
 };
 
 use crate::{
-    api_context::ApiContext, app::ssg::generate_static::spawn_regenerate_static_pages,
+    api_context::ApiContext,
+    app::{
+        domain_verification::helpers::sync_domain_verifications,
+        ssg::generate_static::spawn_regenerate_static_pages,
+    },
     middleware::auth::verify_site_owner,
 };
 
@@ -31,15 +38,18 @@ pub async fn update_site_metadata(
 
     let admin_or_cron = user.user_type == UserType::Admin || user.user_type == UserType::Cron;
 
-    // Owner can only update domains
+    // Owner can only update domains and the www policy
     if (dto.disabled.is_some() || dto.site_type.is_some()) && !admin_or_cron {
         return Err(ApiError::forbidden());
     }
 
     if let Some(domains) = &dto.domains {
+        validate_domain_kinds(domains)?;
         validate_custom_domains(&domain_strings(domains))?;
+        validate_primary_domain(domains)?;
     }
-    let domains_updated = dto.domains.is_some();
+    let domains_updated = dto.domains.is_some() || dto.www_policy.is_some();
+    let new_domains = dto.domains.as_ref().map(domain_strings);
     let disabled_updated = dto.disabled.is_some();
 
     let mut tx = context
@@ -54,31 +64,26 @@ pub async fn update_site_metadata(
         || dto.site_type.is_some()
         || dto.owner_email.is_some()
         || dto.owner_id.is_some()
+        || dto.www_policy.is_some()
     {
-        let metadata = context
+        context
             .metadata_repo
             .update_site_metadata_with_tx(&mut tx, &id, &dto.clone().into())
             .await
             .map_err(|e| {
                 ApiError::internal_error().message(format!("Failed to update site: {}", e))
             })?;
-
-        // Clear Metadata Cache
-        context
-            .cache
-            .create_or_update_metadata(
-                &id,
-                SiteMetadata {
-                    owner_id: metadata.owner_id,
-                    site_type: metadata.site_type,
-                    disabled: metadata.disabled,
-                },
-            )
-            .await;
     }
 
     if let Some(domains) = dto.domains {
-        let domain_models = vec_from_viewmodel(domains);
+        let mut domain_models = vec_from_viewmodel(domains);
+        // Owners can't verify domains. Domains start unverified until a DNS check passes,
+        // except local domains, which are never checked
+        if !admin_or_cron {
+            for domain in domain_models.iter_mut() {
+                domain.verified = domain.verified && is_local_domain(&domain.domain);
+            }
+        }
         context
             .metadata_repo
             .set_site_domains(&mut tx, &id, &domain_models)
@@ -86,20 +91,27 @@ pub async fn update_site_metadata(
             .map_err(|e| {
                 ApiError::internal_error().message(format!("Failed to save domains: {}", e))
             })?;
-
-        // Update domain -> site_id mapping
-        context
-            .cache
-            .update_site_domains(&id, &domain_models.into_iter().map(|d| d.domain).collect())
-            .await
     }
 
     tx.commit().await.map_err(|e| {
         ApiError::internal_error().message(format!("Failed to save domains: {}", e))
     })?;
 
+    // Domain -> site_id mappings are reloaded on request, suspended domains are not mapped.
+    // New domains may have resolved to another site's wildcard domain
+    if domains_updated {
+        context.cache.remove_domain_mapping(&id).await;
+    }
+    if let Some(domains) = &new_domains {
+        context.cache.remove_domains(domains).await;
+        sync_domain_verifications(&context).await;
+    }
+    // Clear Metadata Cache, the canonical host depends on domains and www policy
+    context.cache.remove_metadata(&id).await;
+
     if disabled_updated || domains_updated {
-        spawn_regenerate_static_pages(&context, &id, None);
+        context.cache.remove_seo_files(&id).await;
+        spawn_regenerate_static_pages(&context, &id, StaticBuildTrigger::Metadata, None);
     }
 
     Ok(())
@@ -1,27 +1,171 @@
-use lib_shared_site_api::{clients::ssg_client::ssg_generate, error::api_error::ApiError};
+use lib_shared_site_api::{
+    clients::ssg_client::ssg_generate,
+    error::api_error::ApiError,
+    util::{
+        domains::site_base_url,
+        site_document::{encode_site_document, parse_site_document},
+        ssg_hash::{hash_site_content, SiteContentHashes},
+    },
+};
 use lib_shared_types::{
-    dto::site_api::ssg_dto::{site_to_ssg_input, SsgGenerateRequest, SsgOptions},
-    entity::site_api::static_page_entity::StaticPageEntity,
+    dto::site_api::ssg_dto::{site_to_ssg_input, SsgGenerateRequest, SsgOptions, SsgSiteInput},
+    entity::site_api::{
+        site_entity::SiteEntity,
+        static_build_entity::{StaticBuildEntity, StaticBuildStatus, StaticBuildTrigger},
+        static_page_entity::StaticPageEntity,
+    },
 };
+use serde_json::{Map, Value};
 use tracing::{error, info, warn};
 
-use crate::api_context::ApiContext;
+use crate::{api_context::ApiContext, db::db_cache_layer::get_protections_from_cache_or_repo};
 
 // Cache-busting version for the shared hydration runtime script
 const RUNTIME_SRC: &str = concat!("/_ps/site.js?v=", env!("CARGO_PKG_VERSION"));
 
-fn base_url_from_domain(domain: &str) -> String {
-    // Local development domains are served over plain http
-    if domain == "localhost" || domain.ends_with(".localhost") {
-        format!("http://{}", domain)
-    } else {
-        format!("https://{}", domain)
+// SSG input with pages behind a protected route moved to `protected_pages`. Returns the input
+// and the protected page routes
+async fn site_ssg_input(
+    context: &ApiContext,
+    site_id: &str,
+    site: &SiteEntity,
+) -> Result<(SsgSiteInput, Vec<String>), ApiError> {
+    let mut input = site_to_ssg_input(site, site_id);
+    let protections = get_protections_from_cache_or_repo(context, site_id).await?;
+    if protections.is_empty() {
+        return Ok((input, vec![]));
+    }
+    let (pages, encoded) =
+        parse_site_document(&site.pages).map_err(|e| ApiError::internal_error().message(e))?;
+    let Value::Object(mut pages) = pages else {
+        return Ok((input, vec![]));
+    };
+    let mut protected_routes: Vec<String> = pages
+        .keys()
+        .filter(|route| protections.find(route).is_some())
+        .cloned()
+        .collect();
+    protected_routes.sort();
+    let mut protected = Map::new();
+    for route in &protected_routes {
+        if let Some(page) = pages.remove(route) {
+            protected.insert(route.clone(), page);
+        }
+    }
+    input.pages = encode_site_document(Value::Object(pages), encoded).to_string();
+    input.protected_pages =
+        Some(encode_site_document(Value::Object(protected), encoded).to_string());
+    Ok((input, protected_routes))
+}
+
+// Find stored pages whose content and shared dependencies are unchanged. Returns their routes,
+// and the page routes that must be regenerated, or None when every page must be regenerated.
+async fn unchanged_pages(
+    context: &ApiContext,
+    site_id: &str,
+    hashes: &SiteContentHashes,
+) -> Result<(Vec<String>, Option<Vec<String>>), ApiError> {
+    let stored = context
+        .site_repo
+        .list_static_pages(site_id)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    let unchanged: Vec<String> = stored
+        .into_iter()
+        .filter(|page| {
+            page.shared_hash == hashes.shared
+                && hashes.pages.get(&page.route) == Some(&page.page_hash)
+        })
+        .map(|page| page.route)
+        .collect();
+
+    if unchanged.is_empty() {
+        return Ok((unchanged, None));
+    }
+    let mut routes: Vec<String> = hashes
+        .pages
+        .keys()
+        .filter(|route| !unchanged.contains(route))
+        .map(|route| hashes.page_route(route).to_string())
+        .collect();
+    routes.sort();
+    routes.dedup();
+    Ok((unchanged, Some(routes)))
+}
+
+// Generate pages for a published site and store them, recording the outcome in `build`
+async fn generate_static_pages(
+    context: &ApiContext,
+    ssg_url: &str,
+    site_id: &str,
+    site: &SiteEntity,
+    base_url: Option<String>,
+    build: &mut StaticBuildEntity,
+) -> Result<(), ApiError> {
+    let (site_input, protected_routes) = site_ssg_input(context, site_id, site).await?;
+    // Every page embeds the payload of unprotected pages, so protecting a page changes them all
+    let options_key = format!(
+        "{}|{}|{}",
+        base_url.as_deref().unwrap_or(""),
+        RUNTIME_SRC,
+        protected_routes.join(",")
+    );
+    let hashes =
+        hash_site_content(site, &options_key).map_err(|e| ApiError::internal_error().message(e))?;
+    let (unchanged, routes) = unchanged_pages(context, site_id, &hashes).await?;
+    build.incremental = routes.is_some();
+
+    let request = SsgGenerateRequest {
+        site: site_input,
+        options: SsgOptions {
+            base_url,
+            runtime_src: RUNTIME_SRC.into(),
+            routes,
+        },
+    };
+
+    let result = ssg_generate(ssg_url, &request).await?;
+    for warning in &result.warnings {
+        warn!("SSG warning for site {}: {}", site_id, warning);
     }
+    let pages: Vec<StaticPageEntity> = result
+        .pages
+        .into_iter()
+        .map(|p| StaticPageEntity {
+            page_hash: hashes.pages.get(&p.route).cloned().unwrap_or_default(),
+            shared_hash: hashes.shared.clone(),
+            route: p.route,
+            body: p.body,
+            content_type: p.content_type,
+            content_updated_at: site.content_updated_at,
+            body_br: None,
+            body_gzip: None,
+        })
+        .collect();
+    build.generator = Some(result.generator);
+    build.page_count = pages.len() as i64;
+    build.warnings = result.warnings;
+    build.blockers = result.blockers;
+
+    // Unchanged pages are only bumped to the new site version once generation succeeded
+    context
+        .site_repo
+        .store_static_pages(site_id, pages, &unchanged, site.content_updated_at)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+    Ok(())
 }
 
 // Prerender the published site with the SSG service and store the result in the static_pages
-// table. Clears stored pages when the site is unpublished or generation fails.
-pub async fn regenerate_static_pages(context: &ApiContext, site_id: &str) -> Result<(), ApiError> {
+// table. Only pages whose content hash changed are regenerated. Clears stored pages when the
+// site is unpublished. A failed generation leaves stored pages untouched; they are not served
+// for newer content. Each run is recorded in the site's static_builds.
+pub async fn regenerate_static_pages(
+    context: &ApiContext,
+    site_id: &str,
+    trigger: StaticBuildTrigger,
+) -> Result<(), ApiError> {
     let Some(ssg_url) = context.config.ssg_url.clone() else {
         return Ok(());
     };
@@ -44,6 +188,17 @@ pub async fn regenerate_static_pages(context: &ApiContext, site_id: &str) -> Res
             .clear_static_pages(site_id)
             .await
             .map_err(|e| ApiError::internal_error().message(e))?;
+        let build = context
+            .static_build_repo
+            .create_build(
+                site_id,
+                trigger,
+                StaticBuildStatus::Cleared,
+                site.content_updated_at,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error().message(e))?;
+        finish_build(context, site_id, &build).await;
         info!(
             "Cleared static pages for site {} (published={}, disabled={})",
             site_id, site.published, metadata.disabled
@@ -51,57 +206,75 @@ pub async fn regenerate_static_pages(context: &ApiContext, site_id: &str) -> Res
         return Ok(());
     }
 
-    let base_url = metadata
-        .domains
-        .first()
-        .map(|d| base_url_from_domain(&d.domain));
+    let base_url = site_base_url(&metadata.domains, metadata.www_policy, |_| true);
 
-    let request = SsgGenerateRequest {
-        site: site_to_ssg_input(&site, site_id),
-        options: SsgOptions {
-            base_url,
-            runtime_src: RUNTIME_SRC.into(),
-        },
-    };
+    let mut build = context
+        .static_build_repo
+        .create_build(
+            site_id,
+            trigger,
+            StaticBuildStatus::Running,
+            site.content_updated_at,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
 
-    match ssg_generate(&ssg_url, &request).await {
-        Ok(result) => {
-            for warning in &result.warnings {
-                warn!("SSG warning for site {}: {}", site_id, warning);
-            }
-            let pages: Vec<StaticPageEntity> = result
-                .pages
-                .into_iter()
-                .map(|p| StaticPageEntity {
-                    route: p.route,
-                    body: p.body,
-                    content_type: p.content_type,
-                    content_updated_at: site.content_updated_at,
-                })
-                .collect();
-            let count = pages.len();
-            context
-                .site_repo
-                .replace_static_pages(site_id, pages)
-                .await
-                .map_err(|e| ApiError::internal_error().message(e))?;
+    let result =
+        generate_static_pages(context, &ssg_url, site_id, &site, base_url, &mut build).await;
+    match &result {
+        Ok(()) => {
+            build.status = StaticBuildStatus::Succeeded;
             info!(
-                "Generated {} static pages for site {} with {}",
-                count, site_id, result.generator
+                "Generated {} static pages for site {} with {} (incremental={})",
+                build.page_count,
+                site_id,
+                build.generator.as_deref().unwrap_or_default(),
+                build.incremental
             );
-            Ok(())
         }
         Err(e) => {
-            // Never leave stale pages around after a failed generation
-            if let Err(clear_err) = context.site_repo.clear_static_pages(site_id).await {
-                error!(
-                    "Failed to clear static pages for site {}: {}",
-                    site_id, clear_err
-                );
-            }
-            Err(e)
+            build.status = StaticBuildStatus::Failed;
+            build.error = Some(e.message.clone());
         }
     }
+    finish_build(context, site_id, &build).await;
+    result
+}
+
+async fn finish_build(context: &ApiContext, site_id: &str, build: &StaticBuildEntity) {
+    if let Err(e) = context.static_build_repo.finish_build(site_id, build).await {
+        error!(
+            "Failed to record static build {} for site {}: {}",
+            build.id, site_id, e
+        );
+    }
+}
+
+// Check the current draft for generator blockers before it is published. Renders no pages,
+// and returns no blockers when static generation is disabled.
+pub async fn check_ssg_blockers(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<Vec<String>, ApiError> {
+    let Some(ssg_url) = context.config.ssg_url.clone() else {
+        return Ok(vec![]);
+    };
+
+    let site = context
+        .site_repo
+        .get_site_latest_version(site_id, false)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+
+    let request = SsgGenerateRequest {
+        site: site_ssg_input(context, site_id, &site).await?.0,
+        options: SsgOptions {
+            base_url: None,
+            runtime_src: RUNTIME_SRC.into(),
+            routes: Some(vec![]),
+        },
+    };
+    Ok(ssg_generate(&ssg_url, &request).await?.blockers)
 }
 
 // Fire-and-forget regeneration, used by publish/update handlers. Skipped when
@@ -109,6 +282,7 @@ pub async fn regenerate_static_pages(context: &ApiContext, site_id: &str) -> Res
 pub fn spawn_regenerate_static_pages(
     context: &ApiContext,
     site_id: &str,
+    trigger: StaticBuildTrigger,
     expected_content_updated_at: Option<i64>,
 ) {
     let context = context.clone();
@@ -129,7 +303,7 @@ pub fn spawn_regenerate_static_pages(
                 Err(_) => return,
             }
         }
-        if let Err(e) = regenerate_static_pages(&context, &site_id).await {
+        if let Err(e) = regenerate_static_pages(&context, &site_id, trigger).await {
             error!("Static generation failed for site {}: {}", site_id, e);
         }
     });
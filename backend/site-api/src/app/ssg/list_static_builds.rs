@@ -0,0 +1,34 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use lib_shared_site_api::error::{api_error::ApiError, helpers::check_bad_form};
+use lib_shared_types::{
+    dto::site_api::static_build_viewmodel::{
+        to_api_response, ListStaticBuildsQuery, StaticBuildViewModel,
+    },
+    shared::user::RequestUser,
+};
+use validator::Validate;
+
+use crate::{api_context::ApiContext, middleware::auth::verify_site_owner};
+
+const DEFAULT_STATIC_BUILD_LIMIT: u32 = 10;
+
+pub async fn list_static_builds(
+    Path(site_id): Path<String>,
+    State(context): State<ApiContext>,
+    Extension(user): Extension<RequestUser>,
+    Query(query): Query<ListStaticBuildsQuery>,
+) -> Result<Json<Vec<StaticBuildViewModel>>, ApiError> {
+    check_bad_form(query.validate())?;
+    verify_site_owner(&context, &user, &site_id).await?;
+
+    let builds = context
+        .static_build_repo
+        .list_builds(&site_id, query.limit.unwrap_or(DEFAULT_STATIC_BUILD_LIMIT))
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(Json(builds.into_iter().map(to_api_response).collect()))
+}
@@ -1,2 +1,3 @@
 pub mod generate_static;
+pub mod list_static_builds;
 pub mod static_serve;
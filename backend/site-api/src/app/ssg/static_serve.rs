@@ -1,12 +1,33 @@
-use lib_shared_site_api::{db::db_error::DbError, error::api_error::ApiError};
-use lib_shared_types::entity::site_api::static_page_entity::StaticPageEntity;
+use std::net::IpAddr;
+
+use axum::http::HeaderMap;
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::{
+        compression::ContentEncoding,
+        http_cache::{site_cache_control, HttpValidators},
+        site_seo::is_seo_file_path,
+    },
+};
+use lib_shared_types::{
+    domain::site_defaults::canonical_locale_path, dto::site_api::ssg_dto::StaticPageViewModel,
+    entity::site_api::static_page_entity::StaticPageEntity,
+};
 use tracing::error;
 
 use crate::{
     api_context::ApiContext,
-    db::db_cache_layer::{get_metadata_from_cache_or_repo, get_site_from_cache_or_repo},
+    app::usage::helpers::record_unique_visitor,
+    db::db_cache_layer::{
+        get_metadata_from_cache_or_repo, get_seo_files_from_cache_or_repo,
+        get_site_from_cache_or_repo,
+    },
 };
 
+// Replaced with the request's subdomain in pages served on a wildcard domain
+const SUBDOMAIN_PLACEHOLDER: &str = "___SUBDOMAIN___";
+
 // '/about/' -> '/about', '' -> '/'
 pub fn normalize_route(path: &str) -> String {
     let trimmed = path.trim_end_matches('/');
@@ -28,11 +49,13 @@ pub async fn get_fresh_static_page(
     if metadata.disabled {
         return Ok(None);
     }
-    let site = get_site_from_cache_or_repo(context, site_id).await?.site;
+    let cached = get_site_from_cache_or_repo(context, site_id).await?;
+    let site = cached.site;
     if !site.published {
         return Ok(None);
     }
-    let route = normalize_route(path);
+    // Locale prefixes are served case-insensitively, like the locale of SPA pages
+    let route = normalize_route(&canonical_locale_path(&cached.meta.locales, path));
     let page = match context.site_repo.get_static_page(site_id, &route).await {
         Ok(page) => page,
         // No row route is a normal miss, anything else is a real DB failure to be reported
@@ -55,18 +78,32 @@ pub async fn get_fresh_static_page(
 
 pub struct ServedStaticPage {
     pub page: StaticPageEntity,
+    // Encoding of `body`, Identity when the page has no stored variant for the request
+    pub encoding: ContentEncoding,
+    pub body: Vec<u8>,
     // True when the requested route had no page and the site's /not-found
     // page was served instead (callers respond with a 404 status)
     pub not_found: bool,
+    // True when the request's validators match, callers respond with 304 and no body
+    pub not_modified: bool,
+    pub validators: HttpValidators,
+    pub cache_control: String,
 }
 
-// Resolve the static page served for a request path, with usage tracking for bandwidth
-// and view count. Used by both the site-api serve path and the static_pages endpoint
+// Resolve the static page served for a request path, with usage tracking for bandwidth,
+// view count and unique visitors. Used by both the site-api serve path and the static_pages endpoint
 // that platform-api proxies, so usage is counted no matter which server fields the request.
+// `visitor` is the address counted in unique visitors, if any.
+// Bandwidth counts the bytes sent in the negotiated `encoding`. Conditional requests
+// matching the page's validators count as a request without bandwidth.
 pub async fn serve_static_page(
     context: &ApiContext,
     site_id: &str,
     path: &str,
+    headers: &HeaderMap,
+    visitor: Option<IpAddr>,
+    encoding: ContentEncoding,
+    subdomain: Option<&str>,
 ) -> Result<Option<ServedStaticPage>, ApiError> {
     let mut not_found = false;
     let mut page = get_fresh_static_page(context, site_id, path).await?;
@@ -74,20 +111,78 @@ pub async fn serve_static_page(
         page = get_fresh_static_page(context, site_id, "/not-found").await?;
         not_found = true;
     }
-    let Some(page) = page else {
+    let Some(mut page) = page else {
         return Ok(None);
     };
+    // Pages are prerendered without a subdomain. Hosts matched by a wildcard domain get theirs
+    // substituted into the uncompressed page
+    if let Some(subdomain) = subdomain {
+        page.body = page.body.replace(SUBDOMAIN_PLACEHOLDER, subdomain);
+        page.body_br = None;
+        page.body_gzip = None;
+    }
+    let variant = match encoding {
+        ContentEncoding::Brotli => page.body_br.take(),
+        ContentEncoding::Gzip => page.body_gzip.take(),
+        ContentEncoding::Identity => None,
+    };
+    let (encoding, body) = match variant {
+        Some(body) => (encoding, body),
+        None => (ContentEncoding::Identity, page.body.clone().into_bytes()),
+    };
 
     let metadata = get_metadata_from_cache_or_repo(context, site_id).await?;
-    let size = page.body.len() as u64;
+    let site = get_site_from_cache_or_repo(context, site_id).await?;
+    let validators =
+        HttpValidators::new(&page.body, page.content_updated_at).with_encoding(encoding);
+    // The not-found fallback always answers with its body and 404 status
+    let not_modified = !not_found && validators.is_not_modified(headers);
+
+    let size = body.len() as u64;
     context
         .cache
         .check_bandwidth_exceeded(site_id, size, metadata.site_type)
         .await?;
-    context
-        .cache
-        .increase_view_count(site_id, size, metadata.site_type)
-        .await;
+    if not_modified {
+        context
+            .cache
+            .increase_not_modified_count(site_id, size, metadata.site_type)
+            .await;
+    } else {
+        context
+            .cache
+            .increase_view_count(site_id, size, metadata.site_type)
+            .await;
+    }
+    record_unique_visitor(context, site_id, headers, visitor).await;
 
-    Ok(Some(ServedStaticPage { page, not_found }))
+    Ok(Some(ServedStaticPage {
+        page,
+        encoding,
+        body,
+        not_found,
+        not_modified,
+        validators,
+        cache_control: site_cache_control(site.meta.cache_control.as_deref()),
+    }))
+}
+
+// Sitemap and robots.txt generated by site-api from the published site. Returns None for other
+// paths, and for the sitemap when the site has no verified domain.
+pub async fn serve_seo_file(
+    context: &ApiContext,
+    site_id: &str,
+    path: &str,
+) -> Result<Option<StaticPageViewModel>, ApiError> {
+    if !is_seo_file_path(path) {
+        return Ok(None);
+    }
+    let files = get_seo_files_from_cache_or_repo(context, site_id).await?;
+    Ok(files
+        .get(path)
+        .map(|(body, content_type)| StaticPageViewModel {
+            route: path.to_string(),
+            body,
+            content_type: content_type.to_string(),
+        }))
 }
@@ -0,0 +1,141 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::Utc;
+use lib_shared_site_api::{
+    error::{api_error::ApiError, helpers::check_bad_form},
+    util::json_extractor::PsJson,
+};
+use lib_shared_types::{
+    dto::{
+        custom_data::list_tables_query::ListTablesQuery,
+        site_api::{
+            create_template_dto::CreateTemplateDto,
+            template_viewmodel::{to_api_response, CreateTemplateResponse},
+        },
+    },
+    entity::site_api::template_entity::{TemplateEntity, TemplateInfoEntity},
+    error::api_error::ApiErrorCode,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api_context::ApiContext;
+
+// Presigned preview upload URLs are valid for 10 minutes
+const PREVIEW_UPLOAD_EXPIRES: u64 = 600;
+
+async fn snapshot_custom_tables(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<Vec<Value>, ApiError> {
+    let all_tables = ListTablesQuery {
+        from: 1,
+        to: i32::MAX,
+    };
+    let tables = context
+        .custom_data_info_repo
+        .list_tables(site_id, all_tables)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(tables
+        .results
+        .into_iter()
+        .map(|table| {
+            json!({
+                "table_name": table.name,
+                "columns": table.columns,
+                "events": table.events,
+            })
+        })
+        .collect())
+}
+
+pub async fn create_template(
+    State(context): State<ApiContext>,
+    PsJson(dto): PsJson<CreateTemplateDto>,
+) -> Result<(StatusCode, Json<CreateTemplateResponse>), ApiError> {
+    check_bad_form(dto.validate())?;
+
+    if context
+        .template_repo
+        .get_template_by_name(&dto.name)
+        .await
+        .is_ok()
+    {
+        return Err(ApiError::bad_request()
+            .code(ApiErrorCode::TemplateNameExists)
+            .message("Template name already exists"));
+    }
+
+    // Snapshot the published version when there is one, otherwise the latest draft
+    let site = context
+        .site_repo
+        .get_site_latest_version(&dto.site_id, true)
+        .await
+        .map_err(|e| ApiError::not_found().message(e))?;
+
+    let custom_tables = if dto.include_custom_tables {
+        snapshot_custom_tables(&context, &dto.site_id).await?
+    } else {
+        vec![]
+    };
+
+    let template_id = Uuid::new_v4().to_string();
+    let preview_key = dto.preview.as_ref().map(|_| template_id.clone());
+    let preview_upload_url = match (&dto.preview, &preview_key) {
+        (Some(preview), Some(key)) => Some(
+            context
+                .s3_client
+                .presign_put_template_preview(
+                    key,
+                    PREVIEW_UPLOAD_EXPIRES,
+                    &preview.content_type,
+                    preview.size,
+                )?
+                .to_string(),
+        ),
+        _ => None,
+    };
+
+    let template = TemplateEntity {
+        info: TemplateInfoEntity {
+            id: template_id.clone(),
+            name: dto.name,
+            description: dto.description,
+            category: dto.category,
+            preview_key,
+            source_site_id: dto.site_id,
+            created_at: Utc::now(),
+        },
+        version: site.version,
+        context: site.context,
+        defaults: site.defaults,
+        editor: site.editor,
+        // Sites started from a template begin with an empty undo history. Like the other
+        // content columns, it is stored as a JSON-encoded string
+        history: json!(json!({ "back": [], "forward": [] }).to_string()).to_string(),
+        pages: site.pages,
+        page_order: site.page_order,
+        custom_tables,
+    };
+    context
+        .template_repo
+        .create_template(&template)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    let info = context
+        .template_repo
+        .get_template_by_name(&template.info.name)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateTemplateResponse {
+            template: to_api_response(info),
+            preview_upload_url,
+        }),
+    ))
+}
@@ -0,0 +1,27 @@
+use axum::extract::{Path, State};
+use lib_shared_site_api::error::api_error::ApiError;
+
+use crate::api_context::ApiContext;
+
+pub async fn delete_template(
+    Path(template_id): Path<String>,
+    State(context): State<ApiContext>,
+) -> Result<(), ApiError> {
+    let template = context
+        .template_repo
+        .delete_template(&template_id)
+        .await
+        .map_err(|_| ApiError::not_found())?;
+
+    if let Some(preview_key) = template.preview_key {
+        if let Err(e) = context
+            .s3_client
+            .delete_template_preview(&preview_key)
+            .await
+        {
+            tracing::warn!("Failed to delete template preview {}: {}", preview_key, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use lib_shared_site_api::error::{api_error::ApiError, helpers::check_bad_form};
+use lib_shared_types::dto::site_api::{
+    create_template_dto::ListTemplatesQuery,
+    template_viewmodel::{to_api_response, TemplateViewModel},
+};
+use validator::Validate;
+
+use crate::api_context::ApiContext;
+
+pub async fn list_templates(
+    State(context): State<ApiContext>,
+    Query(query): Query<ListTemplatesQuery>,
+) -> Result<Json<Vec<TemplateViewModel>>, ApiError> {
+    check_bad_form(query.validate())?;
+
+    let templates = context
+        .template_repo
+        .list_templates(query.category)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    Ok(Json(templates.into_iter().map(to_api_response).collect()))
+}
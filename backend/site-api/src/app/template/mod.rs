@@ -0,0 +1,3 @@
+pub mod create_template;
+pub mod delete_template;
+pub mod list_templates;
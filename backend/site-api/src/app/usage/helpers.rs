@@ -1,15 +1,33 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, net::IpAddr};
 
 use crate::{app::usage::notify::notify_allowance_exceeded, util::mail_helpers::make_mail_params};
-use chrono::Utc;
-use lib_shared_site_api::db::db_error::DbError;
-use lib_shared_types::entity::site_api::{
-    site_metadata_entity::UpdateSiteMetadataEntity, site_usage_entity::SiteUsageEntityWithTotals,
+use axum::http::{header::USER_AGENT, HeaderMap};
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, Utc};
+use lib_shared_site_api::{
+    db::db_error::DbError,
+    error::api_error::ApiError,
+    util::analytics::{
+        country_code, device_class, dimension_value, hour_start, referrer_host, HourlyUsageKey,
+    },
+};
+use lib_shared_types::{
+    dto::site_api::{
+        record_page_view_dto::RecordPageViewDto, site_usage_viewmodel::UniqueVisitorCounts,
+    },
+    entity::site_api::site_usage_hourly_entity::DeviceClass,
+    entity::site_api::{
+        site_metadata_entity::UpdateSiteMetadataEntity,
+        site_usage_entity::SiteUsageEntityWithTotals,
+    },
 };
 use tracing::{error, info};
+use uuid::Uuid;
 
 use crate::api_context::ApiContext;
 
+// Daily visitor sketches are kept for about a year, 4 KB per site and day
+const VISITOR_SKETCH_RETENTION_DAYS: u64 = 400;
+
 pub async fn populate_usage_cache(context: &ApiContext) -> Result<(), DbError> {
     let result = context.usage_repo.list_usage_totals().await?;
 
@@ -89,8 +107,200 @@ async fn persist_custom_data_usage(context: &ApiContext) -> Result<i64, DbError>
     result
 }
 
+// Count a page view in its hour, by referrer host, UTM parameters, device and country
+pub fn record_hourly_view(
+    context: &ApiContext,
+    site_id: &str,
+    site_host: &str,
+    headers: &HeaderMap,
+    dto: &RecordPageViewDto,
+) {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let country = context
+        .config
+        .country_header
+        .as_deref()
+        .and_then(|name| country_code(header(name)));
+    context.cache.hourly_usage.record(HourlyUsageKey {
+        site_id: site_id.to_string(),
+        hour: hour_start(Utc::now()),
+        route: dto.route.clone(),
+        referrer: referrer_host(dto.referrer.as_deref(), site_host),
+        utm_source: dimension_value(dto.utm_source.as_deref()),
+        utm_medium: dimension_value(dto.utm_medium.as_deref()),
+        utm_campaign: dimension_value(dto.utm_campaign.as_deref()),
+        device: device_class(header(USER_AGENT.as_str())),
+        country,
+    });
+}
+
+// Persist hourly page views aggregated since the last run. Buckets are put back when
+// saving fails, and retried on the next run
+pub async fn persist_hourly_usage_helper(context: ApiContext) -> () {
+    let buckets = context.cache.hourly_usage.drain();
+    if buckets.is_empty() {
+        return;
+    }
+    if let Err(e) = context.usage_repo.insert_hourly_usage(&buckets).await {
+        error!(
+            err = e.to_string(),
+            "Failed to save hourly site usage to DB"
+        );
+        context.cache.hourly_usage.restore(buckets);
+        return;
+    }
+    info!("Persisted {} hourly site usage buckets", buckets.len());
+}
+
+// Redirect hits are counted in memory, so serving a redirect does not write to the site DB
+pub async fn persist_redirect_hits_helper(context: ApiContext) -> () {
+    let sites = context.cache.redirect_hits.drain();
+    let mut persisted = 0;
+    for (site_id, counts) in sites {
+        match context
+            .redirect_repo
+            .add_hit_counts(&site_id, &counts)
+            .await
+        {
+            Ok(()) => persisted += counts.len(),
+            // The site was deleted
+            Err(DbError::NoDb(_)) => {}
+            Err(e) => {
+                error!(
+                    err = e.to_string(),
+                    "Failed to save redirect hits of site {} to DB", site_id
+                );
+                context.cache.redirect_hits.restore(&site_id, counts);
+            }
+        }
+    }
+    if persisted > 0 {
+        info!("Persisted hits of {} redirects", persisted);
+    }
+}
+
+// Salt of the day's visitor hashes. Loaded from the metadata DB, so every server and restart
+// hashes a visitor the same way
+async fn visitor_salt(context: &ApiContext, day: NaiveDate) -> Result<Vec<u8>, DbError> {
+    if let Some(salt) = context.cache.unique_visitors.salt(day) {
+        return Ok(salt);
+    }
+    let expires_at = (day + Days::new(1)).and_time(NaiveTime::MIN).and_utc();
+    let salt = context
+        .usage_repo
+        .get_or_create_visitor_salt(day, &Uuid::new_v4().into_bytes(), expires_at)
+        .await?;
+    context.cache.unique_visitors.set_salt(day, salt.clone());
+    Ok(salt)
+}
+
+// Count the visitor of a site view in today's unique visitors. Bots and requests without
+// a visitor address are not counted
+pub async fn record_unique_visitor(
+    context: &ApiContext,
+    site_id: &str,
+    headers: &HeaderMap,
+    visitor: Option<IpAddr>,
+) {
+    let Some(ip) = visitor else {
+        return;
+    };
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if device_class(Some(user_agent)) == DeviceClass::Bot {
+        return;
+    }
+    let day = Utc::now().date_naive();
+    let salt = match visitor_salt(context, day).await {
+        Ok(salt) => salt,
+        Err(e) => {
+            error!(err = e.to_string(), "Failed to load the visitor salt");
+            return;
+        }
+    };
+    context
+        .cache
+        .unique_visitors
+        .record(site_id, day, &salt, ip, user_agent);
+}
+
+// Unique visitors of today and visitor-days of this month, from persisted sketches and
+// visitors since the last persist
+pub async fn unique_visitor_counts(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<UniqueVisitorCounts, ApiError> {
+    let today = Utc::now().date_naive();
+    let month_start = today.with_day(1).unwrap_or(today);
+    let saved = context
+        .usage_repo
+        .list_visitor_sketches(site_id, month_start, today)
+        .await
+        .map_err(|e| ApiError::internal_error().message(e))?;
+
+    let mut daily = context
+        .cache
+        .unique_visitors
+        .sketch(site_id, today)
+        .unwrap_or_default();
+    let mut monthly = daily.clone();
+    for (day, sketch) in &saved {
+        monthly.merge(sketch);
+        if *day == today {
+            daily.merge(sketch);
+        }
+    }
+    for day in month_start.iter_days().take_while(|day| *day < today) {
+        if let Some(sketch) = context.cache.unique_visitors.sketch(site_id, day) {
+            monthly.merge(&sketch);
+        }
+    }
+    Ok(UniqueVisitorCounts {
+        daily: daily.estimate(),
+        monthly_visitor_days: monthly.estimate(),
+    })
+}
+
+// Merge unique visitor sketches recorded since the last run into the saved sketches.
+// Sketches are put back when saving fails, and retried on the next run
+pub async fn persist_visitor_sketches_helper(context: ApiContext) -> () {
+    let sketches = context.cache.unique_visitors.drain();
+    if sketches.is_empty() {
+        return;
+    }
+    if let Err(e) = context.usage_repo.merge_visitor_sketches(&sketches).await {
+        error!(
+            err = e.to_string(),
+            "Failed to save unique visitor sketches to DB"
+        );
+        context.cache.unique_visitors.restore(sketches);
+        return;
+    }
+    info!("Persisted {} unique visitor sketches", sketches.len());
+}
+
 pub async fn persist_usage_helper(context: ApiContext) -> () {
     let cache_length = context.cache.cache.weighted_size();
+    let retention_start = Utc::now().date_naive() - Days::new(VISITOR_SKETCH_RETENTION_DAYS);
+    if let Err(e) = context
+        .usage_repo
+        .delete_visitor_sketches_before(retention_start)
+        .await
+    {
+        error!(err = e.to_string(), "Failed to delete old visitor sketches");
+    }
+    if let Err(e) = context
+        .usage_repo
+        .delete_expired_visitor_salts(Utc::now())
+        .await
+    {
+        error!(
+            err = e.to_string(),
+            "Failed to delete expired visitor salts"
+        );
+    }
 
     if let Err(e) = context.usage_repo.insert_usage(&context.cache.cache).await {
         error!(err = e.to_string(), "Failed to save site usage to DB");
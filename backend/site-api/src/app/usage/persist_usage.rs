@@ -3,9 +3,16 @@ use lib_shared_site_api::error::api_error::ApiError;
 
 use crate::api_context::ApiContext;
 
-use super::helpers::persist_usage_helper;
+use super::helpers::{
+    persist_hourly_usage_helper, persist_redirect_hits_helper, persist_usage_helper,
+    persist_visitor_sketches_helper,
+};
 
+// Runs the usage persist jobs now, instead of waiting for their crons
 pub async fn persist_usage(State(context): State<ApiContext>) -> Result<(), ApiError> {
+    persist_hourly_usage_helper(context.clone()).await;
+    persist_visitor_sketches_helper(context.clone()).await;
+    persist_redirect_hits_helper(context.clone()).await;
     persist_usage_helper(context).await;
     Ok(())
 }
@@ -1,6 +1,8 @@
 /// App configuration
 ///
 /// Passed via command line, or environment variables.
+use std::net::{IpAddr, SocketAddr};
+
 use clap::Parser;
 use lib_shared_types::shared::core::ExecEnv;
 use sqlx::sqlite::SqliteJournalMode;
@@ -47,6 +49,75 @@ pub struct Config {
     #[clap(long, env = "SSG_URL")]
     pub ssg_url: Option<String>,
 
+    /// Secret used to sign visitor logins to password protected pages, shared by every
+    /// instance. Required outside dev, where a random secret is generated when unset.
+    #[clap(long, env = "SITE_ACCESS_SECRET")]
+    pub site_access_secret: Option<String>,
+
+    /// Nameserver used to verify custom domains, e.g. "127.0.0.1:5353".
+    /// The system resolver configuration is used when unset.
+    #[clap(long, env = "DNS_NAMESERVER")]
+    pub dns_nameserver: Option<SocketAddr>,
+
+    /// Host that custom domains may CNAME to instead of adding a TXT verification record
+    #[clap(long, env = "DOMAIN_CNAME_TARGET")]
+    pub domain_cname_target: Option<String>,
+
+    /// Comma separated addresses that custom domains may point to with A/AAAA records
+    #[clap(long, env = "DOMAIN_TARGET_IPS", value_delimiter = ',')]
+    pub domain_target_ips: Vec<IpAddr>,
+
+    /// Days a custom domain may fail verification before it is no longer served
+    #[clap(long, env = "DOMAIN_SUSPEND_DAYS", default_value_t = 7)]
+    pub domain_suspend_days: u32,
+
+    /// Port of the HTTPS listener serving verified custom domains, with certificates
+    /// obtained over ACME. TLS is disabled when unset.
+    #[clap(long, env = "TLS_PORT")]
+    pub tls_port: Option<u16>,
+
+    /// ACME directory URL. Set to a local Pebble server, e.g.
+    /// "https://127.0.0.1:14000/dir", for testing
+    #[clap(
+        long,
+        env = "ACME_DIRECTORY_URL",
+        default_value = "https://acme-v02.api.letsencrypt.org/directory"
+    )]
+    pub acme_directory_url: String,
+
+    /// Contact email of the ACME account, notified by the CA about expiring certificates
+    #[clap(long, env = "ACME_CONTACT_EMAIL")]
+    pub acme_contact_email: Option<String>,
+
+    /// PEM file of an extra root certificate trusted by the ACME client, e.g. Pebble's CA
+    #[clap(long, env = "ACME_CA_CERT")]
+    pub acme_ca_cert: Option<String>,
+
+    /// Directory storing the ACME account key and certificates.
+    /// Defaults to "db/certificates" in the app directory.
+    #[clap(long, env = "CERTIFICATE_DIR")]
+    pub certificate_dir: Option<String>,
+
+    /// Days before expiry that certificates are renewed
+    #[clap(long, env = "ACME_RENEW_DAYS", default_value_t = 30)]
+    pub acme_renew_days: u32,
+
+    /// Request header holding the visitor's country code, set by a proxy or CDN,
+    /// e.g. "cf-ipcountry". Page views are not counted by country when unset.
+    #[clap(long, env = "COUNTRY_HEADER")]
+    pub country_header: Option<String>,
+
+    /// Comma separated addresses of proxies in front of the API. X-Forwarded-For and
+    /// X-Real-IP are only read from requests these proxies forward. Must include platform-api,
+    /// whose static page requests are counted in unique visitors by their forwarded address.
+    #[clap(
+        long,
+        env = "TRUSTED_PROXIES",
+        value_delimiter = ',',
+        default_value = "127.0.0.1,::1"
+    )]
+    pub trusted_proxies: Vec<IpAddr>,
+
     /// Public key used to verify Admin
     #[clap(long, env = "SITE_ADMIN_PUBLIC_KEY")]
     pub admin_public_key: String,
@@ -71,3 +142,10 @@ pub struct Config {
     #[clap(long, env = "MAILSENDER_API_KEY")]
     pub mailsender_api_key: String,
 }
+
+impl Config {
+    /// Secret signing visitor access, preview and experiment tokens. Set at startup
+    pub fn site_access_secret(&self) -> &str {
+        self.site_access_secret.as_deref().unwrap_or_default()
+    }
+}
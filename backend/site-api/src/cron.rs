@@ -4,7 +4,14 @@ use crate::{
     api_context::ApiContext,
     app::{
         backup::backup_sites::backup_sites_helper,
-        usage::helpers::{persist_usage_helper, reset_cache_helper},
+        certificate::renew_certificates::renew_certificates_helper,
+        domain_verification::run_domain_verifications::run_domain_verifications_helper,
+        publish::run_publish_schedules::run_publish_schedules_helper,
+        site::prune_site_versions::prune_site_versions_helper,
+        usage::helpers::{
+            persist_hourly_usage_helper, persist_redirect_hits_helper, persist_usage_helper,
+            persist_visitor_sketches_helper, reset_cache_helper,
+        },
     },
 };
 
@@ -26,10 +33,53 @@ pub fn setup_cron_jobs(context: &ApiContext) {
         persist_usage_helper(job_context_clone.clone())
     }));
 
+    // Hourly page view analytics, unique visitors and redirect hits cron
+    // every 10 minutes: "0 */10 * * * *"
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 */10 * * * *", move || {
+        persist_hourly_usage_helper(job_context.clone())
+    }));
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 */10 * * * *", move || {
+        persist_visitor_sketches_helper(job_context.clone())
+    }));
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 */10 * * * *", move || {
+        persist_redirect_hits_helper(job_context.clone())
+    }));
+
     // Monthly reset site usage cron
     // At 00:01:00am, on the 1st day, every month between January and December
     let job_context = context.clone();
     scheduler.add(Job::new("0 1 0 1 1-12 ? *", move || {
         reset_cache_helper(job_context.clone())
     }));
+
+    // Scheduled publish cron
+    // every minute: "0 * * * * *"
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 * * * * *", move || {
+        run_publish_schedules_helper(job_context.clone())
+    }));
+
+    // Site version retention cron
+    // prune every day at 3am: "0 0 3 * * *"
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 0 3 * * *", move || {
+        prune_site_versions_helper(job_context.clone())
+    }));
+
+    // Custom domain verification cron
+    // every hour: "0 0 * * * *"
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 0 * * * *", move || {
+        run_domain_verifications_helper(job_context.clone())
+    }));
+
+    // ACME certificate renewal cron
+    // every hour at half past: "0 30 * * * *"
+    let job_context = context.clone();
+    scheduler.add(Job::new("0 30 * * * *", move || {
+        renew_certificates_helper(job_context.clone())
+    }));
 }
@@ -1,10 +1,23 @@
 use std::collections::{HashMap, HashSet};
 
 use axum::http::HeaderMap;
+use chrono::Utc;
+use lib_shared_site_api::auth::verify_jwt::verify_preview_token;
 use lib_shared_site_api::cache::cache_helpers::{
-    get_site_defaults, get_site_description, get_site_title,
+    get_page_heads, get_site_cache_control, get_site_defaults, get_site_description,
+    get_site_home_page, get_site_image, get_site_locales, get_site_title, get_site_trailing_slash,
 };
-use lib_shared_site_api::util::domains::{domain_without_port, origin_domain};
+use lib_shared_site_api::util::domains::{
+    alias_redirect_host, canonical_host, domain_without_port, origin_domain, site_base_url,
+    wildcard_pattern,
+};
+use lib_shared_site_api::util::experiments::{Experiment, SiteExperiments};
+use lib_shared_site_api::util::maintenance::SiteMaintenance;
+use lib_shared_site_api::util::redirects::{validate_redirect, RedirectRule, SiteRedirects};
+use lib_shared_site_api::util::site_access::{
+    password_fingerprint, preview_token, ProtectedRoute, SiteProtections,
+};
+use lib_shared_site_api::util::site_seo::{build_site_seo_files, SiteSeoFiles};
 use lib_shared_site_api::{cache::cache::SiteMetadata, error::api_error::ApiError};
 use lib_shared_types::cache::site_data::{CachedSiteData, CachedSiteHead};
 use lib_shared_types::dto::site_api::get_current_site_dto::GetCurrentSiteResponse;
@@ -13,14 +26,24 @@ use lib_shared_types::{
     dto::site_api::get_current_site_dto::to_api_response, error::api_error::ApiErrorCode,
 };
 use serde_json::{Map, Value};
+use tracing::warn;
 
 use crate::api_context::ApiContext;
 
 pub fn site_response_to_cached(site: GetCurrentSiteResponse) -> CachedSiteData {
     let defaults = get_site_defaults(&site);
+    let title = get_site_title(&defaults, &site.name);
+    let description = get_site_description(&defaults, &site.name);
+    let image = get_site_image(&defaults);
     let meta = CachedSiteHead {
-        title: get_site_title(&defaults, &site.name),
-        description: get_site_description(&defaults, &site.name),
+        pages: get_page_heads(&site, &title, &description, image.as_deref()),
+        home_page: get_site_home_page(&defaults),
+        title,
+        description,
+        image,
+        cache_control: get_site_cache_control(&defaults),
+        trailing_slash: get_site_trailing_slash(&defaults),
+        locales: get_site_locales(&defaults),
     };
     CachedSiteData { site, meta }
 }
@@ -48,6 +71,7 @@ pub fn site_to_value(site: &SiteEntity, site_id: &str) -> Result<Value, serde_js
         pages: filtered_pages,
         published: site.published,
         content_updated_at: site.content_updated_at,
+        subdomain: None,
     };
     let cached_site = site_response_to_cached(response);
     Ok(serde_json::to_value(cached_site)?)
@@ -106,17 +130,55 @@ pub async fn get_pages_from_cache_or_repo(
     Ok(pages)
 }
 
+// Resolve a preview link, falling back to the legacy `preview_id` column of site_versions.
+// Password protected links are unlocked by a preview token, see unlock_preview_link
+async fn get_preview_site(
+    context: &ApiContext,
+    site_id: &str,
+    preview_id: &str,
+    headers: &HeaderMap,
+) -> Result<SiteEntity, ApiError> {
+    let Ok(link) = context
+        .preview_link_repo
+        .get_link(site_id, preview_id)
+        .await
+    else {
+        return context
+            .site_repo
+            .get_site_by_preview_id(site_id, preview_id)
+            .await
+            .map_err(|_| ApiError::not_found().message("Site preview not found"));
+    };
+
+    if link.is_expired(Utc::now()) {
+        return Err(ApiError::not_found()
+            .code(ApiErrorCode::PreviewExpired)
+            .message("Site preview has expired"));
+    }
+    if let Some(password_hash) = &link.password_hash {
+        let secret = context.config.site_access_secret();
+        let fingerprint = preview_token(headers)
+            .and_then(|token| verify_preview_token(secret, site_id, preview_id, token));
+        if fingerprint != Some(password_fingerprint(password_hash)) {
+            return Err(ApiError::unauthorized().code(ApiErrorCode::PreviewPasswordRequired));
+        }
+    }
+
+    context
+        .site_repo
+        .get_site_by_version(site_id, &link.version_id.to_string())
+        .await
+        .map_err(|_| ApiError::not_found().message("Site preview not found"))
+}
+
 pub async fn get_site_or_preview(
     context: &ApiContext,
     site_id: &str,
     preview: Option<String>,
+    headers: &HeaderMap,
 ) -> Result<CachedSiteData, ApiError> {
     Ok(if let Some(preview_id) = preview {
-        let site = context
-            .site_repo
-            .get_site_by_preview_id(site_id, &preview_id)
-            .await
-            .map_err(|_| ApiError::not_found().message("Site preview not found"))?;
+        let site = get_preview_site(context, site_id, &preview_id, headers).await?;
         site_response_to_cached(to_api_response(&site, site_id))
     } else {
         // Get site from cache
@@ -146,23 +208,104 @@ pub async fn get_metadata_from_cache_or_repo(
                 owner_id: meta.owner_id,
                 site_type: meta.site_type,
                 disabled: meta.disabled,
+                canonical_host: canonical_host(&meta.domains, meta.www_policy),
+                base_url: site_base_url(&meta.domains, meta.www_policy, |d| d.verified),
+                alias_host: alias_redirect_host(&meta.domains, meta.www_policy),
+                domains: meta.domains,
             })
         })
         .await
 }
 
+// Sitemap and robots.txt of the published site. The base URL is the canonical host, or the
+// first verified domain
+pub async fn get_seo_files_from_cache_or_repo(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<SiteSeoFiles, ApiError> {
+    context
+        .cache
+        .get_seo_files_with(site_id, async move {
+            let meta = context
+                .metadata_repo
+                .get_site_metadata(site_id)
+                .await
+                .map_err(|e| ApiError::not_found().message(e))?;
+            let site = context
+                .site_repo
+                .get_site_latest_version(site_id, true)
+                .await
+                .map_err(|e| ApiError::not_found().message(e))?;
+            if !site.published || meta.disabled {
+                return Err(ApiError::not_found().code(ApiErrorCode::SiteUnpublished));
+            }
+            let base_url = site_base_url(&meta.domains, meta.www_policy, |d| d.verified);
+            let protections = get_protections_from_cache_or_repo(context, site_id).await?;
+            build_site_seo_files(&site, base_url.as_deref(), &protections)
+                .map_err(|e| ApiError::internal_error().message(e))
+        })
+        .await
+}
+
+// Enabled redirect rules of a site, in match order
+pub async fn get_redirects_from_cache_or_repo(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<SiteRedirects, ApiError> {
+    context
+        .cache
+        .get_redirects_with(site_id, async move {
+            let redirects = context
+                .redirect_repo
+                .list_enabled_redirects(site_id)
+                .await
+                .map_err(|e| ApiError::internal_error().message(e))?;
+            let rules = redirects
+                .into_iter()
+                .filter_map(|redirect| {
+                    // Rules are validated when saved, so this only skips corrupt rows
+                    let pattern = validate_redirect(&redirect.source, &redirect.target)
+                        .inspect_err(|e| warn!("Skipping redirect {}: {}", redirect.id, e))
+                        .ok()?;
+                    Some(RedirectRule {
+                        id: redirect.id,
+                        pattern,
+                        target: redirect.target,
+                        status_code: redirect.status_code as u16,
+                    })
+                })
+                .collect();
+            Ok(SiteRedirects::new(rules))
+        })
+        .await
+}
+
+// Hosts resolved by the repo are cached. Hosts matched by a wildcard domain are cached by
+// the wildcard pattern, so arbitrary subdomains don't each take a cache entry
 pub async fn get_site_id_by_domain_from_cache_or_repo(
     context: &ApiContext,
     domain: String,
 ) -> Result<String, ApiError> {
-    Ok(match context.cache.get_site_id_by_domain(&domain).await {
-        Some(site_id) => site_id,
-        None => context
-            .metadata_repo
-            .get_site_id_by_hostname(&domain)
-            .await
-            .map_err(|_| ApiError::bad_request().message("Error fetching site ID by hostname"))?,
-    })
+    if let Some(site_id) = context.cache.get_site_id_by_domain(&domain).await {
+        return Ok(site_id);
+    }
+    if let Some(pattern) = wildcard_pattern(&domain) {
+        if let Some(site_id) = context.cache.get_site_id_by_domain(&pattern).await {
+            return Ok(site_id);
+        }
+    }
+    let (site_id, matched) = context
+        .metadata_repo
+        .get_site_id_by_hostname(&domain)
+        .await
+        .map_err(|_| ApiError::bad_request().message("Error fetching site ID by hostname"))?;
+    let key = if matched.starts_with("*.") {
+        matched
+    } else {
+        domain
+    };
+    context.cache.insert_domain_mapping(&site_id, &key).await;
+    Ok(site_id)
 }
 
 // Resolve the site a browser request belongs to. Pages served through a platform subdomain
@@ -182,3 +325,68 @@ pub async fn get_site_id_by_host_or_origin(
         }
     }
 }
+
+// Password protected routes of a site, matched by longest prefix
+pub async fn get_protections_from_cache_or_repo(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<SiteProtections, ApiError> {
+    context
+        .cache
+        .get_protections_with(site_id, async move {
+            let routes = context
+                .protected_route_repo
+                .list_protected_routes(site_id)
+                .await
+                .map_err(|e| ApiError::internal_error().message(e))?;
+            Ok(SiteProtections::new(
+                routes
+                    .into_iter()
+                    .map(|route| ProtectedRoute {
+                        id: route.id,
+                        route: route.route,
+                        password_hash: route.password_hash,
+                    })
+                    .collect(),
+            ))
+        })
+        .await
+}
+
+// Maintenance settings of a site, the default (disabled) when never configured
+pub async fn get_maintenance_from_cache_or_repo(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<SiteMaintenance, ApiError> {
+    context
+        .cache
+        .get_maintenance_with(site_id, async move {
+            let maintenance = context
+                .maintenance_repo
+                .get_maintenance(site_id)
+                .await
+                .map_err(|e| ApiError::internal_error().message(e))?;
+            Ok(maintenance.map(SiteMaintenance::from).unwrap_or_default())
+        })
+        .await
+}
+
+// Enabled A/B experiments of a site
+pub async fn get_experiments_from_cache_or_repo(
+    context: &ApiContext,
+    site_id: &str,
+) -> Result<SiteExperiments, ApiError> {
+    context
+        .cache
+        .get_experiments_with(site_id, async move {
+            let experiments = context
+                .experiment_repo
+                .list_enabled_experiments(site_id)
+                .await
+                .map_err(|e| ApiError::internal_error().message(e))?;
+            Ok(SiteExperiments::new(
+                experiments.into_iter().map(Experiment::from).collect(),
+            ))
+        })
+        .await
+}
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::util::domain_verification::{is_local_domain, VerificationState};
+use lib_shared_types::entity::site_api::domain_verification_entity::DomainVerificationEntity;
+use sqlx::{sqlite::SqliteRow, Error, Row, SqlitePool};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::site_db_pool_manager::SqlitePoolConnection;
+
+pub type DynDomainVerificationRepo = Arc<dyn DomainVerificationRepoTrait + Send + Sync>;
+
+pub struct DomainCheckResult<'a> {
+    pub state: &'a VerificationState,
+    pub checked_at: DateTime<Utc>,
+    pub last_result: Option<&'a str>,
+    pub last_error: Option<&'a str>,
+}
+
+#[async_trait]
+pub trait DomainVerificationRepoTrait {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error>;
+    // Issue tokens for new custom domains, and remove verifications of removed domains
+    async fn sync_verifications(&self) -> Result<(), Error>;
+    async fn list_verifications(&self) -> Result<Vec<DomainVerificationEntity>, Error>;
+    async fn list_verifications_by_site_id(
+        &self,
+        site_id: &str,
+    ) -> Result<Vec<DomainVerificationEntity>, Error>;
+    // Save a check, and the domain's verified flag
+    async fn save_check(
+        &self,
+        domain: &str,
+        result: DomainCheckResult<'_>,
+    ) -> Result<DomainVerificationEntity, Error>;
+}
+
+pub struct DomainVerificationRepo {
+    pub metadata_db_pool: SqlitePool,
+}
+
+fn row_to_domain_verification(row: SqliteRow) -> Result<DomainVerificationEntity, Error> {
+    Ok(DomainVerificationEntity {
+        domain: row.try_get("domain")?,
+        site_id: row.try_get("site_id")?,
+        token: row.try_get("token")?,
+        status: row.try_get("status")?,
+        verified: row.try_get("verified")?,
+        last_checked_at: row.try_get("last_checked_at")?,
+        last_result: row.try_get("last_result")?,
+        last_error: row.try_get("last_error")?,
+        failing_since: row.try_get("failing_since")?,
+        failed_checks: row.try_get("failed_checks")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl DomainVerificationRepoTrait for DomainVerificationRepo {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error> {
+        Ok(self.metadata_db_pool.acquire().await?)
+    }
+
+    async fn sync_verifications(&self) -> Result<(), Error> {
+        let mut tx = self.metadata_db_pool.begin().await?;
+
+        // A domain moved to another site is verified again
+        sqlx::query(
+            r#"
+            DELETE FROM domain_verifications
+            WHERE NOT EXISTS (
+                SELECT 1 FROM domains d
+                WHERE d.domain = domain_verifications.domain
+                    AND d.site_id = domain_verifications.site_id
+            )
+        "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let unverified: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT domain, site_id FROM domains
+            WHERE domain NOT IN (SELECT domain FROM domain_verifications)
+        "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (domain, site_id) in unverified {
+            if is_local_domain(&domain) {
+                continue;
+            }
+            // Another site may have claimed the domain in this sync, the first claim is verified
+            let result = sqlx::query(
+                r#"
+                INSERT INTO domain_verifications(domain, site_id, token)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(domain) DO NOTHING
+            "#,
+            )
+            .bind(&domain)
+            .bind(&site_id)
+            .bind(Uuid::new_v4().simple().to_string())
+            .execute(&mut *tx)
+            .await?;
+            if result.rows_affected() == 0 {
+                warn!(
+                    "Domain {} of site {} is already being verified for another site",
+                    domain, site_id
+                );
+            }
+        }
+
+        tx.commit().await
+    }
+
+    async fn list_verifications(&self) -> Result<Vec<DomainVerificationEntity>, Error> {
+        sqlx::query("SELECT * FROM domain_verifications ORDER BY site_id, domain")
+            .try_map(row_to_domain_verification)
+            .fetch_all(&mut *self.get_db_conn().await?)
+            .await
+    }
+
+    async fn list_verifications_by_site_id(
+        &self,
+        site_id: &str,
+    ) -> Result<Vec<DomainVerificationEntity>, Error> {
+        sqlx::query("SELECT * FROM domain_verifications WHERE site_id = ? ORDER BY domain")
+            .bind(site_id)
+            .try_map(row_to_domain_verification)
+            .fetch_all(&mut *self.get_db_conn().await?)
+            .await
+    }
+
+    async fn save_check(
+        &self,
+        domain: &str,
+        result: DomainCheckResult<'_>,
+    ) -> Result<DomainVerificationEntity, Error> {
+        let mut tx = self.metadata_db_pool.begin().await?;
+
+        let verification = sqlx::query(
+            r#"
+            UPDATE domain_verifications
+            SET status = ?1, verified = ?2, failing_since = ?3, last_checked_at = ?4,
+                last_result = ?5, last_error = ?6, failed_checks = ?7
+            WHERE domain = ?8
+            RETURNING *
+        "#,
+        )
+        .bind(result.state.status.to_string())
+        .bind(result.state.verified)
+        .bind(result.state.failing_since)
+        .bind(result.checked_at)
+        .bind(result.last_result)
+        .bind(result.last_error)
+        .bind(result.state.failed_checks)
+        .bind(domain)
+        .try_map(row_to_domain_verification)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE domains SET verified = ?1 WHERE domain = ?2")
+            .bind(result.state.verified)
+            .bind(domain)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(verification)
+    }
+}
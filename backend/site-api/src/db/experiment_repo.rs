@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lib_shared_site_api::db::db_error::{map_sqlx_err, DbError};
+use lib_shared_types::entity::site_api::experiment_entity::{
+    ExperimentEntity, ExperimentVariantEntity,
+};
+use sqlx::{sqlite::SqliteRow, Error, Row, SqliteConnection};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynExperimentRepo = Arc<dyn ExperimentRepoTrait + Send + Sync>;
+
+pub struct ExperimentVariantFields {
+    pub name: String,
+    pub page_route: String,
+    pub weight: i64,
+}
+
+// Values of an experiment written by create
+pub struct ExperimentFields {
+    pub route: String,
+    pub name: String,
+    pub enabled: bool,
+    pub conversion_table: Option<String>,
+    pub variants: Vec<ExperimentVariantFields>,
+}
+
+// Values of an experiment written by update, variants are fixed once created
+pub struct ExperimentUpdateFields {
+    pub name: String,
+    pub enabled: bool,
+    pub conversion_table: Option<String>,
+}
+
+#[async_trait]
+pub trait ExperimentRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    async fn create_experiment(
+        &self,
+        id: &str,
+        fields: ExperimentFields,
+    ) -> Result<ExperimentEntity, DbError>;
+    async fn list_experiments(&self, id: &str) -> Result<Vec<ExperimentEntity>, DbError>;
+    async fn list_enabled_experiments(&self, id: &str) -> Result<Vec<ExperimentEntity>, DbError>;
+    async fn get_experiment(
+        &self,
+        id: &str,
+        experiment_id: i64,
+    ) -> Result<ExperimentEntity, DbError>;
+    async fn update_experiment(
+        &self,
+        id: &str,
+        experiment_id: i64,
+        fields: ExperimentUpdateFields,
+    ) -> Result<ExperimentEntity, DbError>;
+    async fn delete_experiment(&self, id: &str, experiment_id: i64) -> Result<(), DbError>;
+    async fn increment_exposures(&self, id: &str, variant_id: i64) -> Result<(), DbError>;
+    async fn increment_conversions(&self, id: &str, variant_id: i64) -> Result<(), DbError>;
+}
+
+pub struct ExperimentRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn map_to_experiment_entity(row: SqliteRow) -> Result<ExperimentEntity, Error> {
+    Ok(ExperimentEntity {
+        id: row.try_get("id")?,
+        route: row.try_get("route")?,
+        name: row.try_get("name")?,
+        enabled: row.try_get("enabled")?,
+        conversion_table: row.try_get("conversion_table")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+        variants: vec![],
+    })
+}
+
+fn map_to_variant_entity(row: SqliteRow) -> Result<ExperimentVariantEntity, Error> {
+    Ok(ExperimentVariantEntity {
+        id: row.try_get("id")?,
+        experiment_id: row.try_get("experiment_id")?,
+        name: row.try_get("name")?,
+        page_route: row.try_get("page_route")?,
+        weight: row.try_get("weight")?,
+        exposures: row.try_get("exposures")?,
+        conversions: row.try_get("conversions")?,
+    })
+}
+
+// Routes are unique per site
+fn map_experiment_sqlx_err(e: sqlx::Error) -> DbError {
+    match &e {
+        sqlx::Error::Database(err) if err.is_unique_violation() => {
+            DbError::Unique("Experiment route".into())
+        }
+        _ => map_sqlx_err(e),
+    }
+}
+
+// Attach variants to experiments, in creation order
+async fn with_variants(
+    conn: &mut SqliteConnection,
+    mut experiments: Vec<ExperimentEntity>,
+) -> Result<Vec<ExperimentEntity>, DbError> {
+    let variants = sqlx::query("SELECT * FROM experiment_variants ORDER BY id")
+        .try_map(map_to_variant_entity)
+        .fetch_all(&mut *conn)
+        .await?;
+    for variant in variants {
+        if let Some(experiment) = experiments
+            .iter_mut()
+            .find(|experiment| experiment.id == variant.experiment_id)
+        {
+            experiment.variants.push(variant);
+        }
+    }
+    Ok(experiments)
+}
+
+async fn get_experiment_by_id(
+    conn: &mut SqliteConnection,
+    experiment_id: i64,
+) -> Result<ExperimentEntity, DbError> {
+    let mut experiment = sqlx::query("SELECT * FROM experiments WHERE id = ?")
+        .bind(experiment_id)
+        .try_map(map_to_experiment_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_sqlx_err)?;
+    experiment.variants =
+        sqlx::query("SELECT * FROM experiment_variants WHERE experiment_id = ? ORDER BY id")
+            .bind(experiment_id)
+            .try_map(map_to_variant_entity)
+            .fetch_all(&mut *conn)
+            .await?;
+    Ok(experiment)
+}
+
+#[async_trait]
+impl ExperimentRepoTrait for ExperimentRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn create_experiment(
+        &self,
+        id: &str,
+        fields: ExperimentFields,
+    ) -> Result<ExperimentEntity, DbError> {
+        let mut tx = self
+            .db_pool_manager
+            .start_transaction(id, &self.manifest_dir)
+            .await?;
+
+        let experiment_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO experiments(route, name, enabled, conversion_table)
+            VALUES (?1, ?2, ?3, ?4)
+            RETURNING id
+        "#,
+        )
+        .bind(fields.route)
+        .bind(fields.name)
+        .bind(fields.enabled)
+        .bind(fields.conversion_table)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_experiment_sqlx_err)?;
+
+        for variant in fields.variants {
+            sqlx::query(
+                r#"
+                INSERT INTO experiment_variants(experiment_id, name, page_route, weight)
+                VALUES (?1, ?2, ?3, ?4)
+            "#,
+            )
+            .bind(experiment_id)
+            .bind(variant.name)
+            .bind(variant.page_route)
+            .bind(variant.weight)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let experiment = get_experiment_by_id(&mut tx, experiment_id).await?;
+        tx.commit().await?;
+        Ok(experiment)
+    }
+
+    async fn list_experiments(&self, id: &str) -> Result<Vec<ExperimentEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let experiments = sqlx::query("SELECT * FROM experiments ORDER BY id")
+            .try_map(map_to_experiment_entity)
+            .fetch_all(&mut *conn)
+            .await?;
+        with_variants(&mut conn, experiments).await
+    }
+
+    async fn list_enabled_experiments(&self, id: &str) -> Result<Vec<ExperimentEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let experiments = sqlx::query("SELECT * FROM experiments WHERE enabled = 1 ORDER BY id")
+            .try_map(map_to_experiment_entity)
+            .fetch_all(&mut *conn)
+            .await?;
+        with_variants(&mut conn, experiments).await
+    }
+
+    async fn get_experiment(
+        &self,
+        id: &str,
+        experiment_id: i64,
+    ) -> Result<ExperimentEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+        get_experiment_by_id(&mut conn, experiment_id).await
+    }
+
+    async fn update_experiment(
+        &self,
+        id: &str,
+        experiment_id: i64,
+        fields: ExperimentUpdateFields,
+    ) -> Result<ExperimentEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE experiments
+            SET name = ?1, enabled = ?2, conversion_table = ?3, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?4
+        "#,
+        )
+        .bind(fields.name)
+        .bind(fields.enabled)
+        .bind(fields.conversion_table)
+        .bind(experiment_id)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::EntityNotFound());
+        }
+        get_experiment_by_id(&mut conn, experiment_id).await
+    }
+
+    async fn delete_experiment(&self, id: &str, experiment_id: i64) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let result = sqlx::query("DELETE FROM experiments WHERE id = ?")
+            .bind(experiment_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::EntityNotFound());
+        }
+        Ok(())
+    }
+
+    async fn increment_exposures(&self, id: &str, variant_id: i64) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("UPDATE experiment_variants SET exposures = exposures + 1 WHERE id = ?")
+            .bind(variant_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn increment_conversions(&self, id: &str, variant_id: i64) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("UPDATE experiment_variants SET conversions = conversions + 1 WHERE id = ?")
+            .bind(variant_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
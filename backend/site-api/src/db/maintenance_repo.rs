@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::db::db_error::{map_sqlx_err, DbError};
+use lib_shared_types::entity::site_api::maintenance_entity::{MaintenanceEntity, MaintenancePage};
+use sqlx::{sqlite::SqliteRow, Error, Row};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynMaintenanceRepo = Arc<dyn MaintenanceRepoTrait + Send + Sync>;
+
+// Maintenance settings written by the owner, replacing the previous settings
+pub struct MaintenanceFields {
+    pub enabled: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub page: MaintenancePage,
+    pub message: Option<String>,
+    pub custom_page: Option<String>,
+    pub allowed_ips: Vec<String>,
+    pub bypass_token: String,
+}
+
+#[async_trait]
+pub trait MaintenanceRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    // None when the site has never configured maintenance
+    async fn get_maintenance(&self, id: &str) -> Result<Option<MaintenanceEntity>, DbError>;
+    async fn save_maintenance(
+        &self,
+        id: &str,
+        fields: MaintenanceFields,
+    ) -> Result<MaintenanceEntity, DbError>;
+}
+
+pub struct MaintenanceRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn map_to_maintenance_entity(row: SqliteRow) -> Result<MaintenanceEntity, Error> {
+    let allowed_ips: String = row.try_get("allowed_ips")?;
+    Ok(MaintenanceEntity {
+        enabled: row.try_get("enabled")?,
+        starts_at: row.try_get("starts_at")?,
+        ends_at: row.try_get("ends_at")?,
+        page: row.try_get("page")?,
+        message: row.try_get("message")?,
+        custom_page: row.try_get("custom_page")?,
+        allowed_ips: serde_json::from_str(&allowed_ips).map_err(|e| Error::Decode(Box::new(e)))?,
+        bypass_token: row.try_get("bypass_token")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[async_trait]
+impl MaintenanceRepoTrait for MaintenanceRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn get_maintenance(&self, id: &str) -> Result<Option<MaintenanceEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query("SELECT * FROM maintenance WHERE id = 1")
+            .try_map(map_to_maintenance_entity)
+            .fetch_optional(&mut *conn)
+            .await?)
+    }
+
+    async fn save_maintenance(
+        &self,
+        id: &str,
+        fields: MaintenanceFields,
+    ) -> Result<MaintenanceEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO maintenance(
+                id, enabled, starts_at, ends_at, page, message, custom_page, allowed_ips,
+                bypass_token
+            )
+            VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(id) DO UPDATE SET
+                enabled = excluded.enabled, starts_at = excluded.starts_at,
+                ends_at = excluded.ends_at, page = excluded.page, message = excluded.message,
+                custom_page = excluded.custom_page, allowed_ips = excluded.allowed_ips,
+                bypass_token = excluded.bypass_token, updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+        "#,
+        )
+        .bind(fields.enabled)
+        .bind(fields.starts_at)
+        .bind(fields.ends_at)
+        .bind(fields.page)
+        .bind(fields.message)
+        .bind(fields.custom_page)
+        .bind(serde_json::json!(fields.allowed_ips).to_string())
+        .bind(fields.bypass_token)
+        .try_map(map_to_maintenance_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_sqlx_err)
+    }
+}
@@ -2,7 +2,16 @@ pub mod backup_repo;
 pub mod custom_data_info_repo;
 pub mod custom_data_repo;
 pub mod db_cache_layer;
+pub mod domain_verification_repo;
+pub mod experiment_repo;
+pub mod maintenance_repo;
+pub mod preview_link_repo;
+pub mod protected_route_repo;
+pub mod publish_schedule_repo;
+pub mod redirect_repo;
 pub mod site_db_pool_manager;
 pub mod site_repo;
 pub mod sites_metadata_repo;
+pub mod static_build_repo;
+pub mod template_repo;
 pub mod usage_repo;
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lib_shared_site_api::db::db_error::{map_sqlx_err, DbError};
+use lib_shared_types::entity::site_api::preview_link_entity::PreviewLinkEntity;
+use sqlx::{sqlite::SqliteRow, Error, Row};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynPreviewLinkRepo = Arc<dyn PreviewLinkRepoTrait + Send + Sync>;
+
+#[async_trait]
+pub trait PreviewLinkRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    async fn create_link(
+        &self,
+        id: &str,
+        version_id: i64,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+    ) -> Result<PreviewLinkEntity, DbError>;
+    async fn list_links(&self, id: &str) -> Result<Vec<PreviewLinkEntity>, DbError>;
+    async fn get_link(&self, id: &str, link_id: &str) -> Result<PreviewLinkEntity, DbError>;
+    async fn delete_link(&self, id: &str, link_id: &str) -> Result<(), DbError>;
+    async fn increment_view_count(&self, id: &str, link_id: &str) -> Result<(), DbError>;
+}
+
+pub struct PreviewLinkRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn map_to_preview_link_entity(row: SqliteRow) -> Result<PreviewLinkEntity, Error> {
+    Ok(PreviewLinkEntity {
+        id: row.try_get("id")?,
+        version_id: row.try_get("version_id")?,
+        expires_at: row.try_get("expires_at")?,
+        password_hash: row.try_get("password_hash")?,
+        view_count: row.try_get("view_count")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl PreviewLinkRepoTrait for PreviewLinkRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn create_link(
+        &self,
+        id: &str,
+        version_id: i64,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+    ) -> Result<PreviewLinkEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query(
+            r#"
+            INSERT INTO preview_links(id, version_id, expires_at, password_hash)
+            VALUES (?1, ?2, ?3, ?4)
+            RETURNING *
+        "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(version_id)
+        .bind(expires_at)
+        .bind(password_hash)
+        .try_map(map_to_preview_link_entity)
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn list_links(&self, id: &str) -> Result<Vec<PreviewLinkEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query(
+            r#"
+            SELECT * FROM preview_links
+            ORDER BY created_at DESC, rowid DESC
+        "#,
+        )
+        .try_map(map_to_preview_link_entity)
+        .fetch_all(&mut *conn)
+        .await?)
+    }
+
+    async fn get_link(&self, id: &str, link_id: &str) -> Result<PreviewLinkEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("SELECT * FROM preview_links WHERE id = ?")
+            .bind(link_id)
+            .try_map(map_to_preview_link_entity)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(map_sqlx_err)
+    }
+
+    async fn delete_link(&self, id: &str, link_id: &str) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let result = sqlx::query("DELETE FROM preview_links WHERE id = ?")
+            .bind(link_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::EntityNotFound());
+        }
+        Ok(())
+    }
+
+    async fn increment_view_count(&self, id: &str, link_id: &str) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("UPDATE preview_links SET view_count = view_count + 1 WHERE id = ?")
+            .bind(link_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lib_shared_site_api::db::db_error::{map_sqlx_err, DbError};
+use lib_shared_types::entity::site_api::protected_route_entity::ProtectedRouteEntity;
+use sqlx::{sqlite::SqliteRow, Error, Row};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynProtectedRouteRepo = Arc<dyn ProtectedRouteRepoTrait + Send + Sync>;
+
+#[async_trait]
+pub trait ProtectedRouteRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    async fn create_protected_route(
+        &self,
+        id: &str,
+        route: &str,
+        password_hash: &str,
+    ) -> Result<ProtectedRouteEntity, DbError>;
+    async fn list_protected_routes(&self, id: &str) -> Result<Vec<ProtectedRouteEntity>, DbError>;
+    async fn get_protected_route(
+        &self,
+        id: &str,
+        route_id: i64,
+    ) -> Result<ProtectedRouteEntity, DbError>;
+    async fn update_protected_route(
+        &self,
+        id: &str,
+        route_id: i64,
+        route: &str,
+        password_hash: &str,
+    ) -> Result<ProtectedRouteEntity, DbError>;
+    async fn delete_protected_route(&self, id: &str, route_id: i64) -> Result<(), DbError>;
+}
+
+pub struct ProtectedRouteRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn map_to_protected_route_entity(row: SqliteRow) -> Result<ProtectedRouteEntity, Error> {
+    Ok(ProtectedRouteEntity {
+        id: row.try_get("id")?,
+        route: row.try_get("route")?,
+        password_hash: row.try_get("password_hash")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+// Routes are unique per site
+fn map_protected_route_sqlx_err(e: sqlx::Error) -> DbError {
+    match &e {
+        sqlx::Error::Database(err) if err.is_unique_violation() => {
+            DbError::Unique("Protected route".into())
+        }
+        _ => map_sqlx_err(e),
+    }
+}
+
+#[async_trait]
+impl ProtectedRouteRepoTrait for ProtectedRouteRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn create_protected_route(
+        &self,
+        id: &str,
+        route: &str,
+        password_hash: &str,
+    ) -> Result<ProtectedRouteEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO protected_routes(route, password_hash)
+            VALUES (?1, ?2)
+            RETURNING *
+        "#,
+        )
+        .bind(route)
+        .bind(password_hash)
+        .try_map(map_to_protected_route_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_protected_route_sqlx_err)
+    }
+
+    async fn list_protected_routes(&self, id: &str) -> Result<Vec<ProtectedRouteEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query("SELECT * FROM protected_routes ORDER BY id")
+            .try_map(map_to_protected_route_entity)
+            .fetch_all(&mut *conn)
+            .await?)
+    }
+
+    async fn get_protected_route(
+        &self,
+        id: &str,
+        route_id: i64,
+    ) -> Result<ProtectedRouteEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("SELECT * FROM protected_routes WHERE id = ?")
+            .bind(route_id)
+            .try_map(map_to_protected_route_entity)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(map_sqlx_err)
+    }
+
+    async fn update_protected_route(
+        &self,
+        id: &str,
+        route_id: i64,
+        route: &str,
+        password_hash: &str,
+    ) -> Result<ProtectedRouteEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE protected_routes
+            SET route = ?1, password_hash = ?2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?3
+            RETURNING *
+        "#,
+        )
+        .bind(route)
+        .bind(password_hash)
+        .bind(route_id)
+        .try_map(map_to_protected_route_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_protected_route_sqlx_err)
+    }
+
+    async fn delete_protected_route(&self, id: &str, route_id: i64) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let result = sqlx::query("DELETE FROM protected_routes WHERE id = ?")
+            .bind(route_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::EntityNotFound());
+        }
+        Ok(())
+    }
+}
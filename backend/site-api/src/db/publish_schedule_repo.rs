@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lib_shared_types::entity::site_api::publish_schedule_entity::{
+    PublishScheduleEntity, PublishScheduleStatus,
+};
+use sqlx::{sqlite::SqliteRow, Error, Row, SqlitePool};
+use std::sync::Arc;
+
+use super::site_db_pool_manager::SqlitePoolConnection;
+
+pub type DynPublishScheduleRepo = Arc<dyn PublishScheduleRepoTrait + Send + Sync>;
+
+#[async_trait]
+pub trait PublishScheduleRepoTrait {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error>;
+    async fn create_schedule(
+        &self,
+        site_id: &str,
+        publish_at: DateTime<Utc>,
+    ) -> Result<PublishScheduleEntity, Error>;
+    async fn list_schedules_by_site_id(
+        &self,
+        site_id: &str,
+    ) -> Result<Vec<PublishScheduleEntity>, Error>;
+    async fn get_schedule(&self, id: i64) -> Result<PublishScheduleEntity, Error>;
+    // Cancel a schedule that has not been claimed yet. Returns RowNotFound otherwise
+    async fn cancel_schedule(&self, id: i64) -> Result<PublishScheduleEntity, Error>;
+    // Atomically claim the earliest due schedule, including `Running` schedules whose last
+    // heartbeat is before `stale_before`, left by an instance that stopped without finishing them
+    async fn claim_due_schedule(
+        &self,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<PublishScheduleEntity>, Error>;
+    // Renew the lease of a claim. Returns false when the schedule was re-claimed
+    async fn renew_claim(&self, id: i64, claimed_at: DateTime<Utc>) -> Result<bool, Error>;
+    // Finish a schedule, if the claim still holds its lease. Returns false otherwise
+    async fn finish_schedule(
+        &self,
+        id: i64,
+        claimed_at: DateTime<Utc>,
+        status: PublishScheduleStatus,
+        error: Option<String>,
+    ) -> Result<bool, Error>;
+}
+
+pub struct PublishScheduleRepo {
+    pub metadata_db_pool: SqlitePool,
+}
+
+fn row_to_publish_schedule(row: SqliteRow) -> Result<PublishScheduleEntity, Error> {
+    Ok(PublishScheduleEntity {
+        id: row.try_get("id")?,
+        site_id: row.try_get("site_id")?,
+        publish_at: row.try_get("publish_at")?,
+        status: row.try_get("status")?,
+        error: row.try_get("error")?,
+        claimed_at: row.try_get("claimed_at")?,
+        completed_at: row.try_get("completed_at")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[async_trait]
+impl PublishScheduleRepoTrait for PublishScheduleRepo {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error> {
+        Ok(self.metadata_db_pool.acquire().await?)
+    }
+
+    async fn create_schedule(
+        &self,
+        site_id: &str,
+        publish_at: DateTime<Utc>,
+    ) -> Result<PublishScheduleEntity, Error> {
+        let schedule = sqlx::query(
+            r#"
+          INSERT INTO publish_schedules(site_id, publish_at, status)
+          VALUES (?1, ?2, ?3)
+          RETURNING *
+        "#,
+        )
+        .bind(site_id)
+        .bind(publish_at)
+        .bind(PublishScheduleStatus::Pending.to_string())
+        .try_map(row_to_publish_schedule)
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn list_schedules_by_site_id(
+        &self,
+        site_id: &str,
+    ) -> Result<Vec<PublishScheduleEntity>, Error> {
+        let schedules = sqlx::query(
+            r#"
+        SELECT * FROM publish_schedules
+        WHERE site_id = ?1
+        ORDER BY publish_at DESC
+    "#,
+        )
+        .bind(site_id)
+        .try_map(row_to_publish_schedule)
+        .fetch_all(&mut *self.get_db_conn().await?)
+        .await?;
+        Ok(schedules)
+    }
+
+    async fn get_schedule(&self, id: i64) -> Result<PublishScheduleEntity, Error> {
+        let schedule = sqlx::query(
+            r#"
+            SELECT * FROM publish_schedules WHERE id = ?
+        "#,
+        )
+        .bind(id)
+        .try_map(row_to_publish_schedule)
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn cancel_schedule(&self, id: i64) -> Result<PublishScheduleEntity, Error> {
+        let schedule = sqlx::query(
+            r#"
+            UPDATE publish_schedules SET status = ?2
+            WHERE id = ?1 AND status = ?3
+            RETURNING *
+        "#,
+        )
+        .bind(id)
+        .bind(PublishScheduleStatus::Cancelled.to_string())
+        .bind(PublishScheduleStatus::Pending.to_string())
+        .try_map(row_to_publish_schedule)
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn claim_due_schedule(
+        &self,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<PublishScheduleEntity>, Error> {
+        // A single UPDATE is atomic in SQLite, so concurrent instances never claim the same row
+        let schedule = sqlx::query(
+            r#"
+            UPDATE publish_schedules SET status = ?1, claimed_at = ?2, heartbeat_at = ?2
+            WHERE id = (
+                SELECT id FROM publish_schedules
+                WHERE (status = ?3 AND publish_at <= ?2)
+                  OR (status = ?1 AND COALESCE(heartbeat_at, claimed_at) <= ?4)
+                ORDER BY publish_at, id
+                LIMIT 1
+            )
+            RETURNING *
+        "#,
+        )
+        .bind(PublishScheduleStatus::Running.to_string())
+        .bind(now)
+        .bind(PublishScheduleStatus::Pending.to_string())
+        .bind(stale_before)
+        .try_map(row_to_publish_schedule)
+        .fetch_optional(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn renew_claim(&self, id: i64, claimed_at: DateTime<Utc>) -> Result<bool, Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE publish_schedules SET heartbeat_at = ?3
+            WHERE id = ?1 AND status = ?4 AND claimed_at = ?2
+        "#,
+        )
+        .bind(id)
+        .bind(claimed_at)
+        .bind(Utc::now())
+        .bind(PublishScheduleStatus::Running.to_string())
+        .execute(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn finish_schedule(
+        &self,
+        id: i64,
+        claimed_at: DateTime<Utc>,
+        status: PublishScheduleStatus,
+        error: Option<String>,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE publish_schedules SET status = ?3, error = ?4, completed_at = ?5
+            WHERE id = ?1 AND status = ?6 AND claimed_at = ?2
+        "#,
+        )
+        .bind(id)
+        .bind(claimed_at)
+        .bind(status.to_string())
+        .bind(error)
+        .bind(Utc::now())
+        .bind(PublishScheduleStatus::Running.to_string())
+        .execute(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lib_shared_site_api::{
+    db::db_error::{map_sqlx_err, DbError},
+    util::redirects::RedirectHitCount,
+};
+use lib_shared_types::entity::site_api::redirect_entity::RedirectEntity;
+use sqlx::{sqlite::SqliteRow, Error, Row};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynRedirectRepo = Arc<dyn RedirectRepoTrait + Send + Sync>;
+
+// Values of a redirect rule written by create and update
+pub struct RedirectFields {
+    pub source: String,
+    pub target: String,
+    pub status_code: u16,
+    pub enabled: bool,
+}
+
+#[async_trait]
+pub trait RedirectRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    async fn create_redirect(
+        &self,
+        id: &str,
+        fields: RedirectFields,
+    ) -> Result<RedirectEntity, DbError>;
+    async fn list_redirects(&self, id: &str) -> Result<Vec<RedirectEntity>, DbError>;
+    async fn list_enabled_redirects(&self, id: &str) -> Result<Vec<RedirectEntity>, DbError>;
+    async fn get_redirect(&self, id: &str, redirect_id: i64) -> Result<RedirectEntity, DbError>;
+    async fn update_redirect(
+        &self,
+        id: &str,
+        redirect_id: i64,
+        fields: RedirectFields,
+    ) -> Result<RedirectEntity, DbError>;
+    async fn delete_redirect(&self, id: &str, redirect_id: i64) -> Result<(), DbError>;
+    // Add hits counted in memory. Hits of deleted redirects are dropped
+    async fn add_hit_counts(
+        &self,
+        id: &str,
+        counts: &[(i64, RedirectHitCount)],
+    ) -> Result<(), DbError>;
+}
+
+pub struct RedirectRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn map_to_redirect_entity(row: SqliteRow) -> Result<RedirectEntity, Error> {
+    Ok(RedirectEntity {
+        id: row.try_get("id")?,
+        source: row.try_get("source")?,
+        target: row.try_get("target")?,
+        status_code: row.try_get("status_code")?,
+        enabled: row.try_get("enabled")?,
+        hit_count: row.try_get("hit_count")?,
+        last_hit_at: row.try_get("last_hit_at")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+// Sources are unique per site
+fn map_redirect_sqlx_err(e: sqlx::Error) -> DbError {
+    match &e {
+        sqlx::Error::Database(err) if err.is_unique_violation() => {
+            DbError::Unique("Redirect source".into())
+        }
+        _ => map_sqlx_err(e),
+    }
+}
+
+#[async_trait]
+impl RedirectRepoTrait for RedirectRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn create_redirect(
+        &self,
+        id: &str,
+        fields: RedirectFields,
+    ) -> Result<RedirectEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO redirects(source, target, status_code, enabled)
+            VALUES (?1, ?2, ?3, ?4)
+            RETURNING *
+        "#,
+        )
+        .bind(fields.source)
+        .bind(fields.target)
+        .bind(fields.status_code)
+        .bind(fields.enabled)
+        .try_map(map_to_redirect_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_redirect_sqlx_err)
+    }
+
+    async fn list_redirects(&self, id: &str) -> Result<Vec<RedirectEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query("SELECT * FROM redirects ORDER BY id")
+            .try_map(map_to_redirect_entity)
+            .fetch_all(&mut *conn)
+            .await?)
+    }
+
+    async fn list_enabled_redirects(&self, id: &str) -> Result<Vec<RedirectEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(
+            sqlx::query("SELECT * FROM redirects WHERE enabled = 1 ORDER BY id")
+                .try_map(map_to_redirect_entity)
+                .fetch_all(&mut *conn)
+                .await?,
+        )
+    }
+
+    async fn get_redirect(&self, id: &str, redirect_id: i64) -> Result<RedirectEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query("SELECT * FROM redirects WHERE id = ?")
+            .bind(redirect_id)
+            .try_map(map_to_redirect_entity)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(map_sqlx_err)
+    }
+
+    async fn update_redirect(
+        &self,
+        id: &str,
+        redirect_id: i64,
+        fields: RedirectFields,
+    ) -> Result<RedirectEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE redirects
+            SET source = ?1, target = ?2, status_code = ?3, enabled = ?4,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?5
+            RETURNING *
+        "#,
+        )
+        .bind(fields.source)
+        .bind(fields.target)
+        .bind(fields.status_code)
+        .bind(fields.enabled)
+        .bind(redirect_id)
+        .try_map(map_to_redirect_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_redirect_sqlx_err)
+    }
+
+    async fn delete_redirect(&self, id: &str, redirect_id: i64) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let result = sqlx::query("DELETE FROM redirects WHERE id = ?")
+            .bind(redirect_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::EntityNotFound());
+        }
+        Ok(())
+    }
+
+    async fn add_hit_counts(
+        &self,
+        id: &str,
+        counts: &[(i64, RedirectHitCount)],
+    ) -> Result<(), DbError> {
+        let pool = self
+            .db_pool_manager
+            .get_db_pool(id, &self.manifest_dir)
+            .await?;
+        let mut tx = pool.begin().await?;
+
+        for (redirect_id, count) in counts {
+            sqlx::query(
+                r#"
+                UPDATE redirects SET hit_count = hit_count + ?1, last_hit_at = ?2
+                WHERE id = ?3
+            "#,
+            )
+            .bind(count.hits)
+            .bind(count.last_hit_at)
+            .bind(redirect_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
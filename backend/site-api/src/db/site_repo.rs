@@ -1,23 +1,34 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use const_format::formatcp;
-use lib_shared_site_api::db::{
-    db_error::{map_sqlx_err, DbError},
-    util::{append_comma, append_nullable_comma},
+use lib_shared_site_api::{
+    db::{
+        db_error::{map_sqlx_err, DbError},
+        util::{append_comma, append_nullable_comma},
+    },
+    util::compression::{compress_brotli, compress_gzip},
 };
 use lib_shared_types::{
     dto::{
         query_dto::ListQuery,
         site_api::{
-            create_site_dto::CreateSiteDto, update_site_dto::UpdateSiteDtoWithContentUpdatedAt,
+            create_site_dto::CreateSiteDto, site_archive_dto::SiteArchiveVersion,
+            update_site_dto::UpdateSiteDtoWithContentUpdatedAt,
         },
     },
     entity::site_api::{
-        site_entity::SiteEntity, site_info_entity::SiteInfoEntity,
-        site_metadata_entity::SiteMetadataEntity, static_page_entity::StaticPageEntity,
+        site_entity::SiteEntity,
+        site_info_entity::{SiteInfoEntity, SiteVersionLabel},
+        site_metadata_entity::SiteMetadataEntity,
+        site_revision_entity::SiteRevisionEntity,
+        static_page_entity::StaticPageEntity,
     },
 };
-use sqlx::{migrate::MigrateDatabase, sqlite::SqliteRow, Error, QueryBuilder, Row, SqlitePool};
+use sqlx::{
+    migrate::{MigrateDatabase, Migrator},
+    sqlite::SqliteRow,
+    Error, QueryBuilder, Row, Sqlite, SqlitePool, Transaction,
+};
 use std::{
     fs::{self, File},
     io::Write,
@@ -30,8 +41,13 @@ use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
 
 pub type DynSiteRepo = Arc<dyn SiteRepoTrait + Send + Sync>;
 
-const SITE_COLUMNS: &str = r#"id, name, version, context, defaults, editor, history, pages, page_order, created_at, updated_at, content_updated_at, published, preview_id"#;
-const SITE_INFO_COLUMNS: &str = r#"id, name, updated_at, published"#;
+static SITE_MIGRATOR: Migrator = sqlx::migrate!("db/sites/migrations");
+
+const SITE_COLUMNS: &str = r#"id, name, version, context, defaults, editor, history, pages, page_order, created_at, updated_at, content_updated_at, published, preview_id,
+    (SELECT site_revisions.id FROM site_revisions WHERE site_revisions.content_updated_at = site_versions.content_updated_at) AS revision_id"#;
+// Number of content snapshots kept per site for merging concurrent edits
+const MAX_SITE_REVISIONS: i64 = 20;
+const SITE_INFO_COLUMNS: &str = r#"id, name, updated_at, published, message, tag"#;
 
 #[async_trait]
 pub trait SiteRepoTrait {
@@ -63,10 +79,35 @@ pub trait SiteRepoTrait {
         id: &str,
         req: UpdateSiteDtoWithContentUpdatedAt,
     ) -> Result<SiteEntity, DbError>;
-    async fn create_draft(&self, id: &str, from_id: i64) -> Result<(), DbError>;
+    async fn create_draft(
+        &self,
+        id: &str,
+        from_id: i64,
+        label: SiteVersionLabel,
+    ) -> Result<(), DbError>;
     async fn delete_draft(&self, id: &str) -> Result<(), DbError>;
-    async fn publish_site(&self, id: &str) -> Result<SiteEntity, DbError>;
+    async fn get_site_revision(
+        &self,
+        id: &str,
+        revision_id: i64,
+    ) -> Result<SiteRevisionEntity, DbError>;
+    async fn publish_site(&self, id: &str, label: SiteVersionLabel) -> Result<SiteEntity, DbError>;
     async fn publish_all_versions(&self, id: &str, published: bool) -> Result<(), DbError>;
+    async fn set_version_label(
+        &self,
+        id: &str,
+        version_id: i64,
+        label: SiteVersionLabel,
+    ) -> Result<(), DbError>;
+    // Delete untagged, unpublished versions outside the retention window that no live preview
+    // link points to, and VACUUM the site DB if anything was removed. Returns the number of
+    // deleted versions
+    async fn prune_site_versions(
+        &self,
+        id: &str,
+        keep_latest: u32,
+        keep_daily_days: u32,
+    ) -> Result<u64, DbError>;
     async fn export_backup(&self, id: &str) -> Result<Vec<u8>, DbError>;
     async fn create_from_backup(&self, id: &str, backup_data: Vec<u8>) -> Result<(), DbError>;
     async fn replace_from_backup(&self, id: &str, backup_data: Vec<u8>) -> Result<(), DbError>;
@@ -75,8 +116,36 @@ pub trait SiteRepoTrait {
         id: &str,
         pages: Vec<StaticPageEntity>,
     ) -> Result<(), DbError>;
+    // Store generated pages in one transaction: `retained` routes are kept and marked as
+    // generated from `content_updated_at`, `pages` are upserted, and other routes deleted
+    async fn store_static_pages(
+        &self,
+        id: &str,
+        pages: Vec<StaticPageEntity>,
+        retained: &[String],
+        content_updated_at: i64,
+    ) -> Result<(), DbError>;
     async fn get_static_page(&self, id: &str, route: &str) -> Result<StaticPageEntity, DbError>;
+    async fn list_static_pages(&self, id: &str) -> Result<Vec<StaticPageEntity>, DbError>;
+    async fn list_static_page_routes(&self, id: &str) -> Result<Vec<String>, DbError>;
     async fn clear_static_pages(&self, id: &str) -> Result<(), DbError>;
+    // Latest migration applied to the site database
+    async fn get_schema_version(&self, id: &str) -> Result<i64, DbError>;
+    // Insert a version from a site archive, keeping its content timestamps
+    async fn insert_site_version(
+        &self,
+        id: &str,
+        version: &SiteArchiveVersion,
+    ) -> Result<(), DbError>;
+}
+
+// Latest site database migration known to this build
+pub fn site_schema_version() -> i64 {
+    SITE_MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or_default()
 }
 
 pub struct SiteRepo {
@@ -197,7 +266,12 @@ impl SiteRepoTrait for SiteRepo {
         id: &str,
         req: UpdateSiteDtoWithContentUpdatedAt,
     ) -> Result<SiteEntity, DbError> {
-        let mut conn = self.get_db_conn(id).await?;
+        let content_updated = req.content_updated_at.is_some();
+        let pool = self
+            .db_pool_manager
+            .get_db_pool(id, &self.manifest_dir)
+            .await?;
+        let mut tx = pool.begin().await?;
 
         let query = QueryBuilder::new("UPDATE site_versions SET");
         let update_count = 0;
@@ -240,15 +314,26 @@ impl SiteRepoTrait for SiteRepo {
             query.push_bind(update_key);
             query.push(")");
         }
+        if let Some(expected) = req.expected_content_updated_at {
+            query.push(" AND content_updated_at = ");
+            query.push_bind(expected);
+        }
 
         query.push(formatcp!(" RETURNING {}", SITE_COLUMNS));
 
-        Ok(query
+        let mut site = query
             .build()
             .try_map(map_to_site_entity)
-            .fetch_one(&mut *conn)
+            .fetch_one(&mut *tx)
             .await
-            .map_err(map_sqlx_err)?)
+            .map_err(map_sqlx_err)?;
+
+        // Snapshot the new content, so updates made from it can still be merged
+        if content_updated {
+            site.revision_id = Some(record_site_revision(&mut tx, &site).await?);
+        }
+        tx.commit().await?;
+        Ok(site)
     }
 
     async fn create_db(&self, id: &str) -> Result<SqlitePool, DbError> {
@@ -274,7 +359,7 @@ impl SiteRepoTrait for SiteRepo {
 
     async fn migrate_db(&self, pool: &SqlitePool) -> Result<(), DbError> {
         // Migrate the database
-        sqlx::migrate!("db/sites/migrations")
+        SITE_MIGRATOR
             .run(pool)
             .await
             .map_err(|e| DbError::Migrate(e.to_string()))?;
@@ -301,8 +386,12 @@ impl SiteRepoTrait for SiteRepo {
             // Remove the connection pool from the map
             self.db_pool_manager.remove_db_pool(id).await?;
 
-            // Delete the database
+            // Delete the database, and the WAL files left when connections were still open
             sqlx::Sqlite::drop_database(&site_db_url).await?;
+            let db_path = site_db_url.trim_start_matches("sqlite:");
+            for suffix in ["-wal", "-shm"] {
+                let _ = fs::remove_file(format!("{}{}", db_path, suffix));
+            }
 
             Ok(())
         } else {
@@ -310,14 +399,21 @@ impl SiteRepoTrait for SiteRepo {
         }
     }
 
-    async fn create_draft(&self, id: &str, from_id: i64) -> Result<(), DbError> {
+    async fn create_draft(
+        &self,
+        id: &str,
+        from_id: i64,
+        label: SiteVersionLabel,
+    ) -> Result<(), DbError> {
         let mut conn = self.get_db_conn(id).await?;
 
         sqlx::query(
-            r#"INSERT INTO site_versions (name, version, context, defaults, editor, history, pages, content_updated_at)
-            SELECT name, version, context, defaults, editor, history, pages, content_updated_at FROM site_versions WHERE id = ?
+            r#"INSERT INTO site_versions (name, version, context, defaults, editor, history, pages, content_updated_at, message, tag)
+            SELECT name, version, context, defaults, editor, history, pages, content_updated_at, ?, ? FROM site_versions WHERE id = ?
             "#,
         )
+        .bind(label.message)
+        .bind(label.tag)
         .bind(from_id)
         .execute(&mut *conn)
         .await?;
@@ -325,6 +421,26 @@ impl SiteRepoTrait for SiteRepo {
         Ok(())
     }
 
+    async fn get_site_revision(
+        &self,
+        id: &str,
+        revision_id: i64,
+    ) -> Result<SiteRevisionEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query(
+            r#"
+               SELECT id, content_updated_at, context, defaults, pages
+               FROM site_revisions WHERE id = ?
+            "#,
+        )
+        .bind(revision_id)
+        .try_map(map_to_site_revision_entity)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_sqlx_err)?)
+    }
+
     async fn delete_draft(&self, id: &str) -> Result<(), DbError> {
         let mut conn = self.get_db_conn(id).await?;
 
@@ -355,7 +471,71 @@ impl SiteRepoTrait for SiteRepo {
         Ok(())
     }
 
-    async fn publish_site(&self, id: &str) -> Result<SiteEntity, DbError> {
+    async fn set_version_label(
+        &self,
+        id: &str,
+        version_id: i64,
+        label: SiteVersionLabel,
+    ) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+               UPDATE site_versions
+               SET message = ?, tag = ?
+               WHERE id = ?
+            "#,
+        )
+        .bind(label.message)
+        .bind(label.tag)
+        .bind(version_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn prune_site_versions(
+        &self,
+        id: &str,
+        keep_latest: u32,
+        keep_daily_days: u32,
+    ) -> Result<u64, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        // Keeps the latest `keep_latest` versions, tagged and published versions, versions
+        // shared by unexpired preview links, and the last version of each day within the
+        // last `keep_daily_days` days
+        let result = sqlx::query(
+            r#"
+               DELETE FROM site_versions
+               WHERE tag IS NULL
+                 AND published = FALSE
+                 AND id NOT IN (SELECT id FROM site_versions ORDER BY id DESC LIMIT ?1)
+                 AND id NOT IN (
+                   SELECT MAX(id) FROM site_versions
+                   WHERE date(updated_at) >= date('now', ?2)
+                   GROUP BY date(updated_at)
+                 )
+                 AND id NOT IN (
+                   SELECT version_id FROM preview_links
+                   WHERE expires_at IS NULL OR expires_at > ?3
+                 )
+            "#,
+        )
+        .bind(keep_latest)
+        .bind(format!("-{} days", keep_daily_days))
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+
+        let deleted = result.rows_affected();
+        if deleted > 0 {
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn publish_site(&self, id: &str, label: SiteVersionLabel) -> Result<SiteEntity, DbError> {
         let mut conn = self.get_db_conn(id).await?;
 
         let mut sites = sqlx::query(formatcp!(
@@ -378,6 +558,18 @@ impl SiteRepoTrait for SiteRepo {
         let draft = sites.next().ok_or(DbError::EntityNotFound())?;
         let published_site = sites.next().ok_or(DbError::EntityNotFound())?;
 
+        // Publishing without a label keeps the draft's label
+        let (draft_message, draft_tag): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT message, tag FROM site_versions WHERE id = ?")
+                .bind(draft.id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(map_sqlx_err)?;
+        let label = SiteVersionLabel {
+            message: label.message.or(draft_message),
+            tag: label.tag.or(draft_tag),
+        };
+
         let query = QueryBuilder::new("UPDATE site_versions SET");
         let update_count = 0;
 
@@ -392,12 +584,16 @@ impl SiteRepoTrait for SiteRepo {
             append_comma(query, "history", Some(draft.history), update_count);
         let (query, update_count) = append_comma(query, "pages", Some(draft.pages), update_count);
         let (query, update_count) = append_comma(query, "published", Some(true), update_count);
-        let (mut query, update_count) = append_comma(
+        let (query, update_count) = append_comma(
             query,
             "content_updated_at",
             Some(draft.content_updated_at),
             update_count,
         );
+        let (query, update_count) =
+            append_nullable_comma(query, "message", label.message, update_count);
+        let (mut query, update_count) =
+            append_nullable_comma(query, "tag", label.tag, update_count);
 
         if update_count == 0 {
             return Err(DbError::NoUpdate);
@@ -492,6 +688,7 @@ impl SiteRepoTrait for SiteRepo {
         id: &str,
         pages: Vec<StaticPageEntity>,
     ) -> Result<(), DbError> {
+        let pages = compress_static_pages(pages).await?;
         let pool = self
             .db_pool_manager
             .get_db_pool(id, &self.manifest_dir)
@@ -502,18 +699,45 @@ impl SiteRepoTrait for SiteRepo {
             .execute(&mut *tx)
             .await?;
         for page in pages {
-            sqlx::query(
-                r#"
-                INSERT INTO static_pages(route, body, content_type, content_updated_at)
-                VALUES (?1, ?2, ?3, ?4)
-                "#,
-            )
-            .bind(page.route)
-            .bind(page.body)
-            .bind(page.content_type)
-            .bind(page.content_updated_at)
-            .execute(&mut *tx)
+            insert_static_page(&mut tx, page).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn store_static_pages(
+        &self,
+        id: &str,
+        pages: Vec<StaticPageEntity>,
+        retained: &[String],
+        content_updated_at: i64,
+    ) -> Result<(), DbError> {
+        let pages = compress_static_pages(pages).await?;
+        let pool = self
+            .db_pool_manager
+            .get_db_pool(id, &self.manifest_dir)
+            .await?;
+        let mut tx = pool.begin().await?;
+
+        let stored: Vec<String> = sqlx::query_scalar("SELECT route FROM static_pages")
+            .fetch_all(&mut *tx)
             .await?;
+        for route in stored {
+            if retained.contains(&route) {
+                sqlx::query("UPDATE static_pages SET content_updated_at = ?1 WHERE route = ?2")
+                    .bind(content_updated_at)
+                    .bind(route)
+                    .execute(&mut *tx)
+                    .await?;
+            } else if !pages.iter().any(|page| page.route == route) {
+                sqlx::query("DELETE FROM static_pages WHERE route = ?1")
+                    .bind(route)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        for page in pages {
+            insert_static_page(&mut tx, page).await?;
         }
         tx.commit().await?;
         Ok(())
@@ -523,7 +747,7 @@ impl SiteRepoTrait for SiteRepo {
         let mut conn = self.get_db_conn(id).await?;
 
         Ok(sqlx::query(
-            r#"SELECT route, body, content_type, content_updated_at FROM static_pages WHERE route = ?"#,
+            r#"SELECT route, body, content_type, content_updated_at, page_hash, shared_hash, body_br, body_gzip FROM static_pages WHERE route = ?"#,
         )
         .bind(route)
         .try_map(map_to_static_page_entity)
@@ -531,6 +755,27 @@ impl SiteRepoTrait for SiteRepo {
         .await?)
     }
 
+    async fn list_static_pages(&self, id: &str) -> Result<Vec<StaticPageEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(sqlx::query(
+            r#"SELECT route, body, content_type, content_updated_at, page_hash, shared_hash, body_br, body_gzip FROM static_pages ORDER BY route"#,
+        )
+        .try_map(map_to_static_page_entity)
+        .fetch_all(&mut *conn)
+        .await?)
+    }
+
+    async fn list_static_page_routes(&self, id: &str) -> Result<Vec<String>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(
+            sqlx::query_scalar("SELECT route FROM static_pages ORDER BY route")
+                .fetch_all(&mut *conn)
+                .await?,
+        )
+    }
+
     async fn clear_static_pages(&self, id: &str) -> Result<(), DbError> {
         let mut conn = self.get_db_conn(id).await?;
 
@@ -539,6 +784,44 @@ impl SiteRepoTrait for SiteRepo {
             .await?;
         Ok(())
     }
+
+    async fn get_schema_version(&self, id: &str) -> Result<i64, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(
+            sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+                .fetch_one(&mut *conn)
+                .await?,
+        )
+    }
+
+    async fn insert_site_version(
+        &self,
+        id: &str,
+        version: &SiteArchiveVersion,
+    ) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO site_versions(name, version, context, defaults, editor, history, pages, page_order, published, content_updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "#,
+        )
+        .bind(&version.name)
+        .bind(&version.version)
+        .bind(&version.context)
+        .bind(&version.defaults)
+        .bind(&version.editor)
+        .bind(&version.history)
+        .bind(&version.pages)
+        .bind(&version.page_order)
+        .bind(version.published)
+        .bind(version.content_updated_at)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
 }
 
 fn map_to_site_entity(row: SqliteRow) -> Result<SiteEntity, Error> {
@@ -557,6 +840,17 @@ fn map_to_site_entity(row: SqliteRow) -> Result<SiteEntity, Error> {
         content_updated_at: row.try_get("content_updated_at")?,
         published: row.try_get("published")?,
         preview_id: row.try_get_unchecked("preview_id")?,
+        revision_id: row.try_get("revision_id")?,
+    })
+}
+
+fn map_to_site_revision_entity(row: SqliteRow) -> Result<SiteRevisionEntity, Error> {
+    Ok(SiteRevisionEntity {
+        id: row.try_get("id")?,
+        content_updated_at: row.try_get("content_updated_at")?,
+        context: row.try_get("context")?,
+        defaults: row.try_get("defaults")?,
+        pages: row.try_get("pages")?,
     })
 }
 
@@ -566,14 +860,95 @@ fn map_to_static_page_entity(row: SqliteRow) -> Result<StaticPageEntity, Error>
         body: row.try_get("body")?,
         content_type: row.try_get("content_type")?,
         content_updated_at: row.try_get("content_updated_at")?,
+        page_hash: row.try_get("page_hash")?,
+        shared_hash: row.try_get("shared_hash")?,
+        body_br: row.try_get("body_br")?,
+        body_gzip: row.try_get("body_gzip")?,
     })
 }
 
+// Stores the page with its precompressed variants, served based on Accept-Encoding
+// Best compression is slow, so pages are compressed on a blocking thread before the write
+// transaction is opened
+async fn compress_static_pages(
+    mut pages: Vec<StaticPageEntity>,
+) -> Result<Vec<StaticPageEntity>, DbError> {
+    Ok(tokio::task::spawn_blocking(move || {
+        for page in pages.iter_mut() {
+            page.body_br = Some(compress_brotli(page.body.as_bytes()));
+            page.body_gzip = Some(compress_gzip(page.body.as_bytes()));
+        }
+        pages
+    })
+    .await?)
+}
+
+// Record the content of an updated site version, and keep the latest revisions. Returns the
+// revision ID
+async fn record_site_revision(
+    tx: &mut Transaction<'_, Sqlite>,
+    site: &SiteEntity,
+) -> Result<i64, Error> {
+    let revision_id = sqlx::query_scalar(
+        r#"
+           INSERT INTO site_revisions (content_updated_at, context, defaults, pages)
+           VALUES (?1, ?2, ?3, ?4)
+           ON CONFLICT(content_updated_at) DO UPDATE SET
+             context = excluded.context, defaults = excluded.defaults, pages = excluded.pages
+           RETURNING id
+        "#,
+    )
+    .bind(site.content_updated_at)
+    .bind(&site.context)
+    .bind(&site.defaults)
+    .bind(&site.pages)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+           DELETE FROM site_revisions WHERE id NOT IN (
+             SELECT id FROM site_revisions ORDER BY id DESC LIMIT ?
+           )
+        "#,
+    )
+    .bind(MAX_SITE_REVISIONS)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(revision_id)
+}
+
+async fn insert_static_page(
+    tx: &mut Transaction<'_, Sqlite>,
+    page: StaticPageEntity,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT OR REPLACE INTO static_pages(route, body, content_type, content_updated_at, page_hash, shared_hash, body_br, body_gzip)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+    )
+    .bind(page.route)
+    .bind(page.body)
+    .bind(page.content_type)
+    .bind(page.content_updated_at)
+    .bind(page.page_hash)
+    .bind(page.shared_hash)
+    .bind(page.body_br)
+    .bind(page.body_gzip)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
 fn map_site_info_entity(row: SqliteRow) -> Result<SiteInfoEntity, Error> {
     Ok(SiteInfoEntity {
         id: row.try_get("id")?,
         name: row.try_get("name")?,
         updated_at: row.try_get("updated_at")?,
         published: row.try_get("published")?,
+        message: row.try_get("message")?,
+        tag: row.try_get("tag")?,
     })
 }
@@ -1,17 +1,20 @@
 use async_trait::async_trait;
-use lib_shared_site_api::db::{db_error::DbError, util::append_comma};
+use lib_shared_site_api::{
+    db::{db_error::DbError, util::append_comma},
+    util::domains::{toggle_www, wildcard_pattern},
+};
 use lib_shared_types::{
     dto::site_api::create_metadata_dto::CreateSiteMetadataDto,
     entity::site_api::{
         custom_domain_entity::CustomDomainRelationEntity,
         site_metadata_entity::{SiteMetadataEntity, UpdateSiteMetadataEntity},
     },
-    shared::site::SiteType,
+    shared::site::{DomainKind, SiteType},
 };
 use sqlx::{
     sqlite::SqliteRow, Error, Executor, QueryBuilder, Row, Sqlite, SqlitePool, Transaction,
 };
-use std::sync::Arc;
+use std::{collections::BTreeSet, str::FromStr, sync::Arc};
 
 use super::site_db_pool_manager::SqlitePoolConnection;
 
@@ -44,6 +47,7 @@ pub trait SitesMetadataRepoTrait {
         dto: &UpdateSiteMetadataEntity,
     ) -> Result<SiteMetadataResult, DbError>;
     async fn list_sites(&self) -> Result<Vec<SiteMetadataEntity>, Error>;
+    async fn count_owner_sites(&self, owner_id: &str, site_type: SiteType) -> Result<i64, Error>;
     async fn delete_site(&self, id: &str) -> Result<(), Error>;
     async fn set_site_domains(
         &self,
@@ -51,7 +55,8 @@ pub trait SitesMetadataRepoTrait {
         id: &str,
         domains: &Vec<CustomDomainRelationEntity>,
     ) -> Result<(), Error>;
-    async fn get_site_id_by_hostname(&self, hostname: &str) -> Result<String, Error>;
+    async fn get_site_id_by_hostname(&self, hostname: &str) -> Result<(String, String), Error>;
+    async fn list_verified_domains(&self) -> Result<Vec<String>, Error>;
     async fn reset(&self) -> Result<(), Error>;
 }
 
@@ -73,15 +78,23 @@ fn row_to_site_metadata(row: SqliteRow) -> Result<SiteMetadataEntity, Error> {
             .map(|r| {
                 let d: Vec<&str> = r.split("|").collect();
                 let verified = d.get(1).unwrap_or(&"0") == &"1";
+                let primary = d.get(2).unwrap_or(&"0") == &"1";
+                let kind = d
+                    .get(3)
+                    .and_then(|kind| DomainKind::from_str(kind).ok())
+                    .unwrap_or_default();
                 CustomDomainRelationEntity {
                     domain: d[0].into(),
                     verified,
+                    primary,
+                    kind,
                 }
             })
             .collect(),
         site_type: row.try_get("site_type")?,
         disabled: row.try_get("disabled")?,
         custom_data_usage: row.try_get("custom_data_usage")?,
+        www_policy: row.try_get("www_policy")?,
     })
 }
 
@@ -125,7 +138,7 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         let site: SiteMetadataEntity = sqlx::query(
             r#"
         SELECT s.id, s.location, s.owner_id, s.owner_email, s.site_type, s.disabled, s.custom_data_usage,
-            GROUP_CONCAT(d.domain || '|' || d.verified) as domains
+            s.www_policy, GROUP_CONCAT(d.domain || '|' || d.verified || '|' || d.is_primary || '|' || d.kind) as domains
         FROM sites s
         LEFT OUTER JOIN domains d ON d.site_id = s.id
         WHERE s.id = ?1
@@ -142,7 +155,7 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         let sites: Vec<SiteMetadataEntity> = sqlx::query(
             r#"
         SELECT s.id, s.location, s.owner_id, s.owner_email, s.site_type, s.disabled, s.custom_data_usage,
-            GROUP_CONCAT(d.domain || '|' || d.verified) as domains
+            s.www_policy, GROUP_CONCAT(d.domain || '|' || d.verified || '|' || d.is_primary || '|' || d.kind) as domains
         FROM sites s
         LEFT OUTER JOIN domains d ON d.site_id = s.id
         GROUP BY s.id
@@ -154,6 +167,19 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         Ok(sites)
     }
 
+    async fn count_owner_sites(&self, owner_id: &str, site_type: SiteType) -> Result<i64, Error> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+        SELECT COUNT(*) FROM sites WHERE owner_id = ?1 AND site_type = ?2
+    "#,
+        )
+        .bind(owner_id)
+        .bind(site_type.to_string())
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+        Ok(count)
+    }
+
     async fn create_site(&self, dto: CreateSiteMetadataDto) -> Result<String, Error> {
         let id = dto.site_id;
         let base_path = "site-api/db/sites";
@@ -199,8 +225,8 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         let (query, count) = append_comma(query, "owner_email", dto.owner_email.clone(), count);
         let (query, count) = append_comma(query, "owner_id", dto.owner_id.clone(), count);
         let (query, count) = append_comma(query, "disabled", dto.disabled, count);
-        let (mut query, count) =
-            append_comma(query, "custom_data_usage", dto.custom_data_usage, count);
+        let (query, count) = append_comma(query, "custom_data_usage", dto.custom_data_usage, count);
+        let (mut query, count) = append_comma(query, "www_policy", dto.www_policy, count);
 
         if count == 0 {
             return Err(DbError::NoUpdate);
@@ -252,8 +278,13 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         .execute(tx.as_mut())
         .await?;
 
-        let mut query: QueryBuilder<'_, Sqlite> =
-            QueryBuilder::new("INSERT INTO domains ( domain, verified, site_id )  VALUES ");
+        if domains.is_empty() {
+            return Ok(());
+        }
+
+        let mut query: QueryBuilder<'_, Sqlite> = QueryBuilder::new(
+            "INSERT INTO domains ( domain, verified, is_primary, kind, site_id )  VALUES ",
+        );
         let mut domain_it = domains.iter().peekable();
         while let Some(domain) = domain_it.next() {
             query.push("(");
@@ -261,6 +292,10 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
             query.push(", ");
             query.push_bind(domain.verified);
             query.push(", ");
+            query.push_bind(domain.primary);
+            query.push(", ");
+            query.push_bind(domain.kind);
+            query.push(", ");
             query.push_bind(id);
             if domain_it.peek().is_none() {
                 query.push(")");
@@ -270,30 +305,86 @@ impl SitesMetadataRepoTrait for SitesMetadataRepo {
         }
         query.build().execute(tx.as_mut()).await?;
 
+        // Checked domains keep the result of their DNS verification, set by `save_check`
+        sqlx::query(
+            r#"
+            UPDATE domains SET verified = (
+                SELECT v.verified FROM domain_verifications v WHERE v.domain = domains.domain
+            )
+            WHERE site_id = ?1 AND domain IN (
+                SELECT domain FROM domain_verifications
+                WHERE site_id = ?1 AND last_checked_at IS NOT NULL
+            )
+        "#,
+        )
+        .bind(id)
+        .execute(tx.as_mut())
+        .await?;
+
         Ok(())
     }
 
-    async fn get_site_id_by_hostname(&self, hostname: &str) -> Result<String, Error> {
-        let result: (String,) = sqlx::query_as(
+    // The www/apex variant of a domain also resolves, for sites with a www policy, then
+    // wildcard domains covering the host. Domains suspended for failing verification are
+    // not served. Returns the site ID and the domain the host matched
+    async fn get_site_id_by_hostname(&self, hostname: &str) -> Result<(String, String), Error> {
+        sqlx::query_as(
             r#"
-            SELECT site_id FROM domains WHERE domain = ?
+            SELECT d.site_id, d.domain FROM domains d
+            JOIN sites s ON s.id = d.site_id
+            WHERE ((d.domain = ?1 AND d.kind != 'Wildcard')
+                    OR (d.domain = ?2 AND d.kind != 'Wildcard' AND s.www_policy != 'Keep')
+                    OR (d.domain = ?3 AND d.kind = 'Wildcard'))
+                AND d.domain NOT IN (
+                    SELECT domain FROM domain_verifications WHERE status = 'Suspended'
+                )
+            ORDER BY d.domain = ?1 DESC, d.domain = ?2 DESC
+            LIMIT 1
         "#,
         )
         .bind(hostname)
+        .bind(toggle_www(hostname))
+        .bind(wildcard_pattern(hostname).unwrap_or_default())
         .fetch_one(&mut *self.get_db_conn().await?)
-        .await?;
+        .await
+    }
 
-        let (id,) = result;
-        Ok(id)
+    // Domains served over TLS, when the ACME certificate listener is enabled. Includes the www
+    // or apex variant of served domains redirected by the www policy. Wildcard certificates
+    // need DNS challenges, so wildcard domains are left out
+    async fn list_verified_domains(&self) -> Result<Vec<String>, Error> {
+        let rows: Vec<(String, bool)> = sqlx::query_as(
+            r#"
+            SELECT d.domain, d.kind = 'Serve' AND s.www_policy != 'Keep' FROM domains d
+            JOIN sites s ON s.id = d.site_id
+            WHERE d.verified = TRUE AND d.kind != 'Wildcard'
+        "#,
+        )
+        .fetch_all(&mut *self.get_db_conn().await?)
+        .await?;
+        let mut domains = BTreeSet::new();
+        for (domain, redirects_www) in rows {
+            if redirects_www {
+                domains.insert(toggle_www(&domain));
+            }
+            domains.insert(domain);
+        }
+        Ok(domains.into_iter().collect())
     }
 
     async fn reset(&self) -> Result<(), Error> {
         let tables = vec![
             "_sqlx_migrations",
             "backups",
+            "domain_verifications",
             "domains",
+            "publish_schedules",
+            "templates",
             "sites",
             "site_usage",
+            "site_usage_hourly",
+            "site_visitor_sketches",
+            "visitor_salts",
         ];
 
         for table in tables.iter() {
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lib_shared_site_api::db::db_error::DbError;
+use lib_shared_types::entity::site_api::static_build_entity::{
+    StaticBuildEntity, StaticBuildStatus, StaticBuildTrigger,
+};
+use sqlx::{sqlite::SqliteRow, Error, Row};
+
+use super::site_db_pool_manager::{DbPoolManager, SqlitePoolConnection};
+
+pub type DynStaticBuildRepo = Arc<dyn StaticBuildRepoTrait + Send + Sync>;
+
+// Number of builds kept per site
+const STATIC_BUILD_HISTORY: i64 = 50;
+
+#[async_trait]
+pub trait StaticBuildRepoTrait {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError>;
+    // Record a new build, and drop builds beyond the history limit
+    async fn create_build(
+        &self,
+        id: &str,
+        trigger: StaticBuildTrigger,
+        status: StaticBuildStatus,
+        content_updated_at: i64,
+    ) -> Result<StaticBuildEntity, DbError>;
+    // Save the result of a build, setting its finish time
+    async fn finish_build(&self, id: &str, build: &StaticBuildEntity) -> Result<(), DbError>;
+    async fn list_builds(&self, id: &str, limit: u32) -> Result<Vec<StaticBuildEntity>, DbError>;
+}
+
+pub struct StaticBuildRepo {
+    pub db_pool_manager: DbPoolManager,
+    pub manifest_dir: String,
+}
+
+fn parse_string_list(row: &SqliteRow, column: &str) -> Result<Vec<String>, Error> {
+    let value: String = row.try_get(column)?;
+    serde_json::from_str(&value).map_err(|e| Error::Decode(Box::new(e)))
+}
+
+fn map_to_static_build_entity(row: SqliteRow) -> Result<StaticBuildEntity, Error> {
+    Ok(StaticBuildEntity {
+        id: row.try_get("id")?,
+        trigger: row.try_get("trigger")?,
+        status: row.try_get("status")?,
+        content_updated_at: row.try_get("content_updated_at")?,
+        generator: row.try_get("generator")?,
+        page_count: row.try_get("page_count")?,
+        incremental: row.try_get("incremental")?,
+        warnings: parse_string_list(&row, "warnings")?,
+        blockers: parse_string_list(&row, "blockers")?,
+        error: row.try_get("error")?,
+        started_at: row.try_get("started_at")?,
+        finished_at: row.try_get("finished_at")?,
+    })
+}
+
+#[async_trait]
+impl StaticBuildRepoTrait for StaticBuildRepo {
+    async fn get_db_conn(&self, id: &str) -> Result<SqlitePoolConnection, DbError> {
+        self.db_pool_manager
+            .get_db_conn(id, &self.manifest_dir)
+            .await
+    }
+
+    async fn create_build(
+        &self,
+        id: &str,
+        trigger: StaticBuildTrigger,
+        status: StaticBuildStatus,
+        content_updated_at: i64,
+    ) -> Result<StaticBuildEntity, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        let build = sqlx::query(
+            r#"
+            INSERT INTO static_builds(trigger, status, content_updated_at)
+            VALUES (?1, ?2, ?3)
+            RETURNING *
+        "#,
+        )
+        .bind(trigger.to_string())
+        .bind(status.to_string())
+        .bind(content_updated_at)
+        .try_map(map_to_static_build_entity)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        sqlx::query("DELETE FROM static_builds WHERE id <= ?1")
+            .bind(build.id - STATIC_BUILD_HISTORY)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(build)
+    }
+
+    async fn finish_build(&self, id: &str, build: &StaticBuildEntity) -> Result<(), DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE static_builds
+            SET status = ?1, generator = ?2, page_count = ?3, incremental = ?4, warnings = ?5,
+                blockers = ?6, error = ?7, finished_at = ?8
+            WHERE id = ?9
+        "#,
+        )
+        .bind(build.status.to_string())
+        .bind(&build.generator)
+        .bind(build.page_count)
+        .bind(build.incremental)
+        .bind(serde_json::json!(build.warnings).to_string())
+        .bind(serde_json::json!(build.blockers).to_string())
+        .bind(&build.error)
+        .bind(Utc::now())
+        .bind(build.id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_builds(&self, id: &str, limit: u32) -> Result<Vec<StaticBuildEntity>, DbError> {
+        let mut conn = self.get_db_conn(id).await?;
+
+        Ok(
+            sqlx::query("SELECT * FROM static_builds ORDER BY id DESC LIMIT ?1")
+                .bind(limit)
+                .try_map(map_to_static_build_entity)
+                .fetch_all(&mut *conn)
+                .await?,
+        )
+    }
+}
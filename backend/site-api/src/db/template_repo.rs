@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lib_shared_types::entity::site_api::template_entity::{TemplateEntity, TemplateInfoEntity};
+use serde_json::Value;
+use sqlx::{sqlite::SqliteRow, Error, Row, SqlitePool};
+
+use super::site_db_pool_manager::SqlitePoolConnection;
+
+pub type DynTemplateRepo = Arc<dyn TemplateRepoTrait + Send + Sync>;
+
+const TEMPLATE_INFO_COLUMNS: &str =
+    r#"id, name, description, category, preview_key, source_site_id, created_at"#;
+
+#[async_trait]
+pub trait TemplateRepoTrait {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error>;
+    async fn create_template(&self, template: &TemplateEntity) -> Result<(), Error>;
+    async fn list_templates(
+        &self,
+        category: Option<String>,
+    ) -> Result<Vec<TemplateInfoEntity>, Error>;
+    async fn get_template(&self, id: &str) -> Result<TemplateEntity, Error>;
+    async fn get_template_by_name(&self, name: &str) -> Result<TemplateInfoEntity, Error>;
+    async fn delete_template(&self, id: &str) -> Result<TemplateInfoEntity, Error>;
+}
+
+pub struct TemplateRepo {
+    pub metadata_db_pool: SqlitePool,
+}
+
+fn row_to_template_info(row: SqliteRow) -> Result<TemplateInfoEntity, Error> {
+    Ok(TemplateInfoEntity {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        description: row.try_get("description")?,
+        category: row.try_get("category")?,
+        preview_key: row.try_get("preview_key")?,
+        source_site_id: row.try_get("source_site_id")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn row_to_template(row: SqliteRow) -> Result<TemplateEntity, Error> {
+    let custom_tables: String = row.try_get("custom_tables")?;
+    Ok(TemplateEntity {
+        version: row.try_get("version")?,
+        context: row.try_get("context")?,
+        defaults: row.try_get("defaults")?,
+        editor: row.try_get("editor")?,
+        history: row.try_get("history")?,
+        pages: row.try_get("pages")?,
+        page_order: row.try_get("page_order")?,
+        custom_tables: serde_json::from_str::<Vec<Value>>(&custom_tables)
+            .map_err(|e| Error::Decode(Box::new(e)))?,
+        info: row_to_template_info(row)?,
+    })
+}
+
+#[async_trait]
+impl TemplateRepoTrait for TemplateRepo {
+    async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error> {
+        Ok(self.metadata_db_pool.acquire().await?)
+    }
+
+    async fn create_template(&self, template: &TemplateEntity) -> Result<(), Error> {
+        let info = &template.info;
+        sqlx::query(
+            r#"
+            INSERT INTO templates(id, name, description, category, preview_key, source_site_id,
+                version, context, defaults, editor, history, pages, page_order, custom_tables)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        "#,
+        )
+        .bind(&info.id)
+        .bind(&info.name)
+        .bind(&info.description)
+        .bind(&info.category)
+        .bind(&info.preview_key)
+        .bind(&info.source_site_id)
+        .bind(&template.version)
+        .bind(&template.context)
+        .bind(&template.defaults)
+        .bind(&template.editor)
+        .bind(&template.history)
+        .bind(&template.pages)
+        .bind(&template.page_order)
+        .bind(Value::Array(template.custom_tables.clone()).to_string())
+        .execute(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_templates(
+        &self,
+        category: Option<String>,
+    ) -> Result<Vec<TemplateInfoEntity>, Error> {
+        let templates = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM templates
+            WHERE ?1 IS NULL OR category = ?1
+            ORDER BY created_at DESC, name ASC
+        "#,
+            TEMPLATE_INFO_COLUMNS
+        ))
+        .bind(category)
+        .try_map(row_to_template_info)
+        .fetch_all(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(templates)
+    }
+
+    async fn get_template(&self, id: &str) -> Result<TemplateEntity, Error> {
+        let template = sqlx::query("SELECT * FROM templates WHERE id = ?1")
+            .bind(id)
+            .try_map(row_to_template)
+            .fetch_one(&mut *self.get_db_conn().await?)
+            .await?;
+
+        Ok(template)
+    }
+
+    async fn get_template_by_name(&self, name: &str) -> Result<TemplateInfoEntity, Error> {
+        let template = sqlx::query(&format!(
+            "SELECT {} FROM templates WHERE name = ?1",
+            TEMPLATE_INFO_COLUMNS
+        ))
+        .bind(name)
+        .try_map(row_to_template_info)
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(template)
+    }
+
+    async fn delete_template(&self, id: &str) -> Result<TemplateInfoEntity, Error> {
+        let template = sqlx::query(&format!(
+            "DELETE FROM templates WHERE id = ?1 RETURNING {}",
+            TEMPLATE_INFO_COLUMNS
+        ))
+        .bind(id)
+        .try_map(row_to_template_info)
+        .fetch_one(&mut *self.get_db_conn().await?)
+        .await?;
+
+        Ok(template)
+    }
+}
@@ -1,15 +1,33 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use chrono::Utc;
-use lib_shared_site_api::{cache::cache::SiteUsageCache, db::separated::Separated};
-use lib_shared_types::entity::site_api::site_usage_entity::{SiteUsageEntity, SiteUsageTotals};
+use chrono::{DateTime, NaiveDate, Utc};
+use lib_shared_site_api::{
+    cache::cache::SiteUsageCache,
+    db::separated::Separated,
+    util::{analytics::HourlyUsageKey, unique_visitors::HyperLogLog},
+};
+use lib_shared_types::entity::site_api::{
+    site_usage_entity::{SiteUsageEntity, SiteUsageTotals},
+    site_usage_hourly_entity::{AnalyticsDimension, DimensionViewsEntity, HourlyViewsEntity},
+};
 use sqlx::{sqlite::SqliteRow, Error, QueryBuilder, Row, Sqlite, SqlitePool};
 
 use super::site_db_pool_manager::SqlitePoolConnection;
 
 pub type DynUsageRepo = Arc<dyn UsageRepoTrait + Send + Sync>;
 
+// SQLite allows 32766 bound parameters per statement, buckets bind 10 each
+const HOURLY_INSERT_CHUNK: usize = 1000;
+
+// Hour range and dimension filters of hourly usage queries. An empty value matches views
+// without the dimension
+pub struct HourlyUsageFilter {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub dimensions: Vec<(AnalyticsDimension, String)>,
+}
+
 #[async_trait]
 pub trait UsageRepoTrait {
     async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error>;
@@ -17,6 +35,37 @@ pub trait UsageRepoTrait {
     async fn list_usages_by_site_id(&self, site_id: &str) -> Result<Vec<SiteUsageEntity>, Error>;
     async fn list_latest_usages(&self) -> Result<Vec<SiteUsageEntity>, Error>;
     async fn list_usage_totals(&self) -> Result<Vec<SiteUsageTotals>, Error>;
+    async fn insert_hourly_usage(&self, buckets: &[(HourlyUsageKey, i64)]) -> Result<(), Error>;
+    async fn list_hourly_views(
+        &self,
+        site_id: &str,
+        filter: &HourlyUsageFilter,
+    ) -> Result<Vec<HourlyViewsEntity>, Error>;
+    async fn list_dimension_views(
+        &self,
+        site_id: &str,
+        filter: &HourlyUsageFilter,
+        dimension: AnalyticsDimension,
+        limit: u32,
+    ) -> Result<Vec<DimensionViewsEntity>, Error>;
+    async fn merge_visitor_sketches(
+        &self,
+        sketches: &[(String, NaiveDate, HyperLogLog)],
+    ) -> Result<(), Error>;
+    async fn list_visitor_sketches(
+        &self,
+        site_id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, HyperLogLog)>, Error>;
+    async fn delete_visitor_sketches_before(&self, day: NaiveDate) -> Result<u64, Error>;
+    async fn get_or_create_visitor_salt(
+        &self,
+        day: NaiveDate,
+        salt: &[u8],
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, Error>;
+    async fn delete_expired_visitor_salts(&self, now: DateTime<Utc>) -> Result<u64, Error>;
 }
 
 pub struct UsageRepo {
@@ -41,6 +90,25 @@ fn map_to_usage_entity(row: SqliteRow) -> Result<SiteUsageEntity, Error> {
     })
 }
 
+fn push_hourly_filter<'a>(
+    query: &mut QueryBuilder<'a, Sqlite>,
+    site_id: &'a str,
+    filter: &'a HourlyUsageFilter,
+) {
+    query
+        .push(" WHERE site_id = ")
+        .push_bind(site_id)
+        .push(" AND hour >= ")
+        .push_bind(filter.start)
+        .push(" AND hour < ")
+        .push_bind(filter.end);
+    for (dimension, value) in &filter.dimensions {
+        query
+            .push(format!(" AND {} = ", dimension.column()))
+            .push_bind(value.as_str());
+    }
+}
+
 #[async_trait]
 impl UsageRepoTrait for UsageRepo {
     async fn get_db_conn(&self) -> Result<SqlitePoolConnection, Error> {
@@ -171,4 +239,206 @@ impl UsageRepoTrait for UsageRepo {
 
         Ok(result)
     }
+
+    // Buckets persisted before add to their views. Buckets of deleted sites are dropped
+    async fn insert_hourly_usage(&self, buckets: &[(HourlyUsageKey, i64)]) -> Result<(), Error> {
+        if buckets.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.metadata_db_pool.begin().await?;
+        for chunk in buckets.chunks(HOURLY_INSERT_CHUNK) {
+            let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(
+                "INSERT INTO site_usage_hourly (site_id, hour, route, referrer, utm_source, utm_medium, utm_campaign, device, country, views) SELECT * FROM (",
+            );
+            query.push_values(chunk, |mut b, (key, views)| {
+                b.push_bind(&key.site_id)
+                    .push_bind(key.hour)
+                    .push_bind(&key.route)
+                    .push_bind(key.referrer.as_deref().unwrap_or_default())
+                    .push_bind(key.utm_source.as_deref().unwrap_or_default())
+                    .push_bind(key.utm_medium.as_deref().unwrap_or_default())
+                    .push_bind(key.utm_campaign.as_deref().unwrap_or_default())
+                    .push_bind(key.device)
+                    .push_bind(key.country.as_deref().unwrap_or_default())
+                    .push_bind(views);
+            });
+            query.push(
+                r#"
+            ) AS bucket WHERE EXISTS (SELECT 1 FROM sites WHERE sites.id = bucket.column1)
+            ON CONFLICT (site_id, hour, route, referrer, utm_source, utm_medium, utm_campaign, device, country)
+            DO UPDATE SET views = views + excluded.views
+            "#,
+            );
+            query.build().execute(tx.as_mut()).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn list_hourly_views(
+        &self,
+        site_id: &str,
+        filter: &HourlyUsageFilter,
+    ) -> Result<Vec<HourlyViewsEntity>, Error> {
+        let mut query: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT hour, SUM(views) AS views FROM site_usage_hourly");
+        push_hourly_filter(&mut query, site_id, filter);
+        query.push(" GROUP BY hour ORDER BY hour");
+
+        let views = query
+            .build()
+            .try_map(|row: SqliteRow| {
+                Ok(HourlyViewsEntity {
+                    hour: row.try_get("hour")?,
+                    views: row.try_get("views")?,
+                })
+            })
+            .fetch_all(&mut *self.get_db_conn().await?)
+            .await?;
+        Ok(views)
+    }
+
+    async fn list_dimension_views(
+        &self,
+        site_id: &str,
+        filter: &HourlyUsageFilter,
+        dimension: AnalyticsDimension,
+        limit: u32,
+    ) -> Result<Vec<DimensionViewsEntity>, Error> {
+        let column = dimension.column();
+        let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+            "SELECT {} AS value, SUM(views) AS views FROM site_usage_hourly",
+            column
+        ));
+        push_hourly_filter(&mut query, site_id, filter);
+        query
+            .push(format!(
+                " GROUP BY {} ORDER BY views DESC, value LIMIT ",
+                column
+            ))
+            .push_bind(limit);
+
+        let views = query
+            .build()
+            .try_map(|row: SqliteRow| {
+                let value: String = row.try_get("value")?;
+                Ok(DimensionViewsEntity {
+                    value: (!value.is_empty()).then_some(value),
+                    views: row.try_get("views")?,
+                })
+            })
+            .fetch_all(&mut *self.get_db_conn().await?)
+            .await?;
+        Ok(views)
+    }
+
+    // Sketches persisted before are merged with the new visitors. Sketches of deleted
+    // sites are dropped
+    async fn merge_visitor_sketches(
+        &self,
+        sketches: &[(String, NaiveDate, HyperLogLog)],
+    ) -> Result<(), Error> {
+        let mut tx = self.metadata_db_pool.begin().await?;
+        for (site_id, day, sketch) in sketches {
+            let saved: Option<Vec<u8>> = sqlx::query_scalar(
+                "SELECT sketch FROM site_visitor_sketches WHERE site_id = ? AND day = ?",
+            )
+            .bind(site_id)
+            .bind(day)
+            .fetch_optional(tx.as_mut())
+            .await?;
+            let mut merged = sketch.clone();
+            if let Some(saved) = saved.as_deref().and_then(HyperLogLog::from_bytes) {
+                merged.merge(&saved);
+            }
+            sqlx::query(
+                r#"
+            INSERT INTO site_visitor_sketches (site_id, day, sketch)
+            SELECT ?, ?, ? WHERE EXISTS (SELECT 1 FROM sites WHERE id = ?)
+            ON CONFLICT (site_id, day) DO UPDATE SET sketch = excluded.sketch
+            "#,
+            )
+            .bind(site_id)
+            .bind(day)
+            .bind(merged.to_bytes())
+            .bind(site_id)
+            .execute(tx.as_mut())
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Sketches of the days from `start` to `end`, inclusive
+    async fn list_visitor_sketches(
+        &self,
+        site_id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, HyperLogLog)>, Error> {
+        let rows: Vec<(NaiveDate, Vec<u8>)> = sqlx::query_as(
+            r#"
+        SELECT day, sketch FROM site_visitor_sketches
+        WHERE site_id = ? AND day >= ? AND day <= ?
+        ORDER BY day
+        "#,
+        )
+        .bind(site_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&mut *self.get_db_conn().await?)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(day, sketch)| Some((day, HyperLogLog::from_bytes(&sketch)?)))
+            .collect())
+    }
+
+    async fn delete_visitor_sketches_before(&self, day: NaiveDate) -> Result<u64, Error> {
+        let result = sqlx::query("DELETE FROM site_visitor_sketches WHERE day < ?")
+            .bind(day)
+            .execute(&mut *self.get_db_conn().await?)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // Salt of `day`, stored from `salt` when no server created it yet. Expired salts are
+    // deleted first, so a salt is never kept past its day
+    async fn get_or_create_visitor_salt(
+        &self,
+        day: NaiveDate,
+        salt: &[u8],
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut tx = self.metadata_db_pool.begin().await?;
+        sqlx::query("DELETE FROM visitor_salts WHERE expires_at <= ?")
+            .bind(Utc::now())
+            .execute(tx.as_mut())
+            .await?;
+        sqlx::query(
+            r#"
+        INSERT INTO visitor_salts (day, salt, expires_at) VALUES (?, ?, ?)
+        ON CONFLICT (day) DO NOTHING
+        "#,
+        )
+        .bind(day)
+        .bind(salt)
+        .bind(expires_at)
+        .execute(tx.as_mut())
+        .await?;
+        let salt = sqlx::query_scalar("SELECT salt FROM visitor_salts WHERE day = ?")
+            .bind(day)
+            .fetch_one(tx.as_mut())
+            .await?;
+        tx.commit().await?;
+        Ok(salt)
+    }
+
+    async fn delete_expired_visitor_salts(&self, now: DateTime<Utc>) -> Result<u64, Error> {
+        let result = sqlx::query("DELETE FROM visitor_salts WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&mut *self.get_db_conn().await?)
+            .await?;
+        Ok(result.rows_affected())
+    }
 }
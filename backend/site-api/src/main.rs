@@ -1,29 +1,48 @@
 use axum::http::{header, Method};
+use axum::serve::ListenerExt;
 use axum::Router;
 use lib_shared_site_api::cache::cache::AppCache;
 use lib_shared_site_api::clients::s3_client::S3Client;
 use lib_shared_site_api::log::{create_trace_layer, setup_logging};
+use lib_shared_types::shared::core::ExecEnv;
 
 use clap::Parser;
 use site_api::api_context::ApiContext;
 use site_api::app::app_router::app_router;
+use site_api::app::certificate::renew_certificates::renew_certificates_helper;
+use site_api::app::publish::run_publish_schedules::run_publish_schedules_helper;
 use site_api::app::usage::helpers::populate_usage_cache;
 use site_api::config::Config;
 use site_api::cron::setup_cron_jobs;
 use site_api::db::backup_repo::{BackupRepo, DynBackupRepo};
 use site_api::db::custom_data_info_repo::{CustomDataInfoRepo, DynCustomDataInfoRepo};
 use site_api::db::custom_data_repo::{CustomDataRepo, DynCustomDataRepo};
+use site_api::db::domain_verification_repo::{DomainVerificationRepo, DynDomainVerificationRepo};
+use site_api::db::experiment_repo::{DynExperimentRepo, ExperimentRepo};
+use site_api::db::maintenance_repo::{DynMaintenanceRepo, MaintenanceRepo};
+use site_api::db::preview_link_repo::{DynPreviewLinkRepo, PreviewLinkRepo};
+use site_api::db::protected_route_repo::{DynProtectedRouteRepo, ProtectedRouteRepo};
+use site_api::db::publish_schedule_repo::{DynPublishScheduleRepo, PublishScheduleRepo};
+use site_api::db::redirect_repo::{DynRedirectRepo, RedirectRepo};
 use site_api::db::site_db_pool_manager::DbPoolManager;
 use site_api::db::site_repo::{DynSiteRepo, SiteRepo};
 use site_api::db::sites_metadata_repo::{DynSitesMetadataRepo, SitesMetadataRepo};
+use site_api::db::static_build_repo::{DynStaticBuildRepo, StaticBuildRepo};
+use site_api::db::template_repo::{DynTemplateRepo, TemplateRepo};
 use site_api::db::usage_repo::{DynUsageRepo, UsageRepo};
+use site_api::util::acme::AcmeClient;
+use site_api::util::certificates::CertificateStore;
+use site_api::util::dns::make_dns_resolver;
+use site_api::util::tls_listener::{make_tls_config, TlsListener};
 use sqlx::migrate::MigrateDatabase;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
 #[tokio::main]
 async fn main() {
@@ -44,11 +63,28 @@ async fn main() {
     let s3_access_key_id = config.s3_access_key_id.clone();
 
     let api_url = format!("{}:{}", host, port);
+    let tls_url = config
+        .tls_port
+        .map(|tls_port| format!("{}:{}", host, tls_port));
 
     // Don't allow empty string as auth bypass
     if config.auth_bypass_api_key == Some("".to_string()) {
         config.auth_bypass_api_key = None;
     }
+    // Visitor logins to protected pages are signed with this secret
+    if config
+        .site_access_secret
+        .as_deref()
+        .is_none_or(str::is_empty)
+    {
+        if config.exec_env != ExecEnv::Dev {
+            panic!("SITE_ACCESS_SECRET must be set outside dev, and shared by every instance");
+        }
+        println!(
+            "SITE_ACCESS_SECRET unset, visitors must log in to protected pages again after restart. Set it when running several instances"
+        );
+        config.site_access_secret = Some(format!("{}{}", Uuid::new_v4(), Uuid::new_v4()));
+    }
     // Notify auth mode
     if config.auth_bypass_api_key.is_some() {
         println!("Auth mode: bypass (In production generate a secure token.)");
@@ -75,6 +111,23 @@ async fn main() {
     }
     let metadata_db_pool = db_pool_manager.connect(metadata_db_url).await.unwrap();
 
+    // Set up TLS certificates of custom domains
+    let certificate_dir = config
+        .certificate_dir
+        .clone()
+        .unwrap_or(format!("{}/db/certificates", manifest_dir));
+    let certificate_store = CertificateStore::new(certificate_dir);
+    let acme_client = match config.tls_port {
+        Some(_) => {
+            certificate_store.load().await.unwrap();
+            println!("ACME directory: {}", config.acme_directory_url);
+            Some(Arc::new(
+                AcmeClient::new(&config, &certificate_store).unwrap(),
+            ))
+        }
+        None => None,
+    };
+
     // Set up ApiContext
     let metadata_repo = Arc::new(SitesMetadataRepo {
         metadata_db_pool: metadata_db_pool.clone(),
@@ -85,6 +138,15 @@ async fn main() {
     let usage_repo = Arc::new(UsageRepo {
         metadata_db_pool: metadata_db_pool.clone(),
     }) as DynUsageRepo;
+    let publish_schedule_repo = Arc::new(PublishScheduleRepo {
+        metadata_db_pool: metadata_db_pool.clone(),
+    }) as DynPublishScheduleRepo;
+    let domain_verification_repo = Arc::new(DomainVerificationRepo {
+        metadata_db_pool: metadata_db_pool.clone(),
+    }) as DynDomainVerificationRepo;
+    let template_repo = Arc::new(TemplateRepo {
+        metadata_db_pool: metadata_db_pool.clone(),
+    }) as DynTemplateRepo;
 
     let site_repo = Arc::new(SiteRepo {
         db_pool_manager: db_pool_manager.clone(),
@@ -94,6 +156,30 @@ async fn main() {
         db_pool_manager: db_pool_manager.clone(),
         manifest_dir: manifest_dir.clone(),
     }) as DynCustomDataInfoRepo;
+    let preview_link_repo = Arc::new(PreviewLinkRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynPreviewLinkRepo;
+    let static_build_repo = Arc::new(StaticBuildRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynStaticBuildRepo;
+    let redirect_repo = Arc::new(RedirectRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynRedirectRepo;
+    let protected_route_repo = Arc::new(ProtectedRouteRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynProtectedRouteRepo;
+    let maintenance_repo = Arc::new(MaintenanceRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynMaintenanceRepo;
+    let experiment_repo = Arc::new(ExperimentRepo {
+        db_pool_manager: db_pool_manager.clone(),
+        manifest_dir: manifest_dir.clone(),
+    }) as DynExperimentRepo;
     let custom_data_repo = Arc::new(CustomDataRepo {
         db_pool_manager,
         manifest_dir,
@@ -102,6 +188,7 @@ async fn main() {
     let s3_client = S3Client::new(s3_url, s3_access_key_id, s3_secret_access_key);
 
     let cache = AppCache::new(config.exec_env);
+    let dns_resolver = make_dns_resolver(&config);
 
     let context = ApiContext {
         config: Arc::new(config),
@@ -112,6 +199,18 @@ async fn main() {
         usage_repo,
         custom_data_info_repo,
         custom_data_repo,
+        publish_schedule_repo,
+        preview_link_repo,
+        template_repo,
+        static_build_repo,
+        redirect_repo,
+        protected_route_repo,
+        maintenance_repo,
+        experiment_repo,
+        domain_verification_repo,
+        dns_resolver,
+        certificate_store,
+        acme_client,
         cache,
     };
     let _ = populate_usage_cache(&context).await;
@@ -147,7 +246,13 @@ async fn main() {
     let sites = context.metadata_repo.list_sites().await.unwrap();
     context.site_repo.migrate_all(sites).await.unwrap();
 
+    // Run publish schedules that were missed while the server was down
+    run_publish_schedules_helper(context.clone()).await;
+    // Obtain certificates for domains verified while the server was down
+    tokio::spawn(renew_certificates_helper(context.clone()));
+
     // Run server
+    let tls_config = make_tls_config(context.certificate_store.clone());
     let mut app = Router::new()
         .merge(app_router(&context))
         .with_state(context)
@@ -155,7 +260,31 @@ async fn main() {
     // Enables logging. Use `RUST_LOG=trace`
     app = create_trace_layer(app);
 
+    if let Some(tls_url) = tls_url {
+        let listener = tokio::net::TcpListener::bind(&tls_url).await.unwrap();
+        println!("TLS listening on {}", tls_url);
+        // tap_io provides peer addresses to handlers, as for the plain listener
+        let listener = TlsListener::new(listener, tls_config)
+            .unwrap()
+            .tap_io(|_| {});
+        let tls_app = app.clone();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                tls_app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+    }
+
     let listener = tokio::net::TcpListener::bind(api_url).await.unwrap();
     tracing::debug!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+    // Peer addresses are used by the maintenance IP allowlist
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
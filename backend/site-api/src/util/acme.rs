@@ -0,0 +1,371 @@
+use std::{fs, time::Duration};
+
+use lib_shared_site_api::util::acme::{base64url, is_challenge_token, key_authorization, EcJwk};
+use rcgen::{CertificateParams, KeyPair};
+use reqwest::{header, Certificate, Client, Response};
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    config::Config,
+    util::certificates::{write_private_key, CertificateStore},
+};
+
+const ACCOUNT_KEY_FILE: &str = "account.key";
+const BAD_NONCE: &str = "urn:ietf:params:acme:error:badNonce";
+// Orders and challenges are polled every 2 seconds, for up to a minute
+const POLL_ATTEMPTS: u32 = 30;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Order {
+    status: String,
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Authorization {
+    status: String,
+    challenges: Vec<Challenge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    challenge_type: String,
+    url: String,
+    token: String,
+    error: Option<Value>,
+}
+
+// Directory, account URL and the next nonce, reused between orders
+#[derive(Default)]
+struct AcmeSession {
+    directory: Option<Directory>,
+    account_url: Option<String>,
+    nonce: Option<String>,
+}
+
+// ACME (RFC 8555) client, obtaining certificates with HTTP-01 challenges
+pub struct AcmeClient {
+    http: Client,
+    directory_url: String,
+    contact_email: Option<String>,
+    account_key: EcdsaKeyPair,
+    jwk: EcJwk,
+    rng: SystemRandom,
+    session: Mutex<AcmeSession>,
+    renewal: Mutex<()>,
+}
+
+// The account key is created on first run, and kept with the certificates
+fn load_account_key(store: &CertificateStore) -> Result<KeyPair, String> {
+    let path = store.dir().join(ACCOUNT_KEY_FILE);
+    if let Ok(pem) = fs::read_to_string(&path) {
+        return KeyPair::from_pem(&pem).map_err(|e| e.to_string());
+    }
+    let key = KeyPair::generate().map_err(|e| e.to_string())?;
+    fs::create_dir_all(store.dir()).map_err(|e| e.to_string())?;
+    write_private_key(&path, &key.serialize_pem())?;
+    Ok(key)
+}
+
+fn location(response: &Response) -> Result<String, String> {
+    response
+        .headers()
+        .get(header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| format!("ACME response from {} has no location", response.url()))
+}
+
+async fn parse<T: DeserializeOwned>(response: Response) -> Result<T, String> {
+    response.json().await.map_err(|e| e.to_string())
+}
+
+impl AcmeClient {
+    pub fn new(config: &Config, store: &CertificateStore) -> Result<Self, String> {
+        let mut http = Client::builder().timeout(Duration::from_secs(30));
+        if let Some(path) = &config.acme_ca_cert {
+            let pem = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            http =
+                http.add_root_certificate(Certificate::from_pem(&pem).map_err(|e| e.to_string())?);
+        }
+        let rng = SystemRandom::new();
+        let key = load_account_key(store)?;
+        let account_key =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &key.serialize_der(), &rng)
+                .map_err(|e| e.to_string())?;
+        let jwk = EcJwk::from_public_key(account_key.public_key().as_ref())?;
+
+        Ok(AcmeClient {
+            http: http.build().map_err(|e| e.to_string())?,
+            directory_url: config.acme_directory_url.clone(),
+            contact_email: config.acme_contact_email.clone(),
+            account_key,
+            jwk,
+            rng,
+            session: Mutex::new(AcmeSession::default()),
+            renewal: Mutex::new(()),
+        })
+    }
+
+    // Held while renewing certificates, so overlapping renewals skip instead of ordering twice
+    pub fn start_renewal(&self) -> Option<MutexGuard<'_, ()>> {
+        self.renewal.try_lock().ok()
+    }
+
+    async fn directory(&self, session: &mut AcmeSession) -> Result<Directory, String> {
+        if let Some(directory) = &session.directory {
+            return Ok(directory.clone());
+        }
+        let response = self
+            .http
+            .get(&self.directory_url)
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .map_err(|e| format!("Failed to get ACME directory: {}", e))?;
+        let directory: Directory = parse(response).await?;
+        session.directory = Some(directory.clone());
+        Ok(directory)
+    }
+
+    async fn nonce(&self, session: &mut AcmeSession) -> Result<String, String> {
+        if let Some(nonce) = session.nonce.take() {
+            return Ok(nonce);
+        }
+        let directory = self.directory(session).await?;
+        let response = self
+            .http
+            .head(&directory.new_nonce)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        response
+            .headers()
+            .get("replay-nonce")
+            .and_then(|nonce| nonce.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| "ACME server returned no nonce".to_string())
+    }
+
+    // Send a JWS signed request. Without a payload, this is a POST-as-GET request
+    async fn post(
+        &self,
+        session: &mut AcmeSession,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> Result<Response, String> {
+        let mut retried = false;
+        loop {
+            let mut protected = json!({
+                "alg": "ES256",
+                "nonce": self.nonce(session).await?,
+                "url": url,
+            });
+            match &session.account_url {
+                Some(account_url) => protected["kid"] = json!(account_url),
+                None => protected["jwk"] = self.jwk.to_value(),
+            }
+            let protected = base64url(protected.to_string().as_bytes());
+            let payload = payload
+                .map(|payload| base64url(payload.to_string().as_bytes()))
+                .unwrap_or_default();
+            let signature = self
+                .account_key
+                .sign(&self.rng, format!("{}.{}", protected, payload).as_bytes())
+                .map_err(|e| e.to_string())?;
+            let body = json!({
+                "protected": protected,
+                "payload": payload,
+                "signature": base64url(signature.as_ref()),
+            });
+
+            let response = self
+                .http
+                .post(url)
+                .header(header::CONTENT_TYPE, "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            session.nonce = response
+                .headers()
+                .get("replay-nonce")
+                .and_then(|nonce| nonce.to_str().ok())
+                .map(str::to_string);
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            let problem: Value = response.json().await.unwrap_or_default();
+            // Nonces may expire, and are retried once with a new nonce
+            if problem["type"] == BAD_NONCE && !retried {
+                retried = true;
+                continue;
+            }
+            return Err(format!("ACME request to {} failed: {}", url, problem));
+        }
+    }
+
+    async fn account(&self, session: &mut AcmeSession) -> Result<(), String> {
+        if session.account_url.is_some() {
+            return Ok(());
+        }
+        let directory = self.directory(session).await?;
+        let mut payload = json!({ "termsOfServiceAgreed": true });
+        if let Some(email) = &self.contact_email {
+            payload["contact"] = json!([format!("mailto:{}", email)]);
+        }
+        let response = self
+            .post(session, &directory.new_account, Some(&payload))
+            .await?;
+        session.account_url = Some(location(&response)?);
+        Ok(())
+    }
+
+    // Complete the HTTP-01 challenge of an authorization. The key authorization is served
+    // from the challenge route until the server validated it
+    async fn authorize(
+        &self,
+        session: &mut AcmeSession,
+        url: &str,
+        store: &CertificateStore,
+    ) -> Result<(), String> {
+        let authorization: Authorization = parse(self.post(session, url, None).await?).await?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let challenge = authorization
+            .challenges
+            .into_iter()
+            .find(|challenge| challenge.challenge_type == "http-01")
+            .ok_or("ACME server offered no HTTP-01 challenge")?;
+        if !is_challenge_token(&challenge.token) {
+            return Err(format!("Invalid challenge token: {}", challenge.token));
+        }
+
+        store.add_challenge(
+            &challenge.token,
+            key_authorization(&challenge.token, &self.jwk.thumbprint()),
+        );
+        let result = self.validate(session, url, &challenge.url).await;
+        store.remove_challenge(&challenge.token);
+        result
+    }
+
+    async fn validate(
+        &self,
+        session: &mut AcmeSession,
+        authorization_url: &str,
+        challenge_url: &str,
+    ) -> Result<(), String> {
+        self.post(session, challenge_url, Some(&json!({}))).await?;
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let authorization: Authorization =
+                parse(self.post(session, authorization_url, None).await?).await?;
+            match authorization.status.as_str() {
+                "valid" => return Ok(()),
+                "pending" | "processing" => continue,
+                status => {
+                    let error = authorization
+                        .challenges
+                        .iter()
+                        .find_map(|challenge| challenge.error.as_ref());
+                    return Err(format!(
+                        "Authorization is {}: {}",
+                        status,
+                        error.map(Value::to_string).unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        Err("Timed out waiting for the challenge to be validated".into())
+    }
+
+    // Poll an order until the certificate is issued
+    async fn wait_for_certificate(
+        &self,
+        session: &mut AcmeSession,
+        order_url: &str,
+        mut order: Order,
+    ) -> Result<String, String> {
+        for _ in 0..POLL_ATTEMPTS {
+            match order.status.as_str() {
+                "valid" => {
+                    return order
+                        .certificate
+                        .ok_or_else(|| "Issued order has no certificate".to_string())
+                }
+                "invalid" => {
+                    return Err(format!(
+                        "Order is invalid: {}",
+                        order.error.map(|e| e.to_string()).unwrap_or_default()
+                    ))
+                }
+                _ => {}
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            order = parse(self.post(session, order_url, None).await?).await?;
+        }
+        Err("Timed out waiting for the certificate".into())
+    }
+
+    // Order a certificate for a domain. Returns the PEM certificate chain and private key
+    pub async fn issue_certificate(
+        &self,
+        domain: &str,
+        store: &CertificateStore,
+    ) -> Result<(String, String), String> {
+        let mut session = self.session.lock().await;
+        let session = &mut *session;
+        self.account(session).await?;
+        let directory = self.directory(session).await?;
+
+        let payload = json!({ "identifiers": [{ "type": "dns", "value": domain }] });
+        let response = self
+            .post(session, &directory.new_order, Some(&payload))
+            .await?;
+        let order_url = location(&response)?;
+        let order: Order = parse(response).await?;
+        for authorization_url in &order.authorizations {
+            self.authorize(session, authorization_url, store).await?;
+        }
+
+        let key = KeyPair::generate().map_err(|e| e.to_string())?;
+        let csr = CertificateParams::new(vec![domain.to_string()])
+            .and_then(|params| params.serialize_request(&key))
+            .map_err(|e| e.to_string())?;
+        let payload = json!({ "csr": base64url(csr.der()) });
+        let order: Order =
+            parse(self.post(session, &order.finalize, Some(&payload)).await?).await?;
+        let certificate_url = self
+            .wait_for_certificate(session, &order_url, order)
+            .await?;
+
+        let certificate = self
+            .post(session, &certificate_url, None)
+            .await?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok((certificate, key.serialize_pem()))
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions, Permissions},
+    io::Write,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use rustls::{
+    crypto::ring::sign::any_supported_type,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+use tracing::{info, warn};
+
+const CERTIFICATE_EXTENSION: &str = "crt";
+const KEY_EXTENSION: &str = "key";
+
+// Private keys are only readable by the server's user. Keys written before are restricted
+// too, since `mode` only applies to new files
+pub fn write_private_key(path: &Path, pem: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.set_permissions(Permissions::from_mode(0o600))
+        .map_err(|e| e.to_string())?;
+    file.write_all(pem.as_bytes()).map_err(|e| e.to_string())
+}
+
+struct StoredCertificate {
+    key: Arc<CertifiedKey>,
+    not_after: DateTime<Utc>,
+}
+
+// TLS certificates of custom domains, kept on disk as `<domain>.crt` and `<domain>.key` PEM
+// files. Also holds the pending HTTP-01 challenges of certificate orders
+#[derive(Clone)]
+pub struct CertificateStore {
+    dir: PathBuf,
+    certificates: Arc<RwLock<HashMap<String, StoredCertificate>>>,
+    challenges: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl std::fmt::Debug for CertificateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateStore")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+fn parse_certificate(cert_pem: &[u8], key_pem: &[u8]) -> Result<StoredCertificate, String> {
+    let chain = CertificateDer::pem_slice_iter(cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let leaf = chain.first().ok_or("Certificate chain is empty")?;
+    let (_, leaf) = x509_parser::parse_x509_certificate(leaf).map_err(|e| e.to_string())?;
+    let not_after = DateTime::from_timestamp(leaf.validity().not_after.timestamp(), 0)
+        .ok_or("Invalid certificate expiry")?;
+
+    let key = PrivateKeyDer::from_pem_slice(key_pem).map_err(|e| e.to_string())?;
+    let signing_key = any_supported_type(&key).map_err(|e| e.to_string())?;
+    Ok(StoredCertificate {
+        key: Arc::new(CertifiedKey::new(chain, signing_key)),
+        not_after,
+    })
+}
+
+fn certificate_path(dir: &Path, domain: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{}.{}", domain, extension))
+}
+
+fn read_certificate(dir: &Path, domain: &str) -> Result<StoredCertificate, String> {
+    let cert = fs::read(certificate_path(dir, domain, CERTIFICATE_EXTENSION))
+        .map_err(|e| e.to_string())?;
+    let key = fs::read(certificate_path(dir, domain, KEY_EXTENSION)).map_err(|e| e.to_string())?;
+    parse_certificate(&cert, &key)
+}
+
+// Read every stored certificate. Invalid files are skipped
+fn read_certificates(dir: &Path) -> Result<Vec<(String, StoredCertificate)>, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
+    let mut certificates = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(CERTIFICATE_EXTENSION) {
+            continue;
+        }
+        let Some(domain) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match read_certificate(dir, domain) {
+            Ok(certificate) => certificates.push((domain.to_string(), certificate)),
+            Err(e) => warn!("Failed to load certificate of {}: {}", domain, e),
+        }
+    }
+    Ok(certificates)
+}
+
+fn write_certificate(
+    dir: &Path,
+    domain: &str,
+    cert_pem: &str,
+    key_pem: &str,
+) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    write_private_key(&certificate_path(dir, domain, KEY_EXTENSION), key_pem)?;
+    fs::write(
+        certificate_path(dir, domain, CERTIFICATE_EXTENSION),
+        cert_pem,
+    )
+    .map_err(|e| e.to_string())
+}
+
+impl CertificateStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CertificateStore {
+            dir: dir.into(),
+            certificates: Arc::new(RwLock::new(HashMap::new())),
+            challenges: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    // Load certificates saved by previous runs. Invalid files are skipped
+    pub async fn load(&self) -> Result<(), String> {
+        let dir = self.dir.clone();
+        let certificates = tokio::task::spawn_blocking(move || read_certificates(&dir))
+            .await
+            .map_err(|e| e.to_string())??;
+        info!("Loaded {} TLS certificates", certificates.len());
+        self.certificates.write().unwrap().extend(certificates);
+        Ok(())
+    }
+
+    // Save an issued certificate, and serve it
+    pub async fn save(&self, domain: &str, cert_pem: &str, key_pem: &str) -> Result<(), String> {
+        let certificate = parse_certificate(cert_pem.as_bytes(), key_pem.as_bytes())?;
+        let (dir, file_domain) = (self.dir.clone(), domain.to_string());
+        let (cert_pem, key_pem) = (cert_pem.to_string(), key_pem.to_string());
+        tokio::task::spawn_blocking(move || {
+            write_certificate(&dir, &file_domain, &cert_pem, &key_pem)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        self.certificates
+            .write()
+            .unwrap()
+            .insert(domain.to_string(), certificate);
+        Ok(())
+    }
+
+    pub fn not_after(&self, domain: &str) -> Option<DateTime<Utc>> {
+        self.certificates
+            .read()
+            .unwrap()
+            .get(domain)
+            .map(|certificate| certificate.not_after)
+    }
+
+    // Stop serving certificates of domains that are no longer verified. Files are kept, and
+    // loaded again when the domain is verified again
+    pub async fn retain_domains(&self, domains: &[String]) {
+        let unloaded: Vec<String> = {
+            let mut certificates = self.certificates.write().unwrap();
+            certificates.retain(|domain, _| domains.contains(domain));
+            domains
+                .iter()
+                .filter(|domain| !certificates.contains_key(*domain))
+                .cloned()
+                .collect()
+        };
+        if unloaded.is_empty() {
+            return;
+        }
+        let dir = self.dir.clone();
+        // Domains without stored files get a new certificate
+        let loaded = tokio::task::spawn_blocking(move || {
+            unloaded
+                .into_iter()
+                .filter_map(|domain| {
+                    let certificate = read_certificate(&dir, &domain).ok()?;
+                    Some((domain, certificate))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+        self.certificates.write().unwrap().extend(loaded);
+    }
+
+    pub fn add_challenge(&self, token: &str, key_authorization: String) {
+        self.challenges
+            .write()
+            .unwrap()
+            .insert(token.to_string(), key_authorization);
+    }
+
+    pub fn remove_challenge(&self, token: &str) {
+        self.challenges.write().unwrap().remove(token);
+    }
+
+    pub fn challenge(&self, token: &str) -> Option<String> {
+        self.challenges.read().unwrap().get(token).cloned()
+    }
+}
+
+// Select the certificate by SNI
+impl ResolvesServerCert for CertificateStore {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let domain = client_hello.server_name()?.to_ascii_lowercase();
+        self.certificates
+            .read()
+            .unwrap()
+            .get(&domain)
+            .map(|certificate| certificate.key.clone())
+    }
+}
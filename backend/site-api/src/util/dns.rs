@@ -0,0 +1,74 @@
+use hickory_resolver::{
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    error::{ResolveError, ResolveErrorKind},
+    proto::rr::RecordType,
+    TokioAsyncResolver,
+};
+use lib_shared_site_api::util::domain_verification::{verification_record_name, DomainRecords};
+use tracing::warn;
+
+use crate::config::Config;
+
+// Resolver for domain verification. Answers are not cached, so owners see DNS changes on the
+// next check. Falls back to public nameservers when the system configuration can't be read
+pub fn make_dns_resolver(config: &Config) -> TokioAsyncResolver {
+    let resolver_config = match config.dns_nameserver {
+        Some(nameserver) => ResolverConfig::from_parts(
+            None,
+            vec![],
+            NameServerConfigGroup::from_ips_clear(&[nameserver.ip()], nameserver.port(), true),
+        ),
+        None => match hickory_resolver::system_conf::read_system_conf() {
+            Ok((system_config, _)) => system_config,
+            Err(e) => {
+                warn!(
+                    "Failed to read system DNS configuration, using defaults: {}",
+                    e
+                );
+                ResolverConfig::default()
+            }
+        },
+    };
+    let mut options = ResolverOpts::default();
+    options.cache_size = 0;
+    TokioAsyncResolver::tokio(resolver_config, options)
+}
+
+// Missing records are not an error
+fn records_or_empty<T>(result: Result<Vec<T>, ResolveError>) -> Result<Vec<T>, String> {
+    match result {
+        Ok(records) => Ok(records),
+        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(vec![]),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub async fn resolve_domain_records(
+    resolver: &TokioAsyncResolver,
+    domain: &str,
+) -> Result<DomainRecords, String> {
+    let txt = resolver
+        .txt_lookup(verification_record_name(domain))
+        .await
+        .map(|lookup| lookup.iter().map(|txt| txt.to_string()).collect());
+    let cnames = resolver
+        .lookup(domain, RecordType::CNAME)
+        .await
+        .map(|lookup| {
+            lookup
+                .iter()
+                .filter_map(|record| record.as_cname())
+                .map(|cname| cname.to_string())
+                .collect()
+        });
+    let ips = resolver
+        .lookup_ip(domain)
+        .await
+        .map(|lookup| lookup.iter().collect());
+
+    Ok(DomainRecords {
+        txt: records_or_empty(txt)?,
+        cnames: records_or_empty(cnames)?,
+        ips: records_or_empty(ips)?,
+    })
+}
@@ -1 +1,5 @@
+pub mod acme;
+pub mod certificates;
+pub mod dns;
 pub mod mail_helpers;
+pub mod tls_listener;
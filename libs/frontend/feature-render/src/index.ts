@@ -8,6 +8,7 @@ export * from './lib/get-page-style'
 export * from './lib/pseudo-class-to-css-class'
 export * from './lib/not-found-page'
 export * from './lib/setup-routes'
+export * from './lib/site-locale'
 export * from './lib/page-head'
 export * from './lib/static-not-found'
 export * from './lib/static-site-app'
@@ -38,6 +38,20 @@ export const computeEvents = (site: ISite, component: IComponent): IEventCollect
 
 export const i18nVarRegex = /\$\{(.*?)\}/g
 
+// Variables pages read from the site runtime. They take precedence over translations
+export const siteVariables = (site: ISite): Record<string, string> => ({
+  subdomain: site.subdomain ?? '',
+})
+
+export const parseSiteVariables = (
+  variables: Record<string, string>,
+  content: string | undefined,
+): string | undefined => {
+  return content?.replace(i18nVarRegex, (match, variable: string) =>
+    variable in variables ? variables[variable] : match,
+  )
+}
+
 export const parseI18n = (
   i18n: Record<string, ITranslations>,
   lang: string,
@@ -0,0 +1,37 @@
+import { deserializeSite } from '@pubstudio/frontend/util-site-deserialize'
+import { mockSerializedSite } from '@pubstudio/frontend/util-test-mock'
+import { IComponent, ISite } from '@pubstudio/shared/type-site'
+import { computePropsContent } from './render'
+import { parseSiteVariables } from './render-helpers'
+
+describe('site variables', () => {
+  let site: ISite
+  let component: IComponent
+
+  beforeEach(() => {
+    site = deserializeSite(JSON.stringify(mockSerializedSite)) as ISite
+    component = site.context.components['test-c-1']
+    component.content = 'Welcome to ${subdomain}'
+  })
+
+  it('renders the subdomain of wildcard domains', () => {
+    site.subdomain = 'summit'
+    const { content } = computePropsContent(site, component, 'release')
+    expect(content).toEqual('Welcome to summit')
+  })
+
+  it('renders an empty subdomain on other domains', () => {
+    const { content } = computePropsContent(site, component, 'release')
+    expect(content).toEqual('Welcome to ')
+  })
+
+  it('leaves translations to i18n', () => {
+    site.context.i18n = { en: { welcome: 'Welcome' } }
+    component.content = '${welcome}'
+    const { content } = computePropsContent(site, component, 'release')
+    expect(content).toEqual('Welcome')
+    expect(parseSiteVariables({ subdomain: 'a' }, '${welcome} ${subdomain}')).toEqual(
+      '${welcome} a',
+    )
+  })
+})
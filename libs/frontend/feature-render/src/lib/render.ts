@@ -4,7 +4,12 @@ import { IComponent, IPage, ISite } from '@pubstudio/shared/type-site'
 import { h, VNode } from 'vue'
 import { computeAttrsInputsMixins } from './compute-attrs-inputs-mixins'
 import { LiveComponent } from './live-component'
-import { computeEvents, parseI18n } from './render-helpers'
+import {
+  computeEvents,
+  parseI18n,
+  parseSiteVariables,
+  siteVariables,
+} from './render-helpers'
 
 export const renderPage = (
   site: ISite,
@@ -28,7 +33,11 @@ export const computePropsContent = (
 
   const content: IContent = component.children?.length
     ? component.children.map((child) => renderComponent(site, child, renderMode))
-    : parseI18n(site.context.i18n, active, data.content)
+    : parseI18n(
+        site.context.i18n,
+        active,
+        parseSiteVariables(siteVariables(site), data.content),
+      )
 
   const props = {
     ...data.attrs,
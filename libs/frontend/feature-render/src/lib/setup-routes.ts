@@ -6,6 +6,7 @@ import {
 import { getSiteRouter } from '@pubstudio/frontend/util-runtime'
 import { IPage, ISite } from '@pubstudio/shared/type-site'
 import { defineComponent } from 'vue'
+import { findPathLocale, localizeRoute } from './site-locale'
 
 export const setupRoutes = (
   userSite: ISite,
@@ -14,6 +15,7 @@ export const setupRoutes = (
 ) => {
   const pageMap = new Map<string, IPage>()
   const router = getSiteRouter()
+  const locales = userSite.defaults.locales ?? []
 
   Object.values(userSite.pages).forEach((page) => {
     if (page.route === '/not-found') {
@@ -34,9 +36,44 @@ export const setupRoutes = (
       })
     }
 
+    // Localized pages are served under each locale's prefix. The alias keeps the page
+    // route as the route path, which pages are looked up by
+    for (const { code } of locales) {
+      if (page.route !== '/not-found') {
+        router.addRoute({
+          path: pathPrefix + localizeRoute(code, page.route),
+          alias: pathPrefix + page.route,
+          name: `${code}:${page.name}`,
+          component: PageContent,
+        })
+      }
+      if (userSite.defaults.homePage === page.route) {
+        router.addRoute({
+          name: `__HOME__${code}`,
+          path: pathPrefix + localizeRoute(code, '/'),
+          alias: pathPrefix + page.route,
+          component: PageContent,
+        })
+      }
+    }
+
     pageMap.set(page.route, page)
   })
 
+  // Locale prefixes match case-insensitively, like the Site API. Route them with the
+  // locale's code, e.g. `/zh-tw/about` as `/zh-TW/about`
+  const pathLocale = findPathLocale(
+    locales,
+    window.location.pathname.slice(pathPrefix.length),
+  )
+  if (pathLocale) {
+    const { pathname, search, hash } = window.location
+    const localized = pathPrefix + localizeRoute(pathLocale.locale.code, pathLocale.route)
+    if (localized !== pathname) {
+      history.replaceState(undefined, '', `${localized}${search}${hash}`)
+    }
+  }
+
   const { pathname } = window.location
   const currentPage = pageMap.get(pathname)
   const currentPath = getCurrentPath()
@@ -0,0 +1,27 @@
+import { findPathLocale, localizeRoute, stripLocalePrefix } from './site-locale'
+
+describe('site locales', () => {
+  const locales = [{ code: 'en' }, { code: 'zh-TW' }]
+
+  it('strips locale prefixes case-insensitively', () => {
+    expect(stripLocalePrefix('zh-TW', '/zh-tw/about')).toEqual('/about')
+    expect(stripLocalePrefix('zh-TW', '/zh-TW')).toEqual('/')
+    expect(stripLocalePrefix('zh-TW', '/zh-TWabout')).toBeUndefined()
+    expect(stripLocalePrefix('zh-TW', '/about')).toBeUndefined()
+  })
+
+  it('finds the locale of a path', () => {
+    expect(findPathLocale(locales, '/zh-tw/about')).toEqual({
+      locale: locales[1],
+      route: '/about',
+    })
+    expect(findPathLocale(locales, '/EN')).toEqual({ locale: locales[0], route: '/' })
+    expect(findPathLocale(locales, '/about')).toBeUndefined()
+    expect(findPathLocale(undefined, '/en')).toBeUndefined()
+  })
+
+  it('localizes routes', () => {
+    expect(localizeRoute('zh-TW', '/')).toEqual('/zh-TW')
+    expect(localizeRoute('zh-TW', '/about')).toEqual('/zh-TW/about')
+  })
+})
@@ -0,0 +1,51 @@
+import { ISite, ISiteLocale } from '@pubstudio/shared/type-site'
+
+export interface IPathLocale {
+  locale: ISiteLocale
+  // Page route without the locale prefix, the locale's home page is `/`
+  route: string
+}
+
+// Served path of a page route in a locale, the home page is `/zh-TW`
+export const localizeRoute = (code: string, route: string): string =>
+  route === '/' ? `/${code}` : `/${code}${route}`
+
+// Route of a localized path, `/zh-TW/about` -> `/about`. Prefixes match
+// case-insensitively, like the Site API
+export const stripLocalePrefix = (code: string, path: string): string | undefined => {
+  const prefix = path.slice(1, code.length + 1)
+  if (!path.startsWith('/') || prefix.toLowerCase() !== code.toLowerCase()) {
+    return undefined
+  }
+  const rest = path.slice(code.length + 1)
+  if (rest === '') {
+    return '/'
+  }
+  return rest.startsWith('/') ? rest : undefined
+}
+
+// The locale a served path is prefixed with, undefined for unprefixed paths
+export const findPathLocale = (
+  locales: ISiteLocale[] | undefined,
+  path: string,
+): IPathLocale | undefined => {
+  for (const locale of locales ?? []) {
+    const route = stripLocalePrefix(locale.code, path)
+    if (route !== undefined) {
+      return { locale, route }
+    }
+  }
+  return undefined
+}
+
+// Render the site in the locale of a served path, unprefixed paths are in the default
+// locale. Returns false when the site has no locales, and the visitor's language applies
+export const setPathLocale = (site: ISite, path: string): boolean => {
+  const locales = site.defaults.locales ?? []
+  const locale = findPathLocale(locales, path)?.locale ?? locales[0]
+  if (!locale) {
+    return false
+  }
+  site.context.activeI18n = locale.code
+  return true
+}
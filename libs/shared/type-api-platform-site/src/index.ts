@@ -10,7 +10,9 @@ export * from './lib/i-get-site-api-response'
 export * from './lib/enum-site-type'
 export * from './lib/enum-site-variant'
 export * from './lib/enum-site-payment-period'
+export * from './lib/enum-www-policy'
 export * from './lib/i-site.view-model'
 export * from './lib/i-create-domain-api-request'
 export * from './lib/i-verify-domain-api-request'
 export * from './lib/i-delete-domain-api-request'
+export * from './lib/enum-maintenance-page'
@@ -0,0 +1,7 @@
+// Holding page served while a site is in maintenance mode
+export enum MaintenancePage {
+  // Generated page with the site title and the owner's message
+  Builtin = 'Builtin',
+  // The owner's HTML document
+  Custom = 'Custom',
+}
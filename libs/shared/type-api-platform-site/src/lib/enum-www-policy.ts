@@ -0,0 +1,6 @@
+// Applied to the site's primary domain when redirecting to the canonical host
+export enum WwwPolicy {
+  Keep = 'Keep',
+  Www = 'Www',
+  Apex = 'Apex',
+}
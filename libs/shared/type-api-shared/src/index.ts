@@ -1,2 +1,3 @@
+export * from './lib/enum-domain-kind'
 export * from './lib/enum-site-checkout-state'
 export * from './lib/i-custom-domain-relation-model'
@@ -0,0 +1,9 @@
+// How requests to a custom domain are handled
+export enum DomainKind {
+  // The site is served on the domain
+  Serve = 'Serve',
+  // Alias, permanently redirected to the site's canonical host
+  Redirect = 'Redirect',
+  // `*.events.example.com`, the site is served on every subdomain
+  Wildcard = 'Wildcard',
+}
@@ -1,4 +1,10 @@
+import { DomainKind } from './enum-domain-kind'
+
 export interface ICustomDomainRelationViewModel {
   domain: string
   verified: boolean
+  // Canonical host of the site, other hosts redirect to it. Must be verified
+  primary?: boolean
+  // Defaults to Serve
+  kind?: DomainKind
 }
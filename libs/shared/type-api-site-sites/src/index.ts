@@ -14,3 +14,40 @@ export * from './lib/i-site-info.view-model'
 export * from './lib/i-list-site-versions-api-response'
 export * from './lib/i-get-site-version-api-request'
 export * from './lib/i-get-site-version-api-response'
+export * from './lib/i-create-publish-schedule-api-request'
+export * from './lib/i-publish-schedule.view-model'
+export * from './lib/i-list-publish-schedules-api-response'
+export * from './lib/i-create-draft-api-request'
+export * from './lib/i-patch-site-api-request'
+export * from './lib/i-patch-site-api-response'
+export * from './lib/i-site-merge-conflict-api-response'
+export * from './lib/i-create-preview-link-api-request'
+export * from './lib/i-preview-link.view-model'
+export * from './lib/i-list-preview-links-api-response'
+export * from './lib/i-unlock-preview-link-api-request'
+export * from './lib/i-unlock-preview-link-api-response'
+export * from './lib/i-import-site-api-request'
+export * from './lib/i-duplicate-site-api-request'
+export * from './lib/i-create-template-api-request'
+export * from './lib/i-template.view-model'
+export * from './lib/i-list-templates-api-response'
+export * from './lib/i-static-build.view-model'
+export * from './lib/i-redirect.view-model'
+export * from './lib/i-create-redirect-api-request'
+export * from './lib/i-update-redirect-api-request'
+export * from './lib/i-list-redirects-api-response'
+export * from './lib/i-protected-route.view-model'
+export * from './lib/i-create-protected-route-api-request'
+export * from './lib/i-update-protected-route-api-request'
+export * from './lib/i-list-protected-routes-api-response'
+export * from './lib/i-maintenance.view-model'
+export * from './lib/i-update-maintenance-api-request'
+export * from './lib/i-experiment.view-model'
+export * from './lib/i-create-experiment-api-request'
+export * from './lib/i-update-experiment-api-request'
+export * from './lib/i-list-experiments-api-response'
+export * from './lib/i-experiment-results.view-model'
+export * from './lib/i-domain-verification.view-model'
+export * from './lib/i-list-domain-verifications-api-response'
+export * from './lib/i-record-page-view-api-request'
+export * from './lib/i-site-analytics.view-model'
@@ -0,0 +1,4 @@
+export interface ICreateDraftApiRequest {
+  message?: string
+  tag?: string
+}
@@ -0,0 +1,15 @@
+export interface ICreateExperimentVariantApiRequest {
+  name: string
+  page_route: string
+  // Relative share of visitors, 1 to 1000
+  weight: number
+}
+
+export interface ICreateExperimentApiRequest {
+  route: string
+  name: string
+  enabled?: boolean
+  conversion_table?: string
+  // 2 to 10 variants, the first is the control
+  variants: ICreateExperimentVariantApiRequest[]
+}
@@ -0,0 +1,5 @@
+export interface ICreatePreviewLinkApiRequest {
+  version_id: number
+  expires_at?: string
+  password?: string
+}
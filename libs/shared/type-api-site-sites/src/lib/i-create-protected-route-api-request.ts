@@ -0,0 +1,4 @@
+export interface ICreateProtectedRouteApiRequest {
+  route: string
+  password: string
+}
@@ -0,0 +1,3 @@
+export interface ICreatePublishScheduleApiRequest {
+  publish_at: string
+}
@@ -0,0 +1,7 @@
+export interface ICreateRedirectApiRequest {
+  source: string
+  target: string
+  // 301, 302, 307 or 308. Defaults to 301
+  status_code?: number
+  enabled?: boolean
+}
@@ -16,3 +16,10 @@ export interface ICreateSiteApiRequest {
   domains: ICustomDomainRelationViewModel[]
   site_type: string
 }
+
+export type ICreateSiteFromTemplateApiRequest = Omit<
+  ICreateSiteApiRequest,
+  'version' | 'context' | 'defaults' | 'editor' | 'history' | 'pages' | 'pageOrder'
+> & {
+  template_id: string
+}
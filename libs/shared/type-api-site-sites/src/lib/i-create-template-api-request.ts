@@ -0,0 +1,16 @@
+export interface ICreateTemplatePreviewUpload {
+  content_type: string
+  size: number
+}
+
+export interface ICreateTemplateApiRequest {
+  // Site to snapshot. The published version is used when there is one
+  site_id: string
+  name: string
+  description?: string
+  category: string
+  // Request a presigned URL for uploading the preview image
+  preview?: ICreateTemplatePreviewUpload
+  // Include custom table schemas, without their rows
+  include_custom_tables?: boolean
+}
@@ -0,0 +1,15 @@
+export type DomainVerificationStatus = 'Pending' | 'Verified' | 'Failing' | 'Suspended'
+
+export interface IDomainVerificationViewModel {
+  domain: string
+  // Name of the TXT record holding the token
+  txt_name: string
+  token: string
+  status: DomainVerificationStatus
+  verified: boolean
+  last_checked_at?: Date
+  // Record type that verified the domain
+  last_result?: string
+  last_error?: string
+  failing_since?: Date
+}
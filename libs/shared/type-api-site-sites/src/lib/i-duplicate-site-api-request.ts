@@ -0,0 +1,11 @@
+export interface IDuplicateSiteApiRequest {
+  owner_id: string
+  // Defaults to the owner email of the source site
+  owner_email?: string
+  // Defaults to the name of the source site
+  name?: string
+  // Defaults to the site type of the source site
+  site_type?: string
+  // Copy custom table schemas, without their rows
+  copy_custom_tables?: boolean
+}
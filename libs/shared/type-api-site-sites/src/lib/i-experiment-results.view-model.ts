@@ -0,0 +1,19 @@
+export interface IExperimentVariantResultViewModel {
+  id: number
+  name: string
+  exposures: number
+  conversions: number
+  conversion_rate: number
+  // Comparison with the control, missing for the control and without enough data
+  lift?: number
+  z_score?: number
+  p_value?: number
+  significant: boolean
+}
+
+export interface IExperimentResultsViewModel {
+  id: number
+  name: string
+  control_id: number
+  variants: IExperimentVariantResultViewModel[]
+}
@@ -0,0 +1,22 @@
+export interface IExperimentVariantViewModel {
+  id: number
+  name: string
+  // Page rendered for the variant, the experiment route for the control
+  page_route: string
+  weight: number
+  exposures: number
+  conversions: number
+}
+
+export interface IExperimentViewModel {
+  id: number
+  route: string
+  name: string
+  enabled: boolean
+  // Custom data table whose inserts count as conversions
+  conversion_table?: string
+  // The first variant is the control
+  variants: IExperimentVariantViewModel[]
+  created_at: Date
+  updated_at: Date
+}
@@ -7,6 +7,10 @@ export interface IGetSiteUsageApiResponse {
   request_count: number
   site_view_count: number
   total_site_view_count: number
+  // Estimated unique visitors of the current UTC day. Visitors can't be linked across days,
+  // so the month so far counts each visitor once per day they visited
+  daily_unique_visitors: number
+  monthly_visitor_days: number
   page_views: Record<string, number>
   total_page_views: Record<string, number>
   request_error_count: number
@@ -20,6 +24,8 @@ export interface IGetSiteUsageApiResponse {
 
 export interface IGetPublicSiteUsageApiResponse {
   total_site_view_count: number
+  daily_unique_visitors: number
+  monthly_visitor_days: number
   total_page_views: Record<string, number>
   last_updated: Date
 }
@@ -0,0 +1,7 @@
+// Query parameters for importing a site archive, which is sent as the request body
+export interface IImportSiteApiRequest {
+  site_id: string
+  owner_id: string
+  owner_email: string
+  site_type: string
+}
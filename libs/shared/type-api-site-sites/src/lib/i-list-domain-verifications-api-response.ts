@@ -0,0 +1,3 @@
+import { IDomainVerificationViewModel } from './i-domain-verification.view-model'
+
+export type IListDomainVerificationsApiResponse = IDomainVerificationViewModel[]
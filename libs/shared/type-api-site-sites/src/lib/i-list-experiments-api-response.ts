@@ -0,0 +1,3 @@
+import { IExperimentViewModel } from './i-experiment.view-model'
+
+export type IListExperimentsApiResponse = IExperimentViewModel[]
@@ -0,0 +1,3 @@
+import { IPreviewLinkViewModel } from './i-preview-link.view-model'
+
+export type IListPreviewLinksApiResponse = IPreviewLinkViewModel[]
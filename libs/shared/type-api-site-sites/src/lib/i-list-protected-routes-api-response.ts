@@ -0,0 +1,3 @@
+import { IProtectedRouteViewModel } from './i-protected-route.view-model'
+
+export type IListProtectedRoutesApiResponse = IProtectedRouteViewModel[]
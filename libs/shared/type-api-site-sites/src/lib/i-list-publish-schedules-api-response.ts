@@ -0,0 +1,3 @@
+import { IPublishScheduleViewModel } from './i-publish-schedule.view-model'
+
+export type IListPublishSchedulesApiResponse = IPublishScheduleViewModel[]
@@ -0,0 +1,3 @@
+import { IRedirectViewModel } from './i-redirect.view-model'
+
+export type IListRedirectsApiResponse = IRedirectViewModel[]
@@ -1,3 +1,4 @@
+import { WwwPolicy } from '@pubstudio/shared/type-api-platform-site'
 import { ICustomDomainRelationViewModel } from '@pubstudio/shared/type-api-shared'
 
 export interface ISiteMetadata {
@@ -8,6 +9,7 @@ export interface ISiteMetadata {
   location: string
   site_type: string
   custom_domains: ICustomDomainRelationViewModel[]
+  www_policy: WwwPolicy
 }
 
 export type IListSitesApiResponse = ISiteMetadata[]
@@ -0,0 +1,7 @@
+import { ITemplateViewModel } from './i-template.view-model'
+
+export interface IListTemplatesApiRequest {
+  category?: string
+}
+
+export type IListTemplatesApiResponse = ITemplateViewModel[]
@@ -0,0 +1,16 @@
+import { MaintenancePage } from '@pubstudio/shared/type-api-platform-site'
+
+export interface IMaintenanceViewModel {
+  enabled: boolean
+  // Whether visitors currently get the holding page
+  active: boolean
+  starts_at?: Date
+  ends_at?: Date
+  page: MaintenancePage
+  message?: string
+  custom_page?: string
+  allowed_ips: string[]
+  // Visiting `/_ps/maintenance?token=` with it lets previewers see the site
+  bypass_token: string
+  updated_at: Date
+}
@@ -0,0 +1,16 @@
+// RFC 6902 JSON Patch operation
+export interface IJsonPatchOperation {
+  op: 'add' | 'remove' | 'replace' | 'move' | 'copy' | 'test'
+  path: string
+  value?: unknown
+  from?: string
+}
+
+export interface IPatchSiteApiRequest {
+  context?: IJsonPatchOperation[]
+  defaults?: IJsonPatchOperation[]
+  pages?: IJsonPatchOperation[]
+  editor?: string
+  history?: string
+  update_key: string
+}
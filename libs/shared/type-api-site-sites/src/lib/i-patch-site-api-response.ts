@@ -0,0 +1,6 @@
+export interface IPatchSiteApiResponse {
+  id: number
+  update_key: string
+  content_updated_at: number
+  updated_at: Date
+}
@@ -0,0 +1,8 @@
+export interface IPreviewLinkViewModel {
+  id: string
+  version_id: number
+  expires_at?: Date
+  has_password: boolean
+  view_count: number
+  created_at: Date
+}
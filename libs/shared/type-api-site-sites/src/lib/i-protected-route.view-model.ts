@@ -0,0 +1,7 @@
+export interface IProtectedRouteViewModel {
+  id: number
+  // Page route, protecting the route and its subroutes. `/` protects the whole site
+  route: string
+  created_at: Date
+  updated_at: Date
+}
@@ -0,0 +1,11 @@
+export type PublishScheduleStatus = 'Pending' | 'Running' | 'Completed' | 'Failed' | 'Cancelled'
+
+export interface IPublishScheduleViewModel {
+  id: number
+  site_id: string
+  publish_at: Date
+  status: PublishScheduleStatus
+  error?: string
+  completed_at?: Date
+  created_at: Date
+}
@@ -1,3 +1,15 @@
+// How publishing handles SSG blockers, i.e. interactive features that need the hydration runtime
+export type SsgBlockerPolicy = 'Ignore' | 'Warn' | 'Refuse'
+
 export interface IPublishSiteApiRequest {
   publish: boolean
+  message?: string
+  tag?: string
+  // Defaults to `Ignore`
+  ssg_blockers?: SsgBlockerPolicy
+}
+
+// Returned instead of an empty response when `ssg_blockers` is `Warn` and blockers were found
+export interface IPublishSiteApiResponse {
+  ssg_blockers: string[]
 }
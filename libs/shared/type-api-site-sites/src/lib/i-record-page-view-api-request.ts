@@ -0,0 +1,9 @@
+export interface IRecordPageViewApiRequest {
+  route: string
+  // `document.referrer` of the page, only its host is counted
+  referrer?: string
+  // UTM parameters of the landing page URL
+  utm_source?: string
+  utm_medium?: string
+  utm_campaign?: string
+}
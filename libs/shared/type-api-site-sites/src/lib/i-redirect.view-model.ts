@@ -0,0 +1,13 @@
+export interface IRedirectViewModel {
+  id: number
+  // Exact path, `/*` prefix, may contain `:param` segments
+  source: string
+  // Site path or absolute URL, may reference source params and `*`
+  target: string
+  status_code: number
+  enabled: boolean
+  hit_count: number
+  last_hit_at?: Date
+  created_at: Date
+  updated_at: Date
+}
@@ -0,0 +1,48 @@
+export type DeviceClass = 'Desktop' | 'Mobile' | 'Tablet' | 'Bot' | 'Unknown'
+
+export type AnalyticsDimension =
+  | 'route'
+  | 'referrer'
+  | 'utm_source'
+  | 'utm_medium'
+  | 'utm_campaign'
+  | 'device'
+  | 'country'
+
+// Filters match exactly. An empty string matches views without the dimension,
+// e.g. `referrer: ''` for direct visits
+export interface IGetSiteAnalyticsApiRequest {
+  // Defaults to 24 hours before `end`
+  start?: string
+  // Exclusive, defaults to now
+  end?: string
+  route?: string
+  referrer?: string
+  utm_source?: string
+  utm_medium?: string
+  utm_campaign?: string
+  device?: DeviceClass
+  country?: string
+  group_by?: AnalyticsDimension
+}
+
+export interface IHourlyViewsViewModel {
+  hour: string
+  views: number
+}
+
+export interface IDimensionViewsViewModel {
+  // Null for views without the dimension
+  value: string | null
+  views: number
+}
+
+export interface ISiteAnalyticsViewModel {
+  start: string
+  end: string
+  total_views: number
+  // Every hour of the range, including hours without views
+  hours: IHourlyViewsViewModel[]
+  // Views by the `group_by` dimension, most viewed first
+  breakdown: IDimensionViewsViewModel[]
+}
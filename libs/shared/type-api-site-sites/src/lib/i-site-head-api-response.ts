@@ -1,4 +1,13 @@
+import { IHeadLink, IHeadMeta } from '@pubstudio/shared/type-site'
+
 export interface ISiteHeadApiResponse {
+  // Served route, the home page is `/`
+  route: string
   title: string
   description: string
+  image?: string
+  meta: IHeadMeta[]
+  alternates: IHeadLink[]
+  // Locale code, when the site has locales
+  lang?: string
 }
@@ -3,4 +3,6 @@ export interface ISiteInfoViewModel {
   name: string
   updated_at: Date
   published: boolean
+  message?: string
+  tag?: string
 }
@@ -0,0 +1,12 @@
+export interface ISiteMergeConflict {
+  field: string
+  route?: string
+  component_id?: string
+}
+
+export interface ISiteMergeConflictApiResponse {
+  code: string
+  message: string
+  status: number
+  conflicts: ISiteMergeConflict[]
+}
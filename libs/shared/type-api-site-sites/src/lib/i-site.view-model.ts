@@ -13,4 +13,8 @@ export interface ISiteViewModel {
   updated_at: Date
   content_updated_at: number
   preview_id?: string
+  // Recorded revision of the content, sent back as `base_revision_id` to merge updates
+  revision_id?: number
+  // Subdomain of the request host, when the site is served on a wildcard domain
+  subdomain?: string
 }
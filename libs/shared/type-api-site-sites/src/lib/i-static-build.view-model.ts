@@ -0,0 +1,26 @@
+export type StaticBuildTrigger = 'Publish' | 'Update' | 'Schedule' | 'Metadata'
+
+export type StaticBuildStatus = 'Running' | 'Succeeded' | 'Failed' | 'Cleared'
+
+export interface IStaticBuildViewModel {
+  id: number
+  trigger: StaticBuildTrigger
+  status: StaticBuildStatus
+  content_updated_at: number
+  generator: string | null
+  page_count: number
+  // True when only changed pages were regenerated
+  incremental: boolean
+  warnings: string[]
+  blockers: string[]
+  error: string | null
+  started_at: Date
+  finished_at: Date | null
+}
+
+export interface IListStaticBuildsApiRequest {
+  // 1-50, defaults to 10
+  limit?: number
+}
+
+export type IListStaticBuildsApiResponse = IStaticBuildViewModel[]
@@ -0,0 +1,14 @@
+export interface ITemplateViewModel {
+  id: string
+  name: string
+  description: string | null
+  category: string
+  // Object key in the template-previews bucket
+  preview_key: string | null
+  created_at: string
+}
+
+export interface ICreateTemplateApiResponse extends ITemplateViewModel {
+  // Presigned PUT URL for the preview image, when requested
+  preview_upload_url: string | null
+}
@@ -0,0 +1,4 @@
+// Send in the `X-Preview-Token` header to view the preview. Also set as a cookie
+export interface IUnlockPreviewLinkApiResponse {
+  token: string
+}
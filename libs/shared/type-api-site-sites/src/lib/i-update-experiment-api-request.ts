@@ -0,0 +1,7 @@
+// Variants are fixed once an experiment is created
+export interface IUpdateExperimentApiRequest {
+  name?: string
+  enabled?: boolean
+  // An empty string removes the conversion table
+  conversion_table?: string
+}
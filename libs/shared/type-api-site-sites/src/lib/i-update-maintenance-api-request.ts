@@ -0,0 +1,16 @@
+import { MaintenancePage } from '@pubstudio/shared/type-api-platform-site'
+
+// Replaces the site's maintenance settings
+export interface IUpdateMaintenanceApiRequest {
+  enabled: boolean
+  starts_at?: string
+  ends_at?: string
+  // Defaults to Builtin
+  page?: MaintenancePage
+  message?: string
+  // Required when `page` is Custom
+  custom_page?: string
+  allowed_ips?: string[]
+  // Invalidates bypass cookies given to previewers
+  reset_bypass_token?: boolean
+}
@@ -0,0 +1,4 @@
+import { ICreateProtectedRouteApiRequest } from './i-create-protected-route-api-request'
+
+// Changing the password signs out visitors who unlocked the route
+export type IUpdateProtectedRouteApiRequest = Partial<ICreateProtectedRouteApiRequest>
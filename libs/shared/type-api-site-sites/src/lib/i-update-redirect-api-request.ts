@@ -0,0 +1,3 @@
+import { ICreateRedirectApiRequest } from './i-create-redirect-api-request'
+
+export type IUpdateRedirectApiRequest = Partial<ICreateRedirectApiRequest>
@@ -9,4 +9,5 @@ export interface IUpdateSiteApiRequest {
   pageOrder?: string
   update_key?: string
   enable_preview?: boolean
+  base_revision_id?: number
 }
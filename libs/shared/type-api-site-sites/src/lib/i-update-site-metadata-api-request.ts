@@ -1,3 +1,4 @@
+import { WwwPolicy } from '@pubstudio/shared/type-api-platform-site'
 import { ICustomDomainRelationViewModel } from '@pubstudio/shared/type-api-shared'
 
 export interface IUpdateSiteMetadataApiRequest {
@@ -6,4 +7,5 @@ export interface IUpdateSiteMetadataApiRequest {
   site_type?: string
   owner_email?: string
   owner_id?: string
+  www_policy?: WwwPolicy
 }
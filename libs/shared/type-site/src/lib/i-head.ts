@@ -15,6 +15,8 @@ export interface IHeadLink {
   type?: string
   blocking?: string
   crossorigin?: string
+  // Language of an `alternate` link, e.g. `en-US` or `x-default`
+  hreflang?: string
 }
 
 export interface IHeadMeta {
@@ -39,6 +41,13 @@ export interface IHead {
   link?: IHeadLink[]
   meta?: IHeadMeta[]
   script?: IHeadScript[]
+  // Locale code -> localized title and description of a page
+  locales?: Record<string, ILocalizedHead>
+}
+
+export interface ILocalizedHead {
+  title?: string
+  description?: string
 }
 
 export type IHeadObject = string | IHeadBase | IHeadLink | IHeadMeta | IHeadScript
@@ -46,8 +55,9 @@ export type IPageHeadObject = IHeadLink | IHeadMeta | IHeadScript
 
 export type IPageHead = IHead
 
-export type IHeadTag = keyof IHead
+// Localized heads are not edited as head tags
+export type IHeadTag = Exclude<keyof IHead, 'locales'>
 
-export type IPageHeadTag = keyof IPageHead
+export type IPageHeadTag = Exclude<keyof IPageHead, 'locales'>
 
 export type IHeadTagStr = IHeadTag | IPageHeadTag
@@ -4,10 +4,33 @@ import { IHead } from './i-head'
 import { IPage } from './i-page'
 import { ISiteContext } from './i-site-context'
 
+export interface ISiteRobotsRule {
+  userAgent: string
+  allow?: string[]
+  disallow?: string[]
+  crawlDelay?: number | null
+}
+
+export interface ISiteLocale {
+  // Language tag, e.g. `en` or `zh-TW`. Pages are served under `/{code}`
+  code: string
+  // Site head of the locale, used by pages without a localized head
+  title?: string
+  description?: string
+}
+
 export interface ISiteDefaults {
   head: IHead
   // Route of home page
   homePage: string
+  // robots.txt rule groups. Omit to allow all crawlers
+  robots?: ISiteRobotsRule[]
+  // Cache-Control header for served pages, defaults to no-cache
+  cacheControl?: string
+  // Redirect served paths to a canonical trailing slash form, defaults to 'ignore'
+  trailingSlash?: 'ignore' | 'remove' | 'add'
+  // Language versions of the site. The first locale is the default
+  locales?: ISiteLocale[]
 }
 
 export type ISitePages = Record<string, IPage>
@@ -27,4 +50,7 @@ export interface ISite {
   updated_at?: string
   content_updated_at?: number
   preview_id?: string
+  // Subdomain of the request host, when the site is served on a wildcard domain.
+  // Set at runtime, pages read it through the `${subdomain}` variable
+  subdomain?: string
 }